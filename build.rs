@@ -0,0 +1,39 @@
+// Build script
+//
+// With the `embedded-inputs` feature, every inputs/dayN.txt present at build
+// time is baked into the binary (see src/embedded.rs). ADVENTCODE_INPUTS_DIR
+// points at a different directory. Without the feature nothing is read.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_INPUTS").is_none() {
+        return;
+    }
+
+    println!("cargo:rerun-if-env-changed=ADVENTCODE_INPUTS_DIR");
+    let dir = match env::var_os("ADVENTCODE_INPUTS_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("inputs"),
+    };
+    // Watching the directory picks up inputs added or removed later
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut table = String::from("static EMBEDDED: &[(u8, &str)] = &[\n");
+    for day in 1..=25u8 {
+        let path = dir.join(format!("day{}.txt", day));
+        if path.is_file() {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let path = fs::canonicalize(&path).unwrap();
+            writeln!(table, "    ({}, include_str!({:?})),", day, path).unwrap();
+        }
+    }
+    table.push_str("];\n");
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out, table).unwrap();
+}
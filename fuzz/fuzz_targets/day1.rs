@@ -0,0 +1,34 @@
+// Fuzz target: day 1 solver
+//
+// Feeds arbitrary UTF-8 into both parts under --strict and in the default
+// warning mode. Any panic is a bug; Ok and Err are both fine.
+
+#![no_main]
+
+use adventcode::day1;
+use adventcode::options::DayOptions;
+use adventcode::output::Output;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Part 2 simulates every click, so huge distances only burn fuzzer time
+    if has_long_number(text) {
+        return;
+    }
+
+    for strict in [false, true] {
+        let opts = DayOptions::default().with_strict(strict);
+        for part2 in [false, true] {
+            let _ = day1::solve(text, part2, &opts, &mut Output::capture());
+        }
+    }
+});
+
+/// True when the input has a run of more than 6 digits
+fn has_long_number(text: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_digit()).any(|run| run.len() > 6)
+}
@@ -0,0 +1,36 @@
+// Fuzz target: day 2 solver
+//
+// Feeds arbitrary UTF-8 into both parts under --strict and in the default
+// warning mode. Any panic is a bug; Ok and Err are both fine.
+
+#![no_main]
+
+use adventcode::day2;
+use adventcode::options::DayOptions;
+use adventcode::output::Output;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Part 2 walks every ID in every range, so keep the ranges small
+    if has_long_number(text) {
+        return;
+    }
+
+    for strict in [false, true] {
+        let opts = DayOptions::default().with_strict(strict);
+        for part2 in [false, true] {
+            let _ = day2::solve(text, part2, &opts, &mut Output::capture());
+        }
+    }
+});
+
+/// True when the input has a run of more than 5 digits
+/// The parser drops whitespace inside a token, so "12\n34" counts as one run
+fn has_long_number(text: &str) -> bool {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.split(|c: char| !c.is_ascii_digit()).any(|run| run.len() > 5)
+}
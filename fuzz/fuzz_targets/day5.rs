@@ -0,0 +1,24 @@
+// Fuzz target: day 5 solver
+//
+// Feeds arbitrary UTF-8 into both parts under --strict and in the default
+// warning mode. Any panic is a bug; Ok and Err are both fine.
+
+#![no_main]
+
+use adventcode::day5;
+use adventcode::options::DayOptions;
+use adventcode::output::Output;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    for strict in [false, true] {
+        let opts = DayOptions::default().with_strict(strict);
+        for part2 in [false, true] {
+            let _ = day5::solve(text, part2, &opts, &mut Output::capture());
+        }
+    }
+});
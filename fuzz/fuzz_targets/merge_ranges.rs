@@ -0,0 +1,35 @@
+// Fuzz target: interval::merge invariants
+//
+// Checks the merged output against the raw input on arbitrary range lists:
+// sorted, disjoint, never adjacent, covering every input endpoint, and never
+// counting more IDs than the input ranges did between them.
+
+#![no_main]
+
+use adventcode::interval;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|pairs: Vec<(u64, u64)>| {
+    // merge expects start <= end, as parse_range guarantees
+    let ranges: Vec<_> = pairs
+        .into_iter()
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    let merged = interval::merge(&ranges);
+
+    for pair in merged.windows(2) {
+        // Sorted with a gap of at least one ID between neighbours
+        assert!(pair[0].1 < pair[1].0, "overlapping or unsorted: {:?}", pair);
+        assert!(pair[1].0 - pair[0].1 > 1, "adjacent ranges not merged: {:?}", pair);
+    }
+
+    for &(start, end) in &ranges {
+        // Every input range sits entirely inside one merged range
+        assert_eq!(interval::find(&merged, start), interval::find(&merged, end));
+        assert!(interval::contains(&merged, start) && interval::contains(&merged, end));
+    }
+
+    let input_total: u128 = ranges.iter().map(|&range| interval::len(range)).sum();
+    assert!(interval::total_len(&merged) <= input_total);
+    assert_eq!(merged.is_empty(), ranges.is_empty());
+});
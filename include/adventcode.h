@@ -0,0 +1,52 @@
+#ifndef ADVENTCODE_H
+#define ADVENTCODE_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Solved; `*out` holds the answer
+#define AOC_OK 0
+
+// `input` or `out` was NULL
+#define AOC_NULL_POINTER 1
+
+// `input` was not valid UTF-8
+#define AOC_INVALID_UTF8 2
+
+// No such day in this build
+#define AOC_UNKNOWN_DAY 3
+
+// `part` was not 1 or 2
+#define AOC_BAD_PART 4
+
+// The solver rejected the input; `*out` holds its message
+#define AOC_SOLVE_FAILED 5
+
+// The solver panicked (a bug); `*out` is left NULL
+#define AOC_PANIC 6
+
+// Solve one part (1 or 2) of a day
+//
+// `input` is a NUL-terminated UTF-8 string. On AOC_OK `*out` receives the
+// answer as the CLI prints it, and on AOC_SOLVE_FAILED the error message;
+// either way release it with `aoc_free_string`. Other codes leave `*out` NULL.
+//
+// # Safety
+//
+// `input` must be NULL or point to a NUL-terminated string, and `out` must be
+// NULL or point to writable storage for one pointer.
+int32_t aoc_solve(uint8_t day, uint8_t part, const char *input, char **out);
+
+// Release a string returned through `aoc_solve`'s `out`; NULL is ignored
+//
+// # Safety
+//
+// `s` must be NULL or a pointer from `aoc_solve` not yet freed.
+void aoc_free_string(char *s);
+
+#endif  /* ADVENTCODE_H */
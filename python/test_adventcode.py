@@ -0,0 +1,79 @@
+"""Smoke tests for the Python bindings.
+
+Build the extension into the current virtualenv, then run pytest:
+
+    maturin develop
+    pytest python/
+
+Every day's documented example (tests/fixtures/examples.txt) must give the
+same answers through Python as through the CLI.
+"""
+
+import pathlib
+import threading
+
+import adventcode
+
+FIXTURES = pathlib.Path(__file__).resolve().parent.parent / "tests" / "fixtures"
+
+
+def expected_answers():
+    """(day, [part1, part2]) rows from the shared examples manifest"""
+    for line in (FIXTURES / "examples.txt").read_text().splitlines():
+        line = line.strip()
+        if line and not line.startswith("#"):
+            day, part1, part2 = line.split()
+            yield int(day), [part1, part2]
+
+
+def test_days_lists_titles():
+    days = dict(adventcode.days())
+    assert days[5] == "CAFETERIA"
+    assert sorted(days) == [day for day, _ in expected_answers()]
+
+
+def test_examples_match_expected_answers():
+    for day, answers in expected_answers():
+        text = (FIXTURES / f"day{day}_example.txt").read_text()
+        for part, expected in enumerate(answers, start=1):
+            answer = adventcode.solve(day, part, text)
+            # Labelled answers ("Password: 3") end with the value
+            assert answer.split()[-1] == expected, (day, part, answer)
+
+
+def test_strict_mode_raises_parse_error_with_diagnostics():
+    try:
+        adventcode.solve(1, 1, "L68\nX12\n", strict=True)
+    except adventcode.ParseError as e:
+        assert isinstance(e, adventcode.AocError)
+        assert "line 2" in str(e) and "X12" in str(e)
+    else:
+        raise AssertionError("expected ParseError")
+
+
+def test_bad_arguments_raise_value_error():
+    for day, part in [(25, 1), (5, 3)]:
+        try:
+            adventcode.solve(day, part, "")
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"expected ValueError for day {day} part {part}")
+
+
+def test_solves_run_without_the_gil():
+    text = (FIXTURES / "day2_example.txt").read_text()
+    results = []
+    threads = [threading.Thread(target=lambda: results.append(adventcode.solve(2, 2, text))) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert results == ["Sum of invalid IDs: 4174379265"] * 4
+
+
+if __name__ == "__main__":
+    for name, test in list(globals().items()):
+        if name.startswith("test_"):
+            test()
+            print(f"ok  {name}")
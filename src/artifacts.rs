@@ -0,0 +1,58 @@
+// Intermediate artifacts
+//
+// `--emit-intermediate <DIR>` asks each day to write the structures its
+// answer is built from, one text file each, so two runs can be diffed when
+// an answer is wrong. File names are fixed per day ("day5_merged.txt",
+// "day4_round_03.txt"), so a rerun overwrites the last one's files. Solvers
+// reach the sink through `DayOptions::artifacts`; when it is None they skip
+// building anything for it.
+
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+
+/// The directory a run writes its artifacts to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifacts {
+    dir: PathBuf,
+}
+
+impl Artifacts {
+    /// Write artifacts into `dir`, creating it (and its parents) if missing
+    pub fn create(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `lines` to the file `name` in the directory, one per line
+    pub fn write<T: Display>(&self, name: &str, lines: impl IntoIterator<Item = T>) -> Result<(), Error> {
+        let text: String = lines.into_iter().map(|line| format!("{}\n", line)).collect();
+        let path = self.dir.join(name);
+        fs::write(&path, text).map_err(|e| Error::Export(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_the_directory_and_writes_one_line_each() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("debug").join("run1");
+        let artifacts = Artifacts::create(&dir).unwrap();
+        artifacts.write("day5_merged.txt", ["3-5", "10-20"]).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("day5_merged.txt")).unwrap(), "3-5\n10-20\n");
+
+        artifacts.write("empty.txt", Vec::<String>::new()).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("empty.txt")).unwrap(), "");
+        assert!(matches!(artifacts.write("missing/x.txt", ["1"]), Err(Error::Export(..))));
+    }
+}
@@ -0,0 +1,103 @@
+// Batch runs over several input files
+//
+// `--files "inputs/day5*.txt"` runs the chosen day and part once per file
+// the pattern matches and prints a table of file, answer and time. The
+// program expands the pattern itself, so it also works from shells that
+// leave wildcards alone (cmd.exe, PowerShell). A file that fails only puts
+// its error in its own row; the rest still run.
+
+use std::path::PathBuf;
+
+use adventcode::history;
+
+/// The files `pattern` matches, in path order; an error when it is not a
+/// valid pattern or matches no file
+pub fn expand(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let paths = glob::glob(pattern).map_err(|e| format!("--files {}: {}", pattern, e))?;
+    let mut files: Vec<PathBuf> = paths.filter_map(Result::ok).filter(|path| path.is_file()).collect();
+    if files.is_empty() {
+        return Err(format!("--files {}: no files match", pattern));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// How the run on one file went
+pub struct Entry {
+    pub file: String,
+    /// The answer's value and how long it took, or why it failed
+    pub outcome: Result<(String, f64), String>,
+}
+
+/// The table's lines: a header, then one row per entry with its columns
+/// lined up; a failed row has its error where the answer would be
+pub fn table(entries: &[Entry]) -> Vec<String> {
+    let cells: Vec<[String; 3]> = entries
+        .iter()
+        .map(|entry| match &entry.outcome {
+            Ok((answer, millis)) => [entry.file.clone(), answer.clone(), history::millis(*millis)],
+            Err(e) => {
+                // On one line, so a multi-line error stays in its row
+                let e = e.split_whitespace().collect::<Vec<_>>().join(" ");
+                [entry.file.clone(), format!("error: {}", e), String::new()]
+            }
+        })
+        .collect();
+    let header = ["File".to_string(), "Answer".to_string(), "Time".to_string()];
+    // An error is left out of the answer column's width, so that one long
+    // message does not push every time far to the right
+    let width = |column: usize| {
+        std::iter::once(&header)
+            .chain(cells.iter().filter(|_| column != 1))
+            .chain(entries.iter().zip(&cells).filter(|(e, _)| column == 1 && e.outcome.is_ok()).map(|(_, c)| c))
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (file_width, answer_width) = (width(0), width(1));
+    std::iter::once(&header)
+        .chain(&cells)
+        .map(|[file, answer, time]| {
+            let line = format!("{:file_width$}  {:answer_width$}  {}", file, answer, time);
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_in_path_order_and_lines_up_the_table() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["day5b.txt", "day5a.txt", "day4.txt", "day5c.txt"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("day5d.txt")).unwrap();
+        let pattern = dir.path().join("day5*.txt");
+        let names: Vec<String> = expand(pattern.to_str().unwrap())
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["day5a.txt", "day5b.txt", "day5c.txt"]);
+        assert!(expand(dir.path().join("day9*.txt").to_str().unwrap()).unwrap_err().ends_with("no files match"));
+        assert!(expand("[").is_err());
+
+        let entries = [
+            Entry { file: "a.txt".to_string(), outcome: Ok(("3".to_string(), 0.25)) },
+            Entry { file: "longer.txt".to_string(), outcome: Err("line 2: malformed fresh range".to_string()) },
+            Entry { file: "c.txt".to_string(), outcome: Ok(("14".to_string(), 12.0)) },
+        ];
+        assert_eq!(
+            table(&entries),
+            [
+                "File        Answer  Time",
+                "a.txt       3       0.250 ms",
+                "longer.txt  error: line 2: malformed fresh range",
+                "c.txt       14      12.0 ms",
+            ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// TUI battery view for day 3
+//
+// One row per bank with the batteries its joltage uses highlighted, the
+// joltage on the right and the running total at the bottom, so the greedy
+// picks can be checked by eye. Tab switches between part 1's two batteries
+// and part 2's twelve; both are worked out once when the view opens. Banks
+// never wrap: a long one scrolls sideways (h/l) with every row, so the
+// highlights stay under the digits they mark.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use adventcode::day3;
+
+/// How a picked battery is drawn
+pub const HIGHLIGHT: Style = Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD.union(Modifier::REVERSED));
+
+/// Columns for the line numbers on the left
+const GUTTER: usize = 6;
+
+/// Columns for the joltages on the right
+const JOLTAGE_WIDTH: usize = 15;
+
+/// Characters a sideways scroll moves by
+const SIDE_STEP: usize = 8;
+
+/// One bank and what each part picks from it
+struct Bank {
+    line: usize,
+    text: String,
+    /// Char indices of the picked batteries, for part 1 and part 2
+    picks: [Vec<usize>; 2],
+    joltage: [u64; 2],
+}
+
+pub struct BatteryView {
+    banks: Vec<Bank>,
+    pub part2: bool,
+    /// First bank and first column on screen
+    top: usize,
+    left: usize,
+    /// Banks on screen in the last draw, for paging
+    height: usize,
+}
+
+impl BatteryView {
+    pub fn new(input: &str, part2: bool) -> Self {
+        let banks = day3::banks(input)
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(line, text)| Bank {
+                picks: [false, true].map(|part2| day3::selected_batteries(&text, part2)),
+                joltage: [false, true].map(|part2| day3::joltage(&text, part2)),
+                line,
+                text,
+            })
+            .collect();
+        Self { banks, part2, top: 0, left: 0, height: 1 }
+    }
+
+    pub fn down(&mut self, rows: usize) {
+        self.top = (self.top + rows).min(self.banks.len().saturating_sub(1));
+    }
+
+    pub fn up(&mut self, rows: usize) {
+        self.top = self.top.saturating_sub(rows);
+    }
+
+    pub fn page_down(&mut self) {
+        self.down(self.height.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.up(self.height.max(1));
+    }
+
+    pub fn right(&mut self) {
+        let widest = self.banks.iter().map(|bank| bank.text.chars().count()).max().unwrap_or(0);
+        self.left = (self.left + SIDE_STEP).min(widest.saturating_sub(1));
+    }
+
+    pub fn left(&mut self) {
+        self.left = self.left.saturating_sub(SIDE_STEP);
+    }
+}
+
+/// Draw `view` into `area`
+pub fn render(f: &mut Frame, area: Rect, view: &mut BatteryView, block: Block<'static>) {
+    let inner = block.inner(area);
+    // The last row is the total
+    view.height = usize::from(inner.height.saturating_sub(1)).max(1);
+    let text_width = usize::from(inner.width).saturating_sub(GUTTER + JOLTAGE_WIDTH + 1);
+    let part = usize::from(view.part2);
+
+    let mut rows: Vec<Line> = view
+        .banks
+        .iter()
+        .skip(view.top)
+        .take(view.height)
+        .map(|bank| {
+            let mut spans = vec![Span::styled(format!("{:>5} ", bank.line), Style::default().fg(Color::Gray))];
+            let shown = bank.text.chars().enumerate().skip(view.left).take(text_width);
+            let mut drawn = 0;
+            for (i, c) in shown {
+                let style = if bank.picks[part].contains(&i) { HIGHLIGHT } else { Style::default() };
+                spans.push(Span::styled(c.to_string(), style));
+                drawn += 1;
+            }
+            spans.push(Span::raw(" ".repeat(text_width - drawn + 1)));
+            spans.push(Span::styled(
+                format!("{:>w$}", bank.joltage[part], w = JOLTAGE_WIDTH),
+                Style::default().fg(Color::Cyan),
+            ));
+            Line::from(spans)
+        })
+        .collect();
+    rows.resize(view.height, Line::from(""));
+
+    let last = (view.top + view.height).min(view.banks.len());
+    let running: u64 = view.banks[..last].iter().map(|bank| bank.joltage[part]).sum();
+    let total: u64 = view.banks.iter().map(|bank| bank.joltage[part]).sum();
+    let shown = if view.banks.is_empty() { "no banks".to_string() } else { format!("banks 1-{} of {}", last, view.banks.len()) };
+    rows.push(Line::from(Span::styled(
+        format!("Running total ({}): {} | Total: {}", shown, running, total),
+        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+    )));
+
+    f.render_widget(Paragraph::new(rows).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, buffer::Buffer, widgets::Borders, Terminal};
+
+    const EXAMPLE: &str = "987654321111111\n811111111111119\n234234234234278\n818181911112111\n";
+
+    fn draw(view: &mut BatteryView, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| render(f, f.area(), view, Block::default().borders(Borders::ALL))).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// Columns of row `y` (bank text only) drawn with the highlight
+    fn highlighted(buffer: &Buffer, y: u16) -> Vec<u16> {
+        (0..buffer.area.width)
+            .filter(|&x| {
+                let cell = &buffer[(x, y)];
+                Some(cell.fg) == HIGHLIGHT.fg && cell.modifier == HIGHLIGHT.add_modifier
+            })
+            .map(|x| x - 1 - GUTTER as u16)
+            .collect()
+    }
+
+    fn row(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn picked_batteries_carry_the_highlight() {
+        let mut view = BatteryView::new(EXAMPLE, false);
+        let buffer = draw(&mut view, 50, 8);
+        // 98, 89, 78 and 92
+        assert_eq!(highlighted(&buffer, 1), [0, 1]);
+        assert_eq!(highlighted(&buffer, 2), [0, 14]);
+        assert_eq!(highlighted(&buffer, 3), [13, 14]);
+        assert_eq!(highlighted(&buffer, 4), [6, 11]);
+        assert!(row(&buffer, 4).trim_end_matches('│').trim_end().ends_with("92"));
+        assert!(row(&buffer, 6).contains("Running total (banks 1-4 of 4): 357 | Total: 357"), "{}", row(&buffer, 6));
+
+        view.part2 = true;
+        let buffer = draw(&mut view, 80, 8);
+        assert_eq!(highlighted(&buffer, 4), [0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert!(row(&buffer, 6).contains("Total: 3121910778619"));
+    }
+
+    #[test]
+    fn scrolling_keeps_highlights_under_their_digits() {
+        let mut view = BatteryView::new(EXAMPLE, false);
+        // Two banks per screen; the running total covers what is on it
+        let buffer = draw(&mut view, 50, 5);
+        assert!(row(&buffer, 3).contains("banks 1-2 of 4): 187 |"), "{}", row(&buffer, 3));
+        view.page_down();
+        let buffer = draw(&mut view, 50, 5);
+        assert!(row(&buffer, 1).contains("    3 234234234234278"));
+        assert!(row(&buffer, 3).contains("banks 1-4 of 4): 357 |"));
+
+        // Eight characters to the left: 78 at 13 and 14 moves to 5 and 6
+        view.right();
+        let buffer = draw(&mut view, 50, 5);
+        assert_eq!(highlighted(&buffer, 1), [5, 6]);
+        assert!(row(&buffer, 1).contains("    3 4234278"));
+        view.left();
+        view.up(10);
+        assert_eq!((view.top, view.left), (0, 0));
+    }
+}
@@ -0,0 +1,292 @@
+// Benchmark baselines
+//
+// `--bench N` times N runs of a part after a warm-up, and reports the
+// median with its spread, the fastest, mean and slowest run.
+// `--bench N --baseline save` stores the median time of each benchmarked
+// day and part, with its spread, in bench-baselines.json in the data
+// directory. `--baseline check` compares a fresh benchmark with the stored
+// entry and fails when the median grew by more than the tolerance. Each
+// baseline also notes the input's SHA-256, the thread count and the build
+// profile, since a comparison across any of those says little about the code.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::history;
+
+/// The baselines' file name in the data directory
+pub const FILE: &str = "bench-baselines.json";
+
+/// How much slower than its baseline a check lets a run be
+pub const DEFAULT_TOLERANCE: f64 = 0.20;
+
+/// The middle, spread and range of a set of timings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub median_ms: f64,
+    /// Median absolute deviation from the median
+    pub mad_ms: f64,
+    pub runs: usize,
+    // Baselines saved before these were kept read them as 0
+    #[serde(default)]
+    pub min_ms: f64,
+    #[serde(default)]
+    pub mean_ms: f64,
+    #[serde(default)]
+    pub max_ms: f64,
+}
+
+impl Stats {
+    /// Stats for `millis`; None when it is empty
+    pub fn of(millis: &[f64]) -> Option<Self> {
+        let median_ms = median(millis.to_vec())?;
+        let deviations = millis.iter().map(|ms| (ms - median_ms).abs()).collect();
+        Some(Self {
+            median_ms,
+            mad_ms: median(deviations)?,
+            runs: millis.len(),
+            min_ms: millis.iter().copied().fold(f64::INFINITY, f64::min),
+            mean_ms: millis.iter().sum::<f64>() / millis.len() as f64,
+            max_ms: millis.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    /// e.g. "median 4.1 ms ± 0.2 ms over 20 runs (min 3.9 ms, mean 4.2 ms,
+    /// max 5.0 ms)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "median {} ± {} over {} runs (min {}, mean {}, max {})",
+            history::millis(self.median_ms),
+            history::millis(self.mad_ms),
+            self.runs,
+            history::millis(self.min_ms),
+            history::millis(self.mean_ms),
+            history::millis(self.max_ms)
+        )
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((values[mid - 1] + values[mid]) / 2.0),
+        _ => Some(values[mid]),
+    }
+}
+
+/// What a timing depends on besides the code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Context {
+    /// SHA-256 of the input, as in the answer cache
+    pub input: String,
+    pub threads: usize,
+    /// "debug" or "release"
+    pub profile: String,
+}
+
+impl Context {
+    /// The context of a run on `input` in this process
+    pub fn current(input: &str) -> Self {
+        Self {
+            input: cache::input_hash(input),
+            threads: crate::parallel::threads(),
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+        }
+    }
+
+    /// How `self` differs from the `baseline` context, one phrase each
+    pub fn differences(&self, baseline: &Context) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.input != baseline.input {
+            differences.push("a different input".to_string());
+        }
+        if self.threads != baseline.threads {
+            differences.push(format!("{} threads (now {})", baseline.threads, self.threads));
+        }
+        if self.profile != baseline.profile {
+            differences.push(format!("a {} build (now {})", baseline.profile, self.profile));
+        }
+        differences
+    }
+}
+
+/// The stored timing of one day and part
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub day: u8,
+    pub part: u8,
+    #[serde(flatten)]
+    pub stats: Stats,
+    #[serde(flatten)]
+    pub context: Context,
+}
+
+/// Every stored baseline, sorted by day then part
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baselines {
+    pub entries: Vec<Baseline>,
+}
+
+impl Baselines {
+    /// The baselines in the file at `path`; a missing file has none
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the baselines to `path`, creating the data directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Baseline> {
+        self.entries.iter().find(|b| b.day == day && b.part == part)
+    }
+
+    /// Store `baseline`, replacing the one for the same day and part
+    pub fn insert(&mut self, baseline: Baseline) {
+        self.entries.retain(|b| (b.day, b.part) != (baseline.day, baseline.part));
+        self.entries.push(baseline);
+        self.entries.sort_by_key(|b| (b.day, b.part));
+    }
+}
+
+/// `$ADVENTCODE_DATA_DIR/bench-baselines.json`, else
+/// `<data dir>/adventcode/bench-baselines.json`
+pub fn default_path() -> Option<PathBuf> {
+    Some(cache::data_dir()?.join(FILE))
+}
+
+/// A fresh benchmark next to its baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub baseline: Stats,
+    pub current: Stats,
+    pub tolerance: f64,
+}
+
+impl Comparison {
+    /// The change in median as a fraction of the baseline's (0.1 = 10% slower)
+    pub fn delta(&self) -> f64 {
+        if self.baseline.median_ms > 0.0 {
+            (self.current.median_ms - self.baseline.median_ms) / self.baseline.median_ms
+        } else {
+            0.0
+        }
+    }
+
+    /// Slower than the baseline by more than the tolerance
+    pub fn regressed(&self) -> bool {
+        self.delta() > self.tolerance
+    }
+}
+
+impl fmt::Display for Comparison {
+    /// e.g. "4.1 ms → 4.6 ms (+12.2%), within 20%"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.regressed() { "REGRESSED beyond" } else { "within" };
+        write!(
+            f,
+            "{} → {} ({:+.1}%), {} {}%",
+            history::millis(self.baseline.median_ms),
+            history::millis(self.current.median_ms),
+            self.delta() * 100.0,
+            verdict,
+            self.tolerance * 100.0
+        )
+    }
+}
+
+/// Parse a tolerance such as "20%" or "20" into a fraction (0.2)
+pub fn parse_tolerance(text: &str) -> Result<f64, String> {
+    let number = text.trim().trim_end_matches('%');
+    match number.parse::<f64>() {
+        Ok(percent) if percent >= 0.0 && percent.is_finite() => Ok(percent / 100.0),
+        _ => Err(format!("tolerance must be a percentage such as 20%, not {}", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_ms: f64) -> Stats {
+        Stats { median_ms, mad_ms: 0.5, runs: 10, min_ms: median_ms - 1.0, mean_ms: median_ms, max_ms: median_ms + 1.0 }
+    }
+
+    #[test]
+    fn stats_take_the_median_and_its_deviation() {
+        let stats = Stats::of(&[10.0, 12.0, 11.0, 30.0, 9.0]).unwrap();
+        assert_eq!(stats.median_ms, 11.0);
+        // Deviations 1, 1, 0, 19, 2: one slow outlier barely moves either
+        assert_eq!(stats.mad_ms, 1.0);
+        assert_eq!((stats.min_ms, stats.mean_ms, stats.max_ms), (9.0, 14.4, 30.0));
+        assert_eq!(stats.to_string(), "median 11.0 ms ± 1.0 ms over 5 runs (min 9.0 ms, mean 14.4 ms, max 30.0 ms)");
+        assert_eq!(Stats::of(&[1.0, 2.0, 4.0, 8.0]).unwrap().median_ms, 3.0);
+        assert_eq!(Stats::of(&[]), None);
+    }
+
+    #[test]
+    fn regressions_are_measured_against_the_tolerance() {
+        let check = |before: f64, after: f64| Comparison { baseline: stats(before), current: stats(after), tolerance: 0.2 };
+        assert!(!check(10.0, 12.0).regressed());
+        assert!(check(10.0, 12.5).regressed());
+        assert!(!check(10.0, 4.0).regressed());
+        assert_eq!(check(10.0, 12.5).to_string(), "10.0 ms → 12.5 ms (+25.0%), REGRESSED beyond 20%");
+        assert_eq!(check(10.0, 9.0).to_string(), "10.0 ms → 9.0 ms (-10.0%), within 20%");
+
+        assert_eq!(parse_tolerance("20%"), Ok(0.2));
+        assert_eq!(parse_tolerance("5"), Ok(0.05));
+        assert!(parse_tolerance("-5%").is_err());
+        assert!(parse_tolerance("fast").is_err());
+    }
+
+    #[test]
+    fn baselines_round_trip_and_note_their_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join(FILE);
+        assert_eq!(Baselines::load(&path).unwrap(), Baselines::default());
+
+        let context = Context { input: cache::input_hash("L68\n"), threads: 4, profile: "release".to_string() };
+        let mut baselines = Baselines::default();
+        baselines.insert(Baseline { day: 3, part: 2, stats: stats(5.0), context: context.clone() });
+        baselines.insert(Baseline { day: 1, part: 1, stats: stats(1.0), context: context.clone() });
+        baselines.insert(Baseline { day: 3, part: 2, stats: stats(4.0), context: context.clone() });
+        baselines.save(&path).unwrap();
+
+        let loaded = Baselines::load(&path).unwrap();
+        assert_eq!(loaded, baselines);
+        let parts: Vec<(u8, u8)> = loaded.entries.iter().map(|b| (b.day, b.part)).collect();
+        assert_eq!(parts, [(1, 1), (3, 2)]);
+        assert_eq!(loaded.get(3, 2).unwrap().stats.median_ms, 4.0);
+
+        let now = Context { input: cache::input_hash("R30\n"), threads: 8, profile: "debug".to_string() };
+        assert_eq!(
+            now.differences(&context),
+            ["a different input", "4 threads (now 8)", "a release build (now debug)"]
+        );
+        assert!(context.differences(&context).is_empty());
+
+        // Baselines from before min, mean and max were kept still load
+        let old = r#"{"entries":[{"day":1,"part":1,"median_ms":2.0,"mad_ms":0.1,"runs":5,"input":"x","threads":1,"profile":"release"}]}"#;
+        fs::write(&path, old).unwrap();
+        assert_eq!(Baselines::load(&path).unwrap().entries[0].stats.max_ms, 0.0);
+    }
+}
@@ -0,0 +1,189 @@
+// Time budgets (--assert-max-time)
+//
+// `--assert-max-time 2s` lets every part run to the end, then fails the
+// process if any of them took longer than the budget, listing the ones that
+// did. Days can get budgets of their own in adventcode.toml:
+//
+//     [max_time]
+//     day4 = "5s"           # both parts
+//
+//     [max_time.day2]
+//     part2 = "30s"         # until the rewrite lands
+//
+// A part's own budget beats its day's, which beats the one on the command
+// line. Budgets only count while --assert-max-time is given.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use adventcode::history;
+
+/// "2s", "500ms", "1.5m", or a bare number of seconds
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let scale = match unit.trim() {
+        "" | "s" => 1.0,
+        "ms" => 0.001,
+        "m" => 60.0,
+        _ => return Err(format!("{:?} is not a duration such as 2s, 500ms or 1.5m", text)),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .and_then(|n| Duration::try_from_secs_f64(n * scale).ok())
+        .ok_or_else(|| format!("{:?} is not a duration such as 2s, 500ms or 1.5m", text))
+}
+
+/// The budget for every part: the command line's, and any from the config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Budgets {
+    default: Duration,
+    /// [part 1, part 2] overrides by day
+    days: BTreeMap<u8, [Option<Duration>; 2]>,
+}
+
+impl Budgets {
+    pub fn new(default: Duration) -> Self {
+        Self { default, days: BTreeMap::new() }
+    }
+
+    /// `default`, with the overrides in the `[max_time]` table of the config
+    /// file at `path`; a missing file or table overrides nothing
+    pub fn load(path: &Path, default: Duration) -> Result<Self, String> {
+        let mut budgets = Self::new(default);
+        let Ok(text) = fs::read_to_string(path) else {
+            return Ok(budgets);
+        };
+        let table: toml::Table =
+            text.parse().map_err(|e: toml::de::Error| format!("{} does not parse: {}", path.display(), e.message()))?;
+        let Some(max_time) = table.get("max_time") else {
+            return Ok(budgets);
+        };
+        let bad = |key: &str, why: &str| format!("{}: [max_time] {}: {}", path.display(), key, why);
+        let max_time = max_time.as_table().ok_or_else(|| format!("{}: [max_time] must be a table", path.display()))?;
+        for (key, value) in max_time {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=25).contains(n))
+                .ok_or_else(|| bad(key, "keys must be day1 to day25"))?;
+            let duration = |value: &toml::Value| {
+                let text = value.as_str().ok_or_else(|| bad(key, "budgets are strings such as \"2s\""))?;
+                parse_duration(text).map_err(|e| bad(key, &e))
+            };
+            let parts = match value {
+                toml::Value::Table(parts) => {
+                    let mut found = [None, None];
+                    for (part, value) in parts {
+                        let i = match part.as_str() {
+                            "part1" => 0,
+                            "part2" => 1,
+                            _ => return Err(bad(key, &format!("unknown key {}, expected part1 or part2", part))),
+                        };
+                        found[i] = Some(duration(value)?);
+                    }
+                    found
+                }
+                value => [Some(duration(value)?); 2],
+            };
+            budgets.days.insert(day, parts);
+        }
+        Ok(budgets)
+    }
+
+    /// How long `day`'s part may take
+    pub fn limit(&self, day: u8, part2: bool) -> Duration {
+        self.days.get(&day).and_then(|parts| parts[usize::from(part2)]).unwrap_or(self.default)
+    }
+
+    /// The runs, as (day, part2, milliseconds), that took longer than their
+    /// budget, in the order given
+    pub fn judge(&self, runs: impl IntoIterator<Item = (u8, bool, f64)>) -> Vec<Overrun> {
+        runs.into_iter()
+            .filter_map(|(day, part2, millis)| {
+                let budget = self.limit(day, part2);
+                (millis > budget.as_secs_f64() * 1000.0).then_some(Overrun { day, part2, millis, budget })
+            })
+            .collect()
+    }
+}
+
+/// A part that took longer than its budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overrun {
+    pub day: u8,
+    pub part2: bool,
+    pub millis: f64,
+    pub budget: Duration,
+}
+
+/// "took 1.20 s, over its 1.00 s budget"
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "took {}, over its {} budget",
+            history::millis(self.millis),
+            history::millis(self.budget.as_secs_f64() * 1000.0)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_take_a_unit() {
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration(" 3 "), Ok(Duration::from_secs(3)));
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn a_parts_budget_beats_its_days_which_beats_the_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adventcode.toml");
+        assert_eq!(Budgets::load(&path, Duration::from_secs(1)), Ok(Budgets::new(Duration::from_secs(1))));
+
+        fs::write(&path, "input_dir = \"inputs\"\n\n[max_time]\nday4 = \"5s\"\n\n[max_time.day2]\npart2 = \"30s\"\n")
+            .unwrap();
+        let budgets = Budgets::load(&path, Duration::from_secs(1)).unwrap();
+        assert_eq!(budgets.limit(1, false), Duration::from_secs(1));
+        assert_eq!(budgets.limit(2, false), Duration::from_secs(1));
+        assert_eq!(budgets.limit(2, true), Duration::from_secs(30));
+        assert_eq!(budgets.limit(4, false), Duration::from_secs(5));
+        assert_eq!(budgets.limit(4, true), Duration::from_secs(5));
+
+        let runs = [(1, false, 999.0), (1, true, 1200.0), (2, true, 29_000.0), (4, false, 6_000.0), (5, true, 0.2)];
+        let over: Vec<(u8, bool, String)> =
+            budgets.judge(runs).iter().map(|o| (o.day, o.part2, o.to_string())).collect();
+        assert_eq!(
+            over,
+            [
+                (1, true, "took 1.20 s, over its 1.00 s budget".to_string()),
+                (4, false, "took 6.00 s, over its 5.00 s budget".to_string()),
+            ]
+        );
+
+        for (config, error) in [
+            ("[max_time]\nday26 = \"1s\"\n", "day26: keys must be day1 to day25"),
+            ("[max_time]\nday2 = 5\n", "day2: budgets are strings"),
+            ("[max_time.day2]\npart3 = \"1s\"\n", "day2: unknown key part3"),
+            ("[max_time]\nday2 = \"soon\"\n", "day2: \"soon\" is not a duration"),
+        ] {
+            fs::write(&path, config).unwrap();
+            let e = Budgets::load(&path, Duration::from_secs(1)).unwrap_err();
+            assert!(e.contains(error), "{}", e);
+        }
+    }
+}
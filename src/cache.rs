@@ -0,0 +1,199 @@
+// Answer cache
+//
+// Remembers answers keyed by day, part, the SHA-256 of the input and the
+// crate version, in a JSON file under the data directory. With `--cached` an
+// unchanged input is answered without solving; editing the input or bumping
+// the version always misses.
+//
+// Parallel --all jobs each load the file and save their own answers to it,
+// so saving re-reads it first and keeps what the others saved meanwhile.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// One cached answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// The answer as printed, e.g. "Password: 984"
+    pub answer: String,
+    /// Just its value, e.g. "984"
+    pub value: String,
+    /// As printed with --pretty, e.g. "Password: 5,657"; missing from
+    /// entries recorded before --pretty existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pretty: Option<String>,
+    /// How long the solve took when it was recorded
+    pub millis: f64,
+}
+
+/// The cache's file name in the data directory
+pub const FILE: &str = "cache.json";
+
+/// Where adventcode keeps its files: `$ADVENTCODE_DATA_DIR`, else
+/// adventcode/ under the platform's data directory
+pub fn data_dir() -> Option<PathBuf> {
+    match std::env::var_os("ADVENTCODE_DATA_DIR") {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => Some(dirs::data_dir()?.join("adventcode")),
+    }
+}
+
+/// The SHA-256 of an input, in hex
+pub fn input_hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The cache file's contents, plus where it lives
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    /// Version stamped into every key; a different version never hits
+    version: String,
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Cache {
+    /// `$ADVENTCODE_DATA_DIR/cache.json`, else `<data dir>/adventcode/cache.json`
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join(FILE))
+    }
+
+    /// Load the cache at `path`; a missing or unreadable file is an empty cache
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            entries,
+        }
+    }
+
+    /// Use a different version stamp (for tests)
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// The answer recorded for this day, part and exact input, if any
+    pub fn get(&self, day: u8, part2: bool, strict: bool, input: &str) -> Option<&Entry> {
+        self.entries.get(&self.key(day, part2, strict, input))
+    }
+
+    pub fn insert(&mut self, day: u8, part2: bool, strict: bool, input: &str, entry: Entry) {
+        let key = self.key(day, part2, strict, input);
+        self.entries.insert(key, entry);
+    }
+
+    /// Write the cache back, creating the data directory if needed; entries
+    /// saved to the file since it was loaded are kept
+    pub fn save(&self) -> io::Result<()> {
+        // One save at a time, so none writes over another's fresh entries
+        static SAVING: Mutex<()> = Mutex::new(());
+        let _saving = SAVING.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut entries = Self::load(&self.path).entries;
+        entries.extend(self.entries.iter().map(|(key, entry)| (key.clone(), entry.clone())));
+        let json = serde_json::to_string_pretty(&entries).map_err(io::Error::other)?;
+        fs::write(&self.path, json)
+    }
+
+    /// Delete the cache file; Ok(false) when there was nothing to delete
+    pub fn clear(path: &Path) -> io::Result<bool> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// e.g. "day5-part2-strict-<sha256>-v0.1.0"
+    /// `--strict` is part of the key so a lenient answer never hides a strict error
+    fn key(&self, day: u8, part2: bool, strict: bool, input: &str) -> String {
+        let hash = input_hash(input);
+        let mode = if strict { "strict" } else { "lenient" };
+        format!("day{}-part{}-{}-{}-v{}", day, if part2 { 2 } else { 1 }, mode, hash, self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(answer: &str) -> Entry {
+        Entry { answer: answer.to_string(), value: answer.to_string(), pretty: None, millis: 1.5 }
+    }
+
+    #[test]
+    fn saved_answers_hit_after_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("cache.json");
+
+        let mut cache = Cache::load(&path);
+        assert_eq!(cache.get(1, false, false, "L68\n"), None);
+        cache.insert(1, false, false, "L68\n", entry("Password: 0"));
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path);
+        assert_eq!(cache.get(1, false, false, "L68\n"), Some(&entry("Password: 0")));
+        assert_eq!(cache.get(1, true, false, "L68\n"), None);
+        assert_eq!(cache.get(1, false, true, "L68\n"), None);
+    }
+
+    #[test]
+    fn saving_keeps_what_others_saved_meanwhile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let (mut day1, mut day5) = (Cache::load(&path), Cache::load(&path));
+        day1.insert(1, false, false, "L68\n", entry("Password: 0"));
+        day5.insert(5, false, false, "3-5\n\n4\n", entry("1"));
+        day1.save().unwrap();
+        day5.save().unwrap();
+
+        let cache = Cache::load(&path);
+        assert!(cache.get(1, false, false, "L68\n").is_some());
+        assert!(cache.get(5, false, false, "3-5\n\n4\n").is_some());
+    }
+
+    #[test]
+    fn edited_input_misses() {
+        let mut cache = Cache::load(Path::new("unused.json"));
+        cache.insert(5, true, false, "3-5\n", entry("3"));
+        assert_eq!(cache.get(5, true, false, "3-6\n"), None);
+    }
+
+    #[test]
+    fn version_bump_invalidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let mut cache = Cache::load(&path).with_version("0.1.0");
+        cache.insert(2, false, false, "11-22", entry("33"));
+        cache.save().unwrap();
+
+        assert!(Cache::load(&path).with_version("0.1.0").get(2, false, false, "11-22").is_some());
+        assert!(Cache::load(&path).with_version("0.2.0").get(2, false, false, "11-22").is_none());
+    }
+
+    #[test]
+    fn clear_removes_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        Cache::load(&path).save().unwrap();
+
+        assert!(Cache::clear(&path).unwrap());
+        assert!(!Cache::clear(&path).unwrap());
+    }
+}
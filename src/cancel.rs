@@ -0,0 +1,32 @@
+// Cancelling a solve
+//
+// A Cancel is a flag shared between a solve and whoever may want it to stop
+// early (`--timeout`'s watchdog). It travels in DayOptions. Solvers with
+// loops that can run for a long time look at it between chunks of work and
+// give up with Error::Cancelled, so a cancelled solve ends instead of
+// running on in the background. Solvers that finish quickly ignore it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::Error;
+
+/// A cancellation flag; clones share it
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    /// Ask the solve to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Error::Cancelled once the solve has been asked to stop
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() { Err(Error::Cancelled) } else { Ok(()) }
+    }
+}
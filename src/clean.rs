@@ -0,0 +1,187 @@
+// `adventcode clean`
+//
+// Deletes what adventcode keeps for itself under the data directory: the
+// answer cache, the run history, saved TUI state and fetched inputs. Every
+// path must resolve inside the data or config directory before anything is
+// removed, so a symlink or a stray setting can never aim the command at the
+// user's own files; one refusal stops the whole clean. --dry-run reports the
+// same lines without deleting.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use adventcode::{cache, history};
+
+/// Something `clean` can delete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Cache,
+    History,
+    State,
+    Fetched,
+}
+
+impl Target {
+    pub const ALL: [Target; 4] = [Target::Cache, Target::History, Target::State, Target::Fetched];
+
+    /// Where it lives under `data_dir`
+    pub fn path(self, data_dir: &Path) -> PathBuf {
+        data_dir.join(match self {
+            Target::Cache => cache::FILE,
+            Target::History => history::FILE,
+            Target::State => "state.json",
+            Target::Fetched => "inputs",
+        })
+    }
+}
+
+/// A target that resolves outside the directories clean may touch
+#[derive(Debug)]
+pub struct Outside {
+    pub path: PathBuf,
+    pub resolved: PathBuf,
+    pub allowed: Vec<PathBuf>,
+}
+
+impl fmt::Display for Outside {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let allowed: Vec<String> = self.allowed.iter().map(|dir| dir.display().to_string()).collect();
+        write!(
+            f,
+            "refusing to delete {}: it resolves to {}, outside adventcode's own directories ({}); nothing was deleted",
+            self.path.display(),
+            self.resolved.display(),
+            allowed.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for Outside {}
+
+/// Delete `targets` under `data_dir`, reporting each path and its size to
+/// `out`; with `dry_run` only report
+///
+/// Every existing target is checked against `allowed` first, so a refusal
+/// leaves everything in place. Returns the total number of bytes.
+pub fn run(
+    targets: &[Target],
+    data_dir: &Path,
+    allowed: &[&Path],
+    dry_run: bool,
+    out: &mut dyn Write,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let allowed: Vec<PathBuf> = allowed.iter().filter_map(|dir| fs::canonicalize(dir).ok()).collect();
+
+    let mut found = Vec::new();
+    for target in targets {
+        let path = target.path(data_dir);
+        if fs::symlink_metadata(&path).is_err() {
+            continue;
+        }
+        let resolved = fs::canonicalize(&path)?;
+        if !allowed.iter().any(|dir| resolved.starts_with(dir)) {
+            return Err(Outside { path, resolved, allowed }.into());
+        }
+        let bytes = size(&resolved)?;
+        found.push((path, bytes));
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for (path, bytes) in &found {
+        if !dry_run {
+            if path.is_dir() {
+                fs::remove_dir_all(path)?;
+            } else {
+                fs::remove_file(path)?;
+            }
+        }
+        writeln!(out, "{} {} ({} bytes)", verb, path.display(), bytes)?;
+    }
+
+    let total = found.iter().map(|(_, bytes)| bytes).sum();
+    if found.is_empty() {
+        writeln!(out, "Nothing to clean in {}", data_dir.display())?;
+    } else {
+        writeln!(out, "{} {} item(s), {} bytes in total", verb, found.len(), total)?;
+    }
+    Ok(total)
+}
+
+/// Bytes in a file, or in everything under a directory
+fn size(path: &Path) -> io::Result<u64> {
+    let meta = fs::symlink_metadata(path)?;
+    if !meta.is_dir() {
+        return Ok(meta.len());
+    }
+    fs::read_dir(path)?.try_fold(0, |total, entry| Ok(total + size(&entry?.path())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A data directory holding a cache, a history and two fetched inputs
+    fn data_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("cache.json"), "{}").unwrap();
+        fs::write(dir.path().join("history.jsonl"), "0123456789\n").unwrap();
+        fs::create_dir(dir.path().join("inputs")).unwrap();
+        fs::write(dir.path().join("inputs/day1.txt"), "L68\n").unwrap();
+        fs::write(dir.path().join("inputs/day2.txt"), "11-22\n").unwrap();
+        dir
+    }
+
+    fn clean(dir: &Path, targets: &[Target], dry_run: bool) -> (u64, String) {
+        let mut out = Vec::new();
+        let total = run(targets, dir, &[dir], dry_run, &mut out).unwrap();
+        (total, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn selectors_delete_only_their_targets() {
+        let dir = data_dir();
+        let (total, out) = clean(dir.path(), &[Target::History, Target::Fetched], false);
+        assert_eq!(total, 11 + 4 + 6);
+        assert_eq!(out.lines().count(), 3);
+        assert!(out.contains("history.jsonl (11 bytes)"), "{}", out);
+        assert!(out.ends_with("Removed 2 item(s), 21 bytes in total\n"), "{}", out);
+        assert!(dir.path().join("cache.json").exists());
+        assert!(!dir.path().join("history.jsonl").exists());
+        assert!(!dir.path().join("inputs").exists());
+
+        // Already gone, and never created: nothing to report
+        let (total, out) = clean(dir.path(), &[Target::History, Target::State], false);
+        assert_eq!(total, 0);
+        assert!(out.starts_with("Nothing to clean"));
+    }
+
+    #[test]
+    fn dry_run_reports_without_deleting() {
+        let dir = data_dir();
+        let (total, out) = clean(dir.path(), &Target::ALL, true);
+        assert_eq!(total, 2 + 11 + 10);
+        assert!(out.contains("Would remove "), "{}", out);
+        assert!(!out.contains("Removed"));
+        assert!(dir.path().join("cache.json").exists());
+        assert!(dir.path().join("inputs/day2.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_targets_that_resolve_elsewhere() {
+        let dir = data_dir();
+        let elsewhere = tempfile::tempdir().unwrap();
+        fs::write(elsewhere.path().join("precious.txt"), "keep me").unwrap();
+        fs::remove_dir_all(dir.path().join("inputs")).unwrap();
+        std::os::unix::fs::symlink(elsewhere.path(), dir.path().join("inputs")).unwrap();
+
+        let mut out = Vec::new();
+        let err = run(&Target::ALL, dir.path(), &[dir.path()], false, &mut out).unwrap_err();
+        assert!(err.to_string().starts_with("refusing to delete"), "{}", err);
+        assert!(elsewhere.path().join("precious.txt").exists());
+        // The check comes first, so the cache survives too
+        assert!(dir.path().join("cache.json").exists());
+    }
+}
@@ -0,0 +1,115 @@
+// Inputs from the clipboard (--paste)
+//
+// `--paste` takes the day's input from the system clipboard, so copying it
+// in the browser is all the downloading there is; `--save-input` also
+// writes it to dayN.txt for the runs after. The clipboard is read by the
+// platform's own tool: pbpaste on macOS, PowerShell's Get-Clipboard on
+// Windows, and wl-paste, xclip or xsel (the first one installed) elsewhere.
+// ADVENTCODE_PASTE_COMMAND names another command whose stdout is the
+// clipboard. An empty clipboard, or one that holds no text, is an error.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Overrides the command the clipboard is read with
+pub const COMMAND_VAR: &str = "ADVENTCODE_PASTE_COMMAND";
+
+/// Something to paste from; tests substitute a fixed text
+pub trait Clipboard {
+    /// The clipboard's contents, as bytes
+    fn read(&self) -> Result<Vec<u8>, String>;
+}
+
+/// The desktop's clipboard, through its command-line tools
+pub struct System;
+
+impl System {
+    /// The commands to try, in order
+    fn commands() -> Vec<Vec<String>> {
+        if let Ok(command) = env::var(COMMAND_VAR) {
+            return vec![shlex::split(&command).unwrap_or_default()];
+        }
+        let commands: &[&[&str]] = if cfg!(target_os = "macos") {
+            &[&["pbpaste"]]
+        } else if cfg!(windows) {
+            &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]]
+        } else {
+            &[&["wl-paste", "--no-newline"], &["xclip", "-selection", "clipboard", "-o"], &["xsel", "--clipboard", "--output"]]
+        };
+        commands.iter().map(|words| words.iter().map(|word| word.to_string()).collect()).collect()
+    }
+}
+
+impl Clipboard for System {
+    fn read(&self) -> Result<Vec<u8>, String> {
+        let mut tried = Vec::new();
+        for words in Self::commands() {
+            let Some((program, args)) = words.split_first() else {
+                return Err(format!("{} is empty", COMMAND_VAR));
+            };
+            let output = match Command::new(program).args(args).stdin(Stdio::null()).output() {
+                Ok(output) => output,
+                // Not installed: try the next tool
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    tried.push(program.clone());
+                    continue;
+                }
+                Err(e) => return Err(format!("could not run {}: {}", program, e)),
+            };
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("{} failed ({}): {}", program, output.status, stderr.trim()));
+            }
+            return Ok(output.stdout);
+        }
+        Err(format!("no clipboard tool found (tried {}); set {} to one", tried.join(", "), COMMAND_VAR))
+    }
+}
+
+/// The clipboard's text, saved to `save_to` too when given
+pub fn paste(clipboard: &dyn Clipboard, save_to: Option<&Path>) -> Result<String, String> {
+    let bytes = clipboard.read().map_err(|e| format!("Could not read the clipboard: {}", e))?;
+    let text = String::from_utf8(bytes).map_err(|e| {
+        format!("The clipboard does not hold text (invalid UTF-8 at byte {})", e.utf8_error().valid_up_to())
+    })?;
+    if text.trim().is_empty() {
+        return Err("The clipboard is empty; copy the puzzle input first".into());
+    }
+    if let Some(path) = save_to {
+        fs::write(path, &text).map_err(|e| format!("Could not save the clipboard to {}: {}", path.display(), e))?;
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clipboard holding fixed bytes
+    struct Fixed(&'static [u8]);
+
+    impl Clipboard for Fixed {
+        fn read(&self) -> Result<Vec<u8>, String> {
+            Ok(self.0.to_vec())
+        }
+    }
+
+    #[test]
+    fn pastes_text_and_saves_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day5.txt");
+        assert_eq!(paste(&Fixed(b"3-5\n\n4\n"), None).unwrap(), "3-5\n\n4\n");
+        assert!(!path.exists());
+        assert_eq!(paste(&Fixed(b"3-5\n\n4\n"), Some(&path)).unwrap(), "3-5\n\n4\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3-5\n\n4\n");
+
+        assert!(paste(&Fixed(b" \n"), Some(&path)).unwrap_err().contains("clipboard is empty"));
+        let e = paste(&Fixed(b"3-5\n\xff\xfe"), Some(&path)).unwrap_err();
+        assert_eq!(e, "The clipboard does not hold text (invalid UTF-8 at byte 4)");
+        // Neither failure touches the saved file
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3-5\n\n4\n");
+    }
+}
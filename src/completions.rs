@@ -0,0 +1,96 @@
+// `adventcode completions SHELL`
+//
+// clap_complete writes the script for the flags and subcommands; what it
+// cannot know is which days there are, so each shell's script gets a hook
+// that asks `adventcode list` when --day is being completed (for run,
+// bench and notes; `new --day` is a day that does not exist yet):
+//
+//   bash  _adventcode_with_days answers after -d/--day and hands everything
+//         else to the generated _adventcode
+//   zsh   the --day specs complete with _adventcode_days, which describes
+//         each day by its title
+//   fish  the --day lines take their values from __fish_adventcode_days
+
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+
+/// The shells there are completion scripts for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const BASH_DAYS: &str = r#"
+_adventcode_with_days() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ "$prev" == "-d" || "$prev" == "--day" ]] && [[ "${COMP_WORDS[1]}" != "new" ]]; then
+        COMPREPLY=( $(compgen -W "$(adventcode list 2>/dev/null | cut -f1)" -- "$cur") )
+        return 0
+    fi
+    _adventcode "$@"
+}
+"#;
+
+const ZSH_DAYS: &str = r#"
+(( $+functions[_adventcode_days] )) ||
+_adventcode_days() {
+    local -a days
+    days=( ${(f)"$(adventcode list 2>/dev/null | cut -f1,2 | sed 's/:/\\:/g' | tr '\t' ':')"} )
+    _describe -t days 'day' days
+}
+"#;
+
+const FISH_DAYS: &str = "\
+function __fish_adventcode_days
+    adventcode list 2>/dev/null | cut -f1,2
+end
+";
+
+/// Write `shell`'s completion script for `cmd` to `out`
+pub fn generate(shell: Shell, cmd: &mut clap::Command, out: &mut dyn Write) -> io::Result<()> {
+    let mut script = Vec::new();
+    let generator = match shell {
+        Shell::Bash => clap_complete::Shell::Bash,
+        Shell::Zsh => clap_complete::Shell::Zsh,
+        Shell::Fish => clap_complete::Shell::Fish,
+    };
+    clap_complete::generate(generator, cmd, "adventcode", &mut script);
+    let script = String::from_utf8(script).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    out.write_all(with_days(shell, &script).as_bytes())
+}
+
+/// The generated `script` with the hook that completes --day
+fn with_days(shell: Shell, script: &str) -> String {
+    match shell {
+        Shell::Bash => {
+            // Defined before the `complete` lines at the end, which now name it
+            let at = script.rfind("\nif [[ \"${BASH_VERSINFO").unwrap_or(script.len());
+            let (body, tail) = script.split_at(at);
+            format!("{}{}{}", body, BASH_DAYS, tail.replace("complete -F _adventcode ", "complete -F _adventcode_with_days "))
+        }
+        Shell::Zsh => {
+            // After `#compdef`, which has to stay the first line
+            let script = script.replace(":DAY:_default'", ":DAY:_adventcode_days'");
+            let at = script.find('\n').map_or(0, |i| i + 1);
+            format!("{}{}{}", &script[..at], ZSH_DAYS, &script[at..])
+        }
+        Shell::Fish => {
+            let lines = script.lines().map(|line| {
+                if line.contains(" -s d -l day ") && line.ends_with(" -r") {
+                    format!("{} -f -a \"(__fish_adventcode_days)\"", line)
+                } else {
+                    line.to_string()
+                }
+            });
+            let mut out = FISH_DAYS.to_string();
+            for line in lines {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            out
+        }
+    }
+}
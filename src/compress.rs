@@ -0,0 +1,164 @@
+// Compressed inputs
+//
+// Archived puzzle inputs can stay compressed: files ending in .gz or .zst,
+// and stdin that starts with the gzip or zstd magic bytes, are decompressed
+// on the fly (with the default `compression` feature). Decoder failures are
+// reported as a CorruptArchive naming the file, never a bare "corrupt
+// deflate stream".
+
+use std::fmt;
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
+
+/// A compression format we can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 2] = [Compression::Gzip, Compression::Zstd];
+
+    /// The format implied by a file's extension ("day4.txt.gz" → Gzip)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        Self::ALL.into_iter().find(|format| ext.eq_ignore_ascii_case(format.extension()))
+    }
+
+    /// The format whose magic bytes start `bytes`, for stdin
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| bytes.starts_with(format.magic()))
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    fn magic(self) -> &'static [u8] {
+        match self {
+            Compression::Gzip => &[0x1f, 0x8b],
+            Compression::Zstd => &[0x28, 0xb5, 0x2f, 0xfd],
+        }
+    }
+}
+
+/// A decoder failure, naming the archive it came from
+#[derive(Debug)]
+pub struct CorruptArchive {
+    /// The compressed file, or None for stdin
+    pub path: Option<PathBuf>,
+    pub format: Compression,
+    pub error: io::Error,
+}
+
+impl fmt::Display for CorruptArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "'{}' is not a valid {} archive: {}", path.display(), self.format.name(), self.error),
+            None => write!(f, "stdin is not a valid {} archive: {}", self.format.name(), self.error),
+        }
+    }
+}
+
+impl std::error::Error for CorruptArchive {}
+
+/// The CorruptArchive inside an io::Error from a decoder, if that is what it is
+pub fn corrupt_archive(e: &io::Error) -> Option<&CorruptArchive> {
+    e.get_ref()?.downcast_ref()
+}
+
+/// Wrap `reader` so it yields decompressed bytes
+/// Errors it returns carry a CorruptArchive naming `path` (None for stdin)
+pub fn decoder<'a>(format: Compression, reader: impl BufRead + 'a, path: Option<&Path>) -> io::Result<Box<dyn Read + 'a>> {
+    let inner = open(format, reader)?;
+    Ok(Box::new(Annotated { inner, path: path.map(Path::to_path_buf), format }))
+}
+
+#[cfg(feature = "compression")]
+fn open<'a>(format: Compression, reader: impl BufRead + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    Ok(match format {
+        // MultiGz so concatenated members (cat a.gz b.gz) read as one stream
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+    })
+}
+
+#[cfg(not(feature = "compression"))]
+fn open<'a>(format: Compression, _reader: impl BufRead + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("reading {} input needs the `compression` feature", format.name()),
+    ))
+}
+
+/// Turns decoder errors into CorruptArchive errors
+struct Annotated<'a> {
+    inner: Box<dyn Read + 'a>,
+    path: Option<PathBuf>,
+    format: Compression,
+}
+
+impl Read for Annotated<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf).map_err(|error| {
+            if error.kind() == io::ErrorKind::Interrupted {
+                return error;
+            }
+            let corrupt = CorruptArchive { path: self.path.clone(), format: self.format, error };
+            io::Error::new(io::ErrorKind::InvalidData, corrupt)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const TEXT: &str = "3-5\n10-14\n\n1\n5\n";
+
+    fn decompress(format: Compression, bytes: &[u8]) -> io::Result<String> {
+        let mut text = String::new();
+        decoder(format, bytes, Some(Path::new("day5.txt.gz")))?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    #[test]
+    fn round_trips_both_formats() {
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(TEXT.as_bytes()).unwrap();
+        let gz = gz.finish().unwrap();
+        let zst = zstd::encode_all(TEXT.as_bytes(), 0).unwrap();
+
+        assert_eq!(Compression::sniff(&gz), Some(Compression::Gzip));
+        assert_eq!(Compression::sniff(&zst), Some(Compression::Zstd));
+        assert_eq!(Compression::sniff(TEXT.as_bytes()), None);
+        assert_eq!(decompress(Compression::Gzip, &gz).unwrap(), TEXT);
+        assert_eq!(decompress(Compression::Zstd, &zst).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn corrupt_archives_name_the_file() {
+        let e = decompress(Compression::Gzip, b"\x1f\x8bnot really gzip").unwrap_err();
+        let corrupt = corrupt_archive(&e).expect("a CorruptArchive");
+        assert!(corrupt.to_string().starts_with("'day5.txt.gz' is not a valid gzip archive"));
+    }
+
+    #[test]
+    fn formats_from_extensions() {
+        assert_eq!(Compression::from_path(Path::new("day4.txt.gz")), Some(Compression::Gzip));
+        assert_eq!(Compression::from_path(Path::new("day4.txt.ZST")), Some(Compression::Zstd));
+        assert_eq!(Compression::from_path(Path::new("day4.txt")), None);
+    }
+}
@@ -0,0 +1,274 @@
+// The plain settings in adventcode.toml
+//
+// Beside its tables ([external], [max_time], [input_url]), which the modules
+// that use them read for themselves, the config file holds a few top-level
+// settings:
+//
+//     input_dir = "inputs"       # where puzzle inputs live
+//     answers = "answers.toml"   # known answers
+//     theme = "light"            # the TUI's colors (theme.rs)
+//     keys = "emacs"             # the TUI's key bindings (settings.rs)
+//     default_part = 2           # the part the TUI selects first
+//     notify_after = 30          # seconds before --notify speaks up
+//     accessible = true          # words for symbols, as --accessible
+//     quiet = true               # bare answers, as --quiet
+//     time = true                # timings on stderr, as --time
+//     session = "53616c..."      # the adventofcode.com session token
+//
+// The same settings can also go in a user config, config.toml in the config
+// directory beside the session file, for defaults shared by every checkout.
+// Each setting comes from the command line, else adventcode.toml, else the
+// user config; --no-config leaves both files out.
+//
+// The TUI's settings screen edits adventcode.toml; saving goes through
+// toml_edit, so the file's comments, layout and tables survive.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The user config's file name in the config directory
+pub const USER_FILE: &str = "config.toml";
+
+/// The top-level settings; None where the file leaves one out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub input_dir: Option<String>,
+    pub answers: Option<String>,
+    pub theme: Option<String>,
+    pub keys: Option<String>,
+    pub default_part: Option<u8>,
+    pub notify_after: Option<f64>,
+    pub accessible: Option<bool>,
+    pub quiet: Option<bool>,
+    pub time: Option<bool>,
+    pub session: Option<String>,
+}
+
+/// Settings the command line gave for this run, which beat the file's
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Overrides {
+    /// --part 1 or 2
+    pub default_part: Option<u8>,
+    pub notify_after: Option<f64>,
+    /// --accessible
+    pub accessible: bool,
+}
+
+impl Config {
+    /// The settings in the config file at `path`; a missing file sets
+    /// nothing, one that does not parse or has a value of the wrong type is
+    /// an error
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Ok(Self::default());
+        };
+        let table: toml::Table =
+            text.parse().map_err(|e: toml::de::Error| format!("{} does not parse: {}", path.display(), e.message()))?;
+        let bad = |key: &str, why: &str| format!("{}: {} {}", path.display(), key, why);
+        let string = |key: &str| match table.get(key) {
+            None => Ok(None),
+            Some(value) => value.as_str().map(|s| Some(s.to_string())).ok_or_else(|| bad(key, "must be a string")),
+        };
+        let default_part = match table.get("default_part") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_integer()
+                    .filter(|part| matches!(part, 1 | 2))
+                    .map(|part| part as u8)
+                    .ok_or_else(|| bad("default_part", "must be 1 or 2"))?,
+            ),
+        };
+        let notify_after = match table.get("notify_after") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_float()
+                    .or_else(|| value.as_integer().map(|secs| secs as f64))
+                    .filter(|secs| *secs >= 0.0)
+                    .ok_or_else(|| bad("notify_after", "must be a number of seconds"))?,
+            ),
+        };
+        let boolean = |key: &str| match table.get(key) {
+            None => Ok(None),
+            Some(value) => value.as_bool().map(Some).ok_or_else(|| bad(key, "must be true or false")),
+        };
+        Ok(Self {
+            input_dir: string("input_dir")?,
+            answers: string("answers")?,
+            theme: string("theme")?,
+            keys: string("keys")?,
+            default_part,
+            notify_after,
+            accessible: boolean("accessible")?,
+            quiet: boolean("quiet")?,
+            time: boolean("time")?,
+            session: string("session")?,
+        })
+    }
+
+    /// The settings of adventcode.toml at `local`, with the user config at
+    /// `user` filling in what it leaves out
+    pub fn layered(local: &Path, user: Option<&Path>) -> Result<Self, String> {
+        let user = user.map(Self::load).transpose()?.unwrap_or_default();
+        Ok(Self::load(local)?.or(user))
+    }
+
+    /// These settings, with `under`'s where these have none
+    pub fn or(self, under: Self) -> Self {
+        Self {
+            input_dir: self.input_dir.or(under.input_dir),
+            answers: self.answers.or(under.answers),
+            theme: self.theme.or(under.theme),
+            keys: self.keys.or(under.keys),
+            default_part: self.default_part.or(under.default_part),
+            notify_after: self.notify_after.or(under.notify_after),
+            accessible: self.accessible.or(under.accessible),
+            quiet: self.quiet.or(under.quiet),
+            time: self.time.or(under.time),
+            session: self.session.or(under.session),
+        }
+    }
+
+    /// Write the settings into the config file at `path`, creating it if
+    /// needed; a setting that is None is taken out of the file, and
+    /// everything else in it is kept as it was
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))] // only the TUI's settings screen saves
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("cannot read {}: {}", path.display(), e)),
+        };
+        let mut doc: toml_edit::DocumentMut =
+            text.parse().map_err(|e: toml_edit::TomlError| format!("{} does not parse: {}", path.display(), e.message()))?;
+
+        let strings = [
+            ("input_dir", &self.input_dir),
+            ("answers", &self.answers),
+            ("theme", &self.theme),
+            ("keys", &self.keys),
+            ("session", &self.session),
+        ];
+        for (key, value) in strings {
+            set(&mut doc, key, value.as_deref().map(toml_edit::value));
+        }
+        set(&mut doc, "default_part", self.default_part.map(|part| toml_edit::value(i64::from(part))));
+        set(&mut doc, "notify_after", self.notify_after.map(toml_edit::value));
+        for (key, value) in [("accessible", self.accessible), ("quiet", self.quiet), ("time", self.time)] {
+            set(&mut doc, key, value.map(toml_edit::value));
+        }
+
+        fs::write(path, doc.to_string()).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+}
+
+/// The config directory: $ADVENTCODE_CONFIG_DIR, or adventcode under the
+/// platform's config directory
+pub fn user_dir() -> Option<PathBuf> {
+    match std::env::var_os("ADVENTCODE_CONFIG_DIR") {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => Some(dirs::config_dir()?.join("adventcode")),
+    }
+}
+
+/// Where the user config lives: config.toml in the config directory
+pub fn user_path() -> Option<PathBuf> {
+    user_dir().map(|dir| dir.join(USER_FILE))
+}
+
+/// Put `item` under `key` at the top of `doc`, or take the key out
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+fn set(doc: &mut toml_edit::DocumentMut, key: &str, item: Option<toml_edit::Item>) {
+    match item {
+        // An unchanged value is left exactly as written (5 stays 5, not 5.0)
+        Some(toml_edit::Item::Value(value))
+            if doc.get(key).and_then(toml_edit::Item::as_value).is_some_and(|old| same(old, &value)) => {}
+        // Replacing only the value keeps the comment above the key
+        Some(toml_edit::Item::Value(value)) if doc.get(key).is_some_and(toml_edit::Item::is_value) => {
+            let decor = doc[key].as_value().map(|old| old.decor().clone());
+            doc[key] = toml_edit::Item::Value(value);
+            if let (Some(decor), Some(new)) = (decor, doc[key].as_value_mut()) {
+                *new.decor_mut() = decor;
+            }
+        }
+        Some(item) => doc[key] = item,
+        None => {
+            doc.remove(key);
+        }
+    }
+}
+
+/// Whether two plain values mean the same, whatever their spelling
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+fn same(old: &toml_edit::Value, new: &toml_edit::Value) -> bool {
+    let number = |value: &toml_edit::Value| value.as_float().or_else(|| value.as_integer().map(|n| n as f64));
+    match (old.as_str(), new.as_str()) {
+        (Some(old), Some(new)) => old == new,
+        _ if old.as_bool().is_some() => old.as_bool() == new.as_bool(),
+        _ => number(old).is_some_and(|old| number(new) == Some(old)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_load_and_save_around_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adventcode.toml");
+        assert_eq!(Config::load(&path), Ok(Config::default()));
+
+        fs::write(&path, "# Where puzzle inputs live\ninput_dir = \"inputs\"\nnotify_after = 5\n\n[external]\nday7 = \"cat\"\n")
+            .unwrap();
+        let mut config = Config::load(&path).unwrap();
+        assert_eq!(config, Config { input_dir: Some("inputs".into()), notify_after: Some(5.0), ..Config::default() });
+
+        config.input_dir = Some("puzzles".into());
+        config.theme = Some("light".into());
+        config.default_part = Some(2);
+        config.notify_after = None;
+        config.accessible = Some(true);
+        config.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# Where puzzle inputs live\ninput_dir = \"puzzles\"\n"), "{}", text);
+        assert!(text.contains("[external]\nday7 = \"cat\"\n"), "{}", text);
+        assert!(!text.contains("notify_after"));
+        assert!(text.contains("accessible = true\n"), "{}", text);
+        assert_eq!(Config::load(&path).unwrap(), config);
+
+        for (text, error) in [
+            ("theme = 3\n", "theme must be a string"),
+            ("default_part = 3\n", "default_part must be 1 or 2"),
+            ("notify_after = \"soon\"\n", "notify_after must be a number of seconds"),
+            ("accessible = \"yes\"\n", "accessible must be true or false"),
+            ("quiet = 1\n", "quiet must be true or false"),
+            ("session = true\n", "session must be a string"),
+        ] {
+            fs::write(&path, text).unwrap();
+            let e = Config::load(&path).unwrap_err();
+            assert!(e.contains(error), "{}", e);
+        }
+    }
+
+    #[test]
+    fn adventcode_toml_beats_the_user_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("adventcode.toml");
+        let user = dir.path().join("config.toml");
+        fs::write(&user, "input_dir = \"~/aoc\"\nquiet = true\ntime = true\nsession = \"abc123\"\n").unwrap();
+        fs::write(&local, "input_dir = \"inputs\"\ntime = false\n").unwrap();
+
+        let config = Config::layered(&local, Some(&user)).unwrap();
+        assert_eq!(config.input_dir.as_deref(), Some("inputs"));
+        assert_eq!((config.quiet, config.time), (Some(true), Some(false)));
+        assert_eq!(config.session.as_deref(), Some("abc123"));
+        assert_eq!(Config::layered(&local, None).unwrap().quiet, None);
+        assert_eq!(Config::layered(&dir.path().join("none.toml"), Some(&user)).unwrap(), Config::load(&user).unwrap());
+
+        fs::write(&user, "time = \"yes\"\n").unwrap();
+        let e = Config::layered(&local, Some(&user)).unwrap_err();
+        assert!(e.contains("config.toml: time must be true or false"), "{}", e);
+    }
+}
@@ -15,32 +15,213 @@
 // Example: "L49" means rotate left 49 positions
 //          "R24" means rotate right 24 positions
 //
+// SEVERAL LOCKS:
+// Blank lines split the input into independent locks, each starting fresh
+// at position 50. The answer is the total over all locks, so a single block
+// solves exactly as before; with more than one, a line like
+// "3 locks: 3 + 0 + 3 = 6" shows the split, and `-- --verbose` lists each
+// lock with the line it starts on.
+//
+// EXPORT:
+// `-- --export-path out.csv` writes the dial's path as CSV while solving:
+// one row per click in part 2, one per instruction in part 1, with columns
+// step, instruction_index, direction, position, hit_zero.
+//
 // ============================================================================
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::Diagnostics;
+use crate::error::Error;
+use crate::input::InputReader;
+use crate::math;
+use crate::options::DayOptions;
+use crate::output::{Answer, Output};
+
+/// Options day 1 understands after `--`
+pub const OPTIONS: &[&str] = &["--verbose", "--export-path <FILE>"];
+
+/// Positions on the dial, 0 through 99
+const DIAL_SIZE: u64 = 100;
+
+/// Where the dial points before the first instruction (given in problem)
+const START: u64 = 50;
+
+/// How many part 2 clicks go by between looks at the cancel flag
+const CANCEL_EVERY: u64 = 1 << 20;
+
+/// Header row of the --export-path CSV
+const EXPORT_HEADER: &str = "step,instruction_index,direction,position,hit_zero";
+
+/// One move of the dial: a single click, or a whole instruction in part 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// 1-based count of steps so far
+    pub step: u64,
+    /// 0-based index of the instruction among the valid ones
+    pub instruction_index: usize,
+    pub direction: char,
+    /// Where the dial points after this step
+    pub position: u64,
+    pub hit_zero: bool,
+}
+
+/// The dial, turned one instruction at a time
+#[derive(Debug, Clone)]
+pub struct Dial {
+    position: u64,
+    steps: u64,
+    instructions: usize,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Self { position: START, steps: 0, instructions: 0 }
+    }
+}
+
+impl Dial {
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Point back at the start for the next lock; step and instruction
+    /// numbers keep counting
+    pub fn start_new_lock(&mut self) {
+        self.position = START;
+    }
+
+    /// Every click of one instruction, in order (part 2's view)
+    ///
+    /// Left decreases the position, right increases it; wrap_mod turns -1
+    /// into 99 and 100 into 0.
+    pub fn clicks(&mut self, direction: char, distance: u32) -> impl Iterator<Item = Step> + '_ {
+        let index = self.next_instruction();
+        let delta = if direction == 'L' { -1 } else { 1 };
+        (0..distance).map(move |_| self.advance(index, direction, delta))
+    }
+
+    /// One instruction as a single jump (part 1's view)
+    pub fn turn(&mut self, direction: char, distance: u32) -> Step {
+        let index = self.next_instruction();
+        let delta = if direction == 'L' { -i64::from(distance) } else { i64::from(distance) };
+        self.advance(index, direction, delta)
+    }
+
+    fn next_instruction(&mut self) -> usize {
+        self.instructions += 1;
+        self.instructions - 1
+    }
+
+    fn advance(&mut self, instruction_index: usize, direction: char, delta: i64) -> Step {
+        // Unlike `%`, wrap_mod never goes negative:
+        //   pos = 105  →  wrap_mod(105, 100) = 5
+        //   pos = -10  →  wrap_mod(-10, 100) = 90
+        self.position = math::wrap_mod(self.position as i64 + delta, DIAL_SIZE);
+        self.steps += 1;
+        Step { step: self.steps, instruction_index, direction, position: self.position, hit_zero: self.position == 0 }
+    }
+}
+
+/// Streams steps to the --export-path CSV as they happen
+struct Export {
+    path: PathBuf,
+    file: BufWriter<File>,
+}
+
+impl Export {
+    fn create(path: &Path) -> Result<Self, Error> {
+        let file = File::create(path).map_err(|e| Error::Export(path.to_path_buf(), e))?;
+        let mut export = Self { path: path.to_path_buf(), file: BufWriter::new(file) };
+        export.write(format_args!("{}", EXPORT_HEADER))?;
+        Ok(export)
+    }
+
+    fn row(&mut self, step: &Step) -> Result<(), Error> {
+        self.write(format_args!(
+            "{},{},{},{},{}",
+            step.step, step.instruction_index, step.direction, step.position, step.hit_zero
+        ))
+    }
+
+    fn finish(mut self) -> Result<(), Error> {
+        self.file.flush().map_err(|e| Error::Export(self.path, e))
+    }
+
+    fn write(&mut self, line: std::fmt::Arguments) -> Result<(), Error> {
+        writeln!(self.file, "{}", line).map_err(|e| Error::Export(self.path.clone(), e))
+    }
+}
+
 /// Main solver for Day 1
 /// 
 /// The dial is circular with 100 positions (0-99):
 ///   ... 98 - 99 - 0 - 1 - 2 ...
 ///        ↑________|________|
 ///        (wraps around)
-pub fn solve(input: &str, part2: bool) {
-    // Start at position 50 (given in problem)
-    let mut pos: i32 = 50;
+pub fn solve(input: &str, part2: bool, opts: &DayOptions, out: &mut Output) -> Result<Answer, Error> {
+    solve_lines(input.lines().map(Ok), part2, opts, out)
+}
+
+/// Same as `solve`, reading one line at a time so memory stays flat
+pub fn solve_stream(
+    reader: &mut InputReader,
+    part2: bool,
+    opts: &DayOptions,
+    out: &mut Output,
+) -> Result<Answer, Error> {
+    solve_lines(reader.lines(), part2, opts, out)
+}
+
+/// Shared body of `solve` and `solve_stream`
+fn solve_lines<L: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<L>>,
+    part2: bool,
+    opts: &DayOptions,
+    out: &mut Output,
+) -> Result<Answer, Error> {
+    // The dial starts at position 50
+    let mut dial = Dial::default();
     
-    // Count how many times we hit position 0
-    let mut zero_hits: i32 = 0;
+    // Count how many times we hit position 0, per lock: (first line, hits)
+    let mut locks: Vec<(usize, u64)> = Vec::new();
+    let mut current: Option<(usize, u64)> = None;
+
+    // Lines we could not parse; skipped with a warning (or an error under --strict)
+    let mut diagnostics = Diagnostics::default();
+
+    // Every step goes to the CSV as it happens, so nothing piles up in memory
+    let mut export = opts.value("--export-path").map(|path| Export::create(Path::new(path))).transpose()?;
+
+    // Where the dial points after each instruction, for --emit-intermediate
+    let mut positions = opts.artifacts.as_ref().map(|_| vec![format!("start: {}", START)]);
 
     // Process each rotation instruction
-    for raw_line in input.lines() {
-        let line = raw_line.trim();
+    for (idx, raw_line) in lines.enumerate() {
+        opts.cancel.check()?;
+        let raw_line = raw_line?;
+        let line = raw_line.as_ref().trim();
         if line.is_empty() {
+            // A blank line ends the current lock; the next starts at 50 again
+            if let Some(lock) = current.take() {
+                locks.push(lock);
+                dial.start_new_lock();
+                if let Some(positions) = positions.as_mut() {
+                    positions.push(format!("line {}: new lock -> {}", idx + 1, START));
+                }
+            }
             continue;
         }
+        let (_, zero_hits) = current.get_or_insert((idx + 1, 0));
 
         // Parse instruction: first char is direction, rest is distance
-        // Example: "L49" → dir = "L", dist = 49
-        let (dir, rest) = line.split_at(1);
-        let dist: i32 = rest.parse().expect("invalid distance");
+        // Example: "L49" → dir = 'L', dist = 49
+        let Some((dir, dist)) = parse_instruction(line) else {
+            diagnostics.push(idx + 1, line, "malformed instruction, expected L or R followed by a distance");
+            continue;
+        };
 
         if part2 {
             // ================================================================
@@ -57,31 +238,17 @@ pub fn solve(input: &str, part2: bool) {
             //                    ↑
             //                 Found it!
             
-            // Simulate each individual click
-            for _ in 0..dist {
-                match dir {
-                    "L" => {
-                        // Rotate left (counter-clockwise, decrease position)
-                        pos -= 1;
-                        if pos < 0 {
-                            // Wrap around: -1 becomes 99
-                            pos = 99;
-                        }
-                    }
-                    "R" => {
-                        // Rotate right (clockwise, increase position)
-                        pos += 1;
-                        if pos > 99 {
-                            // Wrap around: 100 becomes 0
-                            pos = 0;
-                        }
-                    }
-                    _ => panic!("unknown direction: {dir}"),
+            // Simulate each individual click, checking if it landed on 0
+            for step in dial.clicks(dir, dist) {
+                // A distance of billions takes a while; --timeout may stop it
+                if step.step % CANCEL_EVERY == 0 {
+                    opts.cancel.check()?;
                 }
-                
-                // Check if this individual click landed on 0
-                if pos == 0 {
-                    zero_hits += 1;
+                if step.hit_zero {
+                    *zero_hits += 1;
+                }
+                if let Some(export) = export.as_mut() {
+                    export.row(&step)?;
                 }
             }
         } else {
@@ -96,28 +263,137 @@ pub fn solve(input: &str, part2: bool) {
             // Example: position 50, instruction "L60"
             //   50 - 60 = -10
             //   -10 % 100 = -10
-            //   (-10 + 100) % 100 = 90  ← final position
-            
-            // Calculate new position based on direction
-            match dir {
-                "L" => pos -= dist,  // Left decreases position
-                "R" => pos += dist,  // Right increases position
-                _ => panic!("unknown direction: {dir}"),
-            }
-
-            // Handle wrapping with modulo arithmetic
-            // The formula ((pos % 100) + 100) % 100 correctly handles negatives:
-            //   pos = 105  →  (105 % 100 + 100) % 100 = 5
-            //   pos = -10  →  (-10 % 100 + 100) % 100 = 90
-            pos = ((pos % 100) + 100) % 100;
+            //   wrap_mod(-10, 100) = 90  ← final position
+            let step = dial.turn(dir, dist);
 
             // Check if we ended at position 0
-            if pos == 0 {
-                zero_hits += 1;
+            if step.hit_zero {
+                *zero_hits += 1;
+            }
+            if let Some(export) = export.as_mut() {
+                export.row(&step)?;
             }
         }
+        if let Some(positions) = positions.as_mut() {
+            positions.push(format!("line {}: {} -> {}", idx + 1, line, dial.position()));
+        }
     }
 
+    if let Some(export) = export {
+        export.finish()?;
+    }
+    if let (Some(artifacts), Some(positions)) = (&opts.artifacts, positions) {
+        artifacts.write("day1_positions.txt", positions)?;
+    }
+    locks.extend(current);
+    tracing::debug!(locks = locks.len(), malformed = diagnostics.items().len(), "parsed the rotations");
+    diagnostics.report(opts.strict, out)?;
+
     // The password is the total count of times we hit position 0
-    println!("Password: {}", zero_hits);
+    let total: u64 = locks.iter().map(|(_, hits)| hits).sum();
+    if opts.flag("--verbose") {
+        for (i, (line, hits)) in locks.iter().enumerate() {
+            out.line(format!("Lock {} (from line {}): {}", i + 1, line, hits));
+        }
+    }
+    if locks.len() > 1 {
+        let counts: Vec<String> = locks.iter().map(|(_, hits)| hits.to_string()).collect();
+        out.line(format!("{} locks: {} = {}", locks.len(), counts.join(" + "), total));
+    }
+    Ok(Answer::new("Password", total))
+}
+
+/// Parse one instruction like "L49" into ('L', 49)
+/// Returns None unless the line is L or R followed by a non-negative distance
+fn parse_instruction(line: &str) -> Option<(char, u32)> {
+    let mut chars = line.chars();
+    let dir = chars.next().filter(|c| matches!(c, 'L' | 'R'))?;
+    let dist = chars.as_str().parse().ok()?;
+    Some((dir, dist))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_lines_are_skipped_with_a_warning() {
+        let input = "L68\nL\nX12\nR-5\nL30\n";
+        let mut out = Output::capture();
+        let answer = solve(input, false, &DayOptions::default(), &mut out).unwrap();
+        assert_eq!(answer.to_string(), "Password: 0");
+        assert_eq!(out.warnings().len(), 3);
+
+        let strict = DayOptions::default().with_strict(true);
+        assert!(solve(input, false, &strict, &mut Output::capture()).is_err());
+    }
+
+    #[test]
+    fn export_path_writes_one_row_per_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("path.csv");
+        let opts = DayOptions::new(vec!["--export-path".to_string(), path.display().to_string()]);
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+        let answer = solve(input, true, &opts, &mut Output::capture()).unwrap();
+        assert_eq!(answer.to_string(), "Password: 6");
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], EXPORT_HEADER);
+        assert_eq!(lines[1], "1,0,L,49,false");
+        assert_eq!(lines.last(), Some(&"462,9,L,32,false"));
+        // One row per click: the distances add up to 462
+        assert_eq!(lines.len() - 1, 462);
+        assert_eq!(lines.iter().filter(|line| line.ends_with(",true")).count(), 6);
+
+        // Part 1 has a row per instruction instead
+        solve(input, false, &opts, &mut Output::capture()).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("1,0,L,82,false"));
+        assert_eq!(csv.lines().last(), Some("10,9,L,32,false"));
+        assert_eq!(csv.lines().count(), 11);
+    }
+
+    #[test]
+    fn blank_lines_separate_independent_locks() {
+        let first = "L68\nL30\nR48\nL5\nR60\n";
+        let second = "L55\nL1\nL99\nR14\nL82\n";
+        let single = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        let opts = DayOptions::new(vec!["--verbose".to_string()]);
+        let count = |input: &str, part2: bool| {
+            let answer = solve(input, part2, &DayOptions::default(), &mut Output::capture()).unwrap();
+            answer.value.to_string().parse::<u64>().unwrap()
+        };
+
+        for part2 in [false, true] {
+            let both = format!("{}\n\n{}", first, second);
+            let mut out = Output::capture();
+            let answer = solve(&both, part2, &opts, &mut out).unwrap();
+            let (a, b) = (count(first, part2), count(second, part2));
+            assert_eq!(answer.value.to_string(), (a + b).to_string());
+            assert_eq!(
+                out.lines(),
+                [
+                    format!("Lock 1 (from line 1): {}", a),
+                    format!("Lock 2 (from line 8): {}", b),
+                    format!("2 locks: {} + {} = {}", a, b, a + b),
+                ]
+            );
+
+            // One block is today's puzzle, answered as before
+            let mut out = Output::capture();
+            let answer = solve(single, part2, &DayOptions::default(), &mut out).unwrap();
+            assert_eq!(answer.to_string(), if part2 { "Password: 6" } else { "Password: 3" });
+            assert!(out.lines().is_empty());
+        }
+    }
+
+    #[test]
+    fn a_cancelled_part_2_gives_up() {
+        let cancel = crate::cancel::Cancel::default();
+        cancel.cancel();
+        let opts = DayOptions::default().with_cancel(cancel);
+        let result = solve("R4000000000\n", true, &opts, &mut Output::capture());
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
 }
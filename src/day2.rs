@@ -13,100 +13,106 @@
 //         Examples: 111 (1 three times), 12341234 (1234 twice),
 //                   1212121212 (12 five times)
 //
+// EXAMPLE:
+// --------
+// Input:
+//   11-22,95-115,998-1012,1188511880-1188511890,222220-222224,
+//   1698522-1698528,446443-446449,38593856-38593862,565653-565659,
+//   824824821-824824827,2121212118-2121212124
+//
+// Part 1 Analysis:
+//   11-22 has two invalid IDs, 11 and 22
+//   95-115 has one, 99; 998-1012 has one, 1010
+//   Result: 1227775554
+//
+// Part 2 Analysis:
+//   95-115 now has 99 and 111; 998-1012 has 999 and 1010
+//   Result: 4174379265
+//
+// STATS (`-- --stats`):
+// ---------------------
+// Extra lines describing the input itself, leaving the answer alone:
+//   Ranges: 11 supplied, 11 after merging
+//   IDs covered: 106
+//   IDs in more than one range: 0
+// The last count comes from a sweep over the range endpoints
+// (interval::multi_covered), so huge ranges cost no more than small ones.
+//
+// PER RANGE (`-- --per-range`):
+// ------------------------------
+// One line per range as given (before merging) with its invalid IDs:
+//   95-115: 1 invalid, subtotal 99
+// and the total of the subtotals. An ID inside two overlapping ranges
+// counts in both, so that total can exceed the answer. Candidates are
+// generated per range (doubled halves in part 1, primitive repeated
+// patterns in part 2) instead of checking every ID.
+//
 // ============================================================================
 
-/// Represents an inclusive numeric range [start, end]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Range {
-    start: u64,
-    end: u64,
-}
+use crate::cancel::Cancel;
+use crate::diagnostics::Diagnostics;
+use crate::error::Error;
+use crate::interval::{self, Interval};
+use crate::math;
+use crate::options::DayOptions;
+use crate::output::{Answer, Output};
+use crate::parallel;
+
+/// Options day 2 understands after `--`
+pub const OPTIONS: &[&str] = &["--stats", "--per-range"];
 
 /// Parses comma-separated ranges in format "start-end,start-end,..."
 /// 
 /// Example input: "11-22,95-115,998-1012"
-/// Returns: Vec<Range> with parsed start and end values
-fn parse_ranges(input: &str) -> Vec<Range> {
-    let cleaned = input.replace("\n", "").replace(" ", "");
-    let mut ranges = Vec::new();
+/// Returns: the parsed ranges, plus a diagnostic for every token that is
+/// malformed or reversed (reversed ranges like "22-11" are swapped)
+/// 
+/// Whitespace and line breaks are ignored, so a range wrapped across lines
+/// still parses; diagnostics point at the line where the token starts.
+/// With `lenient` (--lenient-numbers) a `_` between digits is skipped too.
+fn parse_ranges(input: &str, lenient: bool) -> (Vec<Interval>, Diagnostics) {
+    // Split into (line number, token) pairs on commas
+    let mut tokens: Vec<(usize, String)> = Vec::new();
+    let mut current = String::new();
+    let mut line = 1;
+    let mut token_line = 1;
     
-    for part in cleaned.split(',') {
-        let part = part.trim();
-        if part.is_empty() {
-            continue;
-        }
-        
-        // Split on '-' to get start and end values
-        if let Some((a, b)) = part.split_once('-') {
-            if let (Ok(start), Ok(end)) = (a.parse::<u64>(), b.parse::<u64>()) {
-                ranges.push(Range { start, end });
+    for c in input.chars() {
+        match c {
+            ',' => tokens.push((token_line, std::mem::take(&mut current))),
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            c => {
+                if current.is_empty() {
+                    token_line = line;
+                }
+                current.push(c);
             }
         }
     }
+    tokens.push((token_line, current));
     
-    ranges
-}
-
-/// Merges overlapping and adjacent ranges to optimize lookup performance.
-/// 
-/// Example: [11-22, 20-30, 95-115] becomes [11-30, 95-115]
-/// 
-/// This reduces the number of ranges we need to check and allows for
-/// efficient binary search in the in_merged_ranges function.
-fn merge_ranges(ranges: &[Range]) -> Vec<Range> {
-    if ranges.is_empty() {
-        return Vec::new();
-    }
-    
-    // Sort ranges by start position
-    let mut sorted = ranges.to_vec();
-    sorted.sort();
-    
-    let mut merged = vec![sorted[0]];
+    let mut ranges = Vec::new();
+    let mut diagnostics = Diagnostics::default();
     
-    // Merge overlapping or adjacent ranges
-    for range in sorted.iter().skip(1) {
-        let last_idx = merged.len() - 1;
-        // If current range overlaps or is adjacent to the last merged range
-        if range.start <= merged[last_idx].end + 1 {
-            // Extend the last merged range
-            merged[last_idx].end = merged[last_idx].end.max(range.end);
-        } else {
-            // Start a new merged range
-            merged.push(*range);
+    for (line, token) in tokens {
+        if token.is_empty() {
+            continue;
         }
-    }
-    
-    merged
-}
-
-/// Checks if a number exists within any of the merged ranges using binary search.
-/// 
-/// Time complexity: O(log n) where n is the number of ranges
-/// 
-/// This is much faster than iterating through all ranges linearly,
-/// especially when there are many ranges.
-fn in_merged_ranges(x: u64, merged_ranges: &[Range]) -> bool {
-    let mut lo = 0;
-    let mut hi = merged_ranges.len();
-    
-    while lo < hi {
-        let mid = lo + (hi - lo) / 2;
-        let range = merged_ranges[mid];
         
-        if x < range.start {
-            // x is smaller, search left half
-            hi = mid;
-        } else if x > range.end {
-            // x is larger, search right half
-            lo = mid + 1;
-        } else {
-            // x is within this range
-            return true;
+        match interval::parse_range(&token, lenient) {
+            Some(parsed) => {
+                if parsed.reversed {
+                    let (start, end) = parsed.range;
+                    diagnostics.push(line, &token, format!("reversed range, treating it as {}-{}", start, end));
+                }
+                ranges.push(parsed.range);
+            }
+            None => diagnostics.push(line, &token, "malformed range, expected start-end"),
         }
     }
     
-    false
+    (ranges, diagnostics)
 }
 
 /// Checks if a number consists of a pattern repeated at least twice (Part 2).
@@ -131,7 +137,7 @@ fn is_invalid_part2(n: u64) -> bool {
     // (pattern must be repeated at least twice, so max length is len/2)
     for pattern_len in 1..=(len / 2) {
         // Only consider pattern lengths that divide evenly into total length
-        if len % pattern_len != 0 {
+        if !len.is_multiple_of(pattern_len) {
             continue;
         }
         
@@ -166,122 +172,380 @@ fn is_invalid_part2(n: u64) -> bool {
 
 /// PART 1 SOLUTION: Find sum of IDs with digit sequence repeated exactly twice
 /// 
-/// STRATEGY: Generate candidates instead of checking every number in ranges
+/// STRATEGY: Sum the candidates in closed form instead of checking numbers
 /// 
-/// Why this is efficient:
-/// - Instead of checking billions of numbers, we generate only valid candidates
-/// - For a max value of 10 billion (10 digits), we only check ~111,111 candidates
-///   (all numbers from 1-9, 10-99, 100-999, 1000-9999, 10000-99999)
+/// Why this works:
+/// - A doubled ID with a half of h digits is t × (10^h + 1): 64 × 101 = 6464
+/// - So the doubled IDs in a range are an arithmetic series in t, and
+///   their sum is (10^h + 1) × (sum of the t's), with no loop over them at all
 /// 
 /// Algorithm:
-/// 1. Find the maximum upper bound across all ranges
-/// 2. Determine max digit length needed
-/// 3. For each even total length (2, 4, 6, 8, ...):
-///    - Generate all patterns of half that length (avoiding leading zeros)
-///    - Form the doubled number by concatenating pattern with itself
-///    - Check if it falls within any range using binary search
+/// 1. For each half length h (1, 2, 3, ... up to half the digits of the
+///    largest bound), let k = 10^h + 1
+/// 2. t runs over h-digit numbers without a leading zero: 10^(h-1) to 10^h - 1
+/// 3. In each merged range [lo, hi], t is also limited to ceil(lo/k)..=hi/k
+/// 4. Add k × sum_arith_series(t_min, t_max) for the overlap
+/// 
+/// Example for h = 2, k = 101, range 1000-1500:
+/// - t runs from max(10, ceil(1000/101) = 10) to min(99, 1500/101 = 14)
+/// - IDs 1010, 1111, 1212, 1313, 1414 sum to 101 × (10 + ... + 14) = 6060
 /// 
-/// Example for length 4:
-/// - Half length = 2
-/// - Generate: 10, 11, 12, ..., 99 (all 2-digit numbers)
-/// - Create: 1010, 1111, 1212, ..., 9999
-/// - Check which ones fall in ranges
+/// The merged ranges never overlap, so no ID is counted twice.
 /// 
-/// Time complexity: O(k * log n) where k is number of candidates, n is number of ranges
-fn sum_invalid_ids(ranges_str: &str) -> u64 {
-    let ranges = parse_ranges(ranges_str);
-    if ranges.is_empty() {
-        return 0;
+/// Time complexity: O(D × n) where D is the digit count, n the number of ranges
+fn sum_invalid_ids(merged: &[Interval]) -> u128 {
+    merged.iter().map(|&range| doubled_in(range).1).sum()
+}
+
+/// Count and sum of the doubled IDs in one range, by the closed form above
+fn doubled_in((lo, hi): Interval) -> (u128, u128) {
+    let max_digits = hi.to_string().len() as u32;
+
+    // u128 so many large invalid IDs cannot overflow the total
+    let mut count = 0u128;
+    let mut invalid_sum = 0u128;
+
+    for half in 1..=max_digits / 2 {
+        // u64 has at most 20 digits, so none of this can overflow
+        let k = math::pow10(half).unwrap() + 1;
+        let first_half = math::pow10(half - 1).unwrap();
+        let last_half = math::pow10(half).unwrap() - 1;
+
+        let t_min = u128::from(lo).div_ceil(k).max(first_half);
+        let t_max = (u128::from(hi) / k).min(last_half);
+        if t_min > t_max {
+            continue;
+        }
+        // Halves fit in a u64 (at most 10 digits)
+        let (t_min, t_max) = (t_min as u64, t_max as u64);
+        let halves = math::sum_arith_series(t_min, t_max, t_max - t_min + 1).unwrap();
+        count += u128::from(t_max - t_min + 1);
+        invalid_sum += k * halves;
     }
-    
-    // Merge ranges for efficient lookup
-    let merged = merge_ranges(&ranges);
-    let max_upper = merged.iter().map(|r| r.end).max().unwrap_or(0);
-    let max_digits = max_upper.to_string().len();
-    
-    let mut invalid_sum = 0u64;
-    
-    // Only check even lengths (since we're doubling patterns)
-    for total_len in (2..=max_digits).step_by(2) {
-        let half = total_len / 2;
-        
-        // Generate all patterns with 'half' digits (no leading zeros)
-        // Example: for half=2, generate 10 to 99
-        let start = 10u64.pow((half - 1) as u32);  // First number with 'half' digits
-        let end = 10u64.pow(half as u32);           // First number with 'half+1' digits
-        
-        for t in start..end {
-            let s = t.to_string();
-            // Create the doubled pattern: "64" + "64" = "6464"
-            let doubled = format!("{}{}", s, s);
-            
-            if let Ok(num) = doubled.parse::<u64>() {
-                // Early exit: if we've exceeded max_upper, no point continuing
-                if num > max_upper {
-                    break;
+
+    (count, invalid_sum)
+}
+
+/// Count and sum of the part 2 invalid IDs in one range, by generating them
+///
+/// An ID of L digits repeating a p-digit pattern t is t × (10^L - 1) / (10^p - 1),
+/// e.g. 121212 = 12 × 10101. Taking only patterns that do not repeat
+/// themselves (12 but not 11) generates every ID exactly once: 1111 comes
+/// from "1" four times, never from "11" twice.
+///
+/// A range as wide as u64 has billions of 10-digit patterns to go through,
+/// so `cancel` is looked at every CANCEL_EVERY of them.
+fn repeated_in((lo, hi): Interval, cancel: &Cancel) -> Result<(u128, u128), Error> {
+    const CANCEL_EVERY: u128 = 1 << 16;
+
+    let (lo, hi) = (u128::from(lo), u128::from(hi));
+    let max_digits = hi.to_string().len() as u32;
+
+    let mut count = 0u128;
+    let mut invalid_sum = 0u128;
+    for digits in 2..=max_digits {
+        let (Some(id_first), Some(id_end)) = (math::pow10(digits - 1), math::pow10(digits)) else {
+            continue;
+        };
+        for pattern in (1..digits).filter(|p| digits.is_multiple_of(*p)) {
+            // 10^p - 1 divides 10^L - 1 because p divides L
+            let k = (id_end - 1) / (math::pow10(pattern).unwrap() - 1);
+            let t_min = lo.max(id_first).div_ceil(k).max(math::pow10(pattern - 1).unwrap());
+            let t_max = (hi.min(id_end - 1) / k).min(math::pow10(pattern).unwrap() - 1);
+            cancel.check()?;
+            for t in t_min..=t_max {
+                if t % CANCEL_EVERY == 0 {
+                    cancel.check()?;
                 }
-                
-                // Check if this invalid ID is in any of our ranges
-                if in_merged_ranges(num, &merged) {
-                    invalid_sum += num;
+                // Patterns fit in a u64 (at most 10 digits)
+                if !is_invalid_part2(t as u64) {
+                    count += 1;
+                    invalid_sum += k * t;
                 }
             }
         }
     }
-    
-    invalid_sum
+    Ok((count, invalid_sum))
+}
+
+/// The ranges in `input` as given, before merging; malformed tokens are
+/// left out and reversed ones swapped, as `solve` does with warnings
+pub fn ranges(input: &str) -> Vec<Interval> {
+    parse_ranges(input, false).0
+}
+
+/// Every invalid ID in `range`, in ascending order, generated one at a time
+///
+/// The same candidates as `doubled_in` (part 1) and `repeated_in` (part 2),
+/// without summing them: asking for the first few IDs of a huge range costs
+/// no more than asking for the first few of a small one.
+pub fn invalid_ids(range: Interval, part2: bool) -> InvalidIds {
+    InvalidIds {
+        lo: u128::from(range.0),
+        hi: u128::from(range.1),
+        part2,
+        digits: 1,
+        max_digits: range.1.to_string().len() as u32,
+        patterns: Vec::new(),
+    }
+}
+
+/// Iterator returned by `invalid_ids`
+#[derive(Debug, Clone)]
+pub struct InvalidIds {
+    lo: u128,
+    hi: u128,
+    part2: bool,
+    /// The digit count of the IDs `patterns` generate
+    digits: u32,
+    max_digits: u32,
+    patterns: Vec<Pattern>,
+}
+
+/// IDs k × t for t in next..=last: one pattern length at one digit count
+#[derive(Debug, Clone)]
+struct Pattern {
+    k: u128,
+    next: u128,
+    last: u128,
+    /// Skip patterns that repeat themselves (part 2), so each ID comes once
+    primitive: bool,
+}
+
+impl Pattern {
+    fn new(digits: u32, pattern: u32, lo: u128, hi: u128, primitive: bool) -> Self {
+        // u64 IDs have at most 20 digits, so none of these powers overflow
+        let (id_first, id_end) = (math::pow10(digits - 1).unwrap(), math::pow10(digits).unwrap());
+        let k = (id_end - 1) / (math::pow10(pattern).unwrap() - 1);
+        let first = lo.max(id_first).div_ceil(k).max(math::pow10(pattern - 1).unwrap());
+        let last = (hi.min(id_end - 1) / k).min(math::pow10(pattern).unwrap() - 1);
+        let mut pattern = Self { k, next: first, last, primitive };
+        pattern.skip_repeating();
+        pattern
+    }
+
+    fn peek(&self) -> Option<u128> {
+        (self.next <= self.last).then(|| self.k * self.next)
+    }
+
+    fn advance(&mut self) {
+        self.next += 1;
+        self.skip_repeating();
+    }
+
+    fn skip_repeating(&mut self) {
+        // Patterns fit in a u64 (at most 10 digits)
+        while self.primitive && self.next <= self.last && is_invalid_part2(self.next as u64) {
+            self.next += 1;
+        }
+    }
+}
+
+impl Iterator for InvalidIds {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            // The smallest next ID among this digit count's patterns
+            let smallest = self.patterns.iter_mut().filter_map(|p| Some((p.peek()?, p))).min_by_key(|(id, _)| *id);
+            if let Some((id, pattern)) = smallest {
+                pattern.advance();
+                // At most hi, which is a u64
+                return Some(id as u64);
+            }
+            self.digits += 1;
+            if self.digits > self.max_digits {
+                return None;
+            }
+            let (digits, lo, hi) = (self.digits, self.lo, self.hi);
+            self.patterns = if self.part2 {
+                (1..digits)
+                    .filter(|p| digits.is_multiple_of(*p))
+                    .map(|p| Pattern::new(digits, p, lo, hi, true))
+                    .collect()
+            } else if digits.is_multiple_of(2) {
+                vec![Pattern::new(digits, digits / 2, lo, hi, false)]
+            } else {
+                Vec::new()
+            };
+        }
+    }
+}
+
+/// The --per-range report: each range as given, then the total
+fn report_per_range(ranges: &[Interval], part2: bool, cancel: &Cancel, out: &mut Output) -> Result<(), Error> {
+    let mut total = 0u128;
+    for &(start, end) in ranges {
+        let (count, subtotal) = if part2 { repeated_in((start, end), cancel)? } else { doubled_in((start, end)) };
+        out.line(format!("{}-{}: {} invalid, subtotal {}", start, end, count, subtotal));
+        total += subtotal;
+    }
+    out.line(format!("Total of subtotals: {} (an ID in overlapping ranges counts once per range)", total));
+    Ok(())
 }
 
 /// PART 2 SOLUTION: Find sum of IDs with digit sequence repeated at least twice
 /// 
-/// STRATEGY: Check every number in the ranges
+/// STRATEGY: Generate the candidates, as part 1 does
 /// 
-/// Why we can't use the Part 1 generation approach:
 /// - Part 1 only needs patterns repeated exactly twice (even lengths only)
 /// - Part 2 needs patterns repeated 2+ times (any length divisible by pattern)
 /// - Examples: 111 (1×3), 1212 (12×2), 123123123 (123×3)
-/// - It's harder to efficiently generate all possible combinations
+/// - `repeated_in` walks the patterns of each length instead of the IDs,
+///   so a range costs about the square root of its width
 /// 
-/// Algorithm:
-/// 1. Parse and merge the ranges
-/// 2. Iterate through every number in every range
-/// 3. For each number, check if it's a repeated pattern (using is_invalid_part2)
-/// 4. Sum up all invalid IDs found
-/// 
-/// Trade-off:
-/// - This is slower than Part 1 (brute force vs generation)
-/// - But it's simpler and handles all repetition counts
-/// - For typical AoC inputs, performance is still acceptable
-/// 
-/// Time complexity: O(R * D²) where R is total range size, D is digits per number
-fn sum_invalid_ids_part2(ranges_str: &str) -> u64 {
-    let ranges = parse_ranges(ranges_str);
-    if ranges.is_empty() {
-        return 0;
-    }
+/// The merged ranges never overlap, so no ID is counted twice. They are
+/// independent and worked on in parallel; once `cancel` is set the sum
+/// becomes Error::Cancelled.
+fn sum_invalid_ids_part2(merged: &[Interval], cancel: &Cancel) -> Result<u128, Error> {
+    let sums = parallel::map(merged, |&range| repeated_in(range, cancel));
+    sums.into_iter().map(|sum| sum.map(|(_, invalid_sum)| invalid_sum)).sum()
+}
+
+/// Main entry point for Day 2 solution
+pub fn solve(input: &str, part2: bool, opts: &DayOptions, out: &mut Output) -> Result<Answer, Error> {
+    let (ranges, diagnostics) = tracing::info_span!("parse").in_scope(|| parse_ranges(input, opts.lenient_numbers));
+    tracing::debug!(ranges = ranges.len(), malformed = diagnostics.items().len(), "parsed the ID ranges");
     
-    // Merge ranges to avoid checking duplicates
-    let merged = merge_ranges(&ranges);
-    let mut invalid_sum = 0u64;
+    // Malformed or reversed ranges are warnings by default, errors under --strict
+    diagnostics.report(opts.strict, out)?;
     
-    // Check every number in every range
-    for range in &merged {
-        for num in range.start..=range.end {
-            if is_invalid_part2(num) {
-                invalid_sum += num;
-            }
-        }
+    // Merge ranges for efficient lookup and to avoid checking duplicates
+    let merged = interval::merge(&ranges);
+    tracing::debug!(merged = merged.len(), "merged the overlapping ranges");
+
+    if let Some(artifacts) = &opts.artifacts {
+        artifacts.write("day2_ranges.txt", ranges.iter().map(|(start, end)| format!("{}-{}", start, end)))?;
+        artifacts.write("day2_merged.txt", merged.iter().map(|(start, end)| format!("{}-{}", start, end)))?;
+    }
+
+    if opts.flag("--stats") {
+        out.line(format!("Ranges: {} supplied, {} after merging", ranges.len(), merged.len()));
+        out.line(format!("IDs covered: {}", interval::total_len(&merged)));
+        out.line(format!("IDs in more than one range: {}", interval::multi_covered(&ranges)));
     }
     
-    invalid_sum
-}
+    if opts.flag("--per-range") {
+        report_per_range(&ranges, part2, &opts.cancel, out)?;
+    }
 
-/// Main entry point for Day 2 solution
-pub fn solve(input: &str, part2: bool) {
     let result = if part2 {
-        sum_invalid_ids_part2(input)
+        sum_invalid_ids_part2(&merged, &opts.cancel)?
     } else {
-        sum_invalid_ids(input)
+        sum_invalid_ids(&merged)
     };
-    println!("Sum of invalid IDs: {}", result);
+    Ok(Answer::new("Sum of invalid IDs", result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_describe_overlapping_ranges() {
+        // 10-20, 15-25 and 18-30 all cover 18-20; 15-17 and 21-25 are
+        // covered twice; 40-45 touches nothing
+        let input = "10-20,15-25,18-30,40-45";
+        let opts = DayOptions::new(vec!["--stats".to_string()]);
+        let mut out = Output::capture();
+        let answer = solve(input, false, &opts, &mut out).unwrap();
+        assert_eq!(
+            out.lines(),
+            ["Ranges: 4 supplied, 2 after merging", "IDs covered: 27", "IDs in more than one range: 11"]
+        );
+        // The answer is the same with or without the stats
+        let plain = solve(input, false, &DayOptions::default(), &mut Output::capture()).unwrap();
+        assert_eq!(answer, plain);
+        assert_eq!(answer.value.to_string(), "77");
+    }
+
+    #[test]
+    fn lenient_numbers_read_underscores_without_merging_tokens() {
+        let (ranges, diagnostics) = parse_ranges("1_000-2_500,998 - 1012", false);
+        assert_eq!(ranges, [(998, 1012)]);
+        assert_eq!(diagnostics.items()[0].message, "malformed range, expected start-end");
+
+        let (ranges, diagnostics) = parse_ranges("1_000-2_500,998 - 1012", true);
+        assert_eq!(ranges, [(1000, 2500), (998, 1012)]);
+        assert!(diagnostics.items().is_empty());
+        // The comma still separates: 10 and 14 stay two ranges, not 1014
+        assert_eq!(parse_ranges("10-10, 14-14", true).0, [(10, 10), (14, 14)]);
+    }
+
+    const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+                           1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+                           824824821-824824827,2121212118-2121212124";
+
+    #[test]
+    fn per_range_subtotals_add_up_to_the_answer() {
+        let opts = DayOptions::new(vec!["--per-range".to_string()]);
+
+        let mut out = Output::capture();
+        solve(EXAMPLE, false, &opts, &mut out).unwrap();
+        assert_eq!(out.lines()[0], "11-22: 2 invalid, subtotal 33");
+        assert_eq!(out.lines()[1], "95-115: 1 invalid, subtotal 99");
+        assert_eq!(out.lines()[2], "998-1012: 1 invalid, subtotal 1010");
+        assert_eq!(out.lines()[3], "1188511880-1188511890: 1 invalid, subtotal 1188511885");
+        assert_eq!(out.lines()[5], "1698522-1698528: 0 invalid, subtotal 0");
+        assert!(out.lines()[11].starts_with("Total of subtotals: 1227775554 "), "{:?}", out.lines());
+
+        let mut out = Output::capture();
+        solve(EXAMPLE, true, &opts, &mut out).unwrap();
+        assert_eq!(out.lines()[1], "95-115: 2 invalid, subtotal 210");
+        assert_eq!(out.lines()[2], "998-1012: 2 invalid, subtotal 2009");
+        assert_eq!(out.lines()[8], "565653-565659: 1 invalid, subtotal 565656");
+        assert_eq!(out.lines()[10], "2121212118-2121212124: 1 invalid, subtotal 2121212121");
+        assert!(out.lines()[11].starts_with("Total of subtotals: 4174379265 "), "{:?}", out.lines());
+    }
+
+    #[test]
+    fn invalid_ids_come_in_order_and_match_the_sums() {
+        for part2 in [false, true] {
+            let ranges = ranges(EXAMPLE);
+            assert_eq!(ranges.len(), 11);
+            let mut total = 0u128;
+            for &range in &ranges {
+                let ids: Vec<u64> = invalid_ids(range, part2).collect();
+                assert!(ids.is_sorted() && ids.iter().all(|id| (range.0..=range.1).contains(id)), "{:?}", ids);
+                let expected = if part2 { repeated_in(range, &Cancel::default()).unwrap() } else { doubled_in(range) };
+                assert_eq!((ids.len() as u128, ids.iter().map(|&id| u128::from(id)).sum()), expected);
+                total += expected.1;
+            }
+            assert_eq!(total, if part2 { 4174379265 } else { 1227775554 });
+        }
+        assert_eq!(invalid_ids((95, 115), false).collect::<Vec<_>>(), [99]);
+        assert_eq!(invalid_ids((95, 1212), true).collect::<Vec<_>>(), [99, 111, 222, 333, 444, 555, 666, 777, 888, 999, 1010, 1111, 1212]);
+
+        // The first IDs of a huge range come without walking it
+        let first: Vec<u64> = invalid_ids((1, u64::MAX), true).take(3).collect();
+        assert_eq!(first, [11, 22, 33]);
+        assert_eq!(invalid_ids((u64::MAX - 5, u64::MAX), true).count(), 0);
+    }
+
+    #[test]
+    fn generated_candidates_match_checking_every_id() {
+        for range in [(1, 10_000), (95, 115), (1_111_110, 1_111_112), (999_990, 1_001_001)] {
+            let expected: Vec<u64> = (range.0..=range.1).filter(|&n| is_invalid_part2(n)).collect();
+            let sum: u128 = expected.iter().map(|&n| u128::from(n)).sum();
+            assert_eq!(repeated_in(range, &Cancel::default()).unwrap(), (expected.len() as u128, sum), "{:?}", range);
+        }
+    }
+
+    #[test]
+    fn a_cancelled_part_2_gives_up() {
+        let cancel = Cancel::default();
+        cancel.cancel();
+        let opts = DayOptions::default().with_cancel(cancel);
+        let result = solve("1-9000000000", true, &opts, &mut Output::capture());
+        assert!(matches!(result, Err(Error::Cancelled)));
+        // Without holding every chunk of the widest range there is
+        let result = solve("1-18446744073709551615", true, &opts, &mut Output::capture());
+        assert!(matches!(result, Err(Error::Cancelled)));
+        // So does --per-range, which generates part 2's candidates range by range
+        let per_range = DayOptions::new(vec!["--per-range".to_string()]).with_cancel(opts.cancel.clone());
+        let result = solve("1-18446744073709551615", true, &per_range, &mut Output::capture());
+        assert!(matches!(result, Err(Error::Cancelled)));
+        // Part 1 generates its candidates and never looks
+        assert!(solve("11-22", false, &opts, &mut Output::capture()).is_ok());
+    }
 }
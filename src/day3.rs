@@ -14,10 +14,10 @@
 // Part 2: Find the maximum joltage by turning on exactly twelve batteries.
 //         The joltage is the 12-digit number formed by the selected digits.
 //
-// STRATEGY FOR PART 2:
+// STRATEGY (BOTH PARTS):
 // Use a greedy approach: at each position in the result (left to right),
 // choose the largest digit from the remaining input, ensuring we have enough
-// digits left to complete the 12-digit number.
+// digits left to complete the number (2 digits in part 1, 12 in part 2).
 //
 // For position i (0-indexed) in the result:
 // - We need (12 - i) more digits total
@@ -29,21 +29,60 @@
 // - Position 1: search indices 1..5, pick 8 at index 1
 // - Continue this way...
 //
+// WRAPPED BANKS (`-- --multiline-banks`):
+// ----------------------------------------
+// Some transcriptions wrap a bank over several lines and put a blank line
+// between banks. Then the digits of consecutive non-blank lines are joined
+// into one bank before any batteries are picked. A blank line between two
+// banks switches this on by itself; a blank line at the very end does not.
+// --stream cannot look ahead, so it needs the option spelled out and
+// rejects a blank line between banks without it.
+//
+// TOP BANKS (`-- --top N`):
+// --------------------------
+// After the total, lists the N banks with the highest joltage, best first
+// (ties go to the earlier line), each with the batteries it turns on:
+//   1. line 7: 98 (batteries 1, 2)
+// A wrapped bank is listed by its first line, its batteries counted across
+// the whole bank.
+// Only N banks are kept while solving, in a min-heap whose weakest entry
+// drops out when a better one arrives.
+//
 // ============================================================================
 
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+
+use crate::cancel::Cancel;
+use crate::error::Error;
+use crate::input::InputReader;
+use crate::options::DayOptions;
+use crate::output::{Answer, Output};
+use crate::parallel;
+
+/// Options day 3 understands after `--`
+pub const OPTIONS: &[&str] = &["--top <N>", "--multiline-banks"];
+
 /// Find the largest k-digit number by selecting k digits from the input
 /// while maintaining their relative order.
 fn find_max_k_digits(digits: &[u32], k: usize) -> u64 {
+    select_k_digits(digits, k).into_iter().fold(0, |result, i| result * 10 + u64::from(digits[i]))
+}
+
+/// Indices of the digits `find_max_k_digits` picks, in order; empty when
+/// there are fewer than k digits
+fn select_k_digits(digits: &[u32], k: usize) -> Vec<usize> {
     if k == 0 || digits.is_empty() {
-        return 0;
+        return Vec::new();
     }
     
     if k > digits.len() {
         // Can't select k digits from fewer than k available
-        return 0;
+        return Vec::new();
     }
     
-    let mut result = 0u64;
+    let mut selected = Vec::with_capacity(k);
     let mut start_idx = 0;
     
     for i in 0..k {
@@ -57,66 +96,348 @@ fn find_max_k_digits(digits: &[u32], k: usize) -> u64 {
         let mut max_digit = digits[start_idx];
         let mut max_idx = start_idx;
         
-        for j in start_idx..search_end {
-            if digits[j] > max_digit {
-                max_digit = digits[j];
+        for (j, &digit) in digits.iter().enumerate().take(search_end).skip(start_idx) {
+            if digit > max_digit {
+                max_digit = digit;
                 max_idx = j;
             }
         }
         
         // Add this digit to our result
-        result = result * 10 + max_digit as u64;
+        selected.push(max_idx);
         
         // Next search starts after the digit we just picked
         start_idx = max_idx + 1;
     }
     
-    result
+    selected
+}
+
+/// The best banks seen so far, at most `limit` of them (--top)
+struct TopBanks {
+    limit: usize,
+    /// A min-heap: the weakest kept bank is on top, ready to drop out.
+    /// Entries are (joltage, line number, bank); a later line is weaker.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>, String)>>,
+}
+
+impl TopBanks {
+    fn new(limit: usize) -> Self {
+        Self { limit, heap: BinaryHeap::with_capacity(limit + 1) }
+    }
+
+    /// Offer the bank on 1-based line `line`
+    fn push(&mut self, line: usize, bank: &str, joltage: u64) {
+        let entry = (joltage, Reverse(line), String::new());
+        if self.heap.len() == self.limit && self.heap.peek().is_none_or(|Reverse(weakest)| entry <= *weakest) {
+            return;
+        }
+        self.heap.push(Reverse((joltage, Reverse(line), bank.to_string())));
+        if self.heap.len() > self.limit {
+            self.heap.pop();
+        }
+    }
+
+    /// One line per kept bank, best first
+    fn report(self, part2: bool, out: &mut Output) {
+        let k = batteries_per_bank(part2);
+        // Ascending order of Reverse(entry) is best first
+        for (rank, Reverse((joltage, Reverse(line), bank))) in self.heap.into_sorted_vec().into_iter().enumerate() {
+            out.line(format!("{}. line {}: {} (batteries {})", rank + 1, line, joltage, batteries(&bank, k)));
+        }
+    }
+}
+
+/// The 1-based positions of the k batteries a bank's joltage uses, e.g. "3, 7"
+fn batteries(bank: &str, k: usize) -> String {
+    let positions: Vec<String> = select_k_digits(&bank_digits(bank), k).iter().map(|i| (i + 1).to_string()).collect();
+    positions.join(", ")
+}
+
+/// How many batteries each bank turns on: 2 in part 1, 12 in part 2
+fn batteries_per_bank(part2: bool) -> usize {
+    if part2 { 12 } else { 2 }
+}
+
+/// The N after --top, if given
+fn top_limit(opts: &DayOptions) -> Result<Option<usize>, Error> {
+    opts.value("--top")
+        .map(|n| n.parse().map_err(|_| Error::Input(format!("--top needs a number of banks, got '{}'", n))))
+        .transpose()
 }
 
 /// Main solver for Day 3
-pub fn solve(input: &str, part2: bool) {
+/// The banks are independent, so a whole input is worked on in parallel
+pub fn solve(input: &str, part2: bool, opts: &DayOptions, out: &mut Output) -> Result<Answer, Error> {
+    let limit = top_limit(opts)?;
+    let banks = tracing::info_span!("parse")
+        .in_scope(|| if opts.flag("--multiline-banks") { wrapped_banks(input) } else { banks(input) });
+    tracing::debug!(banks = banks.len(), multiline = opts.flag("--multiline-banks"), "parsed the banks");
+    let joltages = parallel::map(&banks, |(_, bank)| bank_joltage(bank, part2));
+    opts.cancel.check()?;
+    let total_joltage: u64 = joltages.iter().sum();
+
+    if let Some(artifacts) = &opts.artifacts {
+        let k = batteries_per_bank(part2);
+        let selections = banks.iter().zip(&joltages).filter(|((_, bank), _)| !bank.trim().is_empty()).map(
+            |((line, bank), joltage)| format!("line {}: {} -> {} (batteries {})", line, bank.trim(), joltage, batteries(bank, k)),
+        );
+        let name = format!("day3_part{}_selections.txt", if part2 { 2 } else { 1 });
+        artifacts.write(&name, selections)?;
+    }
+
+    if let Some(limit) = limit {
+        let mut top = TopBanks::new(limit);
+        // A blank line is no bank to rank
+        for ((line, bank), joltage) in banks.iter().zip(joltages).filter(|((_, bank), _)| !bank.trim().is_empty()) {
+            top.push(*line, bank, joltage);
+        }
+        top.report(part2, out);
+    }
+    Ok(Answer::new("Total output joltage", total_joltage))
+}
+
+/// Same as `solve`, reading one bank at a time so memory stays flat
+pub fn solve_stream(
+    reader: &mut InputReader,
+    part2: bool,
+    opts: &DayOptions,
+    out: &mut Output,
+) -> Result<Answer, Error> {
+    let multiline = opts.flag("--multiline-banks");
+    solve_lines(reader.lines(), part2, multiline, top_limit(opts)?, &opts.cancel, out)
+}
+
+/// Body of `solve_stream`, one bank after another
+fn solve_lines<L: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<L>>,
+    part2: bool,
+    multiline: bool,
+    limit: Option<usize>,
+    cancel: &Cancel,
+    out: &mut Output,
+) -> Result<Answer, Error> {
     let mut total_joltage = 0u64;
+    let mut top = limit.map(TopBanks::new);
+    let mut finish = |line: usize, bank: &str| {
+        let joltage = bank_joltage(bank, part2);
+        total_joltage += joltage;
+        if let Some(top) = top.as_mut() {
+            top.push(line, bank, joltage);
+        }
+    };
+
+    // The wrapped bank being read: its first line and digits so far
+    let mut wrapped: Option<(usize, String)> = None;
+    // Whether a bank has been read, and a blank line has come after one;
+    // blank lines before the first bank separate nothing
+    let mut seen_bank = false;
+    let mut blank_after_bank = false;
+    for (i, line) in lines.enumerate() {
+        cancel.check()?;
+        let line = line?;
+        let line = line.as_ref().trim();
+        if multiline {
+            if line.is_empty() {
+                if let Some((first, bank)) = wrapped.take() {
+                    finish(first, &bank);
+                }
+            } else {
+                wrapped.get_or_insert_with(|| (i + 1, String::new())).1.push_str(line);
+            }
+            continue;
+        }
 
-    for line in input.lines() {
-        let line = line.trim();
         if line.is_empty() {
+            blank_after_bank |= seen_bank;
             continue;
         }
+        if blank_after_bank {
+            return Err(Error::Input(format!(
+                "line {}: blank lines separate wrapped banks here, which --stream cannot detect; \
+                 pass -- --multiline-banks",
+                i + 1
+            )));
+        }
+        seen_bank = true;
+        finish(i + 1, line);
+    }
+    if let Some((first, bank)) = wrapped {
+        finish(first, &bank);
+    }
+    cancel.check()?;
 
-        // Convert line to vector of digit values
-        let digits: Vec<u32> = line
-            .chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
+    if let Some(top) = top {
+        top.report(part2, out);
+    }
+    Ok(Answer::new("Total output joltage", total_joltage))
+}
 
-        if part2 {
-            // Part 2: Select 12 batteries
-            if digits.len() < 12 {
-                continue; // Need at least 12 batteries
-            }
-            
-            let max_joltage = find_max_k_digits(&digits, 12);
-            total_joltage += max_joltage;
+/// The banks in `input`, each with the 1-based line it starts on: one per
+/// line, or wrapped banks joined when a blank line separates them
+pub fn banks(input: &str) -> Vec<(usize, String)> {
+    if separates_banks(input) {
+        tracing::debug!("blank lines separate the banks: joining each one's lines");
+        wrapped_banks(input)
+    } else {
+        tracing::debug!("one bank per line");
+        input.lines().enumerate().map(|(i, line)| (i + 1, line.to_string())).collect()
+    }
+}
+
+/// Where in `bank` (as char indices) the batteries its joltage uses sit;
+/// empty for a bank with too few batteries
+pub fn selected_batteries(bank: &str, part2: bool) -> Vec<usize> {
+    let digits: Vec<(usize, u32)> = bank.chars().enumerate().filter_map(|(i, c)| Some((i, c.to_digit(10)?))).collect();
+    let values: Vec<u32> = digits.iter().map(|&(_, d)| d).collect();
+    select_k_digits(&values, batteries_per_bank(part2)).into_iter().map(|i| digits[i].0).collect()
+}
+
+/// The most joltage `bank` can produce, as `solve` counts it
+pub fn joltage(bank: &str, part2: bool) -> u64 {
+    bank_joltage(bank, part2)
+}
+
+/// Whether a blank line sits between two non-blank ones (a trailing blank
+/// line does not count)
+fn separates_banks(input: &str) -> bool {
+    input.trim().lines().any(|line| line.trim().is_empty())
+}
+
+/// Banks wrapped over consecutive non-blank lines, joined, each with the
+/// 1-based line it starts on
+fn wrapped_banks(input: &str) -> Vec<(usize, String)> {
+    let mut banks: Vec<(usize, String)> = Vec::new();
+    let mut in_bank = false;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            in_bank = false;
+        } else if in_bank && let Some((_, bank)) = banks.last_mut() {
+            bank.push_str(line);
         } else {
-            // Part 1: Select 2 batteries
-            if digits.len() < 2 {
-                continue; // Need at least 2 batteries
-            }
+            banks.push((i + 1, line.to_string()));
+            in_bank = true;
+        }
+    }
+    banks
+}
 
-            // Find maximum joltage by checking all pairs
-            let mut max_joltage = 0u32;
+/// A bank's joltage digits, ignoring anything else on the line
+fn bank_digits(line: &str) -> Vec<u32> {
+    line.chars().filter_map(|c| c.to_digit(10)).collect()
+}
 
-            for i in 0..digits.len() {
-                for j in (i + 1)..digits.len() {
-                    let joltage = digits[i] * 10 + digits[j];
-                    max_joltage = max_joltage.max(joltage);
-                }
+/// The most joltage one bank (one line) can produce: its best 2 batteries
+/// in part 1, 12 in part 2, picked as `batteries` reports them; 0 for a
+/// blank line or a bank with too few batteries
+fn bank_joltage(line: &str, part2: bool) -> u64 {
+    find_max_k_digits(&bank_digits(line), batteries_per_bank(part2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_banks_are_sorted_with_ties_by_line() {
+        // Two banks tie at 98 and two at 89; the earlier line ranks first
+        let input = "811111111111119\n987654321111111\n12\n9181\n89\n99\n";
+        let opts = DayOptions::new(vec!["--top".to_string(), "4".to_string()]);
+
+        let mut out = Output::capture();
+        let answer = solve(input, false, &opts, &mut out).unwrap();
+        assert_eq!(answer.to_string(), "Total output joltage: 485");
+        let expected = [
+            "1. line 6: 99 (batteries 1, 2)",
+            "2. line 2: 98 (batteries 1, 2)",
+            "3. line 4: 98 (batteries 1, 3)",
+            "4. line 1: 89 (batteries 1, 15)",
+        ];
+        assert_eq!(out.lines(), expected);
+
+        // Streaming keeps the same four
+        let mut out = Output::capture();
+        solve_stream(&mut InputReader::from_text(input), false, &opts, &mut out).unwrap();
+        assert_eq!(out.lines(), expected);
+
+        let bad = DayOptions::new(vec!["--top=many".to_string()]);
+        assert!(solve(input, false, &bad, &mut Output::capture()).is_err());
+    }
+
+    #[test]
+    fn selected_batteries_index_the_bank_text() {
+        assert_eq!(selected_batteries("818181911112111", false), [6, 11]);
+        assert_eq!(joltage("818181911112111", false), 92);
+        // Non-digits keep their place in the text but are never picked
+        assert_eq!(selected_batteries("8 1-9", false), [0, 4]);
+        assert!(selected_batteries("12345", true).is_empty());
+
+        // The answer is made of the batteries reported, in both parts
+        for bank in ["987654321111111", "234234234234278", "818181911112111", "1923"] {
+            for part2 in [false, true] {
+                let picked: String = selected_batteries(bank, part2).iter().map(|&i| bank.as_bytes()[i] as char).collect();
+                assert_eq!(picked.parse::<u64>().unwrap_or(0), joltage(bank, part2), "{} part2={}", bank, part2);
             }
+        }
+        assert_eq!(banks("12\n\n34\n5\n"), [(1, "12".to_string()), (3, "345".to_string())]);
+    }
+
+    #[test]
+    fn wrapped_banks_join_their_lines() {
+        let one_line = "987654321111111\n811111111111119\n";
+        let wrapped = "98765\n43211\n11111\n\n811111111111119\n";
+        let forced = DayOptions::new(vec!["--multiline-banks".to_string()]);
+        for part2 in [false, true] {
+            let expected = solve(one_line, part2, &DayOptions::default(), &mut Output::capture()).unwrap();
+            // Detected from the blank line, or asked for
+            assert_eq!(solve(wrapped, part2, &DayOptions::default(), &mut Output::capture()).unwrap(), expected);
+            assert_eq!(solve(wrapped, part2, &forced, &mut Output::capture()).unwrap(), expected);
+            let mut reader = InputReader::from_text(wrapped);
+            assert_eq!(solve_stream(&mut reader, part2, &forced, &mut Output::capture()).unwrap(), expected);
+        }
+
+        // A trailing blank line is not a separator
+        assert!(!separates_banks("12\n34\n\n"));
+        assert!(!separates_banks("\n12\n34\n"));
+        assert!(separates_banks("12\n\n34\n"));
+        let answer = solve("12\n34\n\n", false, &DayOptions::default(), &mut Output::capture()).unwrap();
+        assert_eq!(answer.value.to_string(), "46");
 
-            total_joltage += max_joltage as u64;
+        // --stream cannot tell, so it refuses instead of guessing
+        let err = solve_stream(&mut InputReader::from_text(wrapped), false, &DayOptions::default(), &mut Output::capture());
+        assert!(err.unwrap_err().to_string().contains("--multiline-banks"));
+        let mut reader = InputReader::from_text("12\n34\n\n");
+        assert!(solve_stream(&mut reader, false, &DayOptions::default(), &mut Output::capture()).is_ok());
+    }
+
+    #[test]
+    fn leading_blank_lines_solve_the_same_streamed() {
+        let top = DayOptions::new(vec!["--top".to_string(), "3".to_string()]);
+        for input in ["\n\n12\n", "\n\n12\n34\n\n"] {
+            for part2 in [false, true] {
+                let mut out = Output::capture();
+                let answer = solve(input, part2, &top, &mut out).unwrap();
+                let mut streamed = Output::capture();
+                let mut reader = InputReader::from_text(input);
+                assert_eq!(solve_stream(&mut reader, part2, &top, &mut streamed).unwrap(), answer, "{:?}", input);
+                // Blank lines are never ranked as banks
+                assert_eq!(out.lines(), streamed.lines(), "{:?}", input);
+                assert!(out.lines().iter().all(|line| !line.contains("line 1:") && !line.contains("line 2:")), "{:?}", out.lines());
+            }
         }
     }
 
-    println!("Total output joltage: {}", total_joltage);
+    #[test]
+    fn a_cancelled_solve_gives_up() {
+        let cancel = Cancel::default();
+        cancel.cancel();
+        let opts = DayOptions::default().with_cancel(cancel);
+        let bank = "9".repeat(100_000);
+        for part2 in [false, true] {
+            assert!(matches!(solve(&bank, part2, &opts, &mut Output::capture()), Err(Error::Cancelled)));
+            let mut reader = InputReader::from_text(&bank);
+            assert!(matches!(solve_stream(&mut reader, part2, &opts, &mut Output::capture()), Err(Error::Cancelled)));
+        }
+    }
 }
@@ -28,15 +28,74 @@
 //   x@@.@.@.@@
 //   @@@@@.x.@@
 //
+// HEATMAP (`-- --heatmap`):
+// -------------------------
+// Prints the grid with each roll replaced by its count of adjacent rolls,
+// always taken from the grid as given (the part 1 state), so in part 2 it
+// shows where the removals start. Rolls showing 0-3 are the accessible ones:
+//   ..33.3343.
+//   366.4.4.54
+//   47675.2.44
+//
+// COORDINATES (`-- --coords-json [--out FILE]`):
+// ----------------------------------------------
+// A JSON document instead of the heatmap and other report lines, written to
+// FILE or else printed as one report line:
+//   part 1: {"rows":10,"cols":10,"accessible":[[0,2],[0,3],...]}
+//   part 2: {"rows":10,"cols":10,"rounds":[[[0,2],...],[[1,1],...],...]}
+// Coordinates are 0-based [row, col], sorted row-major within each list.
+//
 // ============================================================================
 
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::artifacts::Artifacts;
+use crate::cancel::Cancel;
+use crate::diagnostics::Diagnostics;
+use crate::error::Error;
+use crate::options::DayOptions;
+use crate::output::{Answer, Output};
+use crate::parallel;
+use crate::sim;
+
+/// Day 4's extra options
+pub const OPTIONS: &[&str] = &["--heatmap", "--coords-json", "--out <FILE>"];
+
+/// Printed under the heatmap
+const HEATMAP_LEGEND: &str = "Legend: 0-8 = adjacent rolls (0-3 accessible), . = empty";
+
 /// Parses the input grid into a 2D vector of characters
-fn parse_grid(input: &str) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| line.chars().collect())
-        .collect()
+///
+/// Every row must be as wide as the first one. Ragged rows are padded
+/// with '.' (or truncated) so the grid stays rectangular, and each one
+/// is recorded as a diagnostic.
+fn parse_grid(input: &str) -> (Vec<Vec<char>>, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let mut grid: Vec<Vec<char>> = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut row: Vec<char> = line.chars().collect();
+        if let Some(first) = grid.first()
+            && row.len() != first.len()
+        {
+            diagnostics.push(
+                idx + 1,
+                line,
+                format!("row is {} cells wide, expected {}", row.len(), first.len()),
+            );
+            row.resize(first.len(), '.');
+        }
+        grid.push(row);
+    }
+
+    (grid, diagnostics)
 }
 
 /// Counts the number of '@' symbols in the 8 adjacent positions
@@ -91,47 +150,31 @@ fn count_adjacent_rolls(grid: &[Vec<char>], row: usize, col: usize) -> usize {
 /// 3. Return the total count of accessible rolls
 /// 
 /// Time complexity: O(R × C) where R is rows and C is columns
-fn count_accessible_rolls(input: &str) -> usize {
-    let grid = parse_grid(input);
-    
+fn count_accessible_rolls(grid: &[Vec<char>]) -> usize {
     if grid.is_empty() {
         return 0;
     }
 
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let mut accessible_count = 0;
-
-    // Check each cell in the grid
-    for row in 0..rows {
-        for col in 0..cols {
-            // Only check cells that contain a paper roll
-            if grid[row][col] == '@' {
-                let adjacent = count_adjacent_rolls(&grid, row, col);
-                
-                // Roll is accessible if fewer than 4 adjacent rolls
-                if adjacent < 4 {
-                    accessible_count += 1;
-                }
-            }
-        }
-    }
+    // Rows are independent, so they are checked in parallel
+    let rows: Vec<usize> = (0..grid.len()).collect();
+    parallel::map(&rows, |&row| accessible_in_row(grid, row).len()).into_iter().sum()
+}
 
-    accessible_count
+/// Columns of the rolls in `row` with fewer than 4 adjacent rolls
+fn accessible_in_row(grid: &[Vec<char>], row: usize) -> Vec<usize> {
+    (0..grid[row].len())
+        .filter(|&col| grid[row][col] == '@' && count_adjacent_rolls(grid, row, col) < 4)
+        .collect()
 }
 
 /// PART 2 SOLUTION: Count total removable paper rolls through iterative removal
 /// 
 /// STRATEGY: Simulate the process of removing accessible rolls repeatedly
 /// 
-/// Algorithm:
-/// 1. Parse the input into a mutable 2D grid
-/// 2. Repeat until no more rolls can be removed:
-///    a. Find all currently accessible rolls (adjacent count < 4)
-///    b. If no accessible rolls found, stop
-///    c. Remove all accessible rolls (replace '@' with '.')
-///    d. Add the count to the running total
-/// 3. Return the total count of removed rolls
+/// Algorithm (one round of `sim::run_until_stable`):
+/// 1. Find all currently accessible rolls (adjacent count < 4)
+/// 2. Remove them all (replace '@' with '.') and add them to the running total
+/// 3. The simulation stops after the first round that finds none
 /// 
 /// Why we remove in batches:
 /// - The problem shows removing all accessible rolls at once per iteration
@@ -140,57 +183,281 @@ fn count_accessible_rolls(input: &str) -> usize {
 /// 
 /// Time complexity: O(I × R × C) where I is iterations, R is rows, C is columns
 /// In practice, I is bounded by the total number of rolls
-fn count_removable_rolls(input: &str) -> usize {
-    let mut grid = parse_grid(input);
-    
-    if grid.is_empty() {
-        return 0;
-    }
+fn count_removable_rolls(rounds: &[Vec<[usize; 2]>]) -> usize {
+    rounds.iter().map(Vec::len).sum()
+}
 
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let mut total_removed = 0;
-
-    // Keep removing accessible rolls until none remain
-    loop {
-        // Find all accessible rolls in current state
-        let mut accessible = Vec::new();
-        
-        for row in 0..rows {
-            for col in 0..cols {
-                if grid[row][col] == '@' {
-                    let adjacent = count_adjacent_rolls(&grid, row, col);
-                    if adjacent < 4 {
-                        accessible.push((row, col));
-                    }
-                }
-            }
+/// The rolls each round of part 2 removes, up to the first round that
+/// removes none (which is left out); Error::Cancelled once `cancel` is set,
+/// checked between rounds
+fn removal_rounds(grid: Vec<Vec<char>>, cancel: &Cancel) -> Result<Vec<Vec<[usize; 2]>>, Error> {
+    let mut rounds = Vec::new();
+    sim::run_until_stable(grid, |grid| {
+        if cancel.is_cancelled() {
+            return false;
         }
-
-        // If no accessible rolls found, we're done
-        if accessible.is_empty() {
-            break;
+        let removed = tracing::info_span!("round", round = rounds.len() + 1).in_scope(|| remove_accessible_rolls(grid));
+        let any = !removed.is_empty();
+        if any {
+            rounds.push(removed);
         }
+        any
+    })?;
+    cancel.check()?;
+    Ok(rounds)
+}
+
+/// [row, col] of every roll accessible right now, row-major
+fn accessible_rolls(grid: &[Vec<char>]) -> Vec<[usize; 2]> {
+    let rows: Vec<usize> = (0..grid.len()).collect();
+    let accessible = parallel::map(&rows, |&row| accessible_in_row(grid, row));
+    accessible.into_iter().enumerate().flat_map(|(row, cols)| cols.into_iter().map(move |col| [row, col])).collect()
+}
+
+/// One round of part 2: remove every roll accessible right now, returning
+/// where they were
+fn remove_accessible_rolls(grid: &mut [Vec<char>]) -> Vec<[usize; 2]> {
+    // Find all accessible rolls in the current state before removing any
+    let accessible = accessible_rolls(grid);
+    for &[row, col] in &accessible {
+        grid[row][col] = '.';
+    }
+    accessible
+}
+
+/// The `--coords-json` document
+#[derive(Debug, Serialize, Deserialize)]
+struct Coordinates {
+    rows: usize,
+    cols: usize,
+    /// Part 1: the accessible rolls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessible: Option<Vec<[usize; 2]>>,
+    /// Part 2: the rolls removed in each round
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounds: Option<Vec<Vec<[usize; 2]>>>,
+}
 
-        // Remove all accessible rolls (replace with '.')
-        for (row, col) in &accessible {
-            grid[*row][*col] = '.';
+/// Solve with `--coords-json`: the document replaces the report lines.
+/// `rounds` are part 2's removals, None for part 1
+fn solve_coordinates(
+    grid: Vec<Vec<char>>,
+    rounds: Option<Vec<Vec<[usize; 2]>>>,
+    opts: &DayOptions,
+    out: &mut Output,
+) -> Result<Answer, Error> {
+    let mut doc = Coordinates {
+        rows: grid.len(),
+        cols: grid.first().map_or(0, Vec::len),
+        accessible: None,
+        rounds: None,
+    };
+    let answer = match rounds {
+        Some(rounds) => {
+            let total = count_removable_rolls(&rounds);
+            doc.rounds = Some(rounds);
+            Answer::new("Total removable rolls", total)
         }
+        None => {
+            let accessible = accessible_rolls(&grid);
+            let total = accessible.len();
+            doc.accessible = Some(accessible);
+            Answer::new("Accessible rolls", total)
+        }
+    };
 
-        // Add to total count
-        total_removed += accessible.len();
+    let json = serde_json::to_string(&doc).map_err(|e| Error::Input(format!("could not encode coordinates: {}", e)))?;
+    match opts.value("--out") {
+        Some(path) => fs::write(path, json + "\n").map_err(|e| Error::Export(Path::new(path).to_path_buf(), e))?,
+        None => out.line(json),
     }
+    Ok(answer)
+}
 
-    total_removed
+/// The grid as text lines, each cell drawn by `cell(row, col, c)`
+fn render_grid(grid: &[Vec<char>], cell: impl Fn(usize, usize, char) -> char) -> Vec<String> {
+    grid.iter()
+        .enumerate()
+        .map(|(row, cells)| cells.iter().enumerate().map(|(col, &c)| cell(row, col, c)).collect())
+        .collect()
+}
+
+/// Every roll replaced by its adjacent-roll count, other cells unchanged
+fn heatmap(grid: &[Vec<char>]) -> Vec<String> {
+    render_grid(grid, |row, col, c| match c {
+        '@' => char::from_digit(count_adjacent_rolls(grid, row, col) as u32, 10).unwrap_or('?'),
+        _ => c,
+    })
+}
+
+/// --emit-intermediate: the starting grid, then the grid after each round
+/// of removals with that round's rolls drawn as x
+fn emit_rounds(grid: &[Vec<char>], rounds: &[Vec<[usize; 2]>], artifacts: &Artifacts) -> Result<(), Error> {
+    artifacts.write("day4_grid.txt", render_grid(grid, |_, _, c| c))?;
+    let mut state = grid.to_vec();
+    for (i, removed) in rounds.iter().enumerate() {
+        for &[row, col] in removed {
+            state[row][col] = 'x';
+        }
+        artifacts.write(&format!("day4_round_{:02}.txt", i + 1), render_grid(&state, |_, _, c| c))?;
+        for &[row, col] in removed {
+            state[row][col] = '.';
+        }
+    }
+    Ok(())
 }
 
 /// Main entry point for Day 4 solution
-pub fn solve(input: &str, part2: bool) {
-    if part2 {
-        let result = count_removable_rolls(input);
-        println!("Total removable rolls: {}", result);
-    } else {
-        let result = count_accessible_rolls(input);
-        println!("Accessible rolls: {}", result);
+pub fn solve(input: &str, part2: bool, opts: &DayOptions, out: &mut Output) -> Result<Answer, Error> {
+    let (grid, diagnostics) = tracing::info_span!("parse").in_scope(|| parse_grid(input));
+    let cols = grid.first().map_or(0, Vec::len);
+    tracing::debug!(rows = grid.len(), cols, malformed = diagnostics.items().len(), "parsed the grid");
+    diagnostics.report(opts.strict, out)?;
+
+    if opts.value("--out").is_some() && !opts.flag("--coords-json") {
+        return Err(Error::Input("--out names the --coords-json file, pass it with --coords-json".to_string()));
+    }
+
+    // Part 2's rounds, simulated once for the answer, the artifacts and the
+    // coordinates alike
+    let rounds =
+        if part2 || opts.artifacts.is_some() { Some(removal_rounds(grid.clone(), &opts.cancel)?) } else { None };
+    if let (Some(artifacts), Some(rounds)) = (&opts.artifacts, &rounds) {
+        emit_rounds(&grid, rounds, artifacts)?;
+    }
+    let rounds = rounds.filter(|_| part2);
+    if opts.flag("--coords-json") {
+        return solve_coordinates(grid, rounds, opts, out);
+    }
+    if opts.flag("--heatmap") {
+        for line in heatmap(&grid) {
+            out.line(line);
+        }
+        out.line(HEATMAP_LEGEND);
+    }
+
+    match rounds {
+        Some(rounds) => Ok(Answer::new("Total removable rolls", count_removable_rolls(&rounds))),
+        None => Ok(Answer::new("Accessible rolls", count_accessible_rolls(&grid))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ragged_rows_are_padded_and_reported() {
+        let (grid, diagnostics) = parse_grid("@@@\n@\n@@@@\n");
+        assert_eq!(grid, vec![vec!['@'; 3], vec!['@', '.', '.'], vec!['@'; 3]]);
+        let lines: Vec<usize> = diagnostics.items().iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+
+        let strict = DayOptions::default().with_strict(true);
+        assert!(solve("@@@\n@\n", false, &strict, &mut Output::capture()).is_err());
+    }
+
+    #[test]
+    fn example_settles_round_by_round() {
+        let (grid, _) = parse_grid(include_str!("../tests/fixtures/day4_example.txt"));
+        let rounds = removal_rounds(grid.clone(), &Cancel::default()).unwrap();
+        assert_eq!(count_removable_rolls(&rounds), 43);
+
+        // Every round removes something, and the total removed reaches 43
+        let step = |g: &mut Vec<Vec<char>>| !remove_accessible_rolls(g).is_empty();
+        let start = rolls(&grid);
+        let removed: Vec<usize> =
+            sim::rounds(grid.clone(), sim::DEFAULT_MAX_ROUNDS, step).map(|g| start - rolls(&g.unwrap())).collect();
+        assert_eq!(removed.last(), Some(&43));
+        assert!(removed.windows(2).all(|w| w[0] < w[1]));
+
+        let (stable, rounds) = sim::run_until_stable(grid, step).unwrap();
+        assert_eq!(rounds, removed.len());
+        assert_eq!(sim::run_until_stable(stable, step).unwrap().1, 0);
+    }
+
+    fn rolls(grid: &[Vec<char>]) -> usize {
+        grid.iter().flatten().filter(|&&c| c == '@').count()
+    }
+
+    #[test]
+    fn heatmap_counts_neighbours_on_the_original_grid() {
+        let input = include_str!("../tests/fixtures/day4_example.txt");
+        let expected = "\
+            ..33.3343.\n\
+            366.4.4.54\n\
+            47675.2.44\n\
+            4.6776..4.\n\
+            35.7875.43\n\
+            .4657665.4\n\
+            .4.6.5.674\n\
+            2.666.6774\n\
+            .55767675.\n\
+            1.3.454.2.";
+
+        // Part 2 still maps the grid before any removal
+        let heatmap = DayOptions::new(vec!["--heatmap".to_string()]);
+        for part2 in [false, true] {
+            let mut out = Output::capture();
+            solve(input, part2, &heatmap, &mut out).unwrap();
+            assert_eq!(out.lines()[..10].join("\n"), expected);
+            assert_eq!(out.lines()[10], HEATMAP_LEGEND);
+        }
+
+        // The 13 accessible rolls are exactly the cells under 4
+        let accessible = expected.chars().filter(|c| matches!(c, '0'..='3')).count();
+        assert_eq!(accessible, 13);
+    }
+
+    #[test]
+    fn coordinates_round_trip_and_match_the_answers() {
+        let input = include_str!("../tests/fixtures/day4_example.txt");
+        let json = DayOptions::new(vec!["--coords-json".to_string()]);
+        let coordinates = |part2: bool| {
+            let mut out = Output::capture();
+            let answer = solve(input, part2, &json, &mut out).unwrap();
+            assert_eq!(out.lines().len(), 1);
+            let doc: Coordinates = serde_json::from_str(&out.lines()[0]).unwrap();
+            assert_eq!((doc.rows, doc.cols), (10, 10));
+            (answer, doc)
+        };
+
+        let (answer, doc) = coordinates(false);
+        let accessible = doc.accessible.unwrap();
+        assert_eq!(answer, Answer::new("Accessible rolls", 13usize));
+        assert_eq!(accessible.len(), 13);
+        assert_eq!(accessible[..3], [[0, 2], [0, 3], [0, 5]]);
+        assert!(accessible.windows(2).all(|w| w[0] < w[1]));
+        assert!(doc.rounds.is_none());
+
+        let (answer, doc) = coordinates(true);
+        let rounds = doc.rounds.unwrap();
+        assert_eq!(answer, Answer::new("Total removable rolls", 43usize));
+        assert_eq!(rounds.iter().map(Vec::len).sum::<usize>(), 43);
+        assert_eq!(rounds[0], accessible);
+        assert!(rounds.iter().all(|round| round.windows(2).all(|w| w[0] < w[1])));
+
+        // --out takes the document off the report lines
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coords.json");
+        let opts = DayOptions::new(vec!["--coords-json".into(), "--out".into(), path.display().to_string()]);
+        let mut out = Output::capture();
+        solve(input, false, &opts, &mut out).unwrap();
+        assert!(out.lines().is_empty());
+        let doc: Coordinates = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(doc.accessible, Some(accessible));
+
+        // --out alone has no document to write
+        let out_only = DayOptions::new(vec!["--out".into(), path.display().to_string()]);
+        assert!(matches!(solve(input, false, &out_only, &mut Output::capture()), Err(Error::Input(_))));
+    }
+
+    #[test]
+    fn a_cancelled_part_2_gives_up() {
+        let cancel = Cancel::default();
+        cancel.cancel();
+        let opts = DayOptions::default().with_cancel(cancel);
+        let input = include_str!("../tests/fixtures/day4_example.txt");
+        assert!(matches!(solve(input, true, &opts, &mut Output::capture()), Err(Error::Cancelled)));
     }
 }
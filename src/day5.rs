@@ -25,7 +25,8 @@
 //    Ranges can overlap: both "10-14" and "12-18" can exist
 //
 // 2. Available ingredient IDs (Part 1 only):
-//    One ID per line
+//    One ID per line, or an inclusive "start-end" range of available IDs
+//    Example: "17" is a single ID, "5-8" means IDs 5, 6, 7 and 8 are available
 //
 // EXAMPLE:
 // --------
@@ -58,6 +59,15 @@
 //   Range 10-20 contains: 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20 (11 IDs)
 //   Result: 14 total fresh ingredient IDs
 //
+// KEY ALGORITHM (Part 1):
+// ------------------------
+// Every availability entry is treated as a range (a bare ID "17" is 17-17).
+// Both the fresh ranges and the availability ranges are merged, then the two
+// sorted, disjoint lists are swept together with two pointers, summing the
+// size of each overlap. Merging first means overlapping availability ranges
+// are never double-counted.
+// Example: fresh [10-20], available [8-12] → overlap 10-12 → 3 IDs
+//
 // KEY ALGORITHM (Part 2):
 // ------------------------
 // 1. Sort ranges by start position
@@ -66,6 +76,66 @@
 //    - Otherwise, save the previous range and start a new one
 // 3. Count total IDs in all merged ranges: sum of (end - start + 1)
 //
+// MISSING MODE (`-- --missing`):
+// -------------------------------
+// Counts the fresh IDs that are NOT among the available ingredients:
+//   total fresh (Part 2) - distinct available IDs that are fresh (Part 1)
+// Both terms come from merged ranges, so duplicated available IDs are only
+// subtracted once and no ID in the ranges is ever enumerated.
+// Example: 14 fresh IDs - 3 available fresh IDs = 11 missing
+//
+// DUPLICATES (`-- --distinct`):
+// -------------------------------
+// By default an available ID listed twice is counted twice (once per
+// occurrence), matching the original per-ID check. With --distinct, exact
+// duplicate entries are dropped before classification. Overlapping but
+// different availability ranges are never double-counted in either mode.
+//
+// VERBOSE (`-- --verbose`):
+// -------------------------
+// Lists every availability entry with its classification, e.g.
+//   5: fresh (3-5)
+//   8: spoiled, 2 away from 10-20
+//   5-8: 1 of 4 fresh
+//   5: fresh (3-5) (duplicate of line 7)
+// A fresh ID names the merged range that contains it; merged ranges are
+// disjoint, so that range is unique. A spoiled ID names the merged range
+// with the nearest boundary, found by binary search: an ID just outside a
+// range is 1 away, and on a tie the lower range wins.
+//
+// GAP ANALYSIS (`-- --gaps`):
+// ----------------------------
+// Measures coverage between the smallest range start and the largest range
+// end: (max_end - min_start + 1) - total fresh IDs. The span is computed in
+// u128 so a range touching u64::MAX cannot overflow. With --verbose the gap
+// intervals themselves are listed.
+// Example: ranges merge to [3-5], [10-20] → span 3..=20 is 18 IDs,
+//          14 are fresh → 4 spoiled IDs, all in the gap 6-9
+//
+// SHOW MERGED (`-- --show-merged`):
+// ----------------------------------
+// Lists the post-merge intervals before the answer, one per line, e.g.
+//   3-5 (3 ids)
+//   10-20 (11 ids)
+//   Total: 14 ids
+//
+// SECTION ORDER:
+// ---------------
+// A pasted input sometimes has the available IDs above the ranges. When the
+// first section holds only bare IDs and the second only start-end ranges,
+// the two are swapped back (with a note) instead of every range line being
+// skipped as malformed. Two sections of bare IDs are an error. Two sections
+// of ranges keep the documented order, since availability ranges are allowed;
+// `-- --swapped-sections` says the first of them is the availability list.
+//
+// STREAMING (`--stream`):
+// ------------------------
+// Keeps only the fresh ranges in memory and checks each available ID with a
+// binary search as its line is read. Equivalent to the default mode as long
+// as the availability section holds bare IDs (a repeated ID counts again, just
+// as in the default mode); an availability range or any of the options above
+// needs the whole list, so ranges are rejected and options read everything.
+//
 // MERGING LOGIC:
 // Two ranges (a, b) and (c, d) can be merged if c <= b + 1
 // (they overlap or are adjacent)
@@ -75,118 +145,746 @@
 //
 // ============================================================================
 
-pub fn solve(input: &str, part2: bool) {
-    if part2 {
-        solve_part2(input);
-    } else {
-        solve_part1(input);
-    }
-}
-
-/// Part 1: Count how many available ingredient IDs are fresh
-/// An ingredient ID is fresh if it falls within any of the fresh ranges
-fn solve_part1(input: &str) {
-    let lines: Vec<&str> = input.lines().collect();
-    
-    // Find the blank line that separates ranges from ingredient IDs
-    let blank_line_idx = lines.iter().position(|&line| line.trim().is_empty())
-        .expect("No blank line found in input");
-    
-    // Parse the fresh ingredient ranges (e.g., "3-5" means IDs 3, 4, 5 are fresh)
-    let mut ranges: Vec<(u64, u64)> = Vec::new();
-    for line in &lines[..blank_line_idx] {
-        if let Some((start, end)) = parse_range(line) {
-            ranges.push((start, end));
+use std::collections::HashMap;
+
+use crate::diagnostics::Diagnostics;
+use crate::error::Error;
+use crate::input::InputReader;
+use crate::interval::{self, Interval};
+use crate::options::DayOptions;
+use crate::output::{Answer, Output};
+
+/// Extra options understood by Day 5
+pub const OPTIONS: &[&str] = &["--missing", "--show-merged", "--distinct", "--verbose", "--gaps", "--swapped-sections"];
+
+pub fn solve(input: &str, part2: bool, opts: &DayOptions, out: &mut Output) -> Result<Answer, Error> {
+    let force_swap = opts.flag("--swapped-sections");
+    let mut inventory =
+        tracing::info_span!("parse").in_scope(|| parse_sections(input, force_swap, opts.lenient_numbers))?;
+    tracing::debug!(
+        fresh = inventory.fresh.len(),
+        available = inventory.available.as_ref().map_or(0, Vec::len),
+        swapped = inventory.swapped,
+        malformed = inventory.diagnostics.items().len(),
+        "parsed the sections"
+    );
+    if inventory.swapped {
+        out.line("Note: the available IDs come before the fresh ranges, reading the sections the other way round");
+    }
+
+    // Malformed lines are warnings by default and errors under --strict
+    std::mem::take(&mut inventory.diagnostics).report(opts.strict, out)?;
+
+    if let Some(artifacts) = &opts.artifacts {
+        let merged = interval::merge(&inventory.fresh);
+        artifacts.write("day5_ranges.txt", inventory.fresh.iter().map(|(start, end)| format!("{}-{}", start, end)))?;
+        artifacts.write("day5_merged.txt", merged.iter().map(|(start, end)| format!("{}-{}", start, end)))?;
+    }
+
+    if opts.flag("--show-merged") {
+        show_merged(&interval::merge(&inventory.fresh), out);
+    }
+
+    let missing = opts.flag("--missing");
+    let verbose = opts.flag("--verbose");
+    if missing && opts.flag("--gaps") {
+        return Err(Error::Input("--missing and --gaps are separate modes, pass only one".to_string()));
+    }
+
+    if opts.flag("--gaps") {
+        // Gap analysis only looks at the fresh ranges
+        let merged = interval::merge(&inventory.fresh);
+        if verbose {
+            for (start, end) in interval::gaps(&merged) {
+                out.line(format!("{}-{} ({} ids)", start, end, end - start + 1));
+            }
         }
+        return Ok(Answer::bare(count_gap_ids(&merged)));
     }
-    
-    // Parse and check available ingredient IDs
-    let mut fresh_count = 0;
-    for line in &lines[blank_line_idx + 1..] {
-        if line.trim().is_empty() {
-            continue;
+
+    // Everything except Part 2 needs the availability section
+    if (missing || !part2) && inventory.available.is_none() {
+        return Err(Error::Input(
+            "No blank line found in input (expected fresh ranges, a blank line, then available IDs)"
+                .to_string(),
+        ));
+    }
+
+    let distinct = opts.flag("--distinct");
+    if verbose {
+        list_available(&inventory, distinct, out);
+    }
+
+    let result = if missing {
+        // Fresh IDs that are not in the available list
+        count_missing_fresh(&inventory)
+    } else if part2 {
+        // Part 2: every ID the ranges consider fresh
+        count_total_fresh(&inventory)
+    } else {
+        // Part 1: available IDs that are fresh
+        count_fresh_available(&inventory, distinct)
+    };
+
+    Ok(Answer::bare(result))
+}
+
+/// Same answers as `solve`, reading the availability section line by line
+///
+/// Only the fresh ranges are kept in memory; each available ID is checked
+/// against them as it is read. Ranges in the availability section and the
+/// extra options need the whole list at once, so options fall back to
+/// reading everything and an availability range is an error.
+pub fn solve_stream(
+    reader: &mut InputReader,
+    part2: bool,
+    opts: &DayOptions,
+    out: &mut Output,
+) -> Result<Answer, Error> {
+    if OPTIONS.iter().any(|option| opts.flag(option)) {
+        let input = reader.read_to_string()?;
+        return solve(&input, part2, opts, out);
+    }
+
+    let mut scanner = SectionScanner { lenient: opts.lenient_numbers, ..SectionScanner::default() };
+    let mut fresh = Vec::new();
+    let mut merged = None;
+    let mut fresh_available: u128 = 0;
+
+    for (idx, line) in reader.lines().enumerate() {
+        opts.cancel.check()?;
+        match scanner.line(idx + 1, &line?) {
+            Some(Entry::Fresh(range)) => fresh.push(range),
+            Some(Entry::Separator) => merged = Some(interval::merge(&fresh)),
+            Some(Entry::Available(Available { line, range: (start, end) })) => {
+                if start != end {
+                    return Err(Error::Input(format!(
+                        "line {}: available ID ranges need the whole input, run without --stream",
+                        line
+                    )));
+                }
+                if !part2 && merged.as_deref().is_some_and(|merged| interval::contains(merged, start)) {
+                    fresh_available += 1;
+                }
+            }
+            None => {}
         }
-        if let Ok(id) = line.trim().parse::<u64>() {
-            // Check if this ID falls within any fresh range
-            if is_fresh(id, &ranges) {
-                fresh_count += 1;
+    }
+
+    scanner.diagnostics.report(opts.strict, out)?;
+
+    if part2 {
+        return Ok(Answer::bare(interval::total_len(&interval::merge(&fresh))));
+    }
+    if merged.is_none() {
+        return Err(Error::Input(
+            "No blank line found in input (expected fresh ranges, a blank line, then available IDs)"
+                .to_string(),
+        ));
+    }
+    Ok(Answer::bare(fresh_available))
+}
+
+/// The fresh ranges after merging, sorted ascending
+/// Malformed lines are skipped, as in the solver's lenient mode
+pub fn merged_fresh_ranges(input: &str) -> Result<Vec<(u64, u64)>, Error> {
+    let inventory = parse_sections(input, false, false)?;
+    Ok(interval::merge(&inventory.fresh))
+}
+
+/// The spoiled stretches between the merged fresh ranges, sorted ascending
+/// Malformed lines are skipped, as in the solver's lenient mode
+pub fn fresh_gaps(input: &str) -> Result<Vec<(u64, u64)>, Error> {
+    Ok(interval::gaps(&merged_fresh_ranges(input)?))
+}
+
+/// Count IDs between the smallest start and largest end that are not fresh
+/// Done in u128 so the span of a range ending at u64::MAX cannot overflow
+fn count_gap_ids(merged: &[Interval]) -> u128 {
+    let (Some(first), Some(last)) = (merged.first(), merged.last()) else {
+        return 0;
+    };
+
+    let span = last.1 as u128 - first.0 as u128 + 1;
+    let covered: u128 = merged
+        .iter()
+        .map(|&range| interval::len(range))
+        .sum();
+
+    span - covered
+}
+
+/// Print each merged range as "start-end (N ids)", then the total
+fn show_merged(merged: &[(u64, u64)], out: &mut Output) {
+    let mut total = 0;
+    for &(start, end) in merged {
+        let count = interval::len((start, end));
+        total += count;
+        out.line(format!("{}-{} ({} ids)", start, end, count));
+    }
+    out.line(format!("Total: {} ids", total));
+}
+
+/// How a single ingredient ID relates to the merged fresh ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Inside exactly one merged range
+    Fresh { range: Interval },
+    /// Outside every range; `closest` is the nearest range and how far its
+    /// nearest boundary is (None when there are no ranges at all)
+    Spoiled { closest: Option<(Interval, u64)> },
+}
+
+impl std::fmt::Display for Freshness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Freshness::Fresh { range: (start, end) } => write!(f, "fresh ({}-{})", start, end),
+            Freshness::Spoiled { closest: Some(((start, end), distance)) } => {
+                write!(f, "spoiled, {} away from {}-{}", distance, start, end)
             }
+            Freshness::Spoiled { closest: None } => write!(f, "spoiled (no fresh ranges)"),
         }
     }
-    
-    println!("{}", fresh_count);
-}
-
-/// Part 2: Count total number of ingredient IDs considered fresh by the ranges
-/// This means counting all IDs within the ranges (after merging overlapping ranges)
-fn solve_part2(input: &str) {
-    let lines: Vec<&str> = input.lines().collect();
-    
-    // Find the blank line that separates ranges from ingredient IDs
-    let blank_line_idx = lines.iter().position(|&line| line.trim().is_empty())
-        .expect("No blank line found in input");
-    
-    // Parse the fresh ingredient ranges
-    let mut ranges: Vec<(u64, u64)> = Vec::new();
-    for line in &lines[..blank_line_idx] {
-        if let Some((start, end)) = parse_range(line) {
-            ranges.push((start, end));
+}
+
+/// Classify one ID against merged fresh ranges (as from merged_fresh_ranges)
+pub fn classify(id: u64, merged: &[Interval]) -> Freshness {
+    match interval::find(merged, id) {
+        Some(range) => Freshness::Fresh { range },
+        None => Freshness::Spoiled { closest: interval::nearest(merged, id) },
+    }
+}
+
+/// List each availability entry with its classification, flagging repeats
+fn list_available(inventory: &Inventory, distinct: bool, out: &mut Output) {
+    let fresh = interval::merge(&inventory.fresh);
+    let mut first_seen: HashMap<Interval, usize> = HashMap::new();
+
+    for entry in inventory.available.as_deref().unwrap_or_default() {
+        let (start, end) = entry.range;
+
+        let mut text = if start == end {
+            format!("{}: {}", start, classify(start, &fresh))
+        } else {
+            let fresh_ids = interval::intersection_size(&fresh, &[entry.range]);
+            format!("{}-{}: {} of {} fresh", start, end, fresh_ids, interval::len(entry.range))
+        };
+
+        let first_line = *first_seen.entry(entry.range).or_insert(entry.line);
+        if first_line != entry.line {
+            let counted = if distinct { ", not counted" } else { "" };
+            text.push_str(&format!(" (duplicate of line {}{})", first_line, counted));
+        }
+
+        out.line(text);
+    }
+}
+
+/// Count the available IDs that are fresh, via interval intersection
+///
+/// Overlapping availability ranges are only counted once. Exact duplicate
+/// entries (the same ID listed twice) count once per occurrence unless
+/// `distinct` is set, in which case they are dropped before classification.
+fn count_fresh_available(inventory: &Inventory, distinct: bool) -> u128 {
+    let entries = inventory.available.as_deref().unwrap_or_default();
+    let mut ranges: Vec<Interval> = entries.iter().map(|entry| entry.range).collect();
+
+    // Pull out exact repeats so the merge below sees each entry once
+    ranges.sort_unstable();
+    let before_dedup = ranges.clone();
+    ranges.dedup();
+
+    // Merge both sides so each ID appears in at most one range per list
+    let fresh = interval::merge(&inventory.fresh);
+    let available = interval::merge(&ranges);
+    let mut total = interval::intersection_size(&fresh, &available);
+
+    if !distinct {
+        // Each repeat of an entry counts again, like the original per-ID check
+        for pair in before_dedup.windows(2) {
+            if pair[0] == pair[1] {
+                total += interval::intersection_size(&fresh, &[pair[1]]);
+            }
         }
     }
-    
+
+    total
+}
+
+/// Count every ID covered by the fresh ranges (after merging overlaps)
+fn count_total_fresh(inventory: &Inventory) -> u128 {
     // Merge overlapping ranges to avoid double-counting
-    let merged_ranges = merge_ranges(&mut ranges);
-    
+    let merged_ranges = interval::merge(&inventory.fresh);
+
     // Count total IDs in all merged ranges
-    let total_fresh: u64 = merged_ranges.iter()
-        .map(|&(start, end)| end - start + 1)
-        .sum();
-    
-    println!("{}", total_fresh);
+    interval::total_len(&merged_ranges)
 }
 
-/// Parse a range string like "3-5" into (3, 5)
-fn parse_range(line: &str) -> Option<(u64, u64)> {
-    let parts: Vec<&str> = line.split('-').collect();
-    if parts.len() == 2 {
-        let start = parts[0].parse::<u64>().ok()?;
-        let end = parts[1].parse::<u64>().ok()?;
-        Some((start, end))
-    } else {
-        None
+/// Count fresh IDs absent from the availability section
+/// Set difference via interval arithmetic: |fresh| - |fresh ∩ available|
+fn count_missing_fresh(inventory: &Inventory) -> u128 {
+    count_total_fresh(inventory) - count_fresh_available(inventory, true)
+}
+
+/// The two sections of the puzzle input, as inclusive (start, end) ranges
+struct Inventory {
+    fresh: Vec<Interval>,
+    /// None when there is no blank line, i.e. only the fresh ranges were given
+    available: Option<Vec<Available>>,
+    /// Lines that could not be parsed, with their line numbers
+    diagnostics: Diagnostics,
+    /// The availability section came first in the input
+    swapped: bool,
+}
+
+/// One line of the availability section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Available {
+    /// 1-based line number in the input
+    line: usize,
+    range: Interval,
+}
+
+/// Check that `kept`, the first lines of `full` (--limit-lines), still has
+/// the blank line between the two sections when `full` has one
+pub fn check_truncated(full: &str, kept: &str) -> Result<(), Error> {
+    let blank = |text: &str| text.lines().position(|line| line.trim().is_empty());
+    match (blank(full), blank(kept)) {
+        (Some(line), None) => Err(Error::Input(format!(
+            "the first {} lines stop before the blank line between the sections; keep at least {} lines",
+            kept.lines().count(),
+            line + 1
+        ))),
+        _ => Ok(()),
     }
 }
 
-/// Check if an ingredient ID is fresh (falls within any range)
-fn is_fresh(id: u64, ranges: &[(u64, u64)]) -> bool {
-    ranges.iter().any(|&(start, end)| id >= start && id <= end)
+/// Split the input into fresh ranges (section 1) and available ranges (section 2)
+/// Bare IDs in section 2 become single-ID ranges, e.g. "17" → (17, 17)
+///
+/// Lines that fail to parse are skipped and recorded as diagnostics, as are
+/// reversed ranges (swapped) and a second blank-line break that would start
+/// an ambiguous third section. Without any blank line the whole input is
+/// treated as fresh ranges, which is all Part 2 needs.
+///
+/// Sections in the wrong order are put back as described under SECTION
+/// ORDER; `force_swap` reads them the other way round regardless. With
+/// `lenient`, numbers may carry `_` and space separators (--lenient-numbers).
+fn parse_sections(input: &str, force_swap: bool, lenient: bool) -> Result<Inventory, Error> {
+    let lines: Vec<(usize, &str)> = input.lines().enumerate().map(|(idx, line)| (idx + 1, line)).collect();
+    let blank = lines.iter().position(|(_, line)| line.trim().is_empty());
+
+    let swapped = match blank {
+        Some(blank) => {
+            let (first, rest) = lines.split_at(blank);
+            match (section_kind(first, lenient), section_kind(&rest[1..], lenient)) {
+                _ if force_swap => true,
+                (SectionKind::Ids, SectionKind::Ranges) => true,
+                (SectionKind::Ids, SectionKind::Ids) => {
+                    return Err(Error::Input(
+                        "both sections hold bare IDs; expected fresh ranges (start-end), a blank line, \
+                         then available IDs"
+                            .to_string(),
+                    ));
+                }
+                _ => false,
+            }
+        }
+        None => false,
+    };
+    tracing::trace!(blank_line = blank.map(|i| lines[i].0), force_swap, swapped, "split the sections");
+
+    // Feed the scanner the availability lines last, whatever their place
+    let ordered: Vec<(usize, &str)> = match blank {
+        Some(blank) if swapped => {
+            let (first, rest) = lines.split_at(blank);
+            let ranges = rest[1..].iter().filter(|(_, line)| !line.trim().is_empty());
+            ranges.chain(&rest[..1]).chain(first).copied().collect()
+        }
+        _ => lines,
+    };
+
+    let mut scanner = SectionScanner { lenient, ..SectionScanner::default() };
+    let mut fresh = Vec::new();
+    let mut available = None;
+
+    for (line_no, line) in ordered {
+        match scanner.line(line_no, line) {
+            Some(Entry::Fresh(range)) => fresh.push(range),
+            Some(Entry::Separator) => available = Some(Vec::new()),
+            Some(Entry::Available(entry)) => available.get_or_insert_with(Vec::new).push(entry),
+            None => {}
+        }
+    }
+
+    Ok(Inventory { fresh, available, diagnostics: scanner.diagnostics, swapped })
+}
+
+/// What the lines of one section look like, malformed ones aside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionKind {
+    /// Only bare IDs such as "17"
+    Ids,
+    /// Only start-end ranges such as "3-5"
+    Ranges,
+    /// Both, or neither
+    Other,
 }
 
-/// Merge overlapping ranges to avoid counting IDs multiple times
-/// For example: [(3,5), (10,14), (12,18)] becomes [(3,5), (10,18)]
-fn merge_ranges(ranges: &mut [(u64, u64)]) -> Vec<(u64, u64)> {
-    if ranges.is_empty() {
-        return Vec::new();
+fn section_kind(lines: &[(usize, &str)], lenient: bool) -> SectionKind {
+    let (mut ids, mut ranges) = (false, false);
+    for (_, line) in lines {
+        if interval::parse_number(line.trim(), lenient).is_some() {
+            ids = true;
+        } else if interval::parse_range(line, lenient).is_some() {
+            ranges = true;
+        }
     }
-    
-    // Sort ranges by start position
-    ranges.sort_by_key(|&(start, _)| start);
-    
-    let mut merged: Vec<(u64, u64)> = Vec::new();
-    let mut current = ranges[0];
-    
-    for &(start, end) in &ranges[1..] {
-        // If ranges overlap or are adjacent, merge them
-        if start <= current.1 + 1 {
-            current.1 = current.1.max(end);
-        } else {
-            // No overlap, save current and start a new range
-            merged.push(current);
-            current = (start, end);
+    match (ids, ranges) {
+        (true, false) => SectionKind::Ids,
+        (false, true) => SectionKind::Ranges,
+        _ => SectionKind::Other,
+    }
+}
+
+/// What one input line contributes, as reported by `SectionScanner`
+enum Entry {
+    Fresh(Interval),
+    /// The blank line that ends the fresh section
+    Separator,
+    Available(Available),
+}
+
+/// Classifies input lines one at a time, so the same rules serve both the
+/// in-memory parser and the streaming solver
+#[derive(Default)]
+struct SectionScanner {
+    in_available: bool,
+    /// Any entry seen yet in the availability section
+    seen_available: bool,
+    /// A blank line followed an availability entry
+    after_blank: bool,
+    /// Numbers may carry `_` and space separators (--lenient-numbers)
+    lenient: bool,
+    diagnostics: Diagnostics,
+}
+
+impl SectionScanner {
+    /// Classify one line; None for skipped (blank or malformed) lines
+    fn line(&mut self, line_no: usize, line: &str) -> Option<Entry> {
+        if !self.in_available {
+            // The first blank line separates ranges from ingredient IDs
+            if line.trim().is_empty() {
+                self.in_available = true;
+                return Some(Entry::Separator);
+            }
+
+            // Parse a fresh ingredient range (e.g., "3-5" means IDs 3, 4, 5 are fresh)
+            let range = parse_fresh(line_no, line, self.lenient, &mut self.diagnostics);
+            if range.is_none() {
+                self.diagnostics.push(line_no, line, "malformed fresh range, expected start-end");
+            }
+            return range.map(Entry::Fresh);
+        }
+
+        if line.trim().is_empty() {
+            // Only blank lines *between* entries are suspicious
+            self.after_blank = self.seen_available || self.after_blank;
+            return None;
+        }
+        if self.after_blank {
+            self.diagnostics.push(
+                line_no,
+                line,
+                "blank line starts a third section, treating it as more available IDs",
+            );
+            self.after_blank = false;
+        }
+
+        // Parse an available ingredient (bare ID or range)
+        match parse_available(line_no, line, self.lenient, &mut self.diagnostics) {
+            Some(range) => {
+                self.seen_available = true;
+                Some(Entry::Available(Available { line: line_no, range }))
+            }
+            None => {
+                self.diagnostics.push(line_no, line, "malformed available ID, expected an ID or start-end");
+                None
+            }
+        }
+    }
+}
+
+/// Parse a fresh range line, noting a diagnostic if its bounds were reversed
+fn parse_fresh(line_no: usize, line: &str, lenient: bool, diagnostics: &mut Diagnostics) -> Option<Interval> {
+    let parsed = interval::parse_range(line, lenient)?;
+    if parsed.reversed {
+        let (start, end) = parsed.range;
+        diagnostics.push(line_no, line, format!("reversed range, treating it as {}-{}", start, end));
+    }
+    Some(parsed.range)
+}
+
+/// Parse an availability line: a bare ID "17" becomes (17, 17),
+/// a range "5-8" is parsed like a fresh range
+fn parse_available(line_no: usize, line: &str, lenient: bool, diagnostics: &mut Diagnostics) -> Option<Interval> {
+    match interval::parse_number(line.trim(), lenient) {
+        Some(id) => Some((id, id)),
+        None => parse_fresh(line_no, line, lenient, diagnostics),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+
+    fn inventory(input: &str) -> Inventory {
+        parse_sections(input, false, false).unwrap()
+    }
+
+    #[test]
+    fn truncation_must_keep_the_section_break() {
+        let kept = |lines| crate::input::first_lines(EXAMPLE, lines).unwrap();
+        let e = check_truncated(EXAMPLE, kept(3)).unwrap_err().to_string();
+        assert_eq!(e, "the first 3 lines stop before the blank line between the sections; keep at least 5 lines");
+        assert!(check_truncated(EXAMPLE, kept(4)).is_err());
+        assert!(check_truncated(EXAMPLE, kept(5)).is_ok());
+        assert!(check_truncated("3-5\n10-14\n16-20\n", "3-5\n").is_ok());
+    }
+
+    #[test]
+    fn example_answers() {
+        assert_eq!(count_fresh_available(&inventory(EXAMPLE), false), 3);
+        assert_eq!(count_total_fresh(&inventory(EXAMPLE)), 14);
+    }
+
+    #[test]
+    fn availability_range_straddling_fresh_boundary() {
+        // 8-12 overlaps fresh 10-20 in 10, 11, 12 only
+        assert_eq!(count_fresh_available(&inventory("10-20\n\n8-12\n"), false), 3);
+        // 18-25 overlaps fresh 10-20 in 18, 19, 20 only
+        assert_eq!(count_fresh_available(&inventory("10-20\n\n18-25\n"), false), 3);
+    }
+
+    #[test]
+    fn availability_range_outside_fresh_ranges() {
+        assert_eq!(count_fresh_available(&inventory("3-5\n10-20\n\n6-9\n21-30\n"), false), 0);
+    }
+
+    #[test]
+    fn missing_counts_fresh_ids_not_available() {
+        // 14 fresh IDs, 3 of which (5, 11, 17) are available
+        assert_eq!(count_missing_fresh(&inventory(EXAMPLE)), 11);
+    }
+
+    #[test]
+    fn missing_subtracts_duplicated_ids_once() {
+        assert_eq!(count_missing_fresh(&inventory("3-5\n10-20\n\n5\n5\n11\n11\n11\n")), 12);
+    }
+
+    #[test]
+    fn overlapping_availability_is_counted_once() {
+        // 11-15, 13-17 and the bare 14 all overlap: 11..=17 is 7 IDs
+        assert_eq!(count_fresh_available(&inventory("10-20\n\n11-15\n13-17\n14\n"), false), 7);
+    }
+
+    #[test]
+    fn diagnostics_report_line_numbers() {
+        let inv = inventory("3-5\n16_20\n10-14\n\n1\n5x\n8\n");
+        let lines: Vec<(usize, &str)> = inv
+            .diagnostics
+            .items()
+            .iter()
+            .map(|d| (d.line, d.text.as_str()))
+            .collect();
+        assert_eq!(lines, vec![(2, "16_20"), (6, "5x")]);
+        // The good lines still parse
+        assert_eq!(inv.fresh, vec![(3, 5), (10, 14)]);
+        let available: Vec<Interval> = inv.available.unwrap().iter().map(|a| a.range).collect();
+        assert_eq!(available, vec![(1, 1), (8, 8)]);
+    }
+
+    #[test]
+    fn third_section_is_reported() {
+        let inv = inventory("3-5\n\n1\n\n\n5\n\n");
+        assert_eq!(inv.diagnostics.items().len(), 1);
+        assert_eq!(inv.diagnostics.items()[0].line, 6);
+        // Extra blank lines right after the separator are not a third section
+        assert!(inventory("3-5\n\n\n1\n").diagnostics.items().is_empty());
+    }
+
+    #[test]
+    fn strict_mode_fails_on_diagnostics() {
+        let opts = DayOptions::default().with_strict(true);
+        let mut out = Output::capture();
+        assert!(matches!(solve("3-5\n16_20\n\n1\n", false, &opts, &mut out), Err(Error::Parse(_))));
+        assert!(solve("3-5\n\n1\n", false, &opts, &mut out).is_ok());
+    }
+
+    #[test]
+    fn lenient_numbers_accept_separators_and_strict_still_rejects_them() {
+        let input = "1_000-2_500\n998 - 1012\n\n1 010\n2_501\n";
+        let lenient = DayOptions::default().with_lenient_numbers(true);
+        let mut out = Output::capture();
+        assert_eq!(solve(input, false, &lenient, &mut out).unwrap().to_string(), "1");
+        assert_eq!(solve(input, true, &lenient, &mut out).unwrap().to_string(), "1503");
+        assert!(out.warnings().is_empty());
+
+        let strict = DayOptions::default().with_strict(true);
+        assert!(matches!(solve(input, false, &strict, &mut out), Err(Error::Parse(_))));
+
+        // "10, 14" is two IDs run together, not 1014
+        let inv = parse_sections("1000-1020\n\n10, 14\n", false, true).unwrap();
+        assert!(inv.available.unwrap().is_empty());
+        assert_eq!(inv.diagnostics.items()[0].line, 3);
+    }
+
+    #[test]
+    fn show_merged_lists_ranges_without_touching_answer() {
+        assert_eq!(merged_fresh_ranges(EXAMPLE).unwrap(), vec![(3, 5), (10, 20)]);
+
+        let opts = DayOptions::new(vec!["--show-merged".to_string()]);
+        let mut out = Output::capture();
+        let answer = solve(EXAMPLE, true, &opts, &mut out).unwrap();
+        assert_eq!(answer.to_string(), "14");
+        assert_eq!(out.lines(), ["3-5 (3 ids)", "10-20 (11 ids)", "Total: 14 ids"]);
+    }
+
+    #[test]
+    fn reversed_range_is_swapped_not_wrapped() {
+        // Used to be (20, 16): end - start + 1 wrapped to an enormous count
+        let inv = inventory("20-16");
+        assert_eq!(count_total_fresh(&inv), 5);
+        assert_eq!(inv.diagnostics.items()[0].line, 1);
+
+        let mut out = Output::capture();
+        let answer = solve("20-16", true, &DayOptions::default(), &mut out).unwrap();
+        assert_eq!(answer.to_string(), "5");
+        assert_eq!(out.warnings().len(), 1);
+
+        let strict = DayOptions::default().with_strict(true);
+        assert!(matches!(solve("20-16", true, &strict, &mut out), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn part1_needs_the_available_section() {
+        let mut out = Output::capture();
+        assert!(matches!(solve("3-5\n", false, &DayOptions::default(), &mut out), Err(Error::Input(_))));
+    }
+
+    #[test]
+    fn duplicate_ids_count_per_occurrence_unless_distinct() {
+        let input = "10-20\n\n11\n11\n";
+        assert_eq!(count_fresh_available(&inventory(input), false), 2);
+        assert_eq!(count_fresh_available(&inventory(input), true), 1);
+
+        let opts = DayOptions::new(vec!["--verbose".to_string(), "--distinct".to_string()]);
+        let mut out = Output::capture();
+        assert_eq!(solve(input, false, &opts, &mut out).unwrap().to_string(), "1");
+        assert_eq!(
+            out.lines(),
+            ["11: fresh (10-20)", "11: fresh (10-20) (duplicate of line 3, not counted)"]
+        );
+    }
+
+    #[test]
+    fn classify_reports_the_unique_matching_range() {
+        let merged = merged_fresh_ranges(EXAMPLE).unwrap();
+        for id in [5, 11, 17] {
+            let Freshness::Fresh { range } = classify(id, &merged) else {
+                panic!("{} should be fresh", id);
+            };
+            // Merged ranges are disjoint: exactly one of them holds the ID
+            let holders: Vec<_> = merged.iter().filter(|r| r.0 <= id && id <= r.1).collect();
+            assert_eq!(holders, [&range]);
+        }
+        assert_eq!(classify(17, &merged), Freshness::Fresh { range: (10, 20) });
+        assert_eq!(classify(8, &merged), Freshness::Spoiled { closest: Some(((10, 20), 2)) });
+    }
+
+    #[test]
+    fn verbose_prints_matched_range() {
+        let opts = DayOptions::new(vec!["--verbose".to_string()]);
+        let mut out = Output::capture();
+        solve(EXAMPLE, false, &opts, &mut out).unwrap();
+        assert_eq!(out.lines()[4], "17: fresh (10-20)");
+        assert_eq!(out.lines()[5], "32: spoiled, 12 away from 10-20");
+    }
+
+    #[test]
+    fn gap_analysis_on_example() {
+        assert_eq!(fresh_gaps(EXAMPLE).unwrap(), vec![(6, 9)]);
+
+        let opts = DayOptions::new(vec!["--gaps".to_string(), "--verbose".to_string()]);
+        let mut out = Output::capture();
+        assert_eq!(solve(EXAMPLE, false, &opts, &mut out).unwrap().to_string(), "4");
+        assert_eq!(out.lines(), ["6-9 (4 ids)"]);
+    }
+
+    #[test]
+    fn gap_count_does_not_overflow_at_u64_max() {
+        let merged = [(0, 1), (u64::MAX - 1, u64::MAX)];
+        assert_eq!(count_gap_ids(&merged), u64::MAX as u128 - 3);
+    }
+
+    #[test]
+    fn swapped_sections_are_put_back() {
+        let swapped = "1\n5\n8\n11\n17\n32\n\n3-5\n10-14\n16-20\n12-18\n";
+        for part2 in [false, true] {
+            let mut out = Output::capture();
+            let expected = solve(EXAMPLE, part2, &DayOptions::default(), &mut out).unwrap();
+            assert!(out.lines().is_empty() && out.warnings().is_empty());
+
+            let answer = solve(swapped, part2, &DayOptions::default(), &mut out).unwrap();
+            assert_eq!(answer, expected);
+            assert!(out.lines()[0].starts_with("Note: the available IDs come before"));
+            assert!(out.warnings().is_empty());
         }
+        // Line numbers still point into the input as written
+        let inv = inventory("1\n8x\n\n3-5\n");
+        assert!(inv.swapped);
+        assert_eq!(inv.diagnostics.items()[0].line, 2);
+    }
+
+    #[test]
+    fn sections_of_the_same_kind_are_not_swapped() {
+        let mut out = Output::capture();
+        let err = solve("1\n5\n\n8\n", false, &DayOptions::default(), &mut out).unwrap_err();
+        assert!(err.to_string().contains("both sections hold bare IDs"));
+
+        // Availability ranges keep the documented order unless told otherwise
+        let input = "10-20\n\n18-25\n";
+        assert_eq!(solve(input, true, &DayOptions::default(), &mut out).unwrap().to_string(), "11");
+        let swap = DayOptions::new(vec!["--swapped-sections".to_string()]);
+        assert_eq!(solve(input, true, &swap, &mut out).unwrap().to_string(), "8");
+        assert_eq!(solve(input, false, &swap, &mut out).unwrap().to_string(), "3");
     }
-    
-    // Don't forget the last range
-    merged.push(current);
-    merged
-}
\ No newline at end of file
+
+    #[test]
+    fn spoiled_ids_report_the_nearest_range() {
+        let merged = merged_fresh_ranges(EXAMPLE).unwrap();
+        let report = |id: u64| format!("{}: {}", id, classify(id, &merged));
+        // The example's spoiled IDs: below the first range, in the gap, above the last
+        assert_eq!(report(1), "1: spoiled, 2 away from 3-5");
+        assert_eq!(report(8), "8: spoiled, 2 away from 10-20");
+        assert_eq!(report(32), "32: spoiled, 12 away from 10-20");
+
+        // Right next to a boundary is 1 away; a tie goes to the lower range
+        assert_eq!(report(6), "6: spoiled, 1 away from 3-5");
+        assert_eq!(report(9), "9: spoiled, 1 away from 10-20");
+        assert_eq!(report(21), "21: spoiled, 1 away from 10-20");
+        assert_eq!(classify(7, &[(3, 5), (9, 20)]), Freshness::Spoiled { closest: Some(((3, 5), 2)) });
+        assert_eq!(classify(7, &[]), Freshness::Spoiled { closest: None });
+    }
+
+    #[test]
+    fn a_cancelled_stream_gives_up() {
+        let cancel = crate::cancel::Cancel::default();
+        cancel.cancel();
+        let opts = DayOptions::default().with_cancel(cancel);
+        let mut reader = InputReader::from_text(EXAMPLE);
+        assert!(matches!(solve_stream(&mut reader, true, &opts, &mut Output::capture()), Err(Error::Cancelled)));
+    }
+}
@@ -0,0 +1,57 @@
+// Parse diagnostics
+//
+// Solvers collect a Diagnostic for every input line they cannot make sense
+// of instead of skipping it silently. By default the diagnostics are printed
+// as warnings and the line is ignored; under `--strict` they fail the run.
+
+use std::fmt;
+
+use crate::error::Error;
+use crate::output::Output;
+
+/// A problem with a single input line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line number in the input
+    pub line: usize,
+    /// The offending line, trimmed
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} ('{}')", self.line, self.message, self.text)
+    }
+}
+
+/// All diagnostics collected while parsing one input
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn push(&mut self, line: usize, text: &str, message: impl Into<String>) {
+        self.items.push(Diagnostic {
+            line,
+            text: text.trim().to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn items(&self) -> &[Diagnostic] {
+        &self.items
+    }
+
+    /// Warn about every diagnostic, or fail when `strict` is set
+    pub fn report(self, strict: bool, out: &mut Output) -> Result<(), Error> {
+        if strict && !self.items.is_empty() {
+            return Err(Error::Parse(self.items));
+        }
+        for diagnostic in self.items() {
+            out.warn(diagnostic);
+        }
+        Ok(())
+    }
+}
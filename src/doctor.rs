@@ -0,0 +1,447 @@
+// `adventcode doctor`
+//
+// Checks the setup that most often goes wrong and prints one line per check
+// (OK, WARN or FAIL) with a remedy under anything that is not OK: can the day
+// modules be found, which days lack input, does the session token still work
+// on adventofcode.com, do answers.toml and adventcode.toml parse, and can this
+// terminal show the TUI. Each check is a plain function of what it inspects,
+// so tests point them at temp directories and a fake HTTP client.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::budget::Budgets;
+use crate::config::Config;
+use crate::external;
+use crate::remote;
+use crate::DayInfo;
+
+/// Fetched with the session cookie to see whether the token still works;
+/// anonymous requests are redirected to the login page
+pub const SESSION_CHECK_URL: &str = "https://adventofcode.com/settings";
+
+/// Keys adventcode.toml may contain
+pub const CONFIG_KEYS: &[&str] = &[
+    "input_dir",
+    "answers",
+    "theme",
+    "keys",
+    "default_part",
+    "notify_after",
+    "accessible",
+    "quiet",
+    "time",
+    "session",
+    "external",
+    "max_time",
+    "input_url",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // pad, not write_str, so report's {:<5} lines the names up
+        f.pad(match self {
+            Status::Ok => "OK",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        })
+    }
+}
+
+/// The outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    /// What to do about a WARN or FAIL
+    pub remedy: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, message: message.into(), remedy: None }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, remedy: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, message: message.into(), remedy: Some(remedy.into()) }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, remedy: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, message: message.into(), remedy: Some(remedy.into()) }
+    }
+}
+
+/// Makes the session check's request; tests substitute a fake
+pub trait Http {
+    /// The HTTP status of GET `url` with the session cookie, without following
+    /// redirects; Err when there was no response at all
+    fn status(&self, url: &str, session: &str) -> Result<u16, String>;
+}
+
+/// The real thing, over HTTPS
+pub struct Web;
+
+impl Http for Web {
+    #[cfg(feature = "fetch")]
+    fn status(&self, url: &str, session: &str) -> Result<u16, String> {
+        let agent = ureq::AgentBuilder::new()
+            .redirects(0)
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent("github.com/zsh28/adventcode2025 (doctor)")
+            .build();
+        match agent.get(url).set("Cookie", &format!("session={}", session)).call() {
+            Ok(response) => Ok(response.status()),
+            Err(ureq::Error::Status(code, _)) => Ok(code),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    #[cfg(not(feature = "fetch"))]
+    fn status(&self, _url: &str, _session: &str) -> Result<u16, String> {
+        Err("this build cannot make HTTPS requests (built without the `fetch` feature)".into())
+    }
+}
+
+/// Are there day modules to run?
+pub fn day_modules(days: &[DayInfo]) -> Check {
+    const NAME: &str = "Day modules";
+    if days.is_empty() {
+        return Check::fail(NAME, "no src/dayN.rs found", "run adventcode from the crate root, where src/ lives");
+    }
+    let numbers: Vec<String> = days.iter().map(|d| d.number.to_string()).collect();
+    Check::ok(NAME, format!("found days {}", numbers.join(", ")))
+}
+
+/// Which days would have nothing to read
+pub fn inputs(days: &[DayInfo]) -> Check {
+    const NAME: &str = "Inputs";
+    let missing: Vec<&DayInfo> = days.iter().filter(|d| !d.has_input && !d.embedded).collect();
+    match missing.as_slice() {
+        [] => Check::ok(NAME, "every day has an input"),
+        [day] => Check::warn(
+            NAME,
+            format!("no input for day {}", day.number),
+            format!("save your puzzle input as {}", day.input_path.display()),
+        ),
+        _ => {
+            let numbers: Vec<String> = missing.iter().map(|d| d.number.to_string()).collect();
+            Check::warn(
+                NAME,
+                format!("no input for days {}", numbers.join(", ")),
+                "save each puzzle input as dayN.txt next to Cargo.toml",
+            )
+        }
+    }
+}
+
+/// Is there a session token, and does adventofcode.com accept it?
+pub fn session(configured: Option<&str>, path: Option<&Path>, http: &dyn Http) -> Check {
+    const NAME: &str = "Session token";
+    let token = match configured {
+        Some(token) => Some(token.trim().to_string()),
+        None => path.and_then(|p| fs::read_to_string(p).ok()).map(|t| t.trim().to_string()),
+    };
+    let Some(token) = token.filter(|t| !t.is_empty()) else {
+        return Check::warn(NAME, "none stored, inputs must be saved by hand", "run `adventcode init` to store one");
+    };
+    match http.status(SESSION_CHECK_URL, &token) {
+        Ok(200) => Check::ok(NAME, "accepted by adventofcode.com"),
+        Ok(status) => Check::fail(
+            NAME,
+            format!("rejected by adventofcode.com (HTTP {}), it has probably expired", status),
+            "log in again and store the new `session` cookie with `adventcode init`",
+        ),
+        Err(e) => Check::warn(NAME, format!("could not check it: {}", e), "check your network connection and try again"),
+    }
+}
+
+/// One day's table in answers.toml
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // only read for the TUI's header
+struct KnownAnswers {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+/// Does answers.toml parse?
+pub fn answers(path: &Path) -> Check {
+    const NAME: &str = "Answers file";
+    let Ok(text) = fs::read_to_string(path) else {
+        return Check::warn(NAME, format!("no {}", path.display()), "run `adventcode init` to create one");
+    };
+    let remedy = format!("fix {}: one [dayN] table per day, with part1 and part2", path.display());
+    let tables: BTreeMap<String, KnownAnswers> = match toml::from_str(&text) {
+        Ok(tables) => tables,
+        Err(e) => return Check::fail(NAME, format!("{} does not parse: {}", path.display(), first_line(&e)), remedy),
+    };
+    let bad: Vec<&String> = tables.keys().filter(|key| !is_day_table(key)).collect();
+    if let Some(key) = bad.first() {
+        return Check::fail(NAME, format!("unknown table [{}] in {}", key, path.display()), remedy);
+    }
+    Check::ok(NAME, format!("{} parses, answers for {} day(s)", path.display(), tables.len()))
+}
+
+/// The days answers.toml has both parts for, in order; none when the file
+/// is missing or does not parse
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub fn answered_days(path: &Path) -> Vec<u8> {
+    let tables: BTreeMap<String, KnownAnswers> =
+        fs::read_to_string(path).ok().and_then(|text| toml::from_str(&text).ok()).unwrap_or_default();
+    let mut days: Vec<u8> = tables
+        .iter()
+        .filter(|(_, answers)| answers.part1.is_some() && answers.part2.is_some())
+        .filter_map(|(key, _)| day_table(key))
+        .collect();
+    days.sort_unstable();
+    days
+}
+
+fn is_day_table(key: &str) -> bool {
+    day_table(key).is_some()
+}
+
+/// 5 for "day5"
+fn day_table(key: &str) -> Option<u8> {
+    key.strip_prefix("day").and_then(|n| n.parse::<u8>().ok()).filter(|n| (1..=25).contains(n))
+}
+
+/// Does adventcode.toml (or the user config) parse, and does it only use
+/// keys we know?
+pub fn config(path: &Path) -> Check {
+    const NAME: &str = "Config file";
+    let Ok(text) = fs::read_to_string(path) else {
+        return Check::ok(NAME, format!("no {}, using the defaults", path.display()));
+    };
+    let table: toml::Table = match text.parse() {
+        Ok(table) => table,
+        Err(e) => {
+            let message = format!("{} does not parse: {}", path.display(), first_line(&e));
+            return Check::fail(NAME, message, format!("fix the syntax in {}", path.display()));
+        }
+    };
+    if let Err(e) = Config::load(path) {
+        return Check::fail(NAME, e, "see the settings section of the README for each key's type");
+    }
+    if let Err(e) = external::load(path) {
+        return Check::fail(NAME, e, "map day1 to day25 to command strings under [external]");
+    }
+    if let Err(e) = Budgets::load(path, Duration::ZERO) {
+        return Check::fail(NAME, e, "give day1 to day25 budgets such as \"2s\" under [max_time]");
+    }
+    if let Err(e) = remote::Credentials::load(path) {
+        return Check::fail(NAME, e, "give bearer and cookie as strings under [input_url]");
+    }
+    let unknown: Vec<&str> = table.keys().map(String::as_str).filter(|key| !CONFIG_KEYS.contains(key)).collect();
+    if unknown.is_empty() {
+        return Check::ok(NAME, format!("{} parses", path.display()));
+    }
+    Check::warn(
+        NAME,
+        format!("unknown key(s) in {}: {}", path.display(), unknown.join(", ")),
+        format!("remove them or check the spelling; known keys are {}", CONFIG_KEYS.join(", ")),
+    )
+}
+
+/// What the terminal check looks at, gathered by `Terminal::detect`
+pub struct Terminal {
+    /// Built with the `tui` feature
+    pub tui_built: bool,
+    /// Both stdin and stdout are terminals
+    pub interactive: bool,
+    pub term: Option<String>,
+    /// Emoji and box drawing will render (otherwise the TUI falls back to ASCII)
+    pub unicode: bool,
+}
+
+impl Terminal {
+    pub fn detect() -> Self {
+        use std::io::IsTerminal;
+        Self {
+            tui_built: cfg!(feature = "tui"),
+            interactive: io::stdin().is_terminal() && io::stdout().is_terminal(),
+            term: std::env::var("TERM").ok(),
+            unicode: crate::glyphs::unicode_supported(),
+        }
+    }
+}
+
+/// Can the TUI run here?
+pub fn terminal(terminal: &Terminal) -> Check {
+    const NAME: &str = "Terminal";
+    if !terminal.tui_built {
+        return Check::warn(NAME, "this build has no TUI", "rebuild with the default `tui` feature, or pass --day N");
+    }
+    if !terminal.interactive {
+        return Check::warn(NAME, "not running in a terminal, the TUI cannot start", "run in a terminal, or pass --day N");
+    }
+    if terminal.term.as_deref() == Some("dumb") {
+        return Check::warn(NAME, "TERM=dumb cannot draw the TUI", "use a terminal emulator, or pass --day N");
+    }
+    if !terminal.unicode {
+        return Check::warn(NAME, "no UTF-8 locale, the TUI will use ASCII", "set LANG to a UTF-8 locale, e.g. C.UTF-8");
+    }
+    Check::ok(NAME, "ready for the TUI")
+}
+
+/// Print each check as "OK    Name: message", with its remedy underneath
+/// Returns the exit code: 1 if anything failed
+pub fn report(checks: &[Check], out: &mut dyn Write) -> io::Result<i32> {
+    for check in checks {
+        writeln!(out, "{:<5} {}: {}", check.status, check.name, check.message)?;
+        if let Some(remedy) = &check.remedy {
+            writeln!(out, "      fix: {}", remedy)?;
+        }
+    }
+    Ok(i32::from(checks.iter().any(|c| c.status == Status::Fail)))
+}
+
+/// toml errors span several lines with a source excerpt; the first says it
+fn first_line(e: &impl fmt::Display) -> String {
+    e.to_string().lines().next().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Catalog;
+
+    /// Answers the session check with a fixed status, or no response
+    struct FakeHttp(Result<u16, String>);
+
+    impl Http for FakeHttp {
+        fn status(&self, url: &str, session: &str) -> Result<u16, String> {
+            assert_eq!(url, SESSION_CHECK_URL);
+            assert_eq!(session, "abc123", "the token is sent trimmed");
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn day_modules_and_inputs_come_from_the_root() {
+        let root = tempfile::tempdir().unwrap();
+        let days = Catalog::discover_in(root.path(), Path::new("")).days;
+        assert_eq!(day_modules(&days).status, Status::Fail);
+        assert_eq!(inputs(&days).status, Status::Ok);
+
+        fs::create_dir(root.path().join("src")).unwrap();
+        for day in [1, 2, 3] {
+            fs::write(root.path().join(format!("src/day{}.rs", day)), "").unwrap();
+        }
+        fs::write(root.path().join("day1.txt"), "L68\n").unwrap();
+        fs::write(root.path().join("day2.txt.gz"), "").unwrap();
+        let days = Catalog::discover_in(root.path(), Path::new("")).days;
+        assert_eq!(day_modules(&days), Check::ok("Day modules", "found days 1, 2, 3"));
+
+        let check = inputs(&days);
+        assert_eq!((check.status, check.message.as_str()), (Status::Warn, "no input for day 3"));
+        assert!(check.remedy.unwrap().ends_with("day3.txt"));
+    }
+
+    #[test]
+    fn session_token_is_checked_against_the_site() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session");
+        let never = FakeHttp(Err("unused".into()));
+        assert_eq!(session(None, None, &never).status, Status::Warn);
+        assert_eq!(session(None, Some(&path), &never).status, Status::Warn);
+        assert_eq!(session(Some("abc123"), Some(&path), &FakeHttp(Ok(200))).status, Status::Ok);
+
+        fs::write(&path, "abc123\n").unwrap();
+        assert_eq!(session(None, Some(&path), &FakeHttp(Ok(200))).status, Status::Ok);
+        let expired = session(None, Some(&path), &FakeHttp(Ok(302)));
+        assert_eq!(expired.status, Status::Fail);
+        assert!(expired.message.contains("HTTP 302"));
+        let offline = session(None, Some(&path), &FakeHttp(Err("dns error".into())));
+        assert_eq!(offline.status, Status::Warn);
+        assert!(offline.message.contains("dns error"));
+    }
+
+    #[test]
+    fn answers_file_must_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.toml");
+        assert_eq!(answers(&path).status, Status::Warn);
+
+        fs::write(&path, "# nothing yet\n").unwrap();
+        assert_eq!(answers(&path).status, Status::Ok);
+        fs::write(&path, "[day1]\npart1 = \"3\"\npart2 = 6\n\n[day5]\npart1 = \"3\"\n").unwrap();
+        assert!(answers(&path).message.ends_with("answers for 2 day(s)"));
+        assert_eq!(answered_days(&path), [1]);
+
+        fs::write(&path, "[day1]\npart1 = \"3\n").unwrap();
+        assert!(answers(&path).message.contains("does not parse"));
+        fs::write(&path, "[day1]\npart3 = \"3\"\n").unwrap();
+        assert_eq!(answers(&path).status, Status::Fail);
+        fs::write(&path, "[dya1]\npart1 = \"3\"\n").unwrap();
+        assert!(answers(&path).message.starts_with("unknown table [dya1]"));
+    }
+
+    #[test]
+    fn config_keys_are_known() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adventcode.toml");
+        assert_eq!(config(&path).status, Status::Ok);
+
+        fs::write(&path, "input_dir = \"inputs\"\nanswers = \"answers.toml\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Ok);
+        fs::write(&path, "input_dir = \"inputs\"\ninput_dri = \"x\"\n").unwrap();
+        let check = config(&path);
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.message.ends_with(": input_dri"));
+        fs::write(&path, "input_dir = \n").unwrap();
+        assert_eq!(config(&path).status, Status::Fail);
+        fs::write(&path, "theme = \"light\"\ndefault_part = 2\n").unwrap();
+        assert_eq!(config(&path).status, Status::Ok);
+        fs::write(&path, "default_part = 3\n").unwrap();
+        assert_eq!(config(&path).status, Status::Fail);
+        fs::write(&path, "[external]\nday7 = \"python3 day7.py\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Ok);
+        fs::write(&path, "[external]\nseven = \"python3 day7.py\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Fail);
+        fs::write(&path, "[max_time.day2]\npart2 = \"30s\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Ok);
+        fs::write(&path, "[max_time]\nday2 = \"a while\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Fail);
+        fs::write(&path, "[input_url]\nbearer = \"t0ken\"\n").unwrap();
+        assert_eq!(config(&path).status, Status::Ok);
+        fs::write(&path, "[input_url]\nbearer = true\n").unwrap();
+        assert_eq!(config(&path).status, Status::Fail);
+    }
+
+    #[test]
+    fn terminal_needs_a_tty_and_the_tui_feature() {
+        let ready = Terminal { tui_built: true, interactive: true, term: Some("xterm-256color".into()), unicode: true };
+        assert_eq!(terminal(&ready).status, Status::Ok);
+        assert_eq!(terminal(&Terminal { interactive: false, ..ready }).status, Status::Warn);
+        let dumb = Terminal { term: Some("dumb".into()), tui_built: true, interactive: true, unicode: true };
+        assert!(terminal(&dumb).message.contains("TERM=dumb"));
+        let no_tui = Terminal { tui_built: false, interactive: true, term: None, unicode: true };
+        assert!(terminal(&no_tui).message.contains("no TUI"));
+    }
+
+    #[test]
+    fn only_failures_set_the_exit_code() {
+        let mut out = Vec::new();
+        let warn = Check::warn("Inputs", "no input for day 3", "save it");
+        assert_eq!(report(&[Check::ok("Day modules", "found days 1"), warn.clone()], &mut out).unwrap(), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), "OK    Day modules: found days 1\nWARN  Inputs: no input for day 3\n      fix: save it\n");
+
+        let fail = Check::fail("Answers file", "bad", "fix it");
+        assert_eq!(report(&[warn, fail], &mut Vec::new()).unwrap(), 1);
+    }
+}
@@ -0,0 +1,304 @@
+// Input editor
+//
+// The TUI's edit mode for fixing a mangled line of dayN.txt in place: cursor
+// movement, typing, Backspace and Delete (which join lines at either edge),
+// Enter to split a line, and one level of undo. The buffer only reaches the
+// disk on `save`, which keeps the file's trailing newline (or its absence).
+// Compressed inputs are read-only here; edit the plain file instead.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use adventcode::compress::Compression;
+
+/// The buffer as it was before the last edit
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+#[derive(Debug)]
+pub struct Editor {
+    path: PathBuf,
+    lines: Vec<String>,
+    /// What the file holds, to tell whether there is anything to save
+    saved: Vec<String>,
+    trailing_newline: bool,
+    row: usize,
+    /// In characters, not bytes
+    col: usize,
+    undo: Option<Snapshot>,
+    /// First line and column on screen
+    top: usize,
+    left: usize,
+}
+
+impl Editor {
+    /// Load `path`; a missing file opens empty and is created on save
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if Compression::from_path(path).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} is compressed; decompress it to edit", path.display()),
+            ));
+        }
+        let (text, trailing_newline) = match fs::read_to_string(path) {
+            Ok(text) => {
+                let trailing = text.ends_with('\n');
+                (text.strip_suffix('\n').map(str::to_string).unwrap_or(text), trailing)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (String::new(), true),
+            Err(e) => return Err(e),
+        };
+        let lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+        Ok(Self {
+            path: path.to_path_buf(),
+            saved: lines.clone(),
+            lines,
+            trailing_newline,
+            row: 0,
+            col: 0,
+            undo: None,
+            top: 0,
+            left: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Line and column (in characters) of the cursor
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// Whether the buffer differs from the file
+    pub fn is_dirty(&self) -> bool {
+        self.lines != self.saved
+    }
+
+    /// The text `save` would write
+    pub fn contents(&self) -> String {
+        let mut text = self.lines.join("\n");
+        if self.trailing_newline {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Write the buffer back to its file, returning the bytes written
+    pub fn save(&mut self) -> io::Result<usize> {
+        let text = self.contents();
+        fs::write(&self.path, &text)?;
+        self.saved = self.lines.clone();
+        Ok(text.len())
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.checkpoint();
+        let at = self.byte(self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+    }
+
+    /// Break the line at the cursor (Enter)
+    pub fn split_line(&mut self) {
+        self.checkpoint();
+        let at = self.byte(self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    /// Delete before the cursor, joining with the line above at its start
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.checkpoint();
+            self.col -= 1;
+            let at = self.byte(self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            self.checkpoint();
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.width(self.row);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Delete under the cursor, joining with the line below at its end
+    pub fn delete(&mut self) {
+        if self.col < self.width(self.row) {
+            self.checkpoint();
+            let at = self.byte(self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            self.checkpoint();
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Put back the buffer from before the last edit; a second undo redoes it
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.take() {
+            let current = Snapshot { lines: std::mem::take(&mut self.lines), row: self.row, col: self.col };
+            (self.lines, self.row, self.col) = (snapshot.lines, snapshot.row, snapshot.col);
+            self.undo = Some(current);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.width(self.row);
+        }
+    }
+
+    pub fn right(&mut self) {
+        if self.col < self.width(self.row) {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.width(self.row));
+        }
+    }
+
+    pub fn down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.width(self.row));
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.col = self.width(self.row);
+    }
+
+    /// Scroll just enough to keep the cursor inside a `height` x `width`
+    /// view, returning its first line and column
+    pub fn scroll(&mut self, height: usize, width: usize) -> (usize, usize) {
+        let (height, width) = (height.max(1), width.max(1));
+        self.top = self.top.clamp(self.row.saturating_sub(height - 1), self.row);
+        self.left = self.left.clamp(self.col.saturating_sub(width - 1), self.col);
+        (self.top, self.left)
+    }
+
+    fn checkpoint(&mut self) {
+        self.undo = Some(Snapshot { lines: self.lines.clone(), row: self.row, col: self.col });
+    }
+
+    /// Characters on line `row`
+    fn width(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    /// Byte offset of character `col` on the cursor's line
+    fn byte(&self, col: usize) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str) -> (tempfile::TempDir, Editor) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        fs::write(&path, text).unwrap();
+        let editor = Editor::open(&path).unwrap();
+        (dir, editor)
+    }
+
+    #[test]
+    fn splits_and_joins_lines() {
+        let (_dir, mut ed) = editor("L68R30\nL5\n");
+        ed.right();
+        ed.right();
+        ed.right();
+        ed.split_line();
+        assert_eq!(ed.lines(), ["L68", "R30", "L5"]);
+        assert_eq!(ed.cursor(), (1, 0));
+
+        ed.backspace();
+        assert_eq!(ed.lines(), ["L68R30", "L5"]);
+        assert_eq!(ed.cursor(), (0, 3));
+        ed.end();
+        ed.delete();
+        assert_eq!(ed.contents(), "L68R30L5\n");
+
+        // Joining at the very ends does nothing
+        ed.down();
+        ed.end();
+        ed.delete();
+        ed.home();
+        ed.up();
+        ed.backspace();
+        assert_eq!(ed.contents(), "L68R30L5\n");
+    }
+
+    #[test]
+    fn undo_is_one_level_and_tracks_dirtiness() {
+        let (_dir, mut ed) = editor("é1");
+        ed.right();
+        ed.insert('x');
+        ed.insert('y');
+        assert_eq!(ed.contents(), "éxy1");
+        assert!(ed.is_dirty());
+
+        ed.undo();
+        assert_eq!(ed.contents(), "éx1");
+        assert_eq!(ed.cursor(), (0, 2));
+        ed.undo();
+        assert_eq!(ed.contents(), "éxy1");
+
+        ed.backspace();
+        ed.backspace();
+        ed.undo();
+        ed.undo();
+        assert_eq!(ed.contents(), "é1");
+        assert!(!ed.is_dirty());
+    }
+
+    #[test]
+    fn saves_and_creates_files() {
+        let (dir, mut ed) = editor("1\n2");
+        ed.insert('0');
+        assert_eq!(ed.save().unwrap(), 4);
+        assert_eq!(fs::read_to_string(dir.path().join("day1.txt")).unwrap(), "01\n2");
+        assert!(!ed.is_dirty());
+
+        let path = dir.path().join("day2.txt");
+        let mut ed = Editor::open(&path).unwrap();
+        assert_eq!(ed.lines(), [""]);
+        ed.insert('7');
+        ed.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "7\n");
+
+        assert!(Editor::open(&dir.path().join("day3.txt.gz")).is_err());
+    }
+}
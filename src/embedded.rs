@@ -0,0 +1,17 @@
+// Embedded inputs
+//
+// A build with the `embedded-inputs` feature carries its puzzle inputs inside
+// the binary: build.rs turns every inputs/dayN.txt into an include_str! entry
+// here. The CLI falls back to these when neither --file nor dayN.txt gives an
+// input. Without the feature the table is empty and no file is read.
+
+#[cfg(feature = "embedded-inputs")]
+include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+#[cfg(not(feature = "embedded-inputs"))]
+static EMBEDDED: &[(u8, &str)] = &[];
+
+/// The input baked in for a day, if there is one
+pub fn input(day: u8) -> Option<&'static str> {
+    EMBEDDED.iter().find(|(number, _)| *number == day).map(|(_, text)| *text)
+}
@@ -0,0 +1,53 @@
+// Errors returned by the day solvers
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::diagnostics::Diagnostic;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Malformed input lines, rejected because of `--strict`
+    Parse(Vec<Diagnostic>),
+    /// The input is structurally unusable (e.g. a missing section)
+    Input(String),
+    /// Reading a streamed input failed part way through
+    Io(std::io::Error),
+    /// Writing a file the day was asked to export failed
+    Export(PathBuf, std::io::Error),
+    /// The solve was asked to stop (see `cancel`)
+    Cancelled,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(diagnostics) => {
+                write!(f, "{} malformed input line(s):", diagnostics.len())?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n  {}", diagnostic)?;
+                }
+                Ok(())
+            }
+            Error::Input(message) => write!(f, "{}", message),
+            Error::Io(e) => write!(f, "failed to read input: {}", e),
+            Error::Export(path, e) => write!(f, "could not write {}: {}", path.display(), e),
+            Error::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// A simulation that never settles is an input the day cannot handle
+impl From<crate::sim::Runaway> for Error {
+    fn from(e: crate::sim::Runaway) -> Self {
+        Error::Input(e.to_string())
+    }
+}
@@ -0,0 +1,190 @@
+// Days solved by external commands
+//
+// adventcode.toml can hand a day to a program outside this crate:
+//
+//     [external]
+//     day7 = "python3 solutions/day7.py --part {part}"
+//
+// The command is split into words like a shell would (quotes work, pipes and
+// redirections do not), `{part}` becomes 1 or 2, and the input goes to its
+// stdin. Whatever it prints on stdout, trimmed, is the answer. A nonzero
+// exit or an empty stdout fails the run, with the command's stderr attached
+// so the real error is not lost. Such days are listed and run like the
+// crate's own, marked "(external)".
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use adventcode::output::{Answer, Value};
+
+/// How often a command with a deadline is checked on
+const POLL: Duration = Duration::from_millis(10);
+
+/// The `[external]` table of the config file at `path`: day number to
+/// command. A missing file (or one without the table) hands no day out; one
+/// that does not parse, or names something other than day1..day25, is an
+/// error.
+pub fn load(path: &Path) -> Result<BTreeMap<u8, String>, String> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(BTreeMap::new());
+    };
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| {
+        format!("{} does not parse: {}", path.display(), e.message())
+    })?;
+    let Some(external) = table.get("external") else {
+        return Ok(BTreeMap::new());
+    };
+    let external = external.as_table().ok_or_else(|| format!("{}: [external] must be a table", path.display()))?;
+    external
+        .iter()
+        .map(|(key, command)| {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=25).contains(n))
+                .ok_or_else(|| format!("{}: [external] {}: keys must be day1 to day25", path.display(), key))?;
+            let command = command
+                .as_str()
+                .filter(|command| !command.trim().is_empty())
+                .ok_or_else(|| format!("{}: [external] {}: the command must be a non-empty string", path.display(), key))?;
+            Ok((day, command.to_string()))
+        })
+        .collect()
+}
+
+/// Run `command` on `input` for one part and return what it printed; with a
+/// `timeout` the command is killed once it runs past it
+pub fn solve(command: &str, part2: bool, input: &str, timeout: Option<Duration>) -> Result<Answer, String> {
+    let line = command.replace("{part}", if part2 { "2" } else { "1" });
+    let words = shlex::split(&line).ok_or_else(|| format!("cannot split `{}` into words (unclosed quote?)", line))?;
+    let (program, args) = words.split_first().ok_or_else(|| "the external command is empty".to_string())?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start `{}`: {}", line, e))?;
+
+    // Feed stdin and drain both pipes at once, so a command that writes a
+    // lot before it reads everything cannot deadlock against us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    // A command may well exit without reading its input; that is its business
+    let writer = thread::spawn(move || drop(stdin.write_all(input.as_bytes())));
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    let status = wait(&mut child, timeout);
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let status = status.map_err(|e| format!("`{}` {}{}", line, e, stderr_note(&stderr)))?;
+    if !status.success() {
+        return Err(format!("`{}` failed ({}){}", line, status, stderr_note(&stderr)));
+    }
+    let answer = stdout.trim();
+    if answer.is_empty() {
+        return Err(format!("`{}` printed no answer{}", line, stderr_note(&stderr)));
+    }
+    let value = answer.parse::<u128>().map(Value::Number).unwrap_or_else(|_| Value::Text(answer.to_string()));
+    Ok(Answer::bare(value))
+}
+
+/// Read a pipe to the end on a thread of its own
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Wait for `child` to exit, killing it if it outlives `timeout`
+fn wait(child: &mut Child, timeout: Option<Duration>) -> io::Result<ExitStatus> {
+    let Some(timeout) = timeout else {
+        return child.wait();
+    };
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("timed out after {:.1}s and was stopped", timeout.as_secs_f64()),
+            ));
+        }
+        thread::sleep(POLL);
+    }
+}
+
+/// The command's stderr to tack onto an error, on one line so the --all
+/// summary and --files table keep one row per run
+fn stderr_note(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("; stderr: {}", lines.join(" | "))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_external_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adventcode.toml");
+        assert!(load(&path).unwrap().is_empty());
+
+        fs::write(&path, "input_dir = \"inputs\"\n\n[external]\nday7 = \"python3 day7.py --part {part}\"\n").unwrap();
+        assert_eq!(load(&path).unwrap(), BTreeMap::from([(7, "python3 day7.py --part {part}".to_string())]));
+
+        fs::write(&path, "[external]\nday26 = \"cat\"\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("day26: keys must be day1 to day25"));
+        fs::write(&path, "[external]\nday7 = 7\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("non-empty string"));
+        fs::write(&path, "external = \"cat\"\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("must be a table"));
+    }
+
+    #[test]
+    fn pipes_the_input_and_takes_stdout_as_the_answer() {
+        assert_eq!(solve("cat", false, "  42\n", None).unwrap(), Answer::bare(42u128));
+        assert_eq!(solve("echo 'part {part}'", true, "", None).unwrap(), Answer::bare("part 2".to_string()));
+        // Big enough to fill a pipe buffer both ways
+        let input = "x".repeat(1 << 20);
+        let Value::Text(echoed) = solve("cat", false, &input, None).unwrap().value else {
+            panic!("expected text")
+        };
+        assert_eq!(echoed.len(), input.len());
+    }
+
+    #[test]
+    fn failures_carry_the_commands_stderr() {
+        let failed = solve("sh -c 'echo boom >&2; echo half; exit 3'", false, "", None).unwrap_err();
+        assert!(failed.contains("failed (exit status: 3); stderr: boom"), "{}", failed);
+        let silent = solve("sh -c 'echo nothing to say >&2'", false, "", None).unwrap_err();
+        assert!(silent.ends_with("printed no answer; stderr: nothing to say"), "{}", silent);
+        assert!(solve("no-such-solver-here", false, "", None).unwrap_err().starts_with("could not start"));
+        assert!(solve("cat 'unclosed", false, "", None).unwrap_err().contains("unclosed quote"));
+
+        let start = Instant::now();
+        let slow = solve("sleep 5", false, "", Some(Duration::from_millis(100))).unwrap_err();
+        assert!(slow.contains("timed out after 0.1s"), "{}", slow);
+        assert!(start.elapsed() < Duration::from_secs(4));
+    }
+}
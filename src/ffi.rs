@@ -0,0 +1,129 @@
+// C ABI
+//
+// With the `ffi` feature the cdylib exports `aoc_solve` and `aoc_free_string`
+// for C and any language with a C FFI. include/adventcode.h is generated from
+// this file by cbindgen (see cbindgen.toml). Nothing unwinds across the
+// boundary: bad pointers, bad UTF-8 and panics all come back as status codes.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::input;
+use crate::options::DayOptions;
+use crate::output::Output;
+
+/// Solved; `*out` holds the answer
+pub const AOC_OK: i32 = 0;
+/// `input` or `out` was NULL
+pub const AOC_NULL_POINTER: i32 = 1;
+/// `input` was not valid UTF-8
+pub const AOC_INVALID_UTF8: i32 = 2;
+/// No such day in this build
+pub const AOC_UNKNOWN_DAY: i32 = 3;
+/// `part` was not 1 or 2
+pub const AOC_BAD_PART: i32 = 4;
+/// The solver rejected the input; `*out` holds its message
+pub const AOC_SOLVE_FAILED: i32 = 5;
+/// The solver panicked (a bug); `*out` is left NULL
+pub const AOC_PANIC: i32 = 6;
+
+/// Solve one part (1 or 2) of a day
+///
+/// `input` is a NUL-terminated UTF-8 string. On AOC_OK `*out` receives the
+/// answer as the CLI prints it, and on AOC_SOLVE_FAILED the error message;
+/// either way release it with `aoc_free_string`. Other codes leave `*out` NULL.
+///
+/// # Safety
+///
+/// `input` must be NULL or point to a NUL-terminated string, and `out` must be
+/// NULL or point to writable storage for one pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc_solve(day: u8, part: u8, input: *const c_char, out: *mut *mut c_char) -> i32 {
+    if input.is_null() || out.is_null() {
+        return AOC_NULL_POINTER;
+    }
+    // SAFETY: checked non-null above; the caller promises a valid pointer
+    unsafe { *out = ptr::null_mut() };
+
+    // SAFETY: the caller promises a NUL-terminated string
+    let Ok(text) = unsafe { CStr::from_ptr(input) }.to_str() else {
+        return AOC_INVALID_UTF8;
+    };
+
+    let (code, message) = match panic::catch_unwind(AssertUnwindSafe(|| solve(day, part, text))) {
+        Ok(Ok(answer)) => (AOC_OK, Some(answer)),
+        Ok(Err((code, message))) => (code, message),
+        Err(_) => (AOC_PANIC, None),
+    };
+
+    if let Some(message) = message {
+        // Interior NULs cannot happen in our own messages, but never panic here
+        let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+        // SAFETY: as above
+        unsafe { *out = message.into_raw() };
+    }
+    code
+}
+
+/// Release a string returned through `aoc_solve`'s `out`; NULL is ignored
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer from `aoc_solve` not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the pointer came from CString::into_raw in aoc_solve
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// The Rust side of `aoc_solve`: the answer, or a status code and message
+fn solve(day: u8, part: u8, text: &str) -> Result<String, (i32, Option<String>)> {
+    let solver = crate::day(day).ok_or((AOC_UNKNOWN_DAY, None))?;
+    let part2 = match part {
+        1 => false,
+        2 => true,
+        _ => return Err((AOC_BAD_PART, None)),
+    };
+
+    let text = input::normalize(text);
+    let mut out = Output::capture();
+    (solver.solve)(&text, part2, &DayOptions::default(), &mut out)
+        .map(|answer| answer.to_string())
+        .map_err(|e| (AOC_SOLVE_FAILED, Some(e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Call aoc_solve the way C would and take ownership of the result
+    fn call(day: u8, part: u8, input: &[u8]) -> (i32, Option<String>) {
+        let input = CString::new(input).unwrap();
+        let mut out = ptr::null_mut();
+        let code = unsafe { aoc_solve(day, part, input.as_ptr(), &mut out) };
+        let text = (!out.is_null()).then(|| unsafe { CStr::from_ptr(out) }.to_string_lossy().into_owned());
+        unsafe { aoc_free_string(out) };
+        (code, text)
+    }
+
+    #[test]
+    fn status_codes() {
+        let example = include_bytes!("../tests/fixtures/day5_example.txt");
+        assert_eq!(call(5, 2, example), (AOC_OK, Some("14".into())));
+        assert_eq!(call(5, 3, example), (AOC_BAD_PART, None));
+        assert_eq!(call(25, 1, example), (AOC_UNKNOWN_DAY, None));
+        assert_eq!(call(5, 1, b"3-5\n\xff\n"), (AOC_INVALID_UTF8, None));
+
+        let (code, message) = call(5, 1, b"3-5");
+        assert_eq!(code, AOC_SOLVE_FAILED);
+        assert!(message.unwrap().contains("No blank line found"));
+
+        let mut out = ptr::null_mut();
+        assert_eq!(unsafe { aoc_solve(5, 1, ptr::null(), &mut out) }, AOC_NULL_POINTER);
+        let input = CString::new("3-5").unwrap();
+        assert_eq!(unsafe { aoc_solve(5, 1, input.as_ptr(), ptr::null_mut()) }, AOC_NULL_POINTER);
+    }
+}
@@ -0,0 +1,141 @@
+// Terminal glyphs
+//
+// The banner, day list and TUI decorate their output with an emoji, check
+// marks, arrows and box-drawing characters. Legacy Windows consoles and some
+// CI logs show those as garbage, so every such glyph comes from a Glyphs set:
+// Unicode by default, plain ASCII with --ascii or when the terminal looks like
+// it cannot render UTF-8.
+//
+// --accessible (or `accessible = true` in adventcode.toml) picks a third
+// set for screen readers, which spells everything out in words: "input:
+// present" for a check mark, "[selected]" for the pointer, and no emoji at
+// all. The TUI also reads `words` to mark in text whatever else it shows
+// with color alone.
+
+/// One set of decorations for the CLI and TUI
+// Some fields are only read by the TUI
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub struct Glyphs {
+    /// Framing the banner and TUI header
+    pub tree: &'static str,
+    /// Day list status when dayN.txt exists / is missing
+    pub has_input: &'static str,
+    pub no_input: &'static str,
+    /// Repeated to draw the banner's rules
+    pub rule: &'static str,
+    /// Between the title and the part in the banner
+    pub separator: &'static str,
+    /// Between the counts in the TUI header's progress line
+    pub dot: &'static str,
+    /// Marks the selected day or part
+    pub pointer: &'static str,
+    /// Prefix for warnings printed by the CLI
+    pub warning: &'static str,
+    /// The arrow keys, in the TUI footer
+    pub up_down: &'static str,
+    /// Whether borders should use ASCII too
+    pub ascii: bool,
+    /// Accessible mode: states are said in words, never with color alone
+    pub words: bool,
+}
+
+impl Glyphs {
+    /// `title` after the tree, or alone when the set has no tree
+    pub fn titled(&self, title: &str) -> String {
+        if self.tree.is_empty() { title.to_string() } else { format!("{} {}", self.tree, title) }
+    }
+}
+
+pub static UNICODE: Glyphs = Glyphs {
+    tree: "🎄",
+    has_input: "✓",
+    no_input: "✗",
+    rule: "─",
+    separator: "│",
+    dot: "·",
+    pointer: "▶ ",
+    warning: "⚠",
+    up_down: "↑↓",
+    ascii: false,
+    words: false,
+};
+
+pub static ASCII: Glyphs = Glyphs {
+    tree: "*",
+    has_input: "OK",
+    no_input: "--",
+    rule: "-",
+    separator: "|",
+    dot: "-",
+    pointer: "> ",
+    warning: "!",
+    up_down: "Up/Down",
+    ascii: true,
+    words: false,
+};
+
+/// For screen readers: words instead of symbols, and no emoji
+pub static WORDS: Glyphs = Glyphs {
+    tree: "",
+    has_input: "input: present",
+    no_input: "input: missing",
+    rule: "-",
+    separator: "|",
+    dot: ";",
+    pointer: "[selected] ",
+    warning: "Warning:",
+    up_down: "Up/Down",
+    ascii: true,
+    words: true,
+};
+
+/// The glyphs to use: words in accessible mode, ASCII when asked for or
+/// when the terminal can't cope
+pub fn detect(force_ascii: bool, accessible: bool) -> &'static Glyphs {
+    if accessible {
+        &WORDS
+    } else if force_ascii || !unicode_supported() {
+        &ASCII
+    } else {
+        &UNICODE
+    }
+}
+
+/// Best guess at whether the terminal renders UTF-8
+pub fn unicode_supported() -> bool {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+    if cfg!(windows) {
+        // Windows Terminal, VS Code and ConEmu cope; the legacy console host does not
+        return !var("WT_SESSION").is_empty() || var("TERM_PROGRAM") == "vscode" || var("ConEmuANSI") == "ON";
+    }
+    if var("TERM") == "dumb" {
+        return false;
+    }
+
+    // The first locale variable that is set decides, as in setlocale(3);
+    // none at all is the usual UTF-8 default of a modern system
+    match ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().map(var).find(|v| !v.is_empty()) {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_set_is_ascii() {
+        for set in [&ASCII, &WORDS] {
+            let glyphs =
+                [set.tree, set.has_input, set.no_input, set.rule, set.separator, set.dot, set.pointer, set.warning, set.up_down];
+            assert!(glyphs.iter().all(|g| g.is_ascii()));
+        }
+        assert!(std::ptr::eq(detect(true, false), &ASCII));
+        assert!(std::ptr::eq(detect(false, true), &WORDS));
+    }
+}
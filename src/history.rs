@@ -0,0 +1,247 @@
+// Run history
+//
+// Every fresh solve is appended to history.jsonl in the data directory: when
+// it ran, the day and part, the SHA-256 of the input, the answer and how long
+// it took. One JSON object per line, so recording a run never rewrites the
+// file and a damaged line costs only itself. `--diff` compares a run with the
+// latest earlier record for the same day, part and input.
+//
+// A part that fails during --all is recorded too, with its error and no
+// answer; `--rerun-failed` runs again the parts whose latest record is such a
+// failure, and their new records take its place.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+
+/// The history's file name in the data directory
+pub const FILE: &str = "history.jsonl";
+
+/// One finished run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// When it finished, in seconds since the Unix epoch
+    pub at: u64,
+    pub day: u8,
+    pub part: u8,
+    /// SHA-256 of the input, as in the answer cache
+    pub input: String,
+    /// The answer's value, e.g. "984"
+    pub answer: String,
+    pub millis: f64,
+    /// Why the run failed; a failed run has no input hash, answer or time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// A record of a run finishing now
+    pub fn now(day: u8, part2: bool, input: &str, answer: &str, millis: f64) -> Self {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self {
+            at,
+            day,
+            part: if part2 { 2 } else { 1 },
+            input: cache::input_hash(input),
+            answer: answer.to_string(),
+            millis,
+            error: None,
+        }
+    }
+
+    /// A record of a run failing now with `error`
+    pub fn failed(day: u8, part2: bool, error: &str) -> Self {
+        Self { input: String::new(), error: Some(error.to_string()), ..Self::now(day, part2, "", "", 0.0) }
+    }
+}
+
+/// `$ADVENTCODE_DATA_DIR/history.jsonl`, else `<data dir>/adventcode/history.jsonl`
+pub fn default_path() -> Option<PathBuf> {
+    Some(cache::data_dir()?.join(FILE))
+}
+
+/// Every record in the file at `path`, oldest first
+/// A missing file is an empty history; lines that do not parse are skipped
+pub fn load(path: &Path) -> Vec<Record> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+/// Add `record` to the end of the file, creating it (and the data directory)
+/// if needed
+pub fn append(path: &Path, record: &Record) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(record).map_err(io::Error::other)?;
+    // One write per record, so records from parallel --all jobs never mix
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{}\n", line).as_bytes())
+}
+
+/// The most recent successful record for the same day, part and input as `run`
+pub fn latest<'a>(records: &'a [Record], run: &Record) -> Option<&'a Record> {
+    records.iter().rev().find(|r| r.error.is_none() && r.day == run.day && r.part == run.part && r.input == run.input)
+}
+
+/// The (day, part) pairs whose most recent record is a failure, in order
+pub fn failing(records: &[Record]) -> Vec<(u8, u8)> {
+    let mut newest = BTreeMap::new();
+    for record in records {
+        newest.insert((record.day, record.part), record.error.is_some());
+    }
+    newest.into_iter().filter(|&(_, failed)| failed).map(|(key, _)| key).collect()
+}
+
+/// A run next to the one before it on the same input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff {
+    /// None when this is the first run recorded for that input
+    pub previous: Option<Record>,
+    pub current: Record,
+}
+
+impl Diff {
+    /// Compare `current` with the latest matching record in `records`
+    pub fn against(records: &[Record], current: Record) -> Self {
+        Self { previous: latest(records, &current).cloned(), current }
+    }
+
+    /// Whether the answer differs from the previous run's
+    pub fn changed(&self) -> bool {
+        self.previous.as_ref().is_some_and(|p| p.answer != self.current.answer)
+    }
+
+    /// "identical", "CHANGED" or "new"
+    pub fn verdict(&self) -> &'static str {
+        match &self.previous {
+            None => "new",
+            Some(_) if self.changed() => "CHANGED",
+            Some(_) => "identical",
+        }
+    }
+
+    /// One aligned line for the --all table,
+    /// e.g. "identical    12.3 ms →    10.1 ms  -17.9%"
+    pub fn row(&self) -> String {
+        let now = millis(self.current.millis);
+        match &self.previous {
+            None => format!("{:<9} {:>10}   {:>10}", self.verdict(), "", now),
+            Some(p) => {
+                let percent = percent(p.millis, self.current.millis).unwrap_or_default();
+                format!("{:<9} {:>10} → {:>10} {:>7}", self.verdict(), millis(p.millis), now, percent)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    /// e.g. "answer identical; 12.3 ms → 10.1 ms (-2.2 ms, -17.9%)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(previous) = &self.previous else {
+            return write!(f, "no earlier run on this input, recorded this one");
+        };
+        if self.changed() {
+            write!(f, "answer CHANGED (was {}, now {}); ", previous.answer, self.current.answer)?;
+        } else {
+            write!(f, "answer identical; ")?;
+        }
+
+        let delta = self.current.millis - previous.millis;
+        let sign = if delta < 0.0 { '-' } else { '+' };
+        write!(f, "{} → {} ({}{}", millis(previous.millis), millis(self.current.millis), sign, millis(delta.abs()))?;
+        if let Some(percent) = percent(previous.millis, self.current.millis) {
+            write!(f, ", {}", percent)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A duration for people: "0.042 ms", "12.3 ms" or "4.56 s"
+pub fn millis(ms: f64) -> String {
+    if ms < 1.0 {
+        format!("{:.3} ms", ms)
+    } else if ms < 1000.0 {
+        format!("{:.1} ms", ms)
+    } else {
+        format!("{:.2} s", ms / 1000.0)
+    }
+}
+
+/// The change from `before` to `after` as "+12.5%", or None from zero
+fn percent(before: f64, after: f64) -> Option<String> {
+    (before > 0.0).then(|| format!("{:+.1}%", (after - before) / before * 100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u8, input: &str, answer: &str, millis: f64) -> Record {
+        Record { at: 0, ..Record::now(day, false, input, answer, millis) }
+    }
+
+    #[test]
+    fn appended_records_load_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("history.jsonl");
+        assert!(load(&path).is_empty());
+
+        let first = record(1, "L68\n", "3", 1.5);
+        let second = record(1, "L68\n", "4", 2.0);
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+        // A damaged line is skipped, not fatal
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "{\"at\": tru\n").unwrap();
+
+        let records = load(&path);
+        assert_eq!(records, [first.clone(), second.clone()]);
+        assert_eq!(latest(&records, &first), Some(&second));
+        assert_eq!(latest(&records, &record(1, "R5\n", "3", 1.0)), None);
+        assert_eq!(latest(&records, &Record { part: 2, ..first }), None);
+    }
+
+    #[test]
+    fn failures_last_until_a_newer_record() {
+        let failed = |day, part2| Record { at: 0, ..Record::failed(day, part2, "boom") };
+        let mut records = vec![failed(3, false), record(1, "L68\n", "3", 1.0), failed(1, true), failed(2, false)];
+        assert_eq!(failing(&records), [(1, 2), (2, 1), (3, 1)]);
+        // A failure is no run to compare with
+        assert_eq!(latest(&records, &failed(1, true)), None);
+
+        records.push(record(2, "11-22", "33", 2.0));
+        assert_eq!(failing(&records), [(1, 2), (3, 1)]);
+        let line = serde_json::to_string(&records[0]).unwrap();
+        assert!(line.contains("\"error\":\"boom\""));
+        assert!(!serde_json::to_string(&records[1]).unwrap().contains("error"));
+    }
+
+    #[test]
+    fn renders_deltas() {
+        let history = [record(1, "L68\n", "3", 12.3), record(2, "11-22", "33", 2000.0)];
+
+        let same = Diff::against(&history, record(1, "L68\n", "3", 10.1));
+        assert!(!same.changed());
+        assert_eq!(same.to_string(), "answer identical; 12.3 ms → 10.1 ms (-2.2 ms, -17.9%)");
+        assert_eq!(same.row(), "identical    12.3 ms →    10.1 ms  -17.9%");
+
+        let changed = Diff::against(&history, record(2, "11-22", "34", 3000.0));
+        assert!(changed.changed());
+        assert_eq!(changed.to_string(), "answer CHANGED (was 33, now 34); 2.00 s → 3.00 s (+1.00 s, +50.0%)");
+        assert_eq!(changed.row(), "CHANGED       2.00 s →     3.00 s  +50.0%");
+
+        let new = Diff::against(&history, record(1, "L99\n", "0", 0.5));
+        assert_eq!(new.previous, None);
+        assert_eq!(new.to_string(), "no earlier run on this input, recorded this one");
+        assert_eq!(new.row(), "new                      0.500 ms");
+    }
+}
@@ -0,0 +1,227 @@
+// `adventcode init`
+//
+// First-time setup in the current directory: a config file, an inputs/
+// directory and an empty answers.toml, plus an optional Advent of Code
+// session token kept in the user config directory (owner-only on Unix, and
+// never inside the project, where it could be committed by accident).
+// Existing files are kept unless the user asks for them to be replaced;
+// --yes answers every question with its default, which never clobbers.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "adventcode.toml";
+pub const INPUTS_DIR: &str = "inputs";
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+const CONFIG_TEMPLATE: &str = "\
+# adventcode project settings, written by `adventcode init`
+
+# Where puzzle inputs live (dayN.txt)
+input_dir = \"inputs\"
+
+# Known answers to check runs against
+answers = \"answers.toml\"
+";
+
+const ANSWERS_TEMPLATE: &str = "\
+# Known answers, one table per day, e.g.
+#
+# [day1]
+# part1 = \"3\"
+# part2 = \"6\"
+";
+
+/// Where the session token is stored: $ADVENTCODE_CONFIG_DIR/session, or
+/// adventcode/session under the platform's config directory
+pub fn session_path() -> Option<PathBuf> {
+    crate::config::user_dir().map(|dir| dir.join("session"))
+}
+
+/// What init did with one item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Created,
+    Updated,
+    Kept,
+    Skipped,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Created => "Created",
+            Action::Updated => "Updated",
+            Action::Kept => "Kept",
+            Action::Skipped => "Skipped",
+        }
+    }
+}
+
+/// Asks the questions, or takes every default with --yes
+pub struct Prompter<'a> {
+    input: &'a mut dyn BufRead,
+    out: &'a mut dyn Write,
+    yes: bool,
+}
+
+impl<'a> Prompter<'a> {
+    pub fn new(input: &'a mut dyn BufRead, out: &'a mut dyn Write, yes: bool) -> Self {
+        Self { input, out, yes }
+    }
+
+    /// One line of answer, or None with --yes or at end of input
+    fn ask(&mut self, question: &str) -> io::Result<Option<String>> {
+        if self.yes {
+            return Ok(None);
+        }
+        write!(self.out, "{} ", question)?;
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            writeln!(self.out)?;
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+
+    /// Keep (the default) or replace an existing file
+    fn replace(&mut self, path: &Path) -> io::Result<bool> {
+        let answer = self.ask(&format!("{} already exists. Keep it or update it? [K/u]", path.display()))?;
+        Ok(answer.is_some_and(|a| a.eq_ignore_ascii_case("u") || a.eq_ignore_ascii_case("update")))
+    }
+}
+
+/// Set up `root` and report each step to `prompter`'s output
+/// Returns the actions taken, in the order of the summary
+pub fn run(root: &Path, session: Option<&Path>, prompter: &mut Prompter) -> io::Result<Vec<(Action, PathBuf)>> {
+    let mut done = Vec::new();
+
+    for (name, template) in [(CONFIG_FILE, CONFIG_TEMPLATE), (ANSWERS_FILE, ANSWERS_TEMPLATE)] {
+        let path = root.join(name);
+        let action = write_file(&path, prompter, |path| fs::write(path, template))?;
+        done.push((action, PathBuf::from(name)));
+    }
+
+    let inputs = root.join(INPUTS_DIR);
+    let action = if inputs.is_dir() {
+        Action::Kept
+    } else {
+        fs::create_dir_all(&inputs)?;
+        Action::Created
+    };
+    done.push((action, PathBuf::from(format!("{}/", INPUTS_DIR))));
+
+    match session {
+        Some(path) => done.push((session_token(path, prompter)?, path.to_path_buf())),
+        None => writeln!(prompter.out, "No config directory for the session token, skipping it")?,
+    }
+
+    writeln!(prompter.out)?;
+    for (action, path) in &done {
+        writeln!(prompter.out, "{:>8} {}", action.label(), path.display())?;
+    }
+    writeln!(prompter.out)?;
+    writeln!(prompter.out, "Next steps:")?;
+    writeln!(prompter.out, "  save your puzzle input as day1.txt (or pipe it on stdin)")?;
+    writeln!(prompter.out, "  adventcode --list     see which days have input")?;
+    writeln!(prompter.out, "  adventcode -d 1       run day 1 (add -2 for part 2)")?;
+    writeln!(prompter.out, "  adventcode            pick a day interactively")?;
+    Ok(done)
+}
+
+/// Create `path` with `write`, or ask before replacing an existing one
+fn write_file(path: &Path, prompter: &mut Prompter, write: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<Action> {
+    if !path.exists() {
+        write(path)?;
+        Ok(Action::Created)
+    } else if prompter.replace(path)? {
+        write(path)?;
+        Ok(Action::Updated)
+    } else {
+        Ok(Action::Kept)
+    }
+}
+
+/// Ask for a session token and store it owner-only; blank skips
+fn session_token(path: &Path, prompter: &mut Prompter) -> io::Result<Action> {
+    if path.exists() && !prompter.replace(path)? {
+        return Ok(Action::Kept);
+    }
+    let question = "Advent of Code session token (the `session` cookie; blank to skip):";
+    let Some(token) = prompter.ask(question)?.filter(|t| !t.is_empty()) else {
+        return Ok(Action::Skipped);
+    };
+
+    let existed = path.exists();
+    save_session(path, &token)?;
+    Ok(if existed { Action::Updated } else { Action::Created })
+}
+
+/// Store `token` at `path` owner-only, creating its directory if needed
+pub fn save_session(path: &Path, token: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_private(path, token)
+}
+
+/// Write a file only its owner can read
+#[cfg(unix)]
+fn write_private(path: &Path, text: &str) -> io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    // mode() only applies to new files; tighten one that already existed
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    writeln!(file, "{}", text)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, text: &str) -> io::Result<()> {
+    fs::write(path, format!("{}\n", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init(root: &Path, answers: &str, yes: bool) -> Vec<(Action, PathBuf)> {
+        let mut input = answers.as_bytes();
+        let mut out = Vec::new();
+        let session = root.join("config").join("session");
+        run(root, Some(&session), &mut Prompter::new(&mut input, &mut out, yes)).unwrap()
+    }
+
+    fn actions(done: &[(Action, PathBuf)]) -> Vec<Action> {
+        done.iter().map(|(action, _)| *action).collect()
+    }
+
+    #[test]
+    fn rerunning_keeps_or_updates_on_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let done = init(dir.path(), "abc123\n", false);
+        assert_eq!(actions(&done), [Action::Created; 4]);
+        assert_eq!(fs::read_to_string(dir.path().join("config/session")).unwrap(), "abc123\n");
+
+        // Default answers keep everything, even with --yes
+        fs::write(dir.path().join(ANSWERS_FILE), "[day1]\npart1 = \"3\"\n").unwrap();
+        assert_eq!(actions(&init(dir.path(), "", true)), [Action::Kept; 4]);
+        assert_eq!(actions(&init(dir.path(), "\n\n\n", false)), [Action::Kept; 4]);
+        assert!(fs::read_to_string(dir.path().join(ANSWERS_FILE)).unwrap().contains("part1"));
+
+        // Update the config, keep answers, replace the token
+        let done = init(dir.path(), "u\nk\nupdate\nxyz\n", false);
+        assert_eq!(actions(&done), [Action::Updated, Action::Kept, Action::Kept, Action::Updated]);
+        assert_eq!(fs::read_to_string(dir.path().join("config/session")).unwrap(), "xyz\n");
+    }
+
+    #[test]
+    fn blank_token_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let done = init(dir.path(), "\n", false);
+        assert_eq!(done[3].0, Action::Skipped);
+        assert!(!dir.path().join("config/session").exists());
+    }
+}
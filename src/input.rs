@@ -0,0 +1,498 @@
+// Puzzle input
+//
+// Line-oriented days can read their puzzle through an InputReader instead of
+// a String, so a generated input of hundreds of MB never has to sit in memory
+// all at once. Days that need the whole text (day 4's grid) keep the &str path.
+//
+// Opening and reading report an InputError naming the path and the OS error,
+// so a wrong path or a permissions problem never turns into a panic.
+//
+// Whole-file loads are capped by a size limit, and files big enough to make
+// a String copy costly are memory-mapped instead (after a UTF-8 check).
+//
+// Text saved on Windows may carry CRLF endings and a leading byte order mark;
+// `normalize` (and `InputReader::lines`) remove both before a solver sees it.
+//
+// dayN.txt.gz and dayN.txt.zst (and compressed stdin) are decompressed on
+// the way in; see compress.rs.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::compress::{self, Compression};
+
+/// What to try when the input cannot be found or read
+const SUGGESTION: &str = "pass the input with --file, or pipe it on stdin";
+
+/// What to try when the input has no puzzle text
+const BLANK_SUGGESTION: &str = "pass --allow-empty to solve it anyway";
+
+/// What to try when the input is over the size limit
+const LIMIT_SUGGESTION: &str = "raise it with --max-input-mb, pass --allow-large-input, or try --stream";
+
+/// Default cap on whole-file loads, in MB (`--max-input-mb`)
+pub const DEFAULT_LIMIT_MB: u64 = 256;
+
+/// Files at least this big are memory-mapped rather than read into a String
+const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+const MB: u64 = 1024 * 1024;
+
+/// Byte order mark some editors put at the start of a UTF-8 file
+const BOM: char = '\u{feff}';
+
+/// Why the puzzle input could not be obtained
+#[derive(Debug)]
+pub enum InputError {
+    /// The path exists but is a directory (or another non-file)
+    NotAFile(PathBuf),
+    /// The file exists but we may not read it
+    PermissionDenied(PathBuf),
+    /// The bytes are not UTF-8 text (an image, a PDF...); `None` means stdin
+    NotText(Option<PathBuf>),
+    /// Any other failure opening or reading; `None` means stdin
+    Read(Option<PathBuf>, io::Error),
+    /// The file was missing and stdin had nothing either
+    Missing(PathBuf),
+    /// `--file -` asked for stdin, and nothing was piped
+    NothingPiped,
+    /// The input has no puzzle text: it is empty, or only whitespace and a
+    /// byte order mark. `source` names it ("'day3.txt'", "stdin"); `bytes`
+    /// is its length as read
+    Blank { source: String, bytes: usize },
+    /// The file is bigger than the size limit (both in bytes)
+    TooLarge { path: PathBuf, size: u64, limit: u64 },
+    /// A compressed file that decompresses to more than the limit (in bytes)
+    DecompressesTooLarge { path: PathBuf, limit: u64 },
+    /// A compressed file or stdin that does not decompress; the io::Error
+    /// carries a CorruptArchive naming the source
+    Corrupt(io::Error),
+}
+
+impl InputError {
+    /// Classify an error from opening or reading `path`
+    fn from_io(path: &Path, e: io::Error) -> Self {
+        if compress::corrupt_archive(&e).is_some() {
+            return InputError::Corrupt(e);
+        }
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => InputError::PermissionDenied(path.to_path_buf()),
+            io::ErrorKind::IsADirectory => InputError::NotAFile(path.to_path_buf()),
+            io::ErrorKind::InvalidData => InputError::NotText(Some(path.to_path_buf())),
+            _ => InputError::Read(Some(path.to_path_buf()), e),
+        }
+    }
+
+    /// Classify an error from reading stdin
+    pub fn stdin(e: io::Error) -> Self {
+        if compress::corrupt_archive(&e).is_some() {
+            InputError::Corrupt(e)
+        } else if e.kind() == io::ErrorKind::InvalidData {
+            InputError::NotText(None)
+        } else {
+            InputError::Read(None, e)
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotAFile(path) => {
+                write!(f, "'{}' is a directory, not an input file ({})", path.display(), SUGGESTION)
+            }
+            InputError::PermissionDenied(path) => write!(
+                f,
+                "'{}' exists but could not be read: permission denied (check its permissions, or {})",
+                path.display(),
+                SUGGESTION
+            ),
+            InputError::NotText(Some(path)) => {
+                write!(f, "'{}' is not UTF-8 text; is it the puzzle input?", path.display())
+            }
+            InputError::NotText(None) => write!(f, "stdin is not UTF-8 text; is it the puzzle input?"),
+            InputError::Read(Some(path), e) => {
+                write!(f, "'{}' could not be read: {} ({})", path.display(), e, SUGGESTION)
+            }
+            InputError::Read(None, e) => write!(f, "stdin could not be read: {}", e),
+            InputError::Missing(path) => write!(
+                f,
+                "'{}' not found and nothing was piped on stdin ({})",
+                path.display(),
+                SUGGESTION
+            ),
+            InputError::NothingPiped => write!(f, "--file - reads the input from stdin, and nothing was piped there"),
+            InputError::Blank { source, bytes: 0 } => {
+                write!(f, "{} is empty (0 bytes), so there is nothing to solve ({})", source, BLANK_SUGGESTION)
+            }
+            InputError::Blank { source, bytes } => write!(
+                f,
+                "{} holds only whitespace ({} bytes), so there is nothing to solve ({})",
+                source, bytes, BLANK_SUGGESTION
+            ),
+            InputError::TooLarge { path, size, limit } => write!(
+                f,
+                "'{}' is {:.1} MB, over the {} MB input limit ({})",
+                path.display(),
+                *size as f64 / MB as f64,
+                limit / MB,
+                LIMIT_SUGGESTION
+            ),
+            InputError::DecompressesTooLarge { path, limit } => write!(
+                f,
+                "'{}' decompresses to more than the {} MB input limit ({})",
+                path.display(),
+                limit / MB,
+                LIMIT_SUGGESTION
+            ),
+            // The CorruptArchive already names the file
+            InputError::Corrupt(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// A buffered source of input lines: a file, stdin, or an in-memory string
+pub struct InputReader<'a> {
+    inner: Box<dyn BufRead + 'a>,
+}
+
+impl<'a> InputReader<'a> {
+    pub fn new(reader: impl BufRead + 'a) -> Self {
+        Self {
+            inner: Box::new(reader),
+        }
+    }
+
+    /// Read lines from text that is already in memory
+    pub fn from_text(text: &'a str) -> Self {
+        Self::new(text.as_bytes())
+    }
+
+    /// The remaining lines, without their "\n" or "\r\n" endings
+    /// BufRead::lines already drops a "\r" before each "\n"; the first line
+    /// also loses a byte order mark, matching `normalize`
+    pub fn lines(&mut self) -> impl Iterator<Item = io::Result<String>> + '_ {
+        (&mut self.inner).lines().enumerate().map(|(idx, line)| {
+            let line = line?;
+            Ok(match line.strip_prefix(BOM) {
+                Some(rest) if idx == 0 => rest.to_string(),
+                _ => line,
+            })
+        })
+    }
+
+    /// Collect everything that is left, for days without a streaming solver;
+    /// like `lines`, without a byte order mark or CRLF endings
+    pub fn read_to_string(&mut self) -> io::Result<String> {
+        let text = self.read_raw()?;
+        Ok(match normalize(&text) {
+            Cow::Borrowed(same) if same.len() == text.len() => text,
+            normalized => normalized.into_owned(),
+        })
+    }
+
+    /// Everything that is left, exactly as read
+    fn read_raw(&mut self) -> io::Result<String> {
+        let mut text = String::new();
+        self.inner.read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    /// True when there is nothing left to read (peeks without consuming)
+    pub fn is_exhausted(&mut self) -> io::Result<bool> {
+        Ok(self.inner.fill_buf()?.is_empty())
+    }
+}
+
+impl InputReader<'static> {
+    /// Open a file, decompressing it if its extension says it is compressed
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        Ok(match Compression::from_path(path) {
+            Some(format) => Self::new(BufReader::new(compress::decoder(format, file, Some(path))?)),
+            None => Self::new(file),
+        })
+    }
+
+    /// Open an input file, with an InputError that says what went wrong
+    pub fn open_file(path: &Path) -> Result<Self, InputError> {
+        if path.is_dir() {
+            return Err(InputError::NotAFile(path.to_path_buf()));
+        }
+        Self::open(path).map_err(|e| InputError::from_io(path, e))
+    }
+
+    /// Read stdin, decompressing it if it starts with gzip or zstd magic bytes
+    pub fn stdin() -> Result<Self, InputError> {
+        let mut stdin = io::stdin().lock();
+        // A read error here will come back on the first real read
+        let format = stdin.fill_buf().ok().and_then(Compression::sniff);
+        Ok(match format {
+            Some(format) => {
+                let decoder = compress::decoder(format, stdin, None).map_err(InputError::stdin)?;
+                Self::new(BufReader::new(decoder))
+            }
+            None => Self::new(stdin),
+        })
+    }
+}
+
+/// The whole puzzle input, read into memory or memory-mapped
+pub enum InputText {
+    Owned(String),
+    /// A mapped file whose bytes were checked to be UTF-8 when it was mapped
+    Mapped(Mmap),
+}
+
+impl InputText {
+    pub fn as_str(&self) -> &str {
+        match self {
+            InputText::Owned(text) => text,
+            // SAFETY: `map_file` only builds Mapped after from_utf8 succeeded
+            // on these exact bytes, and the map is read-only
+            InputText::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+}
+
+/// Load a whole input file, refusing files over `limit_mb` (None = no limit)
+/// Large files are memory-mapped; the text seen by solvers is the same either way.
+/// Compressed files are decompressed, and the limit applies to what comes out
+pub fn load_file(path: &Path, limit_mb: Option<u64>) -> Result<InputText, InputError> {
+    if Compression::from_path(path).is_some() {
+        return read_compressed(path, limit_mb);
+    }
+
+    let size = fs::metadata(path).map_err(|e| InputError::from_io(path, e))?.len();
+    if let Some(limit) = limit_mb.map(|mb| mb.saturating_mul(MB))
+        && size > limit
+    {
+        return Err(InputError::TooLarge { path: path.to_path_buf(), size, limit });
+    }
+
+    if size >= MMAP_THRESHOLD {
+        map_file(path)
+    } else {
+        read_file(path)
+    }
+}
+
+/// Read a file into a String
+pub fn read_file(path: &Path) -> Result<InputText, InputError> {
+    fs::read_to_string(path)
+        .map(InputText::Owned)
+        .map_err(|e| InputError::from_io(path, e))
+}
+
+/// Decompress a whole .gz or .zst file into a String
+fn read_compressed(path: &Path, limit_mb: Option<u64>) -> Result<InputText, InputError> {
+    if path.is_dir() {
+        return Err(InputError::NotAFile(path.to_path_buf()));
+    }
+    let limit = limit_mb.map(|mb| mb.saturating_mul(MB));
+    let reader = InputReader::open(path).map_err(|e| InputError::from_io(path, e))?;
+
+    // Stop one byte past the limit: enough to know it was exceeded without
+    // inflating a compression bomb all the way
+    let mut bytes = Vec::new();
+    reader
+        .inner
+        .take(limit.map_or(u64::MAX, |limit| limit + 1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| InputError::from_io(path, e))?;
+    if let Some(limit) = limit
+        && bytes.len() as u64 > limit
+    {
+        return Err(InputError::DecompressesTooLarge { path: path.to_path_buf(), limit });
+    }
+
+    String::from_utf8(bytes).map(InputText::Owned).map_err(|_| InputError::NotText(Some(path.to_path_buf())))
+}
+
+/// Memory-map a file, checking that its contents are UTF-8
+///
+/// The map assumes nobody truncates the file while we hold it, the usual
+/// caveat for mmap; puzzle inputs are not edited mid-run.
+pub fn map_file(path: &Path) -> Result<InputText, InputError> {
+    let file = File::open(path).map_err(|e| InputError::from_io(path, e))?;
+    if file.metadata().map_err(|e| InputError::from_io(path, e))?.len() == 0 {
+        // Zero-length maps are an error on some platforms
+        return Ok(InputText::Owned(String::new()));
+    }
+
+    // SAFETY: read-only map of a regular file; see the note above on truncation
+    let map = unsafe { Mmap::map(&file) }.map_err(|e| InputError::from_io(path, e))?;
+    if std::str::from_utf8(&map).is_err() {
+        // Same error read_to_string gives, so both paths report alike
+        return Err(InputError::NotText(Some(path.to_path_buf())));
+    }
+    Ok(InputText::Mapped(map))
+}
+
+/// Strip a leading byte order mark and turn CRLF line endings into LF
+///
+/// Nothing else changes: a lone '\r' is content and stays. Text with neither
+/// is borrowed, so the common case (and a memory-mapped file) is not copied.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix(BOM).unwrap_or(text);
+    if text.contains("\r\n") {
+        Cow::Owned(text.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// True when `text` has no puzzle text: nothing, or only whitespace after a
+/// byte order mark
+pub fn is_blank(text: &str) -> bool {
+    text.strip_prefix(BOM).unwrap_or(text).trim().is_empty()
+}
+
+/// The first `lines` lines of `text`, each with its line ending, or None
+/// when `text` has no more than that (--limit-lines)
+pub fn first_lines(text: &str, lines: usize) -> Option<&str> {
+    if lines == 0 {
+        return (!text.is_empty()).then_some("");
+    }
+    let (newline, _) = text.match_indices('\n').nth(lines - 1)?;
+    (newline + 1 < text.len()).then(|| &text[..newline + 1])
+}
+
+/// Read everything from `reader`, which came from `path` (None for stdin),
+/// as it is: the caller normalizes it, after checking it is not blank
+pub fn read_all(reader: &mut InputReader, path: Option<&Path>) -> Result<String, InputError> {
+    reader.read_raw().map_err(|e| match path {
+        Some(path) => InputError::from_io(path, e),
+        None => InputError::stdin(e),
+    })
+}
+
+/// Where a day's input lives by default: dayN.txt, or a compressed copy
+/// (dayN.txt.gz, dayN.txt.zst) when only that exists
+pub fn default_path(day: u8) -> PathBuf {
+    default_path_in(Path::new(""), day)
+}
+
+/// Where a day's input is read from, for a run in `root`: dayN.txt (or a
+/// compressed copy) in the input directory `dir`, else in `root`'s inputs/
+/// directory. When neither has one, the path in `dir`, which stdin then
+/// stands in for
+pub fn resolve_in(root: &Path, dir: &Path, day: u8) -> PathBuf {
+    let path = default_path_in(&root.join(dir), day);
+    if path.exists() {
+        return path;
+    }
+    Some(default_path_in(&root.join(FALLBACK_DIR), day)).filter(|path| path.exists()).unwrap_or(path)
+}
+
+/// Where inputs are looked for when the input directory has none
+pub const FALLBACK_DIR: &str = "inputs";
+
+/// A day's copy of the puzzle's worked example, kept beside its input as
+/// dayN_example.txt; None when there is no such file
+pub fn example_path_in(dir: &Path, day: u8) -> Option<PathBuf> {
+    Some(dir.join(format!("day{}_example.txt", day))).filter(|path| path.exists())
+}
+
+/// `default_path` under `dir` instead of the current directory
+pub fn default_path_in(dir: &Path, day: u8) -> PathBuf {
+    let plain = dir.join(format!("day{}.txt", day));
+    if plain.exists() {
+        return plain;
+    }
+    Compression::ALL
+        .into_iter()
+        .map(|format| dir.join(format!("day{}.txt.{}", day, format.extension())))
+        .find(|path| path.exists())
+        .unwrap_or(plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_lines_cuts_after_the_nth_line_ending() {
+        assert_eq!(first_lines("a\nb\nc\n", 2), Some("a\nb\n"));
+        assert_eq!(first_lines("a\nb\nc", 2), Some("a\nb\n"));
+        assert_eq!(first_lines("a\nb\n", 2), None);
+        assert_eq!(first_lines("a\nb", 2), None);
+        assert_eq!(first_lines("a\nb\n", 5), None);
+        assert_eq!(first_lines("a\n", 0), Some(""));
+    }
+
+    #[test]
+    fn blank_is_nothing_but_whitespace_and_a_bom() {
+        assert!(is_blank(""));
+        assert!(is_blank(" \r\n\t\n"));
+        assert!(is_blank("\u{feff}"));
+        assert!(is_blank("\u{feff}\r\n"));
+        assert!(!is_blank("\n0\n"));
+    }
+
+    #[test]
+    fn lines_match_str_lines() {
+        let text = "L68\r\nL30\n\nR48";
+        let mut reader = InputReader::from_text(text);
+        let lines: Vec<String> = reader.lines().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, text.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn normalize_strips_bom_and_crlf_only() {
+        assert_eq!(normalize("\u{feff}3-5\r\n\r\n4\r\n"), "3-5\n\n4\n");
+        assert!(matches!(normalize("3-5\n4\n"), Cow::Borrowed(_)));
+        // A lone CR and a BOM past the start are content
+        assert_eq!(normalize("a\rb\n\u{feff}c"), "a\rb\n\u{feff}c");
+
+        let mut reader = InputReader::from_text("\u{feff}L68\r\nL30\r\n");
+        let lines: Vec<String> = reader.lines().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, ["L68", "L30"]);
+        let mut reader = InputReader::from_text("\u{feff}L68\r\nL30\r\n");
+        assert_eq!(reader.read_to_string().unwrap(), "L68\nL30\n");
+    }
+
+    #[test]
+    fn inputs_resolve_to_the_input_dir_then_inputs() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("puzzles")).unwrap();
+        std::fs::create_dir_all(root.join("inputs")).unwrap();
+        let resolve = |dir: &str, day| resolve_in(root, Path::new(dir), day);
+
+        // Nowhere: the input directory's path, for the missing-input message
+        assert_eq!(resolve("puzzles", 1), root.join("puzzles/day1.txt"));
+        std::fs::write(root.join("inputs/day1.txt"), "L68\n").unwrap();
+        assert_eq!(resolve("puzzles", 1), root.join("inputs/day1.txt"));
+        assert_eq!(resolve("", 1), root.join("inputs/day1.txt"));
+        std::fs::write(root.join("day1.txt"), "L68\n").unwrap();
+        assert_eq!(resolve("", 1), root.join("day1.txt"));
+        std::fs::write(root.join("puzzles/day1.txt"), "L68\n").unwrap();
+        assert_eq!(resolve("puzzles", 1), root.join("puzzles/day1.txt"));
+
+        // An absolute directory is taken as it is
+        let elsewhere = tempfile::tempdir().unwrap();
+        std::fs::write(elsewhere.path().join("day1.txt"), "L68\n").unwrap();
+        assert_eq!(resolve_in(root, elsewhere.path(), 1), elsewhere.path().join("day1.txt"));
+        assert_eq!(resolve_in(root, elsewhere.path(), 2), elsewhere.path().join("day2.txt"));
+    }
+
+    #[test]
+    fn invalid_utf8_fails_the_same_way_mapped_or_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.txt");
+        std::fs::write(&path, b"L68\n\xff\n").unwrap();
+
+        let read = read_file(&path).err().unwrap().to_string();
+        let mapped = map_file(&path).err().unwrap().to_string();
+        assert_eq!(read, mapped);
+    }
+}
@@ -0,0 +1,298 @@
+// Inclusive integer intervals shared by the range-based days (2 and 5)
+//
+// Ranges are plain (start, end) tuples with both ends included. Parsing
+// reports reversed input like "20-16" instead of producing a range whose
+// length underflows; callers turn that into a warning (swapped) or, under
+// --strict, an error.
+
+/// An inclusive range [start, end], always with start <= end once parsed
+pub type Interval = (u64, u64);
+
+/// A successfully parsed "start-end" token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedRange {
+    /// The normalized range (bounds swapped if they were reversed)
+    pub range: Interval,
+    /// Whether the input had start > end
+    pub reversed: bool,
+}
+
+/// Parse a range string like "3-5" into (3, 5)
+///
+/// A reversed range such as "20-16" is normalized to (16, 20) and flagged,
+/// so callers can decide between warning and rejecting it. With `lenient`
+/// the bounds may carry readability separators (see `parse_number`) and
+/// spaces around the dash, as in "1_000 - 2_500".
+pub fn parse_range(text: &str, lenient: bool) -> Option<ParsedRange> {
+    let (a, b) = text.trim().split_once('-')?;
+    let start = parse_number(a, lenient)?;
+    let end = parse_number(b, lenient)?;
+    Some(ParsedRange {
+        range: (start.min(end), start.max(end)),
+        reversed: start > end,
+    })
+}
+
+/// Parse an ID such as "1000"
+///
+/// With `lenient` (--lenient-numbers) the text is trimmed, and a single `_`
+/// or space between two digits is skipped: "1_000" and "12 341" parse, while
+/// "1__000", "_1" and "10, 14" still do not.
+pub fn parse_number(text: &str, lenient: bool) -> Option<u64> {
+    if !lenient {
+        return text.parse().ok();
+    }
+    let mut digits = String::with_capacity(text.len());
+    let mut chars = text.trim().chars().peekable();
+    let mut after_digit = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '0'..='9' => {
+                digits.push(c);
+                after_digit = true;
+            }
+            '_' | ' ' if after_digit && chars.peek().is_some_and(char::is_ascii_digit) => after_digit = false,
+            _ => return None,
+        }
+    }
+    digits.parse().ok()
+}
+
+/// Number of IDs in a range
+/// Returned as u128 because the full range 0-u64::MAX holds 2^64 IDs
+pub fn len(range: Interval) -> u128 {
+    (range.1 - range.0) as u128 + 1
+}
+
+/// Merges overlapping and adjacent ranges, returning them sorted by start
+///
+/// Example: [11-22, 20-30, 95-115] becomes [11-30, 95-115]
+///
+/// Two ranges (a, b) and (c, d) with a <= c can be merged if c <= b + 1
+/// (they overlap or are adjacent).
+pub fn merge(ranges: &[Interval]) -> Vec<Interval> {
+    if ranges.is_empty() {
+        return Vec::new();
+    }
+
+    // Sort ranges by start position
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable();
+
+    let mut merged: Vec<Interval> = Vec::new();
+    let mut current = sorted[0];
+
+    for &(start, end) in &sorted[1..] {
+        // If ranges overlap or are adjacent, merge them
+        if start <= current.1.saturating_add(1) {
+            current.1 = current.1.max(end);
+        } else {
+            // No overlap, save current and start a new range
+            merged.push(current);
+            current = (start, end);
+        }
+    }
+
+    // Don't forget the last range
+    merged.push(current);
+    merged
+}
+
+/// The uncovered stretches between consecutive merged ranges
+///
+/// Example: [3-5, 10-20, 25-30] has gaps [6-9, 21-24]
+pub fn gaps(merged: &[Interval]) -> Vec<Interval> {
+    merged
+        .windows(2)
+        .map(|pair| (pair[0].1 + 1, pair[1].0 - 1))
+        .collect()
+}
+
+/// Total number of IDs covered by merged (disjoint) ranges
+pub fn total_len(merged: &[Interval]) -> u128 {
+    merged.iter().map(|&range| len(range)).sum()
+}
+
+/// Checks if a number exists within any of the merged ranges using binary search.
+///
+/// Time complexity: O(log n) where n is the number of ranges
+pub fn contains(merged: &[Interval], x: u64) -> bool {
+    find(merged, x).is_some()
+}
+
+/// Returns the merged range containing `x`, if any
+///
+/// Merged ranges are disjoint, so at most one range can match and binary
+/// search finds it in O(log n).
+pub fn find(merged: &[Interval], x: u64) -> Option<Interval> {
+    let mut lo = 0;
+    let mut hi = merged.len();
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let (start, end) = merged[mid];
+
+        if x < start {
+            // x is smaller, search left half
+            hi = mid;
+        } else if x > end {
+            // x is larger, search right half
+            lo = mid + 1;
+        } else {
+            // x is within this range
+            return Some(merged[mid]);
+        }
+    }
+
+    None
+}
+
+/// Returns the merged range whose nearest boundary is closest to `x`,
+/// with that distance (0 when `x` is inside the range)
+///
+/// Uses binary search for the first range starting after `x`, so only
+/// its neighbour to the left needs comparing. Ties go to the lower range.
+pub fn nearest(merged: &[Interval], x: u64) -> Option<(Interval, u64)> {
+    let idx = merged.partition_point(|&(start, _)| start <= x);
+
+    let below = idx.checked_sub(1).map(|i| {
+        let range = merged[i];
+        (range, x.saturating_sub(range.1))
+    });
+    let above = merged.get(idx).map(|&range| (range, range.0 - x));
+
+    match (below, above) {
+        (Some(b), Some(a)) => Some(if a.1 < b.1 { a } else { b }),
+        (b, a) => b.or(a),
+    }
+}
+
+/// Count the IDs contained in both lists of ranges
+/// Both lists must be sorted and disjoint (i.e. the output of `merge`),
+/// which lets us walk them together with two pointers in O(a + b)
+pub fn intersection_size(a: &[Interval], b: &[Interval]) -> u128 {
+    let mut total = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start <= end {
+            total += len((start, end));
+        }
+
+        // Advance whichever range finishes first; the other may still
+        // overlap the next range on the opposite side
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    total
+}
+
+/// Count the IDs inside more than one of `ranges` (which may overlap)
+///
+/// Sweeps over the range endpoints instead of visiting IDs: each range
+/// opens at its start and closes just past its end, and every stretch
+/// between two endpoints with two or more ranges open counts in full.
+/// O(n log n) in the number of ranges, however long they are.
+pub fn multi_covered(ranges: &[Interval]) -> u128 {
+    // u128 so the end of a range reaching u64::MAX can still close past it
+    let mut events: Vec<(u128, i32)> = Vec::with_capacity(ranges.len() * 2);
+    for &(start, end) in ranges {
+        events.push((u128::from(start), 1));
+        events.push((u128::from(end) + 1, -1));
+    }
+    events.sort_unstable();
+
+    let mut total = 0;
+    let mut open = 0;
+    let mut previous = 0;
+    for (position, change) in events {
+        if open >= 2 {
+            total += position - previous;
+        }
+        open += change;
+        previous = position;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_flags_reversed_bounds() {
+        assert_eq!(
+            parse_range("3-5", false),
+            Some(ParsedRange { range: (3, 5), reversed: false })
+        );
+        assert_eq!(
+            parse_range("20-16", false),
+            Some(ParsedRange { range: (16, 20), reversed: true })
+        );
+        assert_eq!(parse_range("16_20", false), None);
+        assert_eq!(parse_range("1-2-3", false), None);
+    }
+
+    #[test]
+    fn lenient_numbers_skip_separators_between_digits() {
+        let range = |text| parse_range(text, true).map(|parsed| parsed.range);
+        assert_eq!(range("1_000-2_500"), Some((1000, 2500)));
+        assert_eq!(range("998 - 1012"), Some((998, 1012)));
+        assert_eq!(parse_range("1_000-2_500", false), None);
+        assert_eq!(parse_range("998 - 1012", false), None);
+
+        assert_eq!(parse_number("12 341", true), Some(12341));
+        assert_eq!(parse_number("12 341", false), None);
+        for text in ["10, 14", "1__000", "1  000", "_1", "1_", "1_x", ""] {
+            assert_eq!(parse_number(text, true), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn merge_joins_overlapping_and_adjacent() {
+        assert_eq!(merge(&[(12, 18), (3, 5), (10, 14), (16, 20)]), vec![(3, 5), (10, 20)]);
+        assert_eq!(merge(&[(10, 14), (15, 18)]), vec![(10, 18)]);
+        assert_eq!(merge(&[(10, 14), (16, 18)]), vec![(10, 14), (16, 18)]);
+        assert_eq!(merge(&[(5, u64::MAX), (0, 1)]), vec![(0, 1), (5, u64::MAX)]);
+    }
+
+    #[test]
+    fn nearest_picks_closest_boundary() {
+        let merged = [(3, 5), (10, 20)];
+        assert_eq!(nearest(&merged, 1), Some(((3, 5), 2)));
+        assert_eq!(nearest(&merged, 4), Some(((3, 5), 0)));
+        assert_eq!(nearest(&merged, 8), Some(((10, 20), 2)));
+        assert_eq!(nearest(&merged, 32), Some(((10, 20), 12)));
+        assert_eq!(nearest(&[], 7), None);
+    }
+
+    #[test]
+    fn lengths_do_not_overflow_on_the_full_range() {
+        let full = [(0, u64::MAX)];
+        assert_eq!(len(full[0]), 1 << 64);
+        assert_eq!(total_len(&full), 1 << 64);
+        assert_eq!(intersection_size(&full, &full), 1 << 64);
+        assert_eq!(multi_covered(&[(0, u64::MAX), (u64::MAX, u64::MAX)]), 1);
+    }
+
+    #[test]
+    fn gaps_between_merged_ranges() {
+        assert_eq!(gaps(&[(3, 5), (10, 20), (25, 30)]), vec![(6, 9), (21, 24)]);
+        assert_eq!(gaps(&[(3, 5)]), vec![]);
+    }
+
+    #[test]
+    fn contains_uses_merged_ranges() {
+        let merged = [(3, 5), (10, 20)];
+        assert!(contains(&merged, 3));
+        assert!(contains(&merged, 20));
+        assert!(!contains(&merged, 8));
+        assert!(!contains(&merged, 21));
+    }
+}
@@ -0,0 +1,75 @@
+pub mod artifacts;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod compress;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod diagnostics;
+pub mod embedded;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod history;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod meta;
+pub mod options;
+pub mod output;
+pub mod parallel;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod sim;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(test)]
+mod testing;
+
+use error::Error;
+use input::InputReader;
+use options::DayOptions;
+use output::{Answer, Output};
+
+/// Signature shared by every day's `solve` function
+pub type Solver = fn(&str, bool, &DayOptions, &mut Output) -> Result<Answer, Error>;
+
+/// Signature of a `solve_stream` function, for days that can work line by line
+pub type StreamSolver = fn(&mut InputReader, bool, &DayOptions, &mut Output) -> Result<Answer, Error>;
+
+/// A day implemented in this crate
+pub struct Day {
+    pub number: u8,
+    /// Extra options the day understands after `--`
+    pub options: &'static [&'static str],
+    pub solve: Solver,
+    /// Line-by-line solver used with `--stream`, when the day has one
+    pub stream: Option<StreamSolver>,
+    /// The day's source file, whose header comment holds its title and example
+    pub source: &'static str,
+}
+
+impl Day {
+    /// Title, overview and example from the header comment
+    pub fn meta(&self) -> meta::DayMeta {
+        meta::parse(self.number, self.source)
+    }
+}
+
+/// Every implemented day, in order
+pub const DAYS: &[Day] = &[
+    Day { number: 1, options: day1::OPTIONS, solve: day1::solve, stream: Some(day1::solve_stream), source: include_str!("day1.rs") },
+    Day { number: 2, options: day2::OPTIONS, solve: day2::solve, stream: None, source: include_str!("day2.rs") },
+    Day { number: 3, options: day3::OPTIONS, solve: day3::solve, stream: Some(day3::solve_stream), source: include_str!("day3.rs") },
+    Day { number: 4, options: day4::OPTIONS, solve: day4::solve, stream: None, source: include_str!("day4.rs") },
+    Day { number: 5, options: day5::OPTIONS, solve: day5::solve, stream: Some(day5::solve_stream), source: include_str!("day5.rs") },
+];
+
+/// Look up an implemented day by number
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -1,540 +1,2620 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-
-use clap::Parser;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
-    Frame, Terminal,
-};
-use regex::Regex;
+use adventcode::artifacts::Artifacts;
+use adventcode::bench::{self, Baseline, Baselines, Comparison, Context, Stats};
+use adventcode::cache::{self, Cache};
+use adventcode::cancel::Cancel;
+use adventcode::embedded;
+use adventcode::history::{self, Diff};
+use adventcode::input::{self, InputError, InputReader, InputText};
+use adventcode::meta::{self, DayMeta};
+use adventcode::options::DayOptions;
+use adventcode::output::{Answer, Output};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum, ValueHint};
+use serde::Serialize;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal};
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+mod batch;
+#[cfg(feature = "tui")]
+mod batteries;
+mod budget;
+mod clean;
+mod clipboard;
+mod completions;
+mod config;
+mod doctor;
+#[cfg(feature = "tui")]
+mod editor;
+mod external;
+mod glyphs;
+mod init;
+mod notes;
+mod notify;
+#[cfg(feature = "tui")]
+mod numberline;
+mod remote;
+mod repl;
+mod report;
+mod scaffold;
+#[cfg(feature = "tui")]
+mod settings;
+#[cfg(feature = "tui")]
+mod state;
+mod style;
+mod template;
+#[cfg(feature = "tui")]
+mod theme;
+#[cfg(feature = "tui")]
+mod timings;
+#[cfg(feature = "tui")]
+mod toast;
+mod trace;
+mod verbose;
+mod watch;
+#[cfg(feature = "tui")]
+mod tui;
+
+use budget::Budgets;
+use config::Config;
+use glyphs::Glyphs;
+use style::{Stream, Style};
+use notify::NotifySettings;
+use template::Template;
+use watch::Watcher;
 
 #[derive(Parser)]
 #[command(name = "adventcode")]
 #[command(about = "Advent of Code solutions", long_about = None)]
 struct Cli {
-    /// Day to run (e.g., 1, 2, 3...) - if not provided, interactive TUI is shown
-    #[arg(short, long)]
-    day: Option<u8>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Directory holding the dayN.txt inputs (default: input_dir in
+    /// adventcode.toml or the user config, else the current directory);
+    /// inputs/dayN.txt is tried when it has none
+    #[arg(long, global = true, value_name = "DIR")]
+    input_dir: Option<PathBuf>,
+
+    /// Ignore the settings in adventcode.toml and the user config
+    /// (config.toml in the config directory); adventcode.toml's
+    /// [external], [max_time] and [input_url] tables still apply, and the
+    /// TUI's settings screen still edits the file
+    #[arg(long, global = true)]
+    no_config: bool,
+
+    /// Write a timeline of the run (input read, parsing, each part's solve,
+    /// day 4's rounds) to PATH in Chrome's Trace Event Format, for
+    /// chrome://tracing or Perfetto
+    #[arg(long, global = true, value_name = "PATH")]
+    trace_json: Option<PathBuf>,
+
+    /// Say on stderr what was found and chosen: day modules and titles,
+    /// config files, where the input came from and how it parsed (-vv:
+    /// also every file probed)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Use plain ASCII instead of emoji and box-drawing characters
+    #[arg(long, global = true)]
+    ascii: bool,
 
-    /// Input file path (if not provided, uses default dayX.txt)
+    /// No color, even on a terminal (as does a non-empty NO_COLOR); piped
+    /// output never has any
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Screen-reader friendly output: words instead of symbols, text markers
+    /// for every colored state, and the high-contrast theme in the TUI
+    #[arg(long, global = true)]
+    accessible: bool,
+
+    /// With no subcommand, the options of `run`
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+/// Running days: `adventcode run`, which is also what the bare command
+/// does with any of these options (with none, it opens the TUI)
+#[derive(Args, Default)]
+struct RunArgs {
+    /// Day to run (e.g., 1, 2, 3...), or several as a list with ranges
+    /// (1,3-5) - if not provided, interactive TUI is shown (builds with the
+    /// default `tui` feature)
+    #[arg(short, long, value_name = "DAY", value_parser = parse_days)]
+    day: Option<Days>,
+
+    /// Input file path (if not provided, uses default dayX.txt); `-` reads
+    /// stdin
     #[arg(short, long)]
     file: Option<String>,
 
-    /// Run part 2 of the puzzle
-    #[arg(short = '2', long)]
+    /// The part to run: 1, 2, or both (part 1 then part 2, on one read of
+    /// the input)
+    #[arg(short, long, value_enum, value_name = "PART")]
+    part: Option<Part>,
+
+    /// Deprecated: use --part 2
+    #[arg(short = '2', long, hide = true, conflicts_with = "part")]
     part2: bool,
 
     /// Run in non-interactive mode (no TUI, plain output)
     #[arg(short, long)]
     quiet: bool,
+
+    /// Open the TUI even with --day, on that day's part selection (with
+    /// --part 2, on Part 2)
+    #[arg(long, conflicts_with_all = ["quiet", "file", "all", "expected", "bench", "format", "output_format"])]
+    tui: bool,
+
+    /// Treat malformed input lines as errors instead of warnings
+    #[arg(long)]
+    strict: bool,
+
+    /// Read "1_000" and "12 341" as numbers, skipping a `_` or space between
+    /// digits (days 2 and 5)
+    #[arg(long)]
+    lenient_numbers: bool,
+
+    /// Read the input line by line instead of loading it all (days 1, 3 and 5)
+    #[arg(long)]
+    stream: bool,
+
+    /// Run the selected day and part once per file matching this glob (e.g.
+    /// "inputs/day5*.txt", quoted so the program expands it) and print a
+    /// table of file, answer and time
+    #[arg(long, value_name = "GLOB", requires = "day", conflicts_with_all = ["file", "example", "stream", "tui", "expected", "diff", "bench", "format"])]
+    files: Option<String>,
+
+    /// Run on the day's example: dayN_example.txt beside its input, or else
+    /// the worked example from the day's header comment
+    #[arg(long, conflicts_with = "file")]
+    example: bool,
+
+    /// Download the input from URL instead of reading a file; the copy is
+    /// kept and reused until --no-cache, and [input_url] in adventcode.toml
+    /// can add a bearer token or cookie
+    #[arg(long, value_name = "URL", requires = "day", conflicts_with_all = ["file", "files", "example", "tui"])]
+    input_url: Option<String>,
+
+    /// Use the clipboard's text as the input (pbpaste, Get-Clipboard,
+    /// wl-paste, xclip or xsel; ADVENTCODE_PASTE_COMMAND picks another)
+    #[arg(long, requires = "day", conflicts_with_all = ["file", "files", "input_url", "example", "tui"])]
+    paste: bool,
+
+    /// With --paste, also save the text as the day's input file (dayN.txt)
+    #[arg(long, requires = "paste")]
+    save_input: bool,
+
+    /// Solve only the first N lines of the input, for a quick smoke run; the
+    /// answer is marked as coming from a truncated input
+    #[arg(long, value_name = "N", conflicts_with_all = ["expected", "bench", "files", "tui"])]
+    limit_lines: Option<NonZeroUsize>,
+
+    /// Reuse the answer from an earlier run when the input is unchanged
+    #[arg(long)]
+    cached: bool,
+
+    /// Ignore the answer cache (overrides --cached)
+    #[arg(long, overrides_with = "cached")]
+    no_cache: bool,
+
+    /// Delete the answer cache and exit
+    #[arg(long)]
+    clear_cache: bool,
+
+    /// Run both parts of every day that has an input, then print a summary
+    #[arg(long, conflicts_with_all = ["day", "file", "part", "part2", "example"])]
+    all: bool,
+
+    /// Run again only the parts whose latest recorded run failed (in --all or
+    /// an earlier --rerun-failed), with the options given now, then print
+    /// their summary
+    #[arg(long, conflicts_with_all = ["all", "day", "file", "part", "part2", "example", "tui"])]
+    rerun_failed: bool,
+
+    /// With --all, run only part 1 of each day
+    #[arg(long, requires = "all", conflicts_with = "only_part2")]
+    only_part1: bool,
+
+    /// With --all, run only part 2 of each day
+    #[arg(long, requires = "all")]
+    only_part2: bool,
+
+    /// With --all, leave out these days (e.g. --skip 2,4)
+    #[arg(long, value_name = "DAYS", value_delimiter = ',', requires = "all")]
+    skip: Vec<u8>,
+
+    /// With --all or --rerun-failed, solve up to N parts at once (default 1);
+    /// each part's output still prints whole and in order
+    #[arg(long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Check the answer against this value (numbers and text alike, as the
+    /// bare answer prints): prints PASS or FAIL, and exits with 1 on a
+    /// mismatch
+    #[arg(long, visible_alias = "expect", value_name = "ANSWER", requires = "day", conflicts_with = "all")]
+    expected: Option<String>,
+
+    /// Compare each run with the last recorded run on the same input:
+    /// whether the answer changed, and how the time moved
+    #[arg(long, conflicts_with_all = ["cached", "stream"])]
+    diff: bool,
+
+    /// Time the selected part N times, after one warm-up run, and print the
+    /// median time and its spread
+    #[arg(long, value_name = "N", requires = "day", conflicts_with_all = ["stream", "cached", "diff", "expected", "format"])]
+    bench: Option<NonZeroUsize>,
+
+    /// Stay running and solve the day again each time its input file
+    /// changes, until Ctrl-C; a missing file is waited for, never read from
+    /// stdin
+    #[arg(
+        long,
+        requires = "day",
+        conflicts_with_all = ["bench", "files", "example", "paste", "input_url", "all", "rerun_failed", "tui"]
+    )]
+    watch: bool,
+
+    /// With --bench, save the timing as the part's baseline, or check it
+    /// against the saved one (exits with 1 when it is slower than --tolerance)
+    #[arg(long, value_enum, requires = "bench")]
+    baseline: Option<BaselineMode>,
+
+    /// How much slower than its baseline `--baseline check` lets a run be
+    /// (default 20%)
+    #[arg(long, value_name = "PERCENT", requires = "baseline")]
+    tolerance: Option<String>,
+
+    /// Write each day's intermediate structures (parsed and merged ranges,
+    /// dial positions, battery picks, removal rounds) as files in DIR,
+    /// creating it if missing
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["stream", "bench", "files"])]
+    emit_intermediate: Option<PathBuf>,
+
+    /// After the run, fail (exit 1) listing every part that took longer than
+    /// DURATION (2s, 500ms, 1.5m); [max_time] in adventcode.toml can give
+    /// days and parts budgets of their own
+    #[arg(long, value_name = "DURATION", value_parser = budget::parse_duration, conflicts_with_all = ["tui", "bench", "files"])]
+    assert_max_time: Option<Duration>,
+
+    /// Show numeric answers with thousands separators (171,371,624,522) in the
+    /// result line; quiet output and --format always carry the plain value
+    #[arg(long)]
+    pretty: bool,
+
+    /// Print how long each part took to solve, on stderr so stdout stays the
+    /// answer (in µs, ms or s, whichever reads best)
+    #[arg(long)]
+    time: bool,
+
+    /// Print one line per completed part from this template instead of the
+    /// banner: {day}, {part}, {title}, {answer}, {duration_ms} and {input}
+    /// are filled in, {{ and }} are literal braces
+    #[arg(long, value_name = "TEMPLATE")]
+    format: Option<Template>,
+
+    /// Print the results as one json, jsonl, csv or md document instead of the
+    /// banner, result lines and --all summary
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with_all = ["format", "diff", "bench"])]
+    output_format: Option<report::Format>,
+
+    /// Also write the answer to FILE once the run has solved, creating its
+    /// directory; with --part both or several days each line says which
+    /// part it is. With --quiet the answer goes only to FILE
+    #[arg(long, value_name = "FILE", requires = "day", conflicts_with_all = ["tui", "files", "watch", "bench"])]
+    output: Option<PathBuf>,
+
+    /// With --output, add to the end of FILE instead of replacing it
+    #[arg(long, requires = "output")]
+    append: bool,
+
+    /// List the available days and exit: one tab-separated line per day with
+    /// its number, title, and whether its input and example files exist
+    /// (true/false)
+    #[arg(long)]
+    list: bool,
+
+    /// With --list: the same fields as a JSON array
+    #[arg(long, requires = "list")]
+    json: bool,
+
+    /// Refuse input files larger than this many MB (default 256)
+    #[arg(long, value_name = "MB")]
+    max_input_mb: Option<u64>,
+
+    /// Load input files of any size (overrides --max-input-mb)
+    #[arg(long)]
+    allow_large_input: bool,
+
+    /// Solve an input that is empty or only whitespace instead of refusing
+    /// it (its answer is rarely the puzzle's)
+    #[arg(long)]
+    allow_empty: bool,
+
+    /// Send a desktop notification when a solve takes longer than --notify-after
+    #[arg(long)]
+    notify: bool,
+
+    /// Seconds a solve must take before --notify sends anything (default 10,
+    /// or notify_after in adventcode.toml)
+    #[arg(long, value_name = "SECS")]
+    notify_after: Option<f64>,
+
+    /// Stop a part that is still solving after SECS seconds and exit with
+    /// 124 (with --all, the other days still run)
+    #[arg(long, value_name = "SECS", conflicts_with_all = ["bench", "tui"])]
+    timeout: Option<f64>,
+
+    /// Leave the answer out of the notification (implies --notify)
+    #[arg(long)]
+    notify_no_answer: bool,
+
+    /// Use at most N threads for the days that work in parallel (default: one
+    /// per core, or RAYON_NUM_THREADS); 1 runs everything in sequence
+    #[arg(long, value_name = "N")]
+    max_threads: Option<NonZeroUsize>,
+
+    /// Extra options for the selected day, given after `--` (e.g. `-- --missing`)
+    #[arg(last = true)]
+    day_args: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one or more days (the default: `adventcode -d 5` is `adventcode
+    /// run -d 5`)
+    Run(Box<RunArgs>),
+    /// List the available days, one tab-separated line each (or --json)
+    List(ListArgs),
+    /// Time one part of a day over many runs
+    Bench(BenchArgs),
+    /// Start a day: write src/dayN.rs from a template and register it in
+    /// src/lib.rs (run from the crate root; never overwrites)
+    New {
+        /// The day to create
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25), value_hint = ValueHint::Other)]
+        day: u8,
+        /// The puzzle's title, for the `// DAY N: TITLE` header
+        #[arg(short, long)]
+        title: String,
+    },
+    /// Set up adventcode.toml, inputs/, answers.toml and a session token here
+    Init {
+        /// Take the default answer to every question (keeps existing files)
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Check the setup: day modules, inputs, session token, answers and config
+    /// files, and the terminal
+    Doctor,
+    /// Delete the answer cache, run history, saved TUI state or fetched inputs
+    #[command(group(ArgGroup::new("what").required(true).multiple(true)))]
+    Clean {
+        /// The answer cache (--cached)
+        #[arg(long, group = "what")]
+        cache: bool,
+        /// The run history (--diff)
+        #[arg(long, group = "what")]
+        history: bool,
+        /// Saved TUI state
+        #[arg(long, group = "what")]
+        state: bool,
+        /// Downloaded puzzle inputs
+        #[arg(long, group = "what")]
+        fetched: bool,
+        /// All of the above
+        #[arg(long, group = "what")]
+        all: bool,
+        /// Show what would be deleted, and how big it is, without deleting
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// A prompt for solving a day again and again: load an input, set
+    /// options, go (help lists the commands)
+    Repl,
+    /// Print a day's note, or replace or delete it (the TUI's n edits it too)
+    Notes {
+        /// The day the note is about
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Replace the note with this text (blank deletes it)
+        #[arg(long, conflicts_with = "delete")]
+        set: Option<String>,
+        /// Delete the note
+        #[arg(long)]
+        delete: bool,
+    },
+    /// Print a completion script for SHELL (`adventcode completions zsh >
+    /// _adventcode`); --day completes to the days there are
+    Completions {
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+}
+
+/// `adventcode list`: `--list` and its JSON form
+#[derive(Args)]
+struct ListArgs {
+    /// The same fields as the tab-separated lines, as a JSON array
+    #[arg(long)]
+    json: bool,
+}
+
+/// `adventcode bench`: the `run --bench` mode with only the options that
+/// apply to it
+#[derive(Args)]
+struct BenchArgs {
+    /// The day to time
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// The part to time, 1 or 2
+    #[arg(short, long, value_name = "PART", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+
+    /// How many timed runs follow the warm-up
+    #[arg(short = 'n', long, value_name = "N", default_value = "10")]
+    runs: NonZeroUsize,
+
+    /// Input file path (if not provided, uses the day's input)
+    #[arg(short, long)]
+    file: Option<String>,
+
+    /// Time the day's example (dayN_example.txt, or the header comment's)
+    #[arg(long, conflicts_with = "file")]
+    example: bool,
+
+    /// Save the timing as the part's baseline, or check it against the saved
+    /// one (exits with 1 when it is slower than --tolerance)
+    #[arg(long, value_enum)]
+    baseline: Option<BaselineMode>,
+
+    /// How much slower than its baseline `--baseline check` lets a run be
+    /// (default 20%)
+    #[arg(long, value_name = "PERCENT", requires = "baseline")]
+    tolerance: Option<String>,
+
+    /// Print only the answer on stdout, and the timing on stderr
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Extra options for the day, given after `--`
+    #[arg(last = true)]
+    day_args: Vec<String>,
+}
+
+impl BenchArgs {
+    /// The same benchmark as `run --bench`
+    fn into_run(self) -> RunArgs {
+        RunArgs {
+            day: Some(Days(vec![self.day])),
+            part: Some(if self.part == 2 { Part::Two } else { Part::One }),
+            bench: Some(self.runs),
+            file: self.file,
+            example: self.example,
+            baseline: self.baseline,
+            tolerance: self.tolerance,
+            quiet: self.quiet,
+            day_args: self.day_args,
+            ..RunArgs::default()
+        }
+    }
+}
+
+/// What --baseline does with a benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BaselineMode {
+    Save,
+    Check,
+}
+
+/// Which part(s) --part runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    Both,
+}
+
+impl Part {
+    /// 1 or 2; None for both
+    fn number(self) -> Option<u8> {
+        match self {
+            Part::One => Some(1),
+            Part::Two => Some(2),
+            Part::Both => None,
+        }
+    }
+
+    /// The parts to solve, in order, as `part2` flags
+    fn parts(self) -> &'static [bool] {
+        match self {
+            Part::One => &[false],
+            Part::Two => &[true],
+            Part::Both => &[false, true],
+        }
+    }
+}
+
+/// The days given to --day, in order and each once
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Days(Vec<u8>);
+
+/// --day's value parser: a comma-separated list of days and ranges of days
+/// ("1,3-5"). Only the syntax is checked here; `run` refuses the days its
+/// catalog has nothing to run for
+fn parse_days(text: &str) -> Result<Days, String> {
+    let bad = |token: &str| format!("'{}' is not a day in 1..=25 or a range like 3-5", token);
+    let day = |text: &str| text.trim().parse::<u8>().ok().filter(|day| (1..=25).contains(day));
+    let mut days = Vec::new();
+    for token in text.split(',').map(str::trim) {
+        let (first, last) = token.split_once('-').unwrap_or((token, token));
+        match (day(first), day(last)) {
+            (Some(first), Some(last)) if first <= last => days.extend(first..=last),
+            _ => return Err(bad(token)),
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(Days(days))
+}
+
+/// `part` as a `part2` flag, for the modes that run one part only
+fn single_part(part: Part, mode: &str) -> Result<bool, String> {
+    match part {
+        Part::Both => Err(format!("--part both does not go with {}; pick --part 1 or --part 2", mode)),
+        part => Ok(part == Part::Two),
+    }
+}
+
+/// Command-line settings shared by every day run
+#[derive(Clone)]
+struct RunSettings {
+    quiet: bool,
+    stream: bool,
+    example: bool,
+    /// Read and populate the answer cache
+    cached: bool,
+    /// Largest input file to load, in MB (None = no limit)
+    size_limit_mb: Option<u64>,
+    glyphs: &'static Glyphs,
+    /// Which of stdout and stderr get color
+    style: Style,
+    /// Notify when a solve finishes slowly (--notify)
+    notify: Option<NotifySettings>,
+    /// Compare with the run history (--diff)
+    diff: bool,
+    /// Where fresh solves are recorded; None without a data directory
+    history: Option<PathBuf>,
+    /// Thousands separators in the result line (--pretty)
+    pretty: bool,
+    /// Each part's solve time on stderr (--time)
+    time: bool,
+    /// Replaces the banner and result lines (--format)
+    format: Option<Template>,
+    /// Replaces the banner, result lines and summary (--output-format)
+    structured: Option<report::Format>,
+    /// Time budgets to judge the finished runs by (--assert-max-time)
+    max_time: Option<Budgets>,
+    /// Download the input instead of reading a file (--input-url)
+    input_url: Option<remote::InputUrl>,
+    /// The clipboard's text, to use as the input (--paste)
+    pasted: Option<String>,
+    /// Solve only this many lines of the input (--limit-lines)
+    limit_lines: Option<usize>,
+    /// Read stdin when the input file is missing; --watch waits for the
+    /// file instead
+    read_stdin: bool,
+    /// Stdin has input piped or redirected in, which a single day reads
+    /// instead of its default input file
+    piped: bool,
+    /// How long a part may solve before it is stopped (--timeout)
+    timeout: Option<Duration>,
+    /// Solve an input with no puzzle text instead of refusing it
+    /// (--allow-empty)
+    allow_empty: bool,
+    /// How many --all parts solve at once (--jobs)
+    jobs: usize,
+    /// Keep the banner and the solver's report lines out of stdout and hand
+    /// the lines back with the answer, for a part solved alongside others
+    capture: bool,
+    opts: DayOptions,
 }
 
 #[derive(Debug, Clone)]
 struct DayInfo {
     number: u8,
-    title: String,
+    meta: DayMeta,
     has_input: bool,
+    /// dayN.txt, or the compressed copy that stands in for it
+    input_path: PathBuf,
+    /// No input file, but the binary carries one (`embedded-inputs` feature)
+    embedded: bool,
+    /// dayN_example.txt, for --example, when there is one
+    example_path: Option<PathBuf>,
+    /// The command that solves it, for a day handed out in adventcode.toml
+    external: Option<String>,
+}
+
+impl DayInfo {
+    /// Has a solver in this crate or an external command
+    fn implemented(&self) -> bool {
+        self.external.is_some() || find_solver(self.number).is_some()
+    }
 }
 
-struct App {
+/// Metadata for every discovered day, loaded once at startup and shared by
+/// the TUI and `run_day`
+struct Catalog {
     days: Vec<DayInfo>,
-    selected_day: ListState,
-    selected_part: usize, // 0 for Part 1, 1 for Part 2
-    in_part_selection: bool,
+    /// The crate root and the input directory in it, which inputs are
+    /// resolved against
+    root: PathBuf,
+    input_dir: PathBuf,
 }
 
-impl App {
-    fn new(days: Vec<DayInfo>) -> Self {
-        let mut selected_day = ListState::default();
-        if !days.is_empty() {
-            selected_day.select(Some(0));
-        }
-        Self {
-            days,
-            selected_day,
-            selected_part: 0,
-            in_part_selection: false,
-        }
+impl Catalog {
+    /// Discover available days by reading the source directory, with the
+    /// inputs beside it (the unit tests, which run from the crate root)
+    #[cfg(test)]
+    fn discover() -> Self {
+        Self::discover_in(Path::new(""), Path::new(""))
     }
 
-    fn next_day(&mut self) {
-        let i = match self.selected_day.selected() {
-            Some(i) => {
-                if i >= self.days.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
+    /// `discover`, with the inputs in `input_dir` (--input-dir)
+    fn discover_with(input_dir: &Path) -> Self {
+        Self::discover_in(Path::new(""), input_dir)
+    }
+
+    /// `discover` for the crate root at `root` instead of the current
+    /// directory; a relative `input_dir` is under `root`
+    fn discover_in(root: &Path, input_dir: &Path) -> Self {
+        let mut catalog = Self { days: Vec::new(), root: root.to_path_buf(), input_dir: input_dir.to_path_buf() };
+
+        // Check for day1.rs through day25.rs
+        for day_num in 1..=25 {
+            let source_file = root.join(format!("src/day{}.rs", day_num));
+            if source_file.exists() {
+                tracing::debug!(day = day_num, path = %source_file.display(), "found a day module");
+                // Parse title, overview and example from the header comment
+                let source = fs::read_to_string(&source_file).unwrap_or_default();
+                catalog.days.push(catalog.unlisted(day_num, &source));
+            } else {
+                tracing::trace!(day = day_num, path = %source_file.display(), "no day module");
             }
-            None => 0,
-        };
-        self.selected_day.select(Some(i));
+        }
+        let dir = root.join("src");
+        tracing::debug!(dir = %dir.display(), found = catalog.days.len(), "looked for day1.rs to day25.rs");
+
+        catalog
     }
 
-    fn previous_day(&mut self) {
-        let i = match self.selected_day.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.days.len() - 1
-                } else {
-                    i - 1
+    /// A day's entry from its source text, inputs resolved but no command
+    fn unlisted(&self, day: u8, source: &str) -> DayInfo {
+        let input_path = input::resolve_in(&self.root, &self.input_dir, day);
+        let has_input = input_path.exists();
+        let example_path = input::example_path_in(input_path.parent().unwrap_or(Path::new("")), day);
+        tracing::trace!(
+            day,
+            input = %input_path.display(),
+            exists = has_input,
+            example = %example_path.as_deref().map_or_else(|| "none".to_string(), |p| p.display().to_string()),
+            "input files"
+        );
+        DayInfo {
+            number: day,
+            meta: meta::parse(day, source),
+            has_input,
+            input_path,
+            embedded: !has_input && embedded::input(day).is_some(),
+            example_path,
+            external: None,
+        }
+    }
+
+    /// Hand the days in `commands` (from adventcode.toml's [external] table)
+    /// to their commands, listing any that have no source file here
+    fn with_external(mut self, commands: BTreeMap<u8, String>) -> Self {
+        for (day, command) in commands {
+            match self.days.iter_mut().find(|d| d.number == day) {
+                Some(info) => info.external = Some(command),
+                None => {
+                    let info = DayInfo { embedded: false, external: Some(command), ..self.unlisted(day, "") };
+                    self.days.push(info);
                 }
             }
-            None => 0,
-        };
-        self.selected_day.select(Some(i));
+        }
+        self.days.sort_by_key(|d| d.number);
+        self
     }
 
-    fn toggle_part(&mut self) {
-        self.selected_part = 1 - self.selected_part;
+    /// Metadata for a day, or a plain "Day N" entry when it has no source file
+    fn info(&self, day: u8) -> DayInfo {
+        self.days
+            .iter()
+            .find(|d| d.number == day)
+            .cloned()
+            .unwrap_or_else(|| self.unlisted(day, ""))
     }
+}
+
+/// Where the inputs are: --input-dir, else adventcode.toml's input_dir, else
+/// the current directory
+fn input_dir(given: Option<&Path>, config: &Config) -> PathBuf {
+    given.map(Path::to_path_buf).or_else(|| config.input_dir.as_ref().map(PathBuf::from)).unwrap_or_default()
+}
 
-    fn get_selected_day(&self) -> Option<&DayInfo> {
-        self.selected_day.selected().and_then(|i| self.days.get(i))
+/// The --file that means stdin
+const STDIN_FILE: &str = "-";
+
+/// Whether stdin has input piped or redirected in: not a terminal, and (on
+/// Unix) a pipe or a file rather than /dev/null
+fn stdin_is_piped() -> bool {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+        use std::os::unix::fs::FileTypeExt;
+        let metadata = stdin.as_fd().try_clone_to_owned().map(fs::File::from).and_then(|file| file.metadata());
+        metadata.is_ok_and(|m| m.file_type().is_fifo() || m.is_file())
     }
+    #[cfg(not(unix))]
+    true
 }
 
-fn main() {
-    let cli = Cli::parse();
+/// Exit code for a missing or unreadable input (EX_NOINPUT from sysexits.h)
+const EXIT_NO_INPUT: i32 = 66;
+
+/// Exit code for a day with no solver yet (EX_UNAVAILABLE from sysexits.h)
+const EXIT_NOT_IMPLEMENTED: i32 = 69;
 
-    // Discover available days
-    let days = discover_days();
+/// Exit code for a part stopped by --timeout (what timeout(1) exits with)
+const EXIT_TIMED_OUT: i32 = 124;
+
+/// Exit code for a panic, a bug rather than a bad input (what Rust exits
+/// with after an uncaught one)
+const EXIT_PANICKED: i32 = 101;
+
+/// A part still solving when its --timeout ran out
+#[derive(Debug)]
+struct TimedOut {
+    day: u8,
+    part2: bool,
+    limit: Duration,
+}
 
-    if days.is_empty() {
-        eprintln!("No day modules found!");
-        std::process::exit(1);
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let part = if self.part2 { 2 } else { 1 };
+        write!(f, "Day {} Part {} timed out after {}s", self.day, part, self.limit.as_secs_f64())
     }
+}
 
-    // If day is specified, run directly
-    if let Some(day) = cli.day {
-        run_day(day, cli.part2, cli.file, cli.quiet);
-    } else if cli.quiet {
-        eprintln!("Error: --day is required when using --quiet mode");
-        std::process::exit(1);
-    } else {
-        // Run TUI
-        match run_tui(days) {
-            Ok((day, part2)) => {
-                // Clear screen and run the selected day
-                println!("\n");
-                run_day(day, part2, None, false);
-            }
-            Err(e) => {
-                eprintln!("TUI error: {}", e);
-                std::process::exit(1);
+impl std::error::Error for TimedOut {}
+
+/// --timeout's watchdog: cancels a solve once `limit` has passed, unless it
+/// is dropped first because the solve finished
+struct Deadline {
+    finished: Option<mpsc::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Deadline {
+    fn arm(limit: Duration, cancel: Cancel) -> Self {
+        let (finished, wait) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            if wait.recv_timeout(limit) == Err(mpsc::RecvTimeoutError::Timeout) {
+                cancel.cancel();
             }
-        }
+        });
+        Self { finished: Some(finished), thread: Some(thread) }
     }
 }
 
-/// Discover available days by reading the source directory
-fn discover_days() -> Vec<DayInfo> {
-    let mut days = Vec::new();
-
-    // Check for day1.rs through day25.rs
-    for day_num in 1..=25 {
-        let source_file = format!("src/day{}.rs", day_num);
-        if std::path::Path::new(&source_file).exists() {
-            // Extract title from the file
-            let title = extract_title_from_file(&source_file, day_num);
-            let has_input = std::path::Path::new(&format!("day{}.txt", day_num)).exists();
-
-            days.push(DayInfo {
-                number: day_num,
-                title,
-                has_input,
-            });
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        // Hanging up wakes the watchdog before its time
+        drop(self.finished.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
         }
     }
+}
 
-    days
+/// A day in 1..=25 that neither has a solver nor an external command
+#[derive(Debug)]
+struct NotImplemented {
+    day: u8,
+    implemented: Vec<u8>,
+    /// src/dayN.rs exists, so the binary only needs rebuilding
+    has_source: bool,
 }
 
-/// Extract the day title from the source file header comment
-fn extract_title_from_file(path: &str, day_num: u8) -> String {
-    if let Ok(content) = fs::read_to_string(path) {
-        // Look for pattern: // DAY N: TITLE
-        let re = Regex::new(r"(?m)^//\s*DAY\s+\d+:\s*(.+?)\s*$").unwrap();
-        if let Some(caps) = re.captures(&content) {
-            return caps.get(1).unwrap().as_str().to_string();
+impl std::fmt::Display for NotImplemented {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let implemented: Vec<String> = self.implemented.iter().map(u8::to_string).collect();
+        let implemented = if implemented.is_empty() { "none".to_string() } else { implemented.join(", ") };
+        write!(f, "Day {} is not implemented (implemented days: {}); ", self.day, implemented)?;
+        if self.has_source {
+            write!(f, "src/day{}.rs is there, but this binary was built without it: rebuild to include it", self.day)
+        } else {
+            write!(
+                f,
+                "scaffold it with `adventcode new --day {} --title ...`, see \"Adding New Days\" in the README",
+                self.day
+            )
         }
     }
-    format!("Day {}", day_num)
 }
 
-/// Run the TUI and return the selected day and part
-fn run_tui(days: Vec<DayInfo>) -> Result<(u8, bool), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+impl std::error::Error for NotImplemented {}
 
-    // Create app state
-    let mut app = App::new(days);
-    let result = run_app(&mut terminal, &mut app)?;
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    result.ok_or_else(|| "No selection made".into())
+/// Parse the command line, refusing `run`'s options given before another
+/// subcommand (`adventcode -d 5 list`), which would be dropped without a word
+fn parse_cli() -> Cli {
+    let matches = Cli::command().get_matches();
+    if let Some(name) = matches.subcommand_name() {
+        let run_args = RunArgs::augment_args(clap::Command::new("run"));
+        if let Some(arg) = run_args
+            .get_arguments()
+            .find(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        {
+            let flag = arg.get_long().map_or_else(|| arg.get_id().to_string(), |long| format!("--{}", long));
+            let message = format!("{} is an option of `adventcode run` and does not go with `{}`", flag, name);
+            Cli::command().error(ErrorKind::ArgumentConflict, message).exit();
+        }
+    }
+    Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
 }
 
-fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut App,
-) -> Result<Option<(u8, bool)>, Box<dyn std::error::Error>> {
-    loop {
-        terminal.draw(|f| ui(f, app))?;
+fn main() {
+    let cli = parse_cli();
+    // Spans are recorded from the start and written however the run ends
+    let recorder = cli.trace_json.is_some().then(trace::Recorder::default);
+    trace::install(recorder.as_ref(), verbose::layer(cli.verbose));
+    let trace = cli.trace_json.clone().zip(recorder);
+    install_panic_hook();
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind != KeyEventKind::Press {
-                continue;
+    let code = match catch_panic(|| run(cli)).unwrap_or_else(|message| Err(Panicked(message).into())) {
+        Ok(code) => code,
+        Err(e) if e.is::<Panicked>() => {
+            eprintln!("Error: {} (set RUST_BACKTRACE=1 to see where)", e);
+            EXIT_PANICKED
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            if e.is::<InputError>() {
+                EXIT_NO_INPUT
+            } else if e.is::<NotImplemented>() {
+                EXIT_NOT_IMPLEMENTED
+            } else if e.is::<TimedOut>() {
+                EXIT_TIMED_OUT
+            } else {
+                1
             }
+        }
+    };
+    if let Some((path, recorder)) = trace
+        && let Err(e) = recorder.write(&path)
+    {
+        eprintln!("Error: could not write the trace to {}: {}", path.display(), e);
+        std::process::exit(code.max(1));
+    }
+    std::process::exit(code);
+}
 
-            if app.in_part_selection {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
-                    KeyCode::Up | KeyCode::Char('k') => app.toggle_part(),
-                    KeyCode::Down | KeyCode::Char('j') => app.toggle_part(),
-                    KeyCode::Enter => {
-                        if let Some(day) = app.get_selected_day() {
-                            return Ok(Some((day.number, app.selected_part == 1)));
-                        }
-                    }
-                    KeyCode::Backspace => app.in_part_selection = false,
-                    _ => {}
+/// Handle the parsed command line and return the process exit code
+fn run(cli: Cli) -> Result<i32, Box<dyn std::error::Error>> {
+    let args = match cli.command {
+        None => cli.run,
+        Some(Command::Run(args)) => *args,
+        Some(Command::Bench(bench)) => bench.into_run(),
+        Some(Command::List(list)) => RunArgs { list: true, json: list.json, ..RunArgs::default() },
+        Some(Command::Init { yes }) => {
+            let mut stdin = io::stdin().lock();
+            let mut stdout = io::stdout().lock();
+            let mut prompter = init::Prompter::new(&mut stdin, &mut stdout, yes);
+            init::run(Path::new("."), init::session_path().as_deref(), &mut prompter)?;
+            return Ok(0);
+        }
+        Some(Command::Notes { day, set, delete }) => {
+            let dir = notes::default_dir().ok_or("No data directory for notes")?;
+            let path = notes::path(&dir, day);
+            let failed = |e: io::Error| format!("Could not write {}: {}", path.display(), e);
+            if delete {
+                if !notes::delete(&dir, day).map_err(failed)? {
+                    eprintln!("Day {} has no note", day);
+                }
+            } else if let Some(text) = set {
+                notes::write(&dir, day, &text).map_err(failed)?;
+                if text.trim().is_empty() {
+                    eprintln!("Deleted the note for Day {}", day);
+                } else {
+                    eprintln!("Saved the note for Day {} to {}", day, path.display());
                 }
             } else {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
-                    KeyCode::Down | KeyCode::Char('j') => app.next_day(),
-                    KeyCode::Up | KeyCode::Char('k') => app.previous_day(),
-                    KeyCode::Enter => app.in_part_selection = true,
-                    _ => {}
+                match notes::read(&dir, day).map_err(|e| format!("Could not read {}: {}", path.display(), e))? {
+                    Some(note) => print!("{}", note),
+                    None => eprintln!("Day {} has no note", day),
                 }
             }
+            return Ok(0);
+        }
+        Some(Command::New { day, title }) => {
+            for path in scaffold::create(Path::new("."), day, &title)? {
+                println!("Wrote {}", path.strip_prefix(".").unwrap_or(&path).display());
+            }
+            println!();
+            println!("Next: fill in solve() in src/day{}.rs, save the input as day{}.txt and run", day, day);
+            println!("  adventcode run -d {}", day);
+            return Ok(0);
+        }
+        Some(Command::Completions { shell }) => {
+            completions::generate(shell, &mut Cli::command(), &mut io::stdout().lock())?;
+            return Ok(0);
         }
+        Some(Command::Repl) => {
+            repl::run(history::default_path())?;
+            return Ok(0);
+        }
+        Some(Command::Clean { cache, history, state, fetched, all, dry_run }) => {
+            let chosen = [cache, history, state, fetched];
+            let targets: Vec<clean::Target> =
+                clean::Target::ALL.into_iter().zip(chosen).filter(|&(_, on)| on || all).map(|(t, _)| t).collect();
+            let data_dir = cache::data_dir().ok_or("No data directory to clean")?;
+            let config_dir = init::session_path().and_then(|path| path.parent().map(Path::to_path_buf));
+            let allowed: Vec<&Path> = [Some(data_dir.as_path()), config_dir.as_deref()].into_iter().flatten().collect();
+            clean::run(&targets, &data_dir, &allowed, dry_run, &mut io::stdout().lock())?;
+            return Ok(0);
+        }
+        Some(Command::Doctor) => {
+            // A broken config file is the config check's to report
+            let config = load_config(cli.no_config).unwrap_or_default();
+            let days = Catalog::discover_with(&input_dir(cli.input_dir.as_deref(), &config)).days;
+            let mut checks = vec![
+                doctor::day_modules(&days),
+                doctor::inputs(&days),
+                doctor::session(config.session.as_deref(), init::session_path().as_deref(), &doctor::Web),
+                doctor::answers(Path::new(init::ANSWERS_FILE)),
+                doctor::config(Path::new(init::CONFIG_FILE)),
+            ];
+            checks.extend(config::user_path().filter(|path| path.exists()).map(|path| doctor::config(&path)));
+            checks.push(doctor::terminal(&doctor::Terminal::detect()));
+            return Ok(doctor::report(&checks, &mut io::stdout().lock())?);
+        }
+    };
+
+    if args.clear_cache {
+        let path = Cache::default_path().ok_or("No data directory for the answer cache")?;
+        if Cache::clear(&path)? {
+            println!("Removed {}", path.display());
+        } else {
+            println!("No answer cache at {}", path.display());
+        }
+        return Ok(0);
     }
-}
 
-fn ui(f: &mut Frame, app: &mut App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(7),  // Header
-            Constraint::Min(10),     // Main content
-            Constraint::Length(3),   // Footer
-        ])
-        .split(f.area());
-
-    // Header
-    let header = Paragraph::new(vec![
-        Line::from(""),
-        Line::from(vec![
-            Span::raw("  "),
-            Span::styled("🎄 ", Style::default().fg(Color::Green)),
-            Span::styled(
-                "Advent of Code Runner",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" 🎄", Style::default().fg(Color::Green)),
-        ])
-        .alignment(Alignment::Center),
-        Line::from(""),
-        Line::from("  Select a day to run")
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray)),
-    ])
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
-    );
-    f.render_widget(header, chunks[0]);
-
-    // Main content area
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(chunks[1]);
-
-    if !app.in_part_selection {
-        // Day selection
-        render_day_list(f, app, main_chunks[0]);
-        render_day_info(f, app, main_chunks[1]);
-    } else {
-        // Part selection
-        render_part_selection(f, app, chunks[1]);
+    let config = load_config(cli.no_config)?;
+    // Config defaults for --quiet and --time; only the flag makes a bare
+    // `adventcode` refuse to open the TUI
+    let quiet = args.quiet || config.quiet == Some(true);
+    let time = args.time || config.time == Some(true);
+
+    // Discover available days, plus those adventcode.toml hands to commands
+    let catalog = Catalog::discover_with(&input_dir(cli.input_dir.as_deref(), &config))
+        .with_external(external::load(Path::new(init::CONFIG_FILE))?);
+
+    if catalog.days.is_empty() {
+        return Err("No day modules found!".into());
     }
 
-    // Footer
-    let footer_text = if app.in_part_selection {
-        "↑↓: Select Part | Enter: Run | Backspace: Back | q: Quit"
-    } else {
-        "↑↓: Navigate | Enter: Select | q: Quit"
-    };
+    let glyphs = glyphs::detect(cli.ascii, cli.accessible || config.accessible == Some(true));
+    let style = Style::detect(cli.no_color);
 
-    let footer = Paragraph::new(footer_text)
-        .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Yellow))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
-    f.render_widget(footer, chunks[2]);
-}
+    if args.list {
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&list_rows(&catalog.days))?);
+        } else {
+            for row in list_rows(&catalog.days) {
+                println!("{}\t{}\t{}\t{}", row.day, row.title.replace('\t', " "), row.input, row.example);
+            }
+        }
+        return Ok(0);
+    }
 
-fn render_day_list(f: &mut Frame, app: &mut App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .days
-        .iter()
-        .map(|day| {
-            let status = if day.has_input { "✓" } else { "✗" };
-            let content = format!("Day {:2}: {} [{}]", day.number, day.title, status);
-            ListItem::new(content)
+    if args.part2 && !quiet {
+        warn(glyphs, style, "-2/--part2 is deprecated; use --part 2");
+    }
+    let part = args.part.or(args.part2.then_some(Part::Two));
+
+    let days = args.day.map_or_else(Vec::new, |days| days.0);
+    // The modes that work on one day's input, or open on one day, take one
+    if days.len() > 1 {
+        let single = [
+            (args.file.is_some(), "--file"),
+            (args.expected.is_some(), "--expected"),
+            (args.bench.is_some(), "--bench"),
+            (args.files.is_some(), "--files"),
+            (args.input_url.is_some(), "--input-url"),
+            (args.paste, "--paste"),
+            (args.tui, "--tui"),
+            (args.watch, "--watch"),
+        ];
+        if let Some((_, flag)) = single.iter().find(|(given, _)| *given) {
+            return Err(format!("{} takes a single --day, not {} days", flag, days.len()).into());
+        }
+    }
+    let day = days.first().copied();
+
+    // A day with nothing to run is refused before any output; the TUI only
+    // offers what it can run
+    if let Some(&day) = days.iter().filter(|_| !args.tui).find(|&&day| !catalog.info(day).implemented()) {
+        let implemented = catalog.days.iter().filter(|d| d.implemented()).map(|d| d.number).collect();
+        let has_source = catalog.days.iter().any(|d| d.number == day);
+        return Err(NotImplemented { day, implemented, has_source }.into());
+    }
+
+    adventcode::parallel::configure(args.max_threads.map(NonZeroUsize::get))?;
+
+    let notify_after = args.notify_after.or(config.notify_after).unwrap_or(notify::DEFAULT_THRESHOLD_SECS);
+    let threshold = Duration::try_from_secs_f64(notify_after)
+        .map_err(|_| format!("--notify-after must be a number of seconds, not {}", notify_after))?;
+    let timeout = args
+        .timeout
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .ok()
+                .filter(|limit| !limit.is_zero())
+                .ok_or_else(|| format!("--timeout must be a positive number of seconds, not {}", secs))
         })
-        .collect();
+        .transpose()?;
+    // What the TUI's settings screen shows as set for this session only
+    let overrides =
+        config::Overrides { default_part: part.and_then(Part::number), notify_after: args.notify_after, accessible: cli.accessible };
 
-    let list = List::new(items)
-        .block(
-            Block::default()
-                .title("Available Days")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green)),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("▶ ");
-
-    f.render_stateful_widget(list, area, &mut app.selected_day);
-}
-
-fn render_day_info(f: &mut Frame, app: &App, area: Rect) {
-    let info_text = if let Some(day) = app.get_selected_day() {
-        let input_status = if day.has_input {
-            format!("✓ Input file: day{}.txt", day.number)
-        } else {
-            format!("✗ No input file (day{}.txt missing)", day.number)
-        };
+    let artifacts = args
+        .emit_intermediate
+        .as_deref()
+        .map(|dir| Artifacts::create(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e)))
+        .transpose()?;
 
-        vec![
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Day: ", Style::default().fg(Color::Cyan)),
-                Span::styled(
-                    day.number.to_string(),
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                ),
-            ]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Title: ", Style::default().fg(Color::Cyan)),
-                Span::raw(&day.title),
-            ]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Status: ", Style::default().fg(Color::Cyan)),
-                Span::raw(input_status),
-            ]),
-            Line::from(""),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Press ", Style::default().fg(Color::Gray)),
-                Span::styled("Enter", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::styled(" to continue", Style::default().fg(Color::Gray)),
-            ]),
-        ]
-    } else {
-        vec![Line::from("No day selected")]
+    let max_time = args.assert_max_time.map(|budget| Budgets::load(Path::new(init::CONFIG_FILE), budget)).transpose()?;
+
+    let input_url = match args.input_url {
+        Some(url) => Some(remote::InputUrl {
+            url,
+            credentials: remote::Credentials::load(Path::new(init::CONFIG_FILE))?,
+            cache_dir: remote::InputUrl::default_cache_dir(),
+            refresh: args.no_cache,
+        }),
+        None => None,
     };
 
-    let info = Paragraph::new(info_text)
-        .block(
-            Block::default()
-                .title("Details")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green)),
-        )
-        .wrap(Wrap { trim: true });
-
-    f.render_widget(info, area);
-}
-
-fn render_part_selection(f: &mut Frame, app: &App, area: Rect) {
-    let day = app.get_selected_day().unwrap();
-
-    // Center the selection box
-    let vertical_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(25),
-            Constraint::Percentage(50),
-            Constraint::Percentage(25),
-        ])
-        .split(area);
-
-    let horizontal_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(15),
-            Constraint::Percentage(70),
-            Constraint::Percentage(15),
-        ])
-        .split(vertical_chunks[1]);
-
-    let center_area = horizontal_chunks[1];
-
-    let inner_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Length(9),  // Part selection
-        ])
-        .split(center_area);
-
-    // Title
-    let title = Paragraph::new(format!("Day {}: {}", day.number, day.title))
-        .alignment(Alignment::Center)
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green)),
-        );
-    f.render_widget(title, inner_chunks[0]);
-
-    // Part selection with clear visual separation
-    let part1_style = if app.selected_part == 0 {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
-            .bg(Color::DarkGray)
-    } else {
-        Style::default().fg(Color::White)
+    let pasted = match day.filter(|_| args.paste) {
+        Some(day) => {
+            // A compressed input stays as it is; the text goes beside it
+            let input_path = catalog.info(day).input_path;
+            let save_to = args.save_input.then(|| input_path.with_file_name(format!("day{}.txt", day)));
+            let text = clipboard::paste(&clipboard::System, save_to.as_deref())?;
+            if let Some(path) = save_to.filter(|_| !quiet) {
+                eprintln!("Saved the clipboard to {}", path.display());
+            }
+            Some(text)
+        }
+        None => None,
     };
 
-    let part2_style = if app.selected_part == 1 {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
-            .bg(Color::DarkGray)
-    } else {
-        Style::default().fg(Color::White)
+    let settings = RunSettings {
+        // A template or document is the whole of stdout, like the bare
+        // answer with --quiet
+        quiet: quiet || args.format.is_some() || args.output_format.is_some(),
+        stream: args.stream,
+        example: args.example,
+        cached: args.cached && !args.no_cache,
+        size_limit_mb: (!args.allow_large_input).then_some(args.max_input_mb.unwrap_or(input::DEFAULT_LIMIT_MB)),
+        glyphs,
+        style,
+        notify: (args.notify || args.notify_no_answer).then_some(NotifySettings { threshold, show_answer: !args.notify_no_answer }),
+        diff: args.diff,
+        history: history::default_path(),
+        pretty: args.pretty,
+        time,
+        format: args.format,
+        structured: args.output_format,
+        max_time,
+        input_url,
+        pasted,
+        limit_lines: args.limit_lines.map(NonZeroUsize::get),
+        read_stdin: !args.watch,
+        // Not for the TUI, which needs the terminal on stdin anyway, nor
+        // for several days, which one pipe cannot feed
+        piped: days.len() == 1 && !args.tui && !args.watch && args.file.is_none() && stdin_is_piped(),
+        timeout,
+        allow_empty: args.allow_empty,
+        jobs: args.jobs.map_or(1, NonZeroUsize::get),
+        capture: false,
+        opts: DayOptions::new(args.day_args)
+            .with_strict(args.strict)
+            .with_lenient_numbers(args.lenient_numbers)
+            .with_artifacts(artifacts),
     };
 
-    let part1_symbol = if app.selected_part == 0 { "▶ " } else { "  " };
-    let part2_symbol = if app.selected_part == 1 { "▶ " } else { "  " };
-
-    let text = vec![
-        Line::from(""),
-        Line::from("  Select which part to run:")
-            .style(Style::default().fg(Color::Gray)),
-        Line::from(""),
-        Line::from(vec![
-            Span::raw("      "),
-            Span::styled(part1_symbol, part1_style),
-            Span::styled("Part 1", part1_style),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::raw("      "),
-            Span::styled(part2_symbol, part2_style),
-            Span::styled("Part 2", part2_style),
-        ]),
-    ];
-
-    let parts_widget = Paragraph::new(text)
-        .block(
-            Block::default()
-                .title(" Select Part ")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Magenta)),
-        );
-    f.render_widget(parts_widget, inner_chunks[1]);
-}
+    if args.all {
+        let parts = [!args.only_part2, !args.only_part1];
+        let plan = plan_all(&catalog.days, parts, &args.skip)?;
+        return Ok(run_all(&plan, &settings));
+    }
 
-fn run_day(day: u8, part2: bool, file: Option<String>, quiet: bool) {
-    // Determine input file path
-    let input_file = file.unwrap_or_else(|| format!("day{}.txt", day));
+    if args.rerun_failed {
+        let path = settings.history.as_deref().ok_or("No data directory for the run history")?;
+        let failing = history::failing(&history::load(path));
+        if failing.is_empty() {
+            println!("No failed runs in {}, nothing to rerun", path.display());
+            return Ok(0);
+        }
+        let plan = plan_failed(&catalog.days, &failing);
+        if !settings.quiet {
+            println!("Rerunning {} part(s) that failed last time", plan.len());
+        }
+        return Ok(run_all(&plan, &settings));
+    }
 
-    // Read input from file or stdin
-    let input = if std::path::Path::new(&input_file).exists() {
-        fs::read_to_string(&input_file)
-            .unwrap_or_else(|_| panic!("Failed to read file: {}", input_file))
+    // If day is specified, run directly (unless --tui asks for the picker)
+    if args.tui {
+        let part2 = match part {
+            Some(part) => single_part(part, "--tui")?,
+            None => config.default_part == Some(2),
+        };
+        run_picker(catalog, &settings, day.map(|day| (day, part2)), tui_config(cli.no_config)?, overrides)?;
+    } else if let (Some(day), Some(runs)) = (day, args.bench) {
+        let tolerance = args.tolerance.as_deref().map_or(Ok(bench::DEFAULT_TOLERANCE), bench::parse_tolerance)?;
+        let part2 = single_part(part.unwrap_or(Part::One), "--bench")?;
+        return run_bench(&catalog.info(day), part2, args.file, &settings, runs.get(), args.baseline, tolerance);
+    } else if let (Some(day), true) = (day, args.watch) {
+        if args.file.as_deref() == Some(STDIN_FILE) {
+            return Err("--watch needs a file to watch, not stdin (--file -)".into());
+        }
+        return watch_day(&catalog.info(day), part.unwrap_or(Part::One), args.file.map(PathBuf::from), &settings);
+    } else if let (Some(day), Some(pattern)) = (day, &args.files) {
+        let part2 = single_part(part.unwrap_or(Part::One), "--files")?;
+        return run_files(&catalog.info(day), part2, pattern, &settings);
+    } else if !days.is_empty() {
+        let part = part.unwrap_or(Part::One);
+        if args.expected.is_some() {
+            single_part(part, "--expected")?;
+        }
+        // --output's lines, written once every day has solved; with --quiet
+        // they are the only place the answers go
+        let mut answers = args.output.as_ref().map(|_| String::new());
+        let to_file_only = quiet && answers.is_some();
+        let mut rows = Vec::new();
+        let mut runs = Vec::new();
+        let mut checked = None;
+        for &day in &days {
+            let info = catalog.info(day);
+            let solved = run_parts(&info, part, args.file.clone(), &settings, |part2, solved| {
+                // --output's lines say which day and part they are once there
+                // are several; --quiet's stdout stays one bare answer per line
+                let number = if part2 { 2 } else { 1 };
+                let which = if days.len() > 1 {
+                    format!("Day {} Part {}: ", day, number)
+                } else if part == Part::Both {
+                    format!("Part {}: ", number)
+                } else {
+                    String::new()
+                };
+                if let Some(answers) = &mut answers {
+                    answers.push_str(&format!("{}{}\n", which, solved.value));
+                }
+                if to_file_only {
+                    print_time(&info, part2, solved, &settings);
+                } else {
+                    print_answer(&info, part2, solved, &settings);
+                }
+                if let Some(diff) = &solved.diff {
+                    print_diff(diff, &settings);
+                }
+            })?;
+            let Some(solved) = solved else {
+                rows.extend(part.parts().iter().map(|&part2| report_row(&info, part2, report::Status::NotImplemented)));
+                continue;
+            };
+            for (part2, solved) in &solved {
+                rows.push(report_row(&info, *part2, report::Status::Ok).solved(&solved.value, solved.millis, &solved.input));
+                runs.push((day, *part2, solved.millis));
+            }
+            checked = solved.into_iter().next().map(|(_, solved)| solved);
+        }
+        if let (Some(path), Some(answers)) = (&args.output, answers.filter(|a| !a.is_empty())) {
+            save_answers(path, &answers, args.append)?;
+        }
+        // One document for every day
+        if let Some(format) = settings.structured {
+            print!("{}", report::render(format, &report::Report { results: rows }));
+        }
+        let over_budget = check_budgets(&settings, runs);
+        // --expected comes with a single day and part
+        if let (Some(expected), Some(solved)) = (&args.expected, &checked) {
+            return Ok(report_expected(solved, expected, quiet, settings.style).max(over_budget));
+        }
+        return Ok(over_budget);
+    } else if args.quiet {
+        return Err("--day is required when using --quiet mode".into());
     } else {
-        if !quiet {
-            eprintln!("⚠ File '{}' not found, reading from stdin...", input_file);
-        }
-        let mut buffer = String::new();
-        io::stdin()
-            .read_to_string(&mut buffer)
-            .expect("Failed to read from stdin");
-        buffer
-    };
+        run_picker(catalog, &settings, None, tui_config(cli.no_config)?, overrides)?;
+    }
 
-    // Print header in non-quiet mode
-    if !quiet {
-        let day_info = discover_days()
-            .into_iter()
-            .find(|d| d.number == day)
-            .unwrap_or_else(|| DayInfo {
-                number: day,
-                title: format!("Day {}", day),
-                has_input: false,
-            });
+    Ok(0)
+}
+
+/// The settings for this run: adventcode.toml over the user config, or
+/// none of either with --no-config
+fn load_config(no_config: bool) -> Result<Config, String> {
+    if no_config {
+        tracing::debug!("--no-config: not reading adventcode.toml or the user config");
+        return Ok(Config::default());
+    }
+    let user = config::user_path();
+    for path in [Some(Path::new(init::CONFIG_FILE)), user.as_deref()].into_iter().flatten() {
+        tracing::debug!(path = %path.display(), exists = path.exists(), "config file");
+    }
+    Config::layered(Path::new(init::CONFIG_FILE), user.as_deref())
+}
+
+/// For the TUI's settings screen, which saves what it edits back to
+/// adventcode.toml: that file's settings, and the user config's under them
+/// (none with --no-config)
+fn tui_config(no_config: bool) -> Result<(Config, Config), String> {
+    let user = match config::user_path() {
+        Some(path) if !no_config => Config::load(&path)?,
+        _ => Config::default(),
+    };
+    Ok((Config::load(Path::new(init::CONFIG_FILE))?, user))
+}
 
-        let part_name = if part2 { "Part 2" } else { "Part 1" };
+/// No --day given (or --tui): let the user pick one in the TUI, starting
+/// from `preselect` (day, part2) if given, then run it
+#[cfg(feature = "tui")]
+fn run_picker(
+    catalog: Catalog,
+    settings: &RunSettings,
+    preselect: Option<(u8, bool)>,
+    (config, user): (Config, Config),
+    overrides: config::Overrides,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = state::State::default_path();
+    let screen = settings::Settings::new(config, PathBuf::from(init::CONFIG_FILE), init::session_path(), overrides)
+        .with_user(user);
+    let history = settings.history.clone();
+    let (day, part2) = tui::run_tui(catalog.days, settings.glyphs, history, state, notes::default_dir(), screen, preselect)
+        .map_err(|e| format!("TUI error: {}", e))?;
 
-        println!("{}", "─".repeat(60));
-        println!(
-            "🎄 Day {}: {} │ {}",
-            day, day_info.title, part_name
-        );
-        println!("{}", "─".repeat(60));
-        print!("Result: ");
+    // Clear screen and run the selected day
+    println!("\n");
+    if let Some(solved) = run_day(&day, part2, None, settings)? {
+        print_answer(&day, part2, &solved, settings);
+        if let Some(diff) = &solved.diff {
+            print_diff(diff, settings);
+        }
     }
+    Ok(())
+}
+
+/// No --day given in a build without the TUI: explain what to do instead
+#[cfg(not(feature = "tui"))]
+fn run_picker(
+    _catalog: Catalog,
+    _settings: &RunSettings,
+    _preselect: Option<(u8, bool)>,
+    _config: (Config, Config),
+    _overrides: config::Overrides,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("this build has no interactive TUI (built without the `tui` feature); \
+         pass --day N to run a day, or --list to see the available days"
+        .into())
+}
+
+/// One day in `--list` and `--list --json`
+#[derive(Serialize)]
+struct ListRow<'a> {
+    day: u8,
+    title: &'a str,
+    /// dayN.txt (or a compressed copy) exists; an embedded input does not count
+    input: bool,
+    example: bool,
+}
+
+fn list_rows(days: &[DayInfo]) -> Vec<ListRow<'_>> {
+    days.iter()
+        .map(|day| ListRow {
+            day: day.number,
+            title: &day.meta.title,
+            input: day.has_input,
+            example: day.example_path.is_some(),
+        })
+        .collect()
+}
 
-    match day {
-        1 => day1::solve(&input, part2),
-        2 => day2::solve(&input, part2),
-        3 => day3::solve(&input, part2),
-        4 => day4::solve(&input, part2),
-        5 => day5::solve(&input, part2),
-        _ => eprintln!("Day {} not implemented yet", day),
+/// The middle line of the banner printed before a day's result
+fn banner(day: &DayInfo, part2: bool, glyphs: &Glyphs) -> String {
+    let part_name = if part2 { "Part 2" } else { "Part 1" };
+    glyphs.titled(&format!("Day {}: {} {} {}", day.number, day.meta.title, glyphs.separator, part_name))
+}
+
+/// Print the banner between two rules
+fn print_banner(day: &DayInfo, part2: bool, glyphs: &Glyphs, style: Style) {
+    println!("{}", glyphs.rule.repeat(60));
+    println!("{}", style.heading(banner(day, part2, glyphs)));
+    println!("{}", glyphs.rule.repeat(60));
+}
+
+/// A warning on stderr after the warning glyph, yellow on a terminal
+fn warn(glyphs: &Glyphs, style: Style, message: impl std::fmt::Display) {
+    eprintln!("{}", style.warning(Stream::Stderr, format_args!("{} {}", glyphs.warning, message)));
+}
+
+/// A (day, part) that --all will run, or skip and say why
+#[derive(Debug)]
+struct Planned<'a> {
+    day: &'a DayInfo,
+    part2: bool,
+    skip: Option<&'static str>,
+}
+
+const SKIPPED_BY_REQUEST: &str = "skipped (by request)";
+const SKIPPED_NO_INPUT: &str = "skipped (no input)";
+
+/// Lay out an --all run: every discovered day, each part allowed by `parts`
+/// ([part 1, part 2]), minus the days in `skip`
+///
+/// Filtered-out runs stay in the plan as skipped so the summary shows them.
+/// Fails when `skip` names an unknown day or the filters leave nothing to run.
+fn plan_all<'a>(days: &'a [DayInfo], parts: [bool; 2], skip: &[u8]) -> Result<Vec<Planned<'a>>, String> {
+    if let Some(unknown) = skip.iter().find(|&&n| !days.iter().any(|d| d.number == n)) {
+        return Err(format!("--skip {}: there is no day {}", unknown, unknown));
+    }
+
+    let plan: Vec<Planned> = days
+        .iter()
+        .flat_map(|day| [false, true].map(|part2| (day, part2)))
+        .map(|(day, part2)| {
+            let skip = if skip.contains(&day.number) || !parts[usize::from(part2)] {
+                Some(SKIPPED_BY_REQUEST)
+            } else if !day.has_input && !day.embedded {
+                // Never fall back to stdin in a loop over days
+                Some(SKIPPED_NO_INPUT)
+            } else {
+                None
+            };
+            Planned { day, part2, skip }
+        })
+        .collect();
+
+    if plan.iter().all(|p| p.skip == Some(SKIPPED_BY_REQUEST)) {
+        return Err("--skip and --only-part1/--only-part2 leave nothing for --all to run".into());
+    }
+    Ok(plan)
+}
+
+/// Lay out a --rerun-failed run: each (day, part) in `failing`, skipping
+/// those without an input; days no longer discovered are left out
+fn plan_failed<'a>(days: &'a [DayInfo], failing: &[(u8, u8)]) -> Vec<Planned<'a>> {
+    failing
+        .iter()
+        .filter_map(|&(number, part)| {
+            let Some(day) = days.iter().find(|d| d.number == number) else {
+                eprintln!("Day {} Part {} failed last time, but there is no day {} any more", number, part, number);
+                return None;
+            };
+            let skip = (!day.has_input && !day.embedded).then_some(SKIPPED_NO_INPUT);
+            Some(Planned { day, part2: part == 2, skip })
+        })
+        .collect()
+}
+
+/// One finished (or skipped) run of an --all plan
+struct Row {
+    day: u8,
+    part2: bool,
+    outcome: String,
+    failed: bool,
+    diff: Option<Diff>,
+    /// The same run for --output-format
+    report: report::Row,
+}
+
+/// Run each part of an --all plan, printing answers as they come
+/// A failing or panicking day becomes a failed row, recorded in the run
+/// history for --rerun-failed, and the rest still run
+fn run_plan(plan: &[Planned], settings: &RunSettings) -> Vec<Row> {
+    if settings.jobs > 1 && plan.len() > 1 {
+        return run_plan_parallel(plan, settings);
+    }
+    plan.iter().map(|p| finish(p, attempt(p, settings), settings)).collect()
+}
+
+/// --jobs: solve the plan's parts on up to `settings.jobs` threads. Each
+/// part's banner, report lines and answer print together, in plan order,
+/// once every part before it has finished
+fn run_plan_parallel(plan: &[Planned], settings: &RunSettings) -> Vec<Row> {
+    let each = RunSettings { capture: true, ..settings.clone() };
+    let next = AtomicUsize::new(0);
+    let (done, finished) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..settings.jobs.min(plan.len()) {
+            let (done, next, each) = (done.clone(), &next, &each);
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(p) = plan.get(i) else { break };
+                    if done.send((i, attempt(p, each))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(done);
+
+        // Parts finish in any order; each waits for those before it
+        let mut waiting = BTreeMap::new();
+        let mut rows = Vec::with_capacity(plan.len());
+        for (i, outcome) in finished {
+            waiting.insert(i, outcome);
+            while let Some(outcome) = waiting.remove(&rows.len()) {
+                rows.push(finish(&plan[rows.len()], outcome, settings));
+            }
+        }
+        rows
+    })
+}
+
+/// What solving a planned part came to: its answer (None when the day has
+/// no solver yet) or why it failed; None for a part the plan skips
+type Outcome = Option<Result<Option<Solved>, String>>;
+
+/// Solve a planned part, unless the plan skips it
+fn attempt(p: &Planned, settings: &RunSettings) -> Outcome {
+    p.skip.is_none().then(|| {
+        // A panic leaves nothing behind that a later day could see
+        match catch_panic(|| run_day(p.day, p.part2, None, settings)) {
+            Ok(run) => run.map_err(|e| e.to_string()),
+            Err(message) => Err(format!("panicked: {}", message)),
+        }
+    })
+}
+
+/// Print what came of a planned part, and its summary row
+fn finish(p: &Planned, outcome: Outcome, settings: &RunSettings) -> Row {
+    let mut row = Row {
+        day: p.day.number,
+        part2: p.part2,
+        outcome: String::new(),
+        failed: false,
+        diff: None,
+        report: report_row(p.day, p.part2, report::Status::Ok),
+    };
+    let Some(outcome) = outcome else {
+        let reason = p.skip.unwrap_or_default();
+        row.outcome = reason.to_string();
+        let reason = reason.trim_start_matches("skipped (").trim_end_matches(')');
+        row.report = report_row(p.day, p.part2, report::Status::Skipped).error(reason);
+        return row;
+    };
+    match outcome {
+        Ok(Some(mut solved)) => {
+            if let Some(output) = solved.output.take() {
+                print_captured(p.day, p.part2, &output, settings);
+            }
+            if settings.format.is_some() {
+                print_answer(p.day, p.part2, &solved, settings);
+            } else if !settings.quiet {
+                print_answer(p.day, p.part2, &solved, settings);
+                println!();
+            } else {
+                // The answers come in the summary
+                print_time(p.day, p.part2, &solved, settings);
+            }
+            row.report = row.report.solved(&solved.value, solved.millis, &solved.input);
+            // Quiet output is bare, in the summary as in print_result
+            row.outcome = if settings.quiet { solved.value } else { solved.answer };
+            row.diff = solved.diff;
+        }
+        Ok(None) => {
+            row.outcome = "not implemented".to_string();
+            row.report.status = report::Status::NotImplemented;
+        }
+        Err(e) => {
+            row.failed = true;
+            row.outcome = format!("failed: {}", e);
+        }
+    }
+    if row.failed {
+        let error = row.outcome.trim_start_matches("failed: ");
+        row.report = report_row(p.day, p.part2, report::Status::Failed).error(error);
+        // Only --rerun-failed reads these; failing to write one is no error
+        if let Some(path) = settings.history.as_deref() {
+            let _ = history::append(path, &history::Record::failed(p.day.number, p.part2, error));
+        }
+    }
+    row
+}
+
+/// The banner and report lines of a part solved alongside others, printed
+/// where run_parts would have printed them
+fn print_captured(day: &DayInfo, part2: bool, output: &Output, settings: &RunSettings) {
+    if !settings.quiet {
+        print_banner(day, part2, settings.glyphs, settings.style);
+    }
+    let warning = settings.glyphs.warning;
+    let mut out = if settings.quiet { Output::Stderr { warning } } else { Output::Stdout { warning } };
+    for line in output.lines() {
+        out.line(line);
+    }
+    for warning in output.warnings() {
+        out.warn(warning);
+    }
+}
+
+/// A panic caught by `main`, with its message
+#[derive(Debug)]
+struct Panicked(String);
+
+impl std::fmt::Display for Panicked {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "panicked: {}", self.0)
+    }
+}
+
+impl std::error::Error for Panicked {}
+
+thread_local! {
+    /// How many `catch_panic` calls this thread is inside
+    static CATCHING: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Run `f`, turning a panic into its message; `catch_panic`'s caller
+/// reports it, so the panic hook prints nothing
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    CATCHING.set(CATCHING.get() + 1);
+    let run = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(CATCHING.get() - 1);
+    run.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Leave the panics `catch_panic` catches to whoever called it, so a solver
+/// bug is one line on stderr rather than the panic message and a backtrace
+/// note. With RUST_BACKTRACE set the default hook prints them all as usual
+fn install_panic_hook() {
+    if std::env::var_os("RUST_BACKTRACE").is_some() {
+        return;
+    }
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if CATCHING.get() == 0 {
+            default(info);
+        }
+    }));
+}
+
+/// The message a panic was raised with, on one line so it fits a summary row
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "(no message)"
+    };
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Run an --all plan and print its summary; returns the exit code
+fn run_all(plan: &[Planned], settings: &RunSettings) -> i32 {
+    let rows = run_plan(plan, settings);
+
+    if let Some(format) = settings.structured {
+        let results = rows.iter().map(|row| row.report.clone()).collect();
+        print!("{}", report::render(format, &report::Report { results }));
+    } else if settings.format.is_some() {
+        // The template lines are the output; only failures still need saying
+        for row in rows.iter().filter(|row| row.failed) {
+            eprintln!("{}", summary_row(row.day, row.part2, &row.outcome));
+        }
+    } else {
+        if !settings.quiet {
+            let glyphs = settings.glyphs;
+            println!("{}", glyphs.rule.repeat(60));
+            println!("{}", settings.style.heading(glyphs.titled("Summary")));
+            println!("{}", glyphs.rule.repeat(60));
+        }
+        for row in &rows {
+            println!("{}", summary_row(row.day, row.part2, &row.outcome));
+        }
+    }
+
+    let diffs: Vec<(&Row, &Diff)> = rows.iter().filter_map(|row| Some((row, row.diff.as_ref()?))).collect();
+    if settings.diff && !diffs.is_empty() {
+        let glyphs = settings.glyphs;
+        println!("{}", glyphs.rule.repeat(60));
+        println!("{}", settings.style.heading(glyphs.titled("Since the last run")));
+        println!("{}", glyphs.rule.repeat(60));
+        for (row, diff) in diffs {
+            println!("{}", summary_row(row.day, row.part2, &diff.row()));
+        }
+    }
+    let runs = rows.iter().filter_map(|row| Some((row.day, row.part2, row.report.duration_ms?)));
+    let over_budget = check_budgets(settings, runs);
+    i32::from(rows.iter().any(|row| row.failed)).max(over_budget)
+}
+
+/// With --assert-max-time, list the runs that took longer than their budget
+/// on stderr; returns the exit code, 1 when there were any
+fn check_budgets(settings: &RunSettings, runs: impl IntoIterator<Item = (u8, bool, f64)>) -> i32 {
+    let Some(budgets) = &settings.max_time else {
+        return 0;
+    };
+    let over = budgets.judge(runs);
+    if over.is_empty() {
+        return 0;
+    }
+    warn(settings.glyphs, settings.style, format_args!("{} part(s) over the time budget:", over.len()));
+    for overrun in &over {
+        eprintln!("{}", summary_row(overrun.day, overrun.part2, &overrun.to_string()));
+    }
+    1
+}
+
+/// A part's --output-format row, before any answer or error is known
+fn report_row(day: &DayInfo, part2: bool, status: report::Status) -> report::Row {
+    report::Row {
+        day: day.number,
+        part: if part2 { 2 } else { 1 },
+        title: day.meta.title.clone(),
+        status,
+        answer: None,
+        duration_ms: None,
+        input: None,
+        error: None,
+    }
+}
+
+/// One line of the --all summary
+fn summary_row(day: u8, part2: bool, outcome: &str) -> String {
+    format!("Day {:2} Part {}: {}", day, if part2 { 2 } else { 1 }, outcome)
+}
+
+/// A day's answer, and " (cached)" when it came from the answer cache
+struct Solved {
+    /// As printed, e.g. "Password: 984"
+    answer: String,
+    /// Just the value, e.g. "984", for --expected
+    value: String,
+    /// The answer with thousands separators, for --pretty
+    pretty: String,
+    marker: &'static str,
+    /// How long the solve took (when it was cached, for a cached answer)
+    millis: f64,
+    /// Where the input came from: a path, "example", "embedded" or "stdin"
+    input: String,
+    /// How it compares with the last recorded run, with --diff
+    diff: Option<Diff>,
+    /// The solver's report lines and warnings, when they were captured
+    /// rather than printed
+    output: Option<Output>,
+}
+
+/// Each part run, as (part2, its answer)
+type SolvedParts = Vec<(bool, Solved)>;
+
+/// Whether `solved` is the answer given to --expected; only surrounding
+/// whitespace is ignored
+fn matches_expected(solved: &Solved, expected: &str) -> bool {
+    solved.value.trim() == expected.trim()
+}
+
+/// Print PASS/FAIL for --expected (on stderr in quiet mode, where stdout
+/// carries only the answer) and return the exit code
+fn report_expected(solved: &Solved, expected: &str, quiet: bool, style: Style) -> i32 {
+    let stream = if quiet { Stream::Stderr } else { Stream::Stdout };
+    let passed = matches_expected(solved, expected);
+    let verdict = if passed {
+        format!("{}: {} (expected {})", style.verdict(stream, true, "PASS"), solved.value, expected.trim())
+    } else {
+        format!("{}: expected {}, got {}", style.verdict(stream, false, "FAIL"), expected.trim(), solved.value)
+    };
+    if quiet {
+        eprintln!("{}", verdict);
+    } else {
+        println!("{}", verdict);
+    }
+    i32::from(!passed)
+}
+
+/// Print the answer after the banner (alone with --quiet, or as the --format
+/// template's line); with --output-format the document carries it instead
+fn print_answer(info: &DayInfo, part2: bool, solved: &Solved, settings: &RunSettings) {
+    print_result(info, part2, solved, settings);
+    print_time(info, part2, solved, settings);
+}
+
+/// With --time, how long the part took, on stderr
+fn print_time(info: &DayInfo, part2: bool, solved: &Solved, settings: &RunSettings) {
+    if settings.time {
+        let part = if part2 { 2 } else { 1 };
+        eprintln!("Day {} Part {} time: {}", info.number, part, elapsed(solved.millis, settings.glyphs));
+    }
+}
+
+/// A solve time in the unit that reads best: "840 µs", "12.3 ms", "2.41 s"
+fn elapsed(millis: f64, glyphs: &Glyphs) -> String {
+    if millis < 1.0 {
+        format!("{:.0} {}", millis * 1000.0, if glyphs.ascii { "us" } else { "µs" })
+    } else if millis < 1000.0 {
+        format!("{:.1} ms", millis)
+    } else {
+        format!("{:.2} s", millis / 1000.0)
+    }
+}
+
+/// The answer, as the result line, bare (--quiet) or through --format
+fn print_result(info: &DayInfo, part2: bool, solved: &Solved, settings: &RunSettings) {
+    if settings.structured.is_some() {
+        return;
+    }
+    if let Some(template) = &settings.format {
+        let values = template::Values {
+            day: info.number,
+            part2,
+            title: &info.meta.title,
+            answer: &solved.value,
+            millis: solved.millis,
+            input: &solved.input,
+        };
+        println!("{}", template.render(&values));
+        return;
+    }
+    // Bare: scripts read the value without knowing each day's label
+    if settings.quiet {
+        println!("{}", solved.value);
+        return;
+    }
+    let answer = if settings.pretty { &solved.pretty } else { &solved.answer };
+    println!("Result: {}{}", settings.style.answer(answer), solved.marker);
+
+    // Show what the puzzle text says the header's example should give; a
+    // dayN_example.txt may hold any example
+    let expected = info.meta.example.as_ref().and_then(|e| e.answers[usize::from(part2)].as_ref());
+    if let (true, Some(expected)) = (settings.example && solved.input == "example", expected) {
+        println!("Expected: {}", expected);
+    }
+}
+
+/// Print a --diff comparison after the answer (on stderr in quiet mode);
+/// a changed answer gets the warning glyph so it stands out
+fn print_diff(diff: &Diff, settings: &RunSettings) {
+    let line = format!("Diff: {}", diff);
+    let stream = if settings.quiet { Stream::Stderr } else { Stream::Stdout };
+    let line =
+        if diff.changed() { settings.style.warning(stream, format_args!("{} {}", settings.glyphs.warning, line)) } else { line };
+    if settings.quiet {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Write --output's answer lines to `path`, after its missing directories,
+/// replacing what it held unless `append`
+fn save_answers(path: &Path, answers: &str, append: bool) -> Result<(), String> {
+    let fail = |e: io::Error| format!("Could not write the answer to {}: {}", path.display(), e);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(fail)?;
+    }
+    let mut file = fs::OpenOptions::new().create(true).write(true).append(append).truncate(!append).open(path).map_err(fail)?;
+    io::Write::write_all(&mut file, answers.as_bytes()).map_err(fail)
+}
+
+/// The library's solver for a day; test builds add stub days of their own
+fn find_solver(day: u8) -> Option<&'static adventcode::Day> {
+    #[cfg(test)]
+    if let Some(stub) = tests::STUB_DAYS.iter().find(|d| d.number == day) {
+        return Some(stub);
+    }
+    adventcode::day(day)
+}
+
+/// Solve one part of a day, printing the banner (unless quiet) but not the
+/// answer; None when the day has no solver yet
+fn run_day(
+    info: &DayInfo,
+    part2: bool,
+    file: Option<String>,
+    settings: &RunSettings,
+) -> Result<Option<Solved>, Box<dyn std::error::Error>> {
+    let part = if part2 { Part::Two } else { Part::One };
+    Ok(run_parts(info, part, file, settings, |_, _| {})?.and_then(|solved| solved.into_iter().next()).map(|(_, solved)| solved))
+}
+
+/// Solve `part` of a day, reading the input once however many parts that
+/// is; each part's banner is printed (unless quiet) before its solve, and
+/// `solved` is called with its answer before the next part starts. None
+/// when the day has no solver yet
+fn run_parts(
+    info: &DayInfo,
+    part: Part,
+    file: Option<String>,
+    settings: &RunSettings,
+    mut each: impl FnMut(bool, &Solved),
+) -> Result<Option<SolvedParts>, Box<dyn std::error::Error>> {
+    let day = info.number;
+    let RunSettings {
+        quiet,
+        stream,
+        example,
+        cached,
+        size_limit_mb,
+        glyphs,
+        style,
+        ref notify,
+        diff,
+        ref history,
+        limit_lines,
+        ref opts,
+        ..
+    } = *settings;
+
+    // Reject unknown per-day options before touching the input; an
+    // external command takes none
+    let external = info.external.as_deref();
+    let solver = find_solver(day).filter(|_| external.is_none());
+    let supported = solver.map_or(&[][..], |s| s.options);
+    opts.validate(day, supported)?;
+
+    // Nothing to read for a day that has no solver yet
+    if solver.is_none() && external.is_none() {
+        if !quiet && !settings.capture {
+            print_banner(info, part == Part::Two, glyphs, style);
+        }
+        eprintln!("Day {} not implemented yet", day);
+        return Ok(None);
+    }
+
+    // --example reads dayN_example.txt when there is one, else the header's
+    let example_file = if example { example_file(info)? } else { None };
+    let example = example && example_file.is_none();
+
+    // Determine input file path; the embedded copy only stands in for the
+    // default, and `--file -` asks for stdin whatever files there are
+    let file_given = file.is_some();
+    let default_input = !file_given && example_file.is_none();
+    let embedded = embedded::input(day).filter(|_| default_input);
+    let piped = example_file.is_none() && file.as_deref() == Some(STDIN_FILE);
+    let input_file = example_file.or(file.map(PathBuf::from)).unwrap_or_else(|| info.input_path.clone());
+
+    // Open input from the header example, a URL or the clipboard, a file,
+    // the binary itself, or stdin
+    let fetched = match (&settings.input_url, &settings.pasted) {
+        _ if example => None,
+        (Some(remote), _) => Some((remote.url.as_str(), remote.load(size_limit_mb)?)),
+        (None, Some(text)) => Some(("clipboard", text.clone())),
+        (None, None) => None,
+    };
+    let from_file = !example && fetched.is_none() && !piped && input_file.exists();
+    // Input piped in beats the default file or embedded copy it would
+    // otherwise be ignored for; nothing piped leaves them be
+    let mut pipe = None;
+    if settings.piped && default_input && (from_file || embedded.is_some()) {
+        let mut reader = InputReader::stdin()?;
+        if !reader.is_exhausted().map_err(InputError::stdin)? {
+            let instead = if from_file { input_file.display().to_string() } else { "the embedded input".to_string() };
+            eprintln!("Reading Day {}'s input from stdin, not {}", day, instead);
+            pipe = Some(reader);
+        }
+    }
+    let from_file = from_file && pipe.is_none();
+    let embedded = embedded.filter(|_| pipe.is_none());
+    let (source, why) = if example {
+        ("example".to_string(), "--example, and there is no example file")
+    } else if let Some((url, _)) = fetched {
+        let why = if settings.input_url.is_some() { "--input-url" } else { "--paste" };
+        (url.to_string(), why)
+    } else if pipe.is_some() {
+        ("stdin".to_string(), "input was piped in, and no --file given")
+    } else if piped {
+        ("stdin".to_string(), "--file -")
+    } else if from_file {
+        let why = if default_input { "the input file" } else if file_given { "--file" } else { "--example" };
+        (input_file.display().to_string(), why)
+    } else if embedded.is_some() {
+        ("embedded".to_string(), "no input file, and the binary carries one")
+    } else {
+        ("stdin".to_string(), "no input file")
+    };
+    tracing::debug!(day, source = source.as_str(), why, "reading the input");
+    let mut reader = if example {
+        let Some(example) = &info.meta.example else {
+            return Err(format!("Day {} has no example in its header comment", day).into());
+        };
+        InputReader::from_text(&example.input)
+    } else if let Some((_, text)) = &fetched {
+        InputReader::from_text(text)
+    } else if let Some(reader) = pipe {
+        reader
+    } else if from_file {
+        InputReader::open_file(&input_file)?
+    } else if let Some(text) = embedded {
+        InputReader::from_text(text)
+    } else if !settings.read_stdin {
+        return Err(InputError::Missing(input_file).into());
+    } else {
+        if piped && io::stdin().is_terminal() {
+            eprintln!("Reading Day {}'s input from stdin: paste or type it, then press Ctrl-D", day);
+        } else if !piped && !quiet {
+            warn(glyphs, style, format_args!("File '{}' not found, reading from stdin...", input_file.display()));
+        }
+        let mut reader = InputReader::stdin()?;
+        if reader.is_exhausted().map_err(InputError::stdin)? {
+            return Err(if piped { InputError::NothingPiped } else { InputError::Missing(input_file) }.into());
+        }
+        reader
+    };
+
+    // With --stream, line-oriented days read as they go; every other run
+    // (and a truncated one) loads the whole input up front, as do both
+    // parts, which solve the same text
+    let stream_solver = solver.and_then(|s| s.stream).filter(|_| stream && limit_lines.is_none() && part != Part::Both);
+    if stream && stream_solver.is_none() && !quiet {
+        if part == Part::Both && solver.is_some_and(|s| s.stream.is_some()) {
+            warn(glyphs, style, "--part both reads the whole input once for both parts instead of streaming it");
+        } else {
+            warn(glyphs, style, format_args!("Day {} cannot stream its input, reading all of it", day));
+        }
+    }
+    let read_span = tracing::info_span!("read input", day).entered();
+    let input = if stream_solver.is_some() {
+        InputText::Owned(String::new())
+    } else if from_file {
+        input::load_file(&input_file, size_limit_mb)?
+    } else {
+        InputText::Owned(input::read_all(&mut reader, None)?)
+    };
+    // A file fresh from `touch` would solve to a plausible-looking 0; a
+    // streamed input is only checked for being empty, since finding out
+    // that it is all whitespace could mean reading all of it
+    if !settings.allow_empty {
+        let blank = if stream_solver.is_some() {
+            reader.is_exhausted().unwrap_or(false).then_some(0)
+        } else {
+            input::is_blank(input.as_str()).then(|| input.as_str().len())
+        };
+        if let Some(bytes) = blank {
+            let source = if source == "stdin" { source } else { format!("'{}'", source) };
+            return Err(InputError::Blank { source, bytes }.into());
+        }
+    }
+    // CRLF endings and a byte order mark never reach the solvers
+    let input = input::normalize(input.as_str());
+    let input = input.as_ref();
+    drop(read_span);
+    if stream_solver.is_some() {
+        tracing::debug!(day, "streaming the input line by line to the solver");
+    } else {
+        tracing::debug!(day, bytes = input.len(), lines = input.lines().count(), "read the input");
+    }
+    let truncated = match limit_lines {
+        Some(lines) => truncate(input, lines, solver.is_some() && day == 5, (glyphs, style))?,
+        None => None,
+    };
+    let input = truncated.unwrap_or(input);
+
+    // Extra report lines stay off stdout in quiet mode
+    let mut out = if settings.capture {
+        Output::capture()
+    } else if quiet {
+        Output::Stderr { warning: glyphs.warning }
+    } else {
+        Output::Stdout { warning: glyphs.warning }
+    };
+
+    // The cache and run history only cover whole-input runs without extra
+    // day options or --lenient-numbers, whose answer depends on nothing but
+    // the input text
+    let whole_input = stream_solver.is_none() && opts.is_empty() && !opts.lenient_numbers && truncated.is_none();
+    if diff && !whole_input && !quiet {
+        let reason = "on whole inputs without day options or --lenient-numbers";
+        warn(glyphs, style, format_args!("--diff only compares runs {}, skipping it", reason));
+    }
+    // A cached answer would skip the solve that writes the artifacts
+    let mut cache = if cached && whole_input && opts.artifacts.is_none() {
+        let cache = Cache::default_path().map(|path| Cache::load(&path));
+        if cache.is_none() && !quiet {
+            warn(glyphs, style, "No data directory for the answer cache, solving without it");
+        }
+        cache
+    } else {
+        None
+    };
+
+    let mut solved = Vec::new();
+    for &part2 in part.parts() {
+        // Print header in non-quiet mode
+        if !quiet && !settings.capture {
+            print_banner(info, part2, glyphs, style);
+        }
+
+        let hit = cache.as_ref().and_then(|c| c.get(day, part2, opts.strict, input)).cloned();
+
+        let mut one = if let Some(entry) = hit {
+            Solved {
+                pretty: entry.pretty.unwrap_or_else(|| entry.answer.clone()),
+                answer: entry.answer,
+                value: entry.value,
+                marker: " (cached)",
+                millis: entry.millis,
+                input: source.clone(),
+                diff: None,
+                output: None,
+            }
+        } else {
+            let _solve_span = if part2 {
+                tracing::info_span!("solve part 2", day).entered()
+            } else {
+                tracing::info_span!("solve part 1", day).entered()
+            };
+            // With --timeout a watchdog cancels the solve once it runs out;
+            // an external command is killed by its own timeout
+            let cancel = Cancel::default();
+            let deadline = settings.timeout.map(|limit| Deadline::arm(limit, cancel.clone()));
+            let timed = deadline.as_ref().map(|_| opts.clone().with_cancel(cancel.clone()));
+            let opts = timed.as_ref().unwrap_or(opts);
+            let start = Instant::now();
+            let result: Result<Answer, Box<dyn std::error::Error>> = match (stream_solver, solver, external) {
+                (Some(solve_stream), _, _) => solve_stream(&mut reader, part2, opts, &mut out).map_err(Into::into),
+                (None, Some(solver), _) => (solver.solve)(input, part2, opts, &mut out).map_err(Into::into),
+                (None, None, Some(command)) => external::solve(command, part2, input, settings.timeout).map_err(Into::into),
+                (None, None, None) => unreachable!("days without a solver returned early"),
+            };
+            drop(deadline);
+            if let Some(limit) = settings.timeout.filter(|_| cancel.is_cancelled()) {
+                return Err(TimedOut { day, part2, limit }.into());
+            }
+            let result = result?;
+            let (answer, value, pretty) = (result.to_string(), result.value.to_string(), result.pretty());
+            let millis = start.elapsed().as_secs_f64() * 1000.0;
+
+            if let Some(cache) = cache.as_mut() {
+                let entry =
+                    cache::Entry { answer: answer.clone(), value: value.clone(), pretty: Some(pretty.clone()), millis };
+                cache.insert(day, part2, opts.strict, input, entry);
+                if let Err(e) = cache.save() {
+                    warn(glyphs, style, format_args!("Could not save the answer cache: {}", e));
+                }
+            }
+            if let Some(notification) =
+                notify.as_ref().and_then(|n| notify::notification(n, day, part2, &answer, start.elapsed()))
+            {
+                notify::send(&notify::Desktop, &notification, glyphs.warning);
+            }
+            let diff = if whole_input {
+                let record = history::Record::now(day, part2, input, &value, millis);
+                record_run(history.as_deref(), record, diff, (glyphs, style))
+            } else {
+                None
+            };
+            let marker = if truncated.is_some() { " (TRUNCATED INPUT)" } else { "" };
+            Solved { answer, value, pretty, marker, millis, input: source.clone(), diff, output: None }
+        };
+        if settings.capture {
+            one.output = Some(std::mem::replace(&mut out, Output::capture()));
+        }
+        each(part2, &one);
+        solved.push((part2, one));
+    }
+
+    Ok(Some(solved))
+}
+
+/// --watch: solve `info` now and again each time its input file changes,
+/// until Ctrl-C. A run that fails is reported and the watching goes on; a
+/// missing file is waited for.
+fn watch_day(info: &DayInfo, part: Part, file: Option<PathBuf>, settings: &RunSettings) -> Result<i32, Box<dyn std::error::Error>> {
+    let path = file.unwrap_or_else(|| info.input_path.clone());
+    // Changes and Ctrl-C arrive on one channel: true to run again, false to stop
+    let (changed, events) = mpsc::channel();
+    let interrupted = changed.clone();
+    ctrlc::set_handler(move || {
+        let _ = interrupted.send(false);
+    })
+    .map_err(|e| format!("Could not catch Ctrl-C: {}", e))?;
+    let _watcher = Watcher::spawn(path.clone(), watch::DEFAULT_DEBOUNCE, move || {
+        let _ = changed.send(true);
+    });
+
+    let glyphs = settings.glyphs;
+    if !settings.quiet {
+        eprintln!("Watching {} (Ctrl-C to stop)", path.display());
+    }
+    loop {
+        let file = Some(path.display().to_string());
+        match run_parts(info, part, file, settings, |part2, solved| {
+            print_answer(info, part2, solved, settings);
+            if let Some(diff) = &solved.diff {
+                print_diff(diff, settings);
+            }
+        }) {
+            Ok(_) => {}
+            Err(e) if matches!(e.downcast_ref(), Some(InputError::Missing(_))) => {
+                warn(glyphs, settings.style, format_args!("{} not found; waiting for it", path.display()));
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        if !events.recv().unwrap_or(false) {
+            return Ok(0);
+        }
+        if !settings.quiet {
+            let rule = glyphs.rule.repeat(8);
+            println!("\n{} {} UTC: {} changed {}", rule, clock(SystemTime::now()), path.display(), rule);
+        }
+    }
+}
+
+/// The time of day of `time`, in UTC: "14:03:07"
+fn clock(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// The file --example reads, dayN_example.txt; None for the example in the
+/// day's header comment, which stands in when there is no file
+fn example_file(info: &DayInfo) -> Result<Option<PathBuf>, String> {
+    match (&info.example_path, &info.meta.example) {
+        (Some(path), _) => Ok(Some(path.clone())),
+        (None, Some(_)) => Ok(None),
+        (None, None) => Err(format!(
+            "Day {} has no example: save the puzzle's example as {}, or add it to the header comment of src/day{}.rs",
+            info.number,
+            info.input_path.with_file_name(format!("day{}_example.txt", info.number)).display(),
+            info.number
+        )),
+    }
+}
+
+/// The first `lines` lines of `input` for --limit-lines, or None when it has
+/// no more than that; says loudly that the answer will not be the real one.
+/// Day 5's two sections must keep the blank line between them
+fn truncate<'a>(
+    input: &'a str,
+    lines: usize,
+    day5: bool,
+    (glyphs, style): (&Glyphs, Style),
+) -> Result<Option<&'a str>, adventcode::error::Error> {
+    let Some(kept) = input::first_lines(input, lines) else {
+        return Ok(None);
+    };
+    if day5 {
+        adventcode::day5::check_truncated(input, kept)?;
+    }
+    let total = input.lines().count();
+    warn(glyphs, style, format_args!("TRUNCATED INPUT ({} lines of {}): the answer is not the puzzle's", lines, total));
+    Ok(Some(kept))
+}
+
+/// --files: run one part on every file `pattern` matches, then print the
+/// table (or --output-format document); returns the exit code
+fn run_files(
+    info: &DayInfo,
+    part2: bool,
+    pattern: &str,
+    settings: &RunSettings,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    if !info.implemented() {
+        return Err(format!("Day {} not implemented yet", info.number).into());
+    }
+    let files = batch::expand(pattern)?;
+
+    if !settings.quiet {
+        print_banner(info, part2, settings.glyphs, settings.style);
+    }
+    // One banner for the batch, not one per file
+    let each = RunSettings { quiet: true, ..settings.clone() };
+    let mut entries = Vec::with_capacity(files.len());
+    let mut results = Vec::with_capacity(files.len());
+    for path in files {
+        let file = path.display().to_string();
+        // As with --all, a panic only fails its own row
+        let run = catch_panic(|| run_day(info, part2, Some(file.clone()), &each));
+        let outcome = match run {
+            Ok(Ok(Some(solved))) => Ok((solved.value, solved.millis)),
+            Ok(Ok(None)) => Err("not implemented".to_string()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(message) => Err(format!("panicked: {}", message)),
+        };
+        results.push(match &outcome {
+            Ok((value, millis)) => report_row(info, part2, report::Status::Ok).solved(value, *millis, &file),
+            Err(e) => report::Row { input: Some(file.clone()), ..report_row(info, part2, report::Status::Failed).error(e) },
+        });
+        entries.push(batch::Entry { file, outcome });
+    }
+
+    if let Some(format) = settings.structured {
+        print!("{}", report::render(format, &report::Report { results }));
+    } else {
+        for line in batch::table(&entries) {
+            println!("{}", line);
+        }
+    }
+    Ok(i32::from(entries.iter().any(|entry| entry.outcome.is_err())))
+}
+
+/// --bench: time one part `runs` times on the loaded input (after a warm-up
+/// run), then save or check its baseline; returns the exit code
+fn run_bench(
+    info: &DayInfo,
+    part2: bool,
+    file: Option<String>,
+    settings: &RunSettings,
+    runs: usize,
+    baseline: Option<BaselineMode>,
+    tolerance: f64,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let day = info.number;
+    let solver = find_solver(day).ok_or_else(|| format!("Day {} not implemented yet", day))?;
+    settings.opts.validate(day, solver.options)?;
+
+    // Every run reads the same text, so it has to come from somewhere that
+    // can be read once and kept: not stdin
+    if file.as_deref() == Some(STDIN_FILE) {
+        return Err("--bench cannot read stdin, which can only be read once: pass the input with --file PATH".into());
+    }
+    let example_file = if settings.example { example_file(info)? } else { None };
+    let embedded = embedded::input(day).filter(|_| file.is_none() && example_file.is_none());
+    let input_file = example_file.or(file.map(PathBuf::from)).unwrap_or_else(|| info.input_path.clone());
+    let text = if settings.example && !input_file.exists() {
+        let example = info.meta.example.as_ref().ok_or_else(|| format!("Day {} has no example in its header comment", day))?;
+        example.input.clone()
+    } else if let Some(remote) = &settings.input_url {
+        remote.load(settings.size_limit_mb)?
+    } else if let Some(text) = &settings.pasted {
+        text.clone()
+    } else if input_file.exists() {
+        input::load_file(&input_file, settings.size_limit_mb)?.as_str().to_string()
+    } else if let Some(text) = embedded {
+        text.to_string()
+    } else {
+        let missing = input_file.display();
+        return Err(format!("--bench cannot read stdin, which can only be read once: '{}' not found", missing).into());
+    };
+    let input = input::normalize(&text);
+    let input = input.as_ref();
+
+    if !settings.quiet {
+        print_banner(info, part2, settings.glyphs, settings.style);
+    }
+    // One warm-up run, then the timed ones; report lines are dropped, since
+    // the same ones would repeat every run. Every run must agree with the
+    // warm-up's answer
+    let solve = || (solver.solve)(input, part2, &settings.opts, &mut Output::capture());
+    let answer = solve()?;
+    let mut millis = Vec::with_capacity(runs);
+    for run in 1..=runs {
+        let start = Instant::now();
+        let again = solve()?;
+        millis.push(start.elapsed().as_secs_f64() * 1000.0);
+        if again.value != answer.value {
+            return Err(format!("Run {} gave {}, but the warm-up run gave {}", run, again, answer).into());
+        }
+    }
+    let stats = Stats::of(&millis).ok_or("--bench needs at least one run")?;
+    let report = |line: String| if settings.quiet { eprintln!("{}", line) } else { println!("{}", line) };
+    if settings.quiet {
+        println!("{}", answer.value);
+    } else {
+        println!("Result: {}", answer);
+    }
+    report(format!("Bench: {}", stats));
+
+    let Some(mode) = baseline else {
+        return Ok(0);
+    };
+    let path = bench::default_path().ok_or("No data directory for benchmark baselines")?;
+    let mut baselines = Baselines::load(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let part = if part2 { 2 } else { 1 };
+    let context = Context::current(input);
+    match mode {
+        BaselineMode::Save => {
+            baselines.insert(Baseline { day, part, stats, context });
+            baselines.save(&path).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+            report(format!("Saved the baseline for Day {} Part {} to {}", day, part, path.display()));
+            Ok(0)
+        }
+        BaselineMode::Check => {
+            let saved = baselines.get(day, part).ok_or_else(|| {
+                format!("No baseline for Day {} Part {} in {}; run with --baseline save first", day, part, path.display())
+            })?;
+            let differences = context.differences(&saved.context);
+            if !differences.is_empty() {
+                let message =
+                    format!("The baseline was recorded with {}, so this comparison may not mean much", differences.join(", "));
+                warn(settings.glyphs, settings.style, message);
+            }
+            let comparison = Comparison { baseline: saved.stats, current: stats, tolerance };
+            report(format!("Day {} Part {}: {}", day, part, comparison));
+            Ok(i32::from(comparison.regressed()))
+        }
+    }
+}
+
+/// Append a fresh solve to the run history at `path`; with `diff`, first
+/// compare it with the last run on the same input
+/// Failing to read or write the history is only worth a warning with --diff
+fn record_run(path: Option<&Path>, record: history::Record, diff: bool, (glyphs, style): (&Glyphs, Style)) -> Option<Diff> {
+    let Some(path) = path else {
+        if diff {
+            warn(glyphs, style, "No data directory for the run history, nothing to compare with");
+        }
+        return None;
+    };
+    let compared = diff.then(|| Diff::against(&history::load(path), record.clone()));
+    if let Err(e) = history::append(path, &record)
+        && diff
+    {
+        warn(glyphs, style, format_args!("Could not record this run in {}: {}", path.display(), e));
+    }
+    compared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner_title_matches_list_title() {
+        // Unit tests run from the crate root, where src/dayN.rs lives
+        let catalog = Catalog::discover();
+        assert!(!catalog.days.is_empty());
+
+        for (day, row) in catalog.days.iter().zip(list_rows(&catalog.days)) {
+            let info = catalog.info(day.number);
+            assert_ne!(info.meta.title, format!("Day {}", day.number), "no header title found");
+            assert_eq!(row.title, info.meta.title);
+            assert!(banner(&info, false, &glyphs::UNICODE).contains(&format!("Day {}: {} │", day.number, day.meta.title)));
+        }
+    }
+
+    #[test]
+    fn unknown_days_get_a_placeholder_title() {
+        let catalog = Catalog::discover_in(Path::new("no-such-crate"), Path::new(""));
+        assert!(catalog.days.is_empty());
+        assert_eq!(banner(&catalog.info(9), true, &glyphs::UNICODE), "🎄 Day 9: Day 9 │ Part 2");
+        assert_eq!(banner(&catalog.info(9), true, &glyphs::ASCII), "* Day 9: Day 9 | Part 2");
+    }
+
+    #[test]
+    fn discovery_reads_inputs_from_the_input_dir_then_inputs() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("puzzles")).unwrap();
+        fs::create_dir_all(root.join("inputs")).unwrap();
+        for day in [1, 2, 3] {
+            fs::write(root.join(format!("src/day{}.rs", day)), "").unwrap();
+        }
+        fs::write(root.join("puzzles/day1.txt"), "L68\n").unwrap();
+        fs::write(root.join("puzzles/day1_example.txt"), "L68\n").unwrap();
+        fs::write(root.join("inputs/day1.txt"), "L68\n").unwrap();
+        fs::write(root.join("inputs/day2.txt"), "11-22\n").unwrap();
+
+        let catalog = Catalog::discover_in(root, Path::new("puzzles"));
+        let found: Vec<(PathBuf, bool)> = catalog.days.iter().map(|d| (d.input_path.clone(), d.has_input)).collect();
+        assert_eq!(
+            found,
+            [
+                (root.join("puzzles/day1.txt"), true),
+                (root.join("inputs/day2.txt"), true),
+                (root.join("puzzles/day3.txt"), false),
+            ]
+        );
+        assert_eq!(catalog.days[0].example_path, Some(root.join("puzzles/day1_example.txt")));
+        assert_eq!(catalog.info(9).input_path, root.join("puzzles/day9.txt"));
+        let config = Config { input_dir: Some("puzzles".into()), ..Config::default() };
+        assert_eq!(input_dir(None, &config), Path::new("puzzles"));
+        assert_eq!(input_dir(Some(Path::new("mine")), &config), Path::new("mine"));
+        assert_eq!(input_dir(None, &Config::default()), Path::new(""));
+    }
+
+    /// A day whose solver always panics, as a ragged grid might make day 4
+    pub(super) const PANICKING_DAY: u8 = 90;
+
+    pub(super) static STUB_DAYS: &[adventcode::Day] =
+        &[adventcode::Day { number: PANICKING_DAY, options: &[], solve: panics, stream: None, source: "" }];
+
+    fn panics(_: &str, _: bool, _: &DayOptions, _: &mut Output) -> Result<adventcode::output::Answer, adventcode::error::Error> {
+        panic!("index out of bounds: the len is 3 but the index is 7\n(row 2)")
+    }
+
+    fn day_info(number: u8, has_input: bool) -> DayInfo {
+        DayInfo {
+            number,
+            meta: meta::parse(number, ""),
+            has_input,
+            input_path: PathBuf::new(),
+            embedded: false,
+            example_path: None,
+            external: None,
+        }
+    }
+
+    /// The summary rows a plan starts with, "-" standing for a run
+    fn rows(plan: &[Planned]) -> Vec<String> {
+        plan.iter().map(|p| summary_row(p.day.number, p.part2, p.skip.unwrap_or("-"))).collect()
+    }
+
+    #[test]
+    fn all_filters_shape_the_summary() {
+        let days = [day_info(1, true), day_info(2, true), day_info(3, false)];
+
+        let plan = plan_all(&days, [true, true], &[]).unwrap();
+        assert_eq!(
+            rows(&plan),
+            [
+                "Day  1 Part 1: -",
+                "Day  1 Part 2: -",
+                "Day  2 Part 1: -",
+                "Day  2 Part 2: -",
+                "Day  3 Part 1: skipped (no input)",
+                "Day  3 Part 2: skipped (no input)",
+            ]
+        );
+
+        let plan = plan_all(&days, [true, false], &[2]).unwrap();
+        assert_eq!(
+            rows(&plan),
+            [
+                "Day  1 Part 1: -",
+                "Day  1 Part 2: skipped (by request)",
+                "Day  2 Part 1: skipped (by request)",
+                "Day  2 Part 2: skipped (by request)",
+                "Day  3 Part 1: skipped (no input)",
+                "Day  3 Part 2: skipped (by request)",
+            ]
+        );
+
+        let plan = plan_all(&days, [false, true], &[1]).unwrap();
+        let runs: Vec<String> = rows(&plan).into_iter().filter(|r| r.ends_with('-')).collect();
+        assert_eq!(runs, ["Day  2 Part 2: -"]);
+    }
+
+    #[test]
+    fn all_filters_that_leave_nothing_are_rejected() {
+        let days = [day_info(1, true), day_info(2, false)];
+        assert!(plan_all(&days, [true, true], &[1, 2]).unwrap_err().contains("nothing"));
+        assert!(plan_all(&days, [true, true], &[7]).unwrap_err().contains("no day 7"));
+        // A day without input is not skipped by request, so this still runs it
+        assert!(plan_all(&days, [false, true], &[1]).is_ok());
+    }
+
+    #[test]
+    fn panicking_days_become_failed_rows() {
+        let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/day5_example.txt");
+        let days = [
+            DayInfo { input_path: input.clone(), ..day_info(PANICKING_DAY, true) },
+            DayInfo { input_path: input, ..day_info(5, true) },
+        ];
+        let plan = plan_all(&days, [true, false], &[]).unwrap();
+        let settings = RunSettings {
+            quiet: true,
+            stream: false,
+            example: false,
+            cached: false,
+            size_limit_mb: None,
+            glyphs: &glyphs::ASCII,
+            style: Style::default(),
+            notify: None,
+            diff: false,
+            history: None,
+            pretty: false,
+            time: false,
+            format: None,
+            structured: None,
+            max_time: None,
+            input_url: None,
+            pasted: None,
+            limit_lines: None,
+            read_stdin: true,
+            piped: false,
+            timeout: None,
+            allow_empty: false,
+            jobs: 1,
+            capture: false,
+            opts: DayOptions::default(),
+        };
+
+        // The panic is caught, kept to one row, and day 5 still runs after it
+        let rows = run_plan(&plan, &settings);
+        let summary: Vec<String> = rows.iter().map(|r| summary_row(r.day, r.part2, &r.outcome)).collect();
+        assert_eq!(
+            summary,
+            [
+                "Day 90 Part 1: failed: panicked: index out of bounds: the len is 3 but the index is 7 (row 2)",
+                "Day 90 Part 2: skipped (by request)",
+                "Day  5 Part 1: 3",
+                "Day  5 Part 2: skipped (by request)",
+            ]
+        );
+        assert_eq!(rows.iter().map(|r| r.failed).collect::<Vec<_>>(), [true, false, false, false]);
+
+        // The --output-format rows say the same
+        use report::Status::*;
+        assert_eq!(rows.iter().map(|r| r.report.status).collect::<Vec<_>>(), [Failed, Skipped, Ok, Skipped]);
+        assert!(rows[0].report.error.as_deref().unwrap().starts_with("panicked: index out of bounds"));
+        assert_eq!(rows[2].report.answer.as_deref(), Some("3"));
+
+        // Two at a time, the same rows in the same order
+        let parallel = run_plan(&plan, &RunSettings { jobs: 2, ..settings.clone() });
+        assert_eq!(parallel.iter().map(|r| summary_row(r.day, r.part2, &r.outcome)).collect::<Vec<_>>(), summary);
+    }
+
+    #[test]
+    fn caught_panics_come_back_as_their_message() {
+        let caught = catch_panic(|| panics("", false, &DayOptions::default(), &mut Output::capture()));
+        assert_eq!(caught.unwrap_err(), "index out of bounds: the len is 3 but the index is 7 (row 2)");
+        assert_eq!(catch_panic(|| 5), Ok(5));
+        assert_eq!(CATCHING.get(), 0);
+    }
+
+    #[test]
+    fn day_lists_and_ranges_are_ordered_without_repeats() {
+        assert_eq!(parse_days("3"), Ok(Days(vec![3])));
+        assert_eq!(parse_days("5,1,3-4"), Ok(Days(vec![1, 3, 4, 5])));
+        assert_eq!(parse_days("2-4, 3,4-4"), Ok(Days(vec![2, 3, 4])));
+        // Any day of the calendar parses, whether or not there is a solver
+        assert_eq!(parse_days("24-25"), Ok(Days(vec![24, 25])));
+        for bad in ["1-", "0", "26", "4-2", "1,,2", "x"] {
+            assert!(parse_days(bad).unwrap_err().ends_with("is not a day in 1..=25 or a range like 3-5"), "{}", bad);
+        }
+        assert_eq!(parse_days("1,0-3").unwrap_err(), "'0-3' is not a day in 1..=25 or a range like 3-5");
+    }
+
+    #[test]
+    fn elapsed_picks_the_unit() {
+        assert_eq!(elapsed(0.0423, &glyphs::UNICODE), "42 µs");
+        assert_eq!(elapsed(0.0423, &glyphs::ASCII), "42 us");
+        assert_eq!(elapsed(12.34, &glyphs::UNICODE), "12.3 ms");
+        assert_eq!(elapsed(2412.0, &glyphs::UNICODE), "2.41 s");
+    }
+
+    #[test]
+    fn expected_ignores_only_surrounding_whitespace() {
+        let solved = Solved {
+            answer: "Password: 984".into(),
+            value: "984".into(),
+            pretty: "Password: 984".into(),
+            marker: "",
+            millis: 0.0,
+            input: "day1.txt".into(),
+            diff: None,
+            output: None,
+        };
+        assert!(matches_expected(&solved, "984"));
+        assert!(matches_expected(&solved, " 984\n"));
+        assert!(!matches_expected(&solved, "Password: 984"));
+        assert!(!matches_expected(&solved, "9 84"));
+        assert!(!matches_expected(&solved, "0984"));
     }
 }
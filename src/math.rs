@@ -0,0 +1,117 @@
+// Integer helpers shared by the days
+//
+// The arithmetic that is easiest to get subtly wrong lives here, once, with
+// its edge cases tested: wrapping a possibly negative position onto a dial
+// (day 1), powers of ten and arithmetic series sums for closed-form counting
+// (day 2), and gcd/lcm for the cycle-length puzzles AoC likes. Anything that
+// can overflow returns None instead of wrapping or panicking.
+
+/// `a` modulo `m`, always in 0..m, even for negative `a`
+///
+/// wrap_mod(-10, 100) is 90 where `-10 % 100` would be -10.
+///
+/// # Panics
+///
+/// If `m` is zero, like the `%` operator.
+pub fn wrap_mod(a: i64, m: u64) -> u64 {
+    assert!(m != 0, "wrap_mod by zero");
+    // i128 holds every i64 and u64, so neither the cast nor the remainder overflows
+    i128::from(a).rem_euclid(i128::from(m)) as u64
+}
+
+/// 10^exp, or None past 10^38 (the largest power of ten in a u128)
+pub fn pow10(exp: u32) -> Option<u128> {
+    10u128.checked_pow(exp)
+}
+
+/// Sum of the `count` evenly spaced integers running from `first` to `last`
+///
+/// (first + last) × count / 2, so 1..=100 is sum_arith_series(1, 100, 100).
+/// None on overflow, or when first + last and count are both odd (no
+/// integer series has that shape).
+pub fn sum_arith_series(first: u64, last: u64, count: u64) -> Option<u128> {
+    let ends = u128::from(first) + u128::from(last);
+    let count = u128::from(count);
+    // Halve whichever factor is even so the product stays as small as possible
+    if ends % 2 == 0 {
+        (ends / 2).checked_mul(count)
+    } else if count % 2 == 0 {
+        ends.checked_mul(count / 2)
+    } else {
+        None
+    }
+}
+
+/// Greatest common divisor; gcd(0, n) is n
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or None if it does not fit in a u64; lcm(0, n) is 0
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_mod_handles_negatives_and_extremes() {
+        assert_eq!(wrap_mod(105, 100), 5);
+        assert_eq!(wrap_mod(-10, 100), 90);
+        assert_eq!(wrap_mod(-100, 100), 0);
+        assert_eq!(wrap_mod(-1, 100), 99);
+        assert_eq!(wrap_mod(0, 1), 0);
+        assert_eq!(wrap_mod(i64::MIN, 100), 92); // -9223372036854775808 ≡ 92
+        assert_eq!(wrap_mod(i64::MAX, u64::MAX), i64::MAX as u64);
+        assert_eq!(wrap_mod(-1, u64::MAX), u64::MAX - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrap_mod by zero")]
+    fn wrap_mod_by_zero_panics() {
+        wrap_mod(5, 0);
+    }
+
+    #[test]
+    fn pow10_stops_at_the_largest_u128_power() {
+        assert_eq!(pow10(0), Some(1));
+        assert_eq!(pow10(19), Some(10_000_000_000_000_000_000));
+        assert_eq!(pow10(38), Some(10u128.pow(38)));
+        assert_eq!(pow10(39), None);
+    }
+
+    #[test]
+    fn series_sums_past_u64() {
+        assert_eq!(sum_arith_series(1, 100, 100), Some(5050));
+        assert_eq!(sum_arith_series(7, 7, 1), Some(7));
+        assert_eq!(sum_arith_series(0, 0, 0), Some(0));
+        // 1 + 2 + 3 + 4: odd ends, so the count is what gets halved
+        assert_eq!(sum_arith_series(1, 4, 4), Some(10));
+        assert_eq!(sum_arith_series(1, 2, 3), None);
+
+        // 1..=u64::MAX: far beyond u64, still inside u128
+        let all = sum_arith_series(1, u64::MAX, u64::MAX).unwrap();
+        assert_eq!(all, (1u128 << 63) * u128::from(u64::MAX));
+        assert!(all > u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+}
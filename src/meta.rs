@@ -0,0 +1,198 @@
+// Day metadata
+//
+// Each day's source file opens with a banner comment: the `DAY N: TITLE`
+// line, then sections such as PROBLEM OVERVIEW and EXAMPLE. This module
+// parses that header into a DayMeta. Headers that don't follow the layout
+// still yield their title, or "Day N" when even that is missing.
+
+/// What a day's header comment says about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayMeta {
+    pub title: String,
+    /// The PROBLEM OVERVIEW section, one entry per comment line
+    pub overview: Vec<String>,
+    pub example: Option<Example>,
+}
+
+/// The worked example from a header's EXAMPLE section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// The indented block under "Input:", with a trailing newline
+    pub input: String,
+    /// The "Result:" values given under "Part 1 ..." and "Part 2 ..."
+    pub answers: [Option<String>; 2],
+}
+
+/// A named section of the header and its comment lines
+struct Section<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Parse the header comment at the top of a day's source file
+///
+/// Layout understood (anything else is ignored):
+///   // DAY 5: CAFETERIA
+///   // PROBLEM OVERVIEW:        ← an unindented all-caps heading ending in ':'
+///   // ------                   ← optional underline
+///   // EXAMPLE:
+///   // Input:
+///   //   <example input, indented two spaces>
+///   // Part 1 ...:
+///   //   Result: 3 ...
+pub fn parse(number: u8, source: &str) -> DayMeta {
+    // The header is the run of comment lines at the top, minus the "// " prefix
+    let header: Vec<&str> = source
+        .lines()
+        .map_while(|line| line.strip_prefix("//"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect();
+
+    let title = match header.iter().enumerate().find_map(|(i, line)| Some((i + 1, parse_title(line)?))) {
+        Some((line, title)) => {
+            tracing::debug!(day = number, line, title = title.as_str(), "title from the `DAY N: TITLE` header line");
+            title
+        }
+        None => {
+            tracing::debug!(day = number, "no `DAY N: TITLE` line in the header comment, using the default title");
+            format!("Day {}", number)
+        }
+    };
+
+    let sections = split_sections(&header);
+    let section = |name: &str| sections.iter().find(|s| s.name == name);
+
+    let overview = section("PROBLEM OVERVIEW")
+        .map(|s| trim_blank_lines(&s.lines).iter().map(|line| line.to_string()).collect())
+        .unwrap_or_default();
+    let example = section("EXAMPLE").and_then(|s| parse_example(&s.lines));
+
+    DayMeta { title, overview, example }
+}
+
+/// "DAY 5: CAFETERIA" → "CAFETERIA"
+fn parse_title(line: &str) -> Option<String> {
+    let (day, title) = line.trim().strip_prefix("DAY ")?.split_once(':')?;
+    day.trim().parse::<u8>().ok()?;
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Group header lines under their headings; lines before the first heading
+/// (the title banner) belong to no section
+fn split_sections<'a>(header: &[&'a str]) -> Vec<Section<'a>> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for &line in header {
+        if is_rule(line) {
+            continue;
+        }
+        if let Some(name) = heading(line) {
+            sections.push(Section { name, lines: Vec::new() });
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push(line);
+        }
+    }
+
+    sections
+}
+
+/// Banner and underline lines: "=====" or "-----"
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+/// "PROBLEM OVERVIEW:" → "PROBLEM OVERVIEW"
+/// A heading is unindented, ends in ':' and starts with an all-caps word,
+/// which keeps lines like "Part 1:" or "Input:" in the section body
+fn heading(line: &str) -> Option<&str> {
+    if line.starts_with(' ') {
+        return None;
+    }
+    let name = line.trim_end().strip_suffix(':')?;
+    let first_word = name.split_whitespace().next()?;
+    let all_caps = first_word.len() >= 2 && first_word.chars().all(|c| c.is_ascii_uppercase());
+    all_caps.then_some(name)
+}
+
+/// Drop blank lines from both ends
+fn trim_blank_lines<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let start = lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(start, |i| i + 1);
+    lines[start..end].iter().map(|line| line.trim_end()).collect()
+}
+
+/// Pull the input block and expected answers out of an EXAMPLE section
+fn parse_example(lines: &[&str]) -> Option<Example> {
+    let input_at = lines.iter().position(|line| line.trim() == "Input:")?;
+
+    // The input is every following line indented by at least two spaces;
+    // an indented blank line stays part of it, a bare "//" ends it
+    let block: Vec<&str> = lines[input_at + 1..]
+        .iter()
+        .map_while(|line| line.strip_prefix("  "))
+        .map(str::trim_end)
+        .collect();
+    if block.iter().all(|line| line.is_empty()) {
+        return None;
+    }
+    let input = block.join("\n") + "\n";
+
+    let mut answers = [None, None];
+    let mut part = None;
+    for line in &lines[input_at + 1 + block.len()..] {
+        let line = line.trim();
+        if line.starts_with("Part 1") {
+            part = Some(0);
+        } else if line.starts_with("Part 2") {
+            part = Some(1);
+        } else if let (Some(part), Some(rest)) = (part, line.strip_prefix("Result:")) {
+            let slot: &mut Option<String> = &mut answers[part];
+            if slot.is_none() {
+                *slot = rest.split_whitespace().next().map(str::to_string);
+            }
+        }
+    }
+
+    Some(Example { input, answers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_day2_header() {
+        let meta = parse(2, include_str!("day2.rs"));
+        assert_eq!(meta.title, "INVALID ID DETECTION");
+        assert_eq!(meta.overview[0], "We need to find \"invalid IDs\" within given numeric ranges.");
+
+        let example = meta.example.expect("day 2 has an example");
+        assert_eq!(example.input, include_str!("../tests/fixtures/day2_example.txt"));
+        assert_eq!(example.answers, [Some("1227775554".into()), Some("4174379265".into())]);
+    }
+
+    #[test]
+    fn parses_day5_header() {
+        let meta = parse(5, include_str!("day5.rs"));
+        assert_eq!(meta.title, "CAFETERIA");
+        assert!(meta.overview.last().unwrap().ends_with("Overlapping ranges must be merged to avoid double-counting."));
+
+        let example = meta.example.expect("day 5 has an example");
+        assert_eq!(example.input, include_str!("../tests/fixtures/day5_example.txt"));
+        assert_eq!(example.answers, [Some("3".into()), Some("14".into())]);
+    }
+
+    #[test]
+    fn unstructured_headers_fall_back_to_the_title() {
+        let meta = parse(4, include_str!("day4.rs"));
+        assert_eq!(meta.title, "PRINTING DEPARTMENT");
+        assert_eq!(meta.example, None);
+
+        let meta = parse(7, "// just a note\nfn main() {}\n");
+        assert_eq!(meta.title, "Day 7");
+        assert!(meta.overview.is_empty());
+        assert_eq!(meta.example, None);
+    }
+}
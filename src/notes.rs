@@ -0,0 +1,94 @@
+// Per-day notes
+//
+// A note is a small free-form text kept beside a day ("part 2 was too low
+// twice: off-by-one in the merge?"). Each lives in its own markdown file,
+// `<data dir>/notes/dayN.md`, so the TUI's editor (n) can open it like an
+// input and `adventcode notes --day N` can print or replace it. A note that
+// is saved blank is deleted. Notes are only ever shown on their own: no
+// answer line, report, template or history record includes them.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use adventcode::cache;
+
+/// `<data dir>/notes`
+pub fn default_dir() -> Option<PathBuf> {
+    cache::data_dir().map(|dir| dir.join("notes"))
+}
+
+/// Where `day`'s note lives in `dir`
+pub fn path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.md", day))
+}
+
+/// The day whose note `path` is, when it is one in `dir`
+#[cfg_attr(not(feature = "tui"), allow(dead_code))] // only the TUI's editor asks
+pub fn day_of(dir: &Path, path: &Path) -> Option<u8> {
+    if path.parent() != Some(dir) {
+        return None;
+    }
+    let day = path.file_name()?.to_str()?.strip_prefix("day")?.strip_suffix(".md")?.parse().ok()?;
+    (path == self::path(dir, day)).then_some(day)
+}
+
+/// `day`'s note; None when there is none, or it is blank
+pub fn read(dir: &Path, day: u8) -> io::Result<Option<String>> {
+    match fs::read_to_string(path(dir, day)) {
+        Ok(text) if text.trim().is_empty() => Ok(None),
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace `day`'s note with `text`, ending it with a newline; blank text
+/// deletes the note instead
+pub fn write(dir: &Path, day: u8, text: &str) -> io::Result<()> {
+    if text.trim().is_empty() {
+        return delete(dir, day).map(|_| ());
+    }
+    fs::create_dir_all(dir)?;
+    let text = if text.ends_with('\n') { text.to_string() } else { format!("{}\n", text) };
+    fs::write(path(dir, day), text)
+}
+
+/// Delete `day`'s note; false when it had none
+pub fn delete(dir: &Path, day: u8) -> io::Result<bool> {
+    match fs::remove_file(path(dir, day)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// The first line with anything on it, for the TUI's Details pane
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub fn first_line(text: &str) -> &str {
+    text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_round_trip_and_blank_ones_are_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes");
+        assert_eq!(read(&notes, 5).unwrap(), None);
+
+        let text = "\npart 2 was too low twice\n  off-by-one in the merge?\n\n- try 3-5 + 4-6";
+        write(&notes, 5, text).unwrap();
+        assert_eq!(read(&notes, 5).unwrap(), Some(format!("{}\n", text)));
+        assert_eq!(first_line(text), "part 2 was too low twice");
+        assert_eq!(day_of(&notes, &path(&notes, 5)), Some(5));
+        assert_eq!(day_of(&notes, &dir.path().join("day5.md")), None);
+        assert_eq!(day_of(&notes, &notes.join("day05.md")), None);
+
+        write(&notes, 5, " \n").unwrap();
+        assert!(!path(&notes, 5).exists());
+        assert!(!delete(&notes, 5).unwrap());
+    }
+}
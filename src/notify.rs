@@ -0,0 +1,136 @@
+// Desktop notifications
+//
+// With --notify, a solve that runs longer than a threshold pops up a desktop
+// notification (through notify-rust) with the day, part, answer and time
+// taken, so a slow part 2 can run while you are away from the terminal.
+// Where no notification service is available the same message goes to
+// stderr instead: a missing daemon is never an error.
+
+use std::time::Duration;
+
+/// Default for --notify-after, in seconds
+pub const DEFAULT_THRESHOLD_SECS: f64 = 10.0;
+
+/// When to notify and what to say
+#[derive(Debug, Clone, Copy)]
+pub struct NotifySettings {
+    /// Solves at least this long get a notification
+    pub threshold: Duration,
+    /// Include the answer (off with --notify-no-answer)
+    pub show_answer: bool,
+}
+
+/// A notification ready to send
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+}
+
+/// Something that can show a notification; tests substitute a recorder
+pub trait Notifier {
+    fn notify(&self, notification: &Notification) -> Result<(), String>;
+}
+
+/// The desktop's notification service
+pub struct Desktop;
+
+impl Notifier for Desktop {
+    #[cfg(feature = "notify")]
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        notify_rust::Notification::new()
+            .appname("adventcode")
+            .summary(&notification.summary)
+            .body(&notification.body)
+            .show()
+            .map(drop)
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "notify"))]
+    fn notify(&self, _notification: &Notification) -> Result<(), String> {
+        Err("this build has no desktop notifications (built without the `notify` feature)".into())
+    }
+}
+
+/// The notification for a finished solve, or None when it was quick enough
+pub fn notification(
+    settings: &NotifySettings,
+    day: u8,
+    part2: bool,
+    answer: &str,
+    elapsed: Duration,
+) -> Option<Notification> {
+    if elapsed < settings.threshold {
+        return None;
+    }
+    let part = if part2 { 2 } else { 1 };
+    let answer = if settings.show_answer { answer } else { "(hidden)" };
+    Some(Notification {
+        summary: format!("Day {} part {} finished", day, part),
+        body: format!("Answer: {}\nTook {:.1}s", answer, elapsed.as_secs_f64()),
+    })
+}
+
+/// Send a notification, falling back to stderr when that fails
+pub fn send(notifier: &dyn Notifier, notification: &Notification, warning: &str) {
+    if let Err(e) = notifier.notify(notification) {
+        eprintln!("{} Could not show a desktop notification: {}", warning, e);
+        eprintln!("{}: {}", notification.summary, notification.body.replace('\n', ", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder {
+        sent: RefCell<Vec<Notification>>,
+        fail: bool,
+    }
+
+    impl Notifier for Recorder {
+        fn notify(&self, notification: &Notification) -> Result<(), String> {
+            if self.fail {
+                return Err("no notification daemon".into());
+            }
+            self.sent.borrow_mut().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    const SETTINGS: NotifySettings = NotifySettings { threshold: Duration::from_secs(10), show_answer: true };
+
+    #[test]
+    fn only_slow_solves_notify() {
+        assert_eq!(notification(&SETTINGS, 2, true, "42", Duration::from_millis(9_999)), None);
+
+        let slow = notification(&SETTINGS, 2, true, "42", Duration::from_millis(12_340)).unwrap();
+        assert_eq!(slow.summary, "Day 2 part 2 finished");
+        assert_eq!(slow.body, "Answer: 42\nTook 12.3s");
+
+        let recorder = Recorder::default();
+        send(&recorder, &slow, "!");
+        assert_eq!(*recorder.sent.borrow(), [slow]);
+    }
+
+    #[test]
+    fn answers_can_be_hidden() {
+        let settings = NotifySettings { show_answer: false, ..SETTINGS };
+        let hidden = notification(&settings, 5, false, "357907198933892", Duration::from_secs(10)).unwrap();
+        assert_eq!(hidden.summary, "Day 5 part 1 finished");
+        assert!(!hidden.body.contains("357907198933892"));
+        assert!(hidden.body.contains("(hidden)"));
+    }
+
+    #[test]
+    fn failures_fall_back_to_stderr() {
+        // Nothing is recorded and nothing panics or propagates
+        let recorder = Recorder { fail: true, ..Default::default() };
+        let slow = notification(&SETTINGS, 1, false, "3", Duration::from_secs(60)).unwrap();
+        send(&recorder, &slow, "!");
+        assert!(recorder.sent.borrow().is_empty());
+    }
+}
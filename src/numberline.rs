@@ -0,0 +1,306 @@
+// TUI number line for day 2
+//
+// Day 2's ranges drawn as bars on a scaled number line: the ranges as
+// given (stacked in lanes where they overlap), the merged ranges under
+// them, and a tick in every column holding an invalid ID. IDs run to 20
+// digits, so rather than fitting everything the view pans (h/l) and zooms
+// (+/-); each column stands for `per_col` consecutive IDs. The ticks come
+// from day2::invalid_ids, asked for the first ID in each column's slice, so
+// a dense range costs one lookup per column however many IDs it holds.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use adventcode::day2;
+use adventcode::interval::{self, Interval};
+
+/// Columns taken by the row labels on the left
+const GUTTER: u16 = 8;
+
+/// Columns between axis labels
+const LABEL_EVERY: usize = 16;
+
+/// Rows besides the range lanes: merged, ticks, axis, labels, a gap, the
+/// selection and the view
+const FIXED_ROWS: u16 = 7;
+
+pub struct NumberLine {
+    merged: Vec<Interval>,
+    /// The ranges as given, in lanes where no two overlap
+    lanes: Vec<Vec<Interval>>,
+    /// Which part's invalid IDs the ticks mark
+    pub part2: bool,
+    /// First ID of the leftmost column
+    start: u64,
+    /// IDs per column, at least 1; None until the next draw fits the view
+    per_col: Option<u64>,
+    /// The invalid ID picked with Left/Right
+    selected: Option<u64>,
+    /// Columns in the last draw, for panning by a share of the screen
+    width: u64,
+}
+
+impl NumberLine {
+    /// The view of day 2's `input`, fitted to the screen on the first draw
+    pub fn new(input: &str, part2: bool) -> Self {
+        let ranges = day2::ranges(input);
+        let mut sorted = ranges.clone();
+        sorted.sort_unstable();
+        let mut lanes: Vec<Vec<Interval>> = Vec::new();
+        for range in sorted {
+            match lanes.iter_mut().find(|lane| lane.last().is_some_and(|last| last.1 < range.0)) {
+                Some(lane) => lane.push(range),
+                None => lanes.push(vec![range]),
+            }
+        }
+        Self { merged: interval::merge(&ranges), lanes, part2, start: 0, per_col: None, selected: None, width: 1 }
+    }
+
+    /// Show every range again (0)
+    pub fn reset(&mut self) {
+        self.per_col = None;
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom(|per_col| (per_col / 2).max(1));
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom(|per_col| per_col.saturating_mul(2));
+    }
+
+    /// Move a quarter of the screen right (or left with a negative `steps`)
+    pub fn pan(&mut self, steps: i64) {
+        let shift = self.per_col().saturating_mul((self.width / 4).max(1)).saturating_mul(steps.unsigned_abs());
+        self.start = if steps < 0 { self.start.saturating_sub(shift) } else { self.start.saturating_add(shift) };
+    }
+
+    /// Pick the next invalid ID on screen to the right (or left)
+    pub fn select(&mut self, forward: bool) {
+        let ticks = self.ticks(self.width as usize);
+        let ids = ticks.iter().map(|&(_, id)| id);
+        self.selected = match self.selected.filter(|id| ticks.iter().any(|&(_, tick)| tick == *id)) {
+            Some(current) if forward => ids.clone().find(|&id| id > current).or(Some(current)),
+            Some(current) => ids.clone().rev().find(|&id| id < current).or(Some(current)),
+            None if forward => ids.clone().next(),
+            None => ids.clone().next_back(),
+        };
+    }
+
+    fn per_col(&self) -> u64 {
+        self.per_col.unwrap_or(1)
+    }
+
+    /// Zoom about the middle of the screen
+    fn zoom(&mut self, change: impl Fn(u64) -> u64) {
+        let half = self.width / 2;
+        let middle = self.start.saturating_add(self.per_col().saturating_mul(half));
+        let per_col = change(self.per_col());
+        self.per_col = Some(per_col);
+        self.start = middle.saturating_sub(per_col.saturating_mul(half));
+    }
+
+    /// Fit every range into `width` columns
+    fn fit(&mut self, width: u64) {
+        let lo = self.merged.first().map_or(0, |r| r.0);
+        let hi = self.merged.last().map_or(0, |r| r.1);
+        let span = u128::from(hi - lo) + 1;
+        self.start = lo;
+        self.per_col = Some(span.div_ceil(u128::from(width.max(1))) as u64);
+    }
+
+    /// The IDs column `col` stands for
+    fn slice(&self, col: usize) -> Interval {
+        let per_col = self.per_col();
+        let first = self.start.saturating_add(per_col.saturating_mul(col as u64));
+        (first, first.saturating_add(per_col - 1))
+    }
+
+    /// The first invalid ID in each of the first `width` columns that has one
+    fn ticks(&self, width: usize) -> Vec<(usize, u64)> {
+        (0..width)
+            .filter_map(|col| {
+                let (a, b) = self.slice(col);
+                let first = self
+                    .merged
+                    .iter()
+                    .filter(|r| r.0 <= b && r.1 >= a)
+                    .find_map(|r| day2::invalid_ids((r.0.max(a), r.1.min(b)), self.part2).next())?;
+                Some((col, first))
+            })
+            .collect()
+    }
+}
+
+/// `value` for an axis whose labels are `step` IDs apart: plain below
+/// 100000, else in 1e6 style with just enough digits to tell labels apart
+fn axis_label(value: u64, step: u64) -> String {
+    if value < 100_000 {
+        return value.to_string();
+    }
+    let exp = value.ilog10();
+    let decimals = exp.saturating_sub(step.max(1).ilog10()).min(6);
+    // Truncated, never rounded, so 9.99e9 cannot turn into 10.0e9
+    let digits = (value / 10u64.pow(exp - decimals)).to_string();
+    let (int, frac) = digits.split_at(1);
+    if frac.is_empty() { format!("{}e{}", int, exp) } else { format!("{}.{}e{}", int, frac, exp) }
+}
+
+/// Draw `line` into `area`, fitting the view first if it has none
+pub fn render(f: &mut Frame, area: Rect, line: &mut NumberLine, ascii: bool, block: Block<'static>) {
+    let inner = block.inner(area);
+    let width = inner.width.saturating_sub(GUTTER).max(1);
+    line.width = u64::from(width);
+    if line.per_col.is_none() {
+        line.fit(line.width);
+    }
+    let width = width as usize;
+    let (bar, merged_bar, tick, axis, mark) = if ascii { ('=', '#', '|', '-', '+') } else { ('━', '█', '│', '─', '┴') };
+
+    let label = |text: &str| Span::raw(format!("{:<width$}", text, width = GUTTER as usize));
+    let bars = |name: &str, ranges: &[Interval], c: char, color: Color| {
+        let mut spans = vec![label(name)];
+        spans.extend((0..width).map(|col| {
+            let (a, b) = line.slice(col);
+            if ranges.iter().any(|r| r.0 <= b && r.1 >= a) {
+                Span::styled(c.to_string(), Style::default().fg(color))
+            } else {
+                Span::raw(" ")
+            }
+        }));
+        Line::from(spans)
+    };
+
+    let mut rows = Vec::new();
+    // Leave room for the fixed rows; lanes past that are cut
+    let lanes = usize::from(inner.height.saturating_sub(FIXED_ROWS).max(1));
+    for (i, lane) in line.lanes.iter().take(lanes).enumerate() {
+        rows.push(bars(if i == 0 { "ranges" } else { "" }, lane, bar, Color::Cyan));
+    }
+    rows.push(bars("merged", &line.merged, merged_bar, Color::Green));
+
+    let ticks = line.ticks(width);
+    let mut spans = vec![label("invalid")];
+    let mut cells = vec![Span::raw(" "); width];
+    for &(col, id) in &ticks {
+        let style = if line.selected == Some(id) {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        cells[col] = Span::styled(tick.to_string(), style);
+    }
+    spans.extend(cells);
+    rows.push(Line::from(spans));
+
+    let axis: String = (0..width).map(|col| if col % LABEL_EVERY == 0 { mark } else { axis }).collect();
+    rows.push(Line::from(vec![label(""), Span::raw(axis)]));
+    let step = line.per_col().saturating_mul(LABEL_EVERY as u64);
+    let mut labels = String::new();
+    for col in (0..width).step_by(LABEL_EVERY) {
+        let text = axis_label(line.slice(col).0, step);
+        // Skip a label that would run into the next one or off the edge
+        if text.len() < LABEL_EVERY && col + text.len() <= width {
+            labels.push_str(&format!("{:<w$}", text, w = LABEL_EVERY.min(width - col)));
+        } else {
+            labels.push_str(&" ".repeat(LABEL_EVERY.min(width - col)));
+        }
+    }
+    rows.push(Line::from(vec![label(""), Span::raw(labels)]));
+    rows.push(Line::from(""));
+
+    let selected = line.selected.filter(|id| ticks.iter().any(|&(_, tick)| tick == *id));
+    rows.push(Line::from(match selected {
+        Some(id) => {
+            let range = line.merged.iter().find(|r| (r.0..=r.1).contains(&id)).copied().unwrap_or((id, id));
+            format!("Selected: {} (in {}-{})", id, range.0, range.1)
+        }
+        None if ticks.is_empty() => "No invalid IDs in view".to_string(),
+        None => format!("{} columns with invalid IDs; Left/Right selects one", ticks.len()),
+    }));
+    let (first, last) = (line.slice(0).0, line.slice(width - 1).1);
+    rows.push(Line::from(Span::styled(
+        format!("View: {}-{}, {} IDs per column", first, last, line.per_col()),
+        Style::default().fg(Color::Gray),
+    )));
+
+    f.render_widget(Paragraph::new(rows).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, widgets::Borders, Terminal};
+
+    const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+                           1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+                           824824821-824824827,2121212118-2121212124";
+
+    /// Draw `line` at 60 columns and return its rows as text
+    fn draw(line: &mut NumberLine) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| render(f, f.area(), line, true, Block::default().borders(Borders::ALL))).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn example_ranges_at_ten_ids_per_column() {
+        let mut line = NumberLine::new(EXAMPLE, false);
+        // Columns of 10 IDs from 0: 11-22 covers columns 1-2, 95-115 covers 9-11
+        line.start = 0;
+        line.per_col = Some(10);
+        let rows = draw(&mut line);
+        let bars = |row: &str| row.chars().skip(1 + GUTTER as usize).take(14).collect::<String>();
+        assert_eq!(bars(&rows[1]), " ==      ===  ");
+        assert_eq!(bars(&rows[2]), " ##      ###  ");
+        // 11 and 22 sit in columns 1 and 2, and 99 in column 9
+        assert_eq!(bars(&rows[3]), " ||      |    ");
+        assert!(rows[5].contains(" 0               160             320 "), "{:?}", rows[5]);
+        assert!(rows[8].contains("View: 0-499, 10 IDs per column"), "{:?}", rows[8]);
+
+        line.select(true);
+        line.select(true);
+        assert_eq!(line.selected, Some(22));
+        assert!(draw(&mut line)[7].contains("Selected: 22 (in 11-22)"));
+
+        // Part 2 adds 111 in column 11
+        line.part2 = true;
+        assert_eq!(bars(&draw(&mut line)[3]), " ||      | |  ");
+    }
+
+    #[test]
+    fn the_first_draw_fits_every_range_and_zoom_keeps_the_middle() {
+        let mut line = NumberLine::new(EXAMPLE, false);
+        draw(&mut line);
+        assert_eq!(line.start, 11);
+        let per_col = line.per_col.unwrap();
+        assert_eq!(per_col, (2121212124u64 - 11 + 1).div_ceil(50));
+
+        let middle = line.start + per_col * 25;
+        line.zoom_in();
+        assert_eq!(line.per_col, Some(per_col / 2));
+        assert!(line.start.abs_diff(middle - per_col / 2 * 25) <= 1);
+        line.pan(-1000);
+        assert_eq!(line.start, 0);
+        line.reset();
+        draw(&mut line);
+        assert_eq!(line.per_col, Some(per_col));
+    }
+
+    #[test]
+    fn axis_labels_carry_just_enough_digits() {
+        assert_eq!(axis_label(160, 160), "160");
+        assert_eq!(axis_label(1_188_511_880, 100_000_000), "1.1e9");
+        assert_eq!(axis_label(1_188_511_880, 1_000_000), "1.188e9");
+        assert_eq!(axis_label(9_999_999_999, 100_000_000), "9.9e9");
+        assert_eq!(axis_label(2_000_000, 10_000_000), "2e6");
+    }
+}
@@ -0,0 +1,128 @@
+// Per-day options
+//
+// Anything after `--` on the command line is forwarded to the selected day,
+// e.g. `cargo run -- -d 5 -- --missing`. Each day declares the flags it
+// understands in its `OPTIONS` constant so typos are reported instead of
+// being silently ignored. An entry with a placeholder, like
+// "--export-path <FILE>", takes a value: `--export-path out.csv` or
+// `--export-path=out.csv`.
+
+use crate::artifacts::Artifacts;
+use crate::cancel::Cancel;
+
+/// Extra flags forwarded to a day's solver
+#[derive(Debug, Clone, Default)]
+pub struct DayOptions {
+    args: Vec<String>,
+    /// Fail on malformed input lines instead of warning (`--strict`)
+    pub strict: bool,
+    /// Skip `_` and single spaces inside numbers (`--lenient-numbers`)
+    pub lenient_numbers: bool,
+    /// Where to write intermediate structures (`--emit-intermediate`)
+    pub artifacts: Option<Artifacts>,
+    /// Set when the solve should stop early (`--timeout`)
+    pub cancel: Cancel,
+}
+
+impl DayOptions {
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args, strict: false, lenient_numbers: false, artifacts: None, cancel: Cancel::default() }
+    }
+
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn with_lenient_numbers(mut self, lenient: bool) -> Self {
+        self.lenient_numbers = lenient;
+        self
+    }
+
+    pub fn with_artifacts(mut self, artifacts: Option<Artifacts>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: Cancel) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// True when no extra flags were passed
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Whether `name` (e.g. "--missing") was passed
+    pub fn flag(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| arg == name)
+    }
+
+    /// The value passed with option `name` (e.g. "--export-path"), if any
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.args.iter().enumerate().find_map(|(i, arg)| {
+            if arg == name {
+                self.args.get(i + 1).map(String::as_str)
+            } else {
+                arg.strip_prefix(name)?.strip_prefix('=')
+            }
+        })
+    }
+
+    /// Check every passed flag against the list a day supports
+    pub fn validate(&self, day: u8, supported: &[&str]) -> Result<(), String> {
+        let mut args = self.args.iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg.as_str(), None),
+            };
+            let Some(spec) = supported.iter().find(|spec| spec.split(' ').next() == Some(name)) else {
+                let hint = if supported.is_empty() {
+                    "it takes no extra options".to_string()
+                } else {
+                    format!("supported: {}", supported.join(", "))
+                };
+                return Err(format!("Day {} does not understand '{}' ({})", day, arg, hint));
+            };
+            let takes_value = spec.contains(' ');
+            if takes_value && inline_value.is_none() && args.next().is_none() {
+                return Err(format!("Day {} option '{}' needs a value ({})", day, name, spec));
+            }
+            if !takes_value && inline_value.is_some() {
+                return Err(format!("Day {} option '{}' takes no value", day, name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(args: &[&str]) -> DayOptions {
+        DayOptions::new(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn options_with_placeholders_take_values() {
+        const SUPPORTED: &[&str] = &["--verbose", "--export-path <FILE>"];
+
+        let spaced = opts(&["--export-path", "out.csv", "--verbose"]);
+        assert_eq!(spaced.validate(1, SUPPORTED), Ok(()));
+        assert_eq!(spaced.value("--export-path"), Some("out.csv"));
+        assert!(spaced.flag("--verbose"));
+
+        let inline = opts(&["--export-path=out.csv"]);
+        assert_eq!(inline.validate(1, SUPPORTED), Ok(()));
+        assert_eq!(inline.value("--export-path"), Some("out.csv"));
+        assert_eq!(inline.value("--export"), None);
+
+        let missing = opts(&["--export-path"]).validate(1, SUPPORTED).unwrap_err();
+        assert_eq!(missing, "Day 1 option '--export-path' needs a value (--export-path <FILE>)");
+        assert!(opts(&["--verbose=yes"]).validate(1, SUPPORTED).is_err());
+        assert!(opts(&["--export-paths", "x"]).validate(1, SUPPORTED).unwrap_err().contains("does not understand"));
+    }
+}
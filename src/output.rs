@@ -0,0 +1,193 @@
+// Solver output
+//
+// Solvers return their Answer and send everything else (verbose listings,
+// warnings) through an Output sink. Keeping the two apart means extra
+// report lines never change the answer line that scripts parse.
+
+use std::fmt;
+
+/// The value a solver produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Number(u128),
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Value {
+    /// For people rather than scripts: numbers get thousands separators
+    /// (171,371,624,522), text is left exactly as it is
+    pub fn pretty(&self) -> String {
+        match self {
+            Value::Number(n) => group_thousands(*n),
+            Value::Text(s) => s.clone(),
+        }
+    }
+}
+
+/// `n` with a comma between each group of three digits
+fn group_thousands(n: u128) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Number(n as u128)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Number(n as u128)
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+/// A solver's result, with the label it is printed under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    /// Human label such as "Password"; empty for a bare value
+    pub label: &'static str,
+    pub value: Value,
+}
+
+impl Answer {
+    pub fn new(label: &'static str, value: impl Into<Value>) -> Self {
+        Self {
+            label,
+            value: value.into(),
+        }
+    }
+
+    /// An answer printed as just its value
+    pub fn bare(value: impl Into<Value>) -> Self {
+        Self::new("", value)
+    }
+
+    /// As displayed, but with the value from `Value::pretty`
+    pub fn pretty(&self) -> String {
+        if self.label.is_empty() {
+            self.value.pretty()
+        } else {
+            format!("{}: {}", self.label, self.value.pretty())
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.label.is_empty() {
+            write!(f, "{}", self.value)
+        } else {
+            write!(f, "{}: {}", self.label, self.value)
+        }
+    }
+}
+
+/// Where a solver's extra report lines and warnings go
+#[derive(Debug)]
+pub enum Output {
+    /// Report lines on stdout (normal mode); warnings go to stderr after
+    /// `warning`, the caller's warning glyph
+    Stdout { warning: &'static str },
+    /// Report lines on stderr, so stdout only carries the answer (quiet mode)
+    Stderr { warning: &'static str },
+    /// Keep everything in memory (tests and programmatic callers)
+    Capture {
+        lines: Vec<String>,
+        warnings: Vec<String>,
+    },
+}
+
+impl Output {
+    pub fn capture() -> Self {
+        Output::Capture {
+            lines: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Emit one report line
+    pub fn line(&mut self, text: impl fmt::Display) {
+        match self {
+            Output::Stdout { .. } => println!("{}", text),
+            Output::Stderr { .. } => eprintln!("{}", text),
+            Output::Capture { lines, .. } => lines.push(text.to_string()),
+        }
+    }
+
+    /// Emit a warning; these always go to stderr unless captured
+    pub fn warn(&mut self, text: impl fmt::Display) {
+        match self {
+            Output::Capture { warnings, .. } => warnings.push(text.to_string()),
+            Output::Stdout { warning } | Output::Stderr { warning } => eprintln!("{} {}", warning, text),
+        }
+    }
+
+    /// Captured report lines (empty for the streaming sinks)
+    pub fn lines(&self) -> &[String] {
+        match self {
+            Output::Capture { lines, .. } => lines,
+            _ => &[],
+        }
+    }
+
+    /// Captured warnings (empty for the streaming sinks)
+    pub fn warnings(&self) -> &[String] {
+        match self {
+            Output::Capture { warnings, .. } => warnings,
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pretty_groups_numbers_only() {
+        assert_eq!(Value::Number(0).pretty(), "0");
+        assert_eq!(Value::Number(999).pretty(), "999");
+        assert_eq!(Value::Number(1000).pretty(), "1,000");
+        assert_eq!(Value::Number(171_371_624_522).pretty(), "171,371,624,522");
+        assert_eq!(Value::Number(u128::MAX).pretty(), "340,282,366,920,938,463,463,374,607,431,768,211,455");
+
+        let digits = "123456789012345678901234567890".to_string();
+        assert_eq!(Value::Text(digits.clone()).pretty(), digits);
+    }
+
+    #[test]
+    fn pretty_answers_keep_their_label() {
+        assert_eq!(Answer::new("Password", 5657u64).pretty(), "Password: 5,657");
+        assert_eq!(Answer::new("Password", 5657u64).to_string(), "Password: 5657");
+        assert_eq!(Answer::bare(1000u64).pretty(), "1,000");
+    }
+}
@@ -0,0 +1,116 @@
+// Shared thread pool
+//
+// Days 2, 3 and 4 spread independent work (ID ranges, battery banks, grid
+// rows) over one rayon pool, sized by --max-threads, else RAYON_NUM_THREADS,
+// else one thread per core. `map` returns results in input order, so answers
+// never depend on the thread count; with one thread, or without the
+// `parallel` feature, the work runs in sequence on the calling thread.
+
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Size the shared pool: `max_threads`, or rayon's default (RAYON_NUM_THREADS,
+/// else the number of cores)
+///
+/// Call once, before any parallel work; later calls fail.
+#[cfg(feature = "parallel")]
+pub fn configure(max_threads: Option<usize>) -> Result<(), String> {
+    builder(max_threads).build_global().map_err(|e| format!("could not start the thread pool: {}", e))
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn configure(_max_threads: Option<usize>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Threads available to `map` right now
+#[cfg(feature = "parallel")]
+pub fn threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn threads() -> usize {
+    1
+}
+
+/// `f` applied to every item, possibly in parallel, results in item order
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    if threads() > 1 {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    items.iter().map(f).collect()
+}
+
+/// Run `f` on a pool of its own with `threads` workers instead of the shared
+/// one (for comparing thread counts in tests and benchmarks)
+#[cfg(feature = "parallel")]
+pub fn with_threads<R: Send>(threads: usize, f: impl FnOnce() -> R + Send) -> R {
+    counted_pool(threads).0.install(f)
+}
+
+/// A pool with `threads` workers, and how many worker threads it started
+/// (the counting hook); each pool counts its own, so pools built at the same
+/// time never add to each other's count
+#[cfg(feature = "parallel")]
+pub fn counted_pool(threads: usize) -> (rayon::ThreadPool, Arc<AtomicUsize>) {
+    let started = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&started);
+    let pool = builder(Some(threads))
+        .start_handler(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .build()
+        .expect("thread pool");
+    // Every worker has started (and been counted) once it runs a broadcast
+    pool.broadcast(|_| ());
+    (pool, started)
+}
+
+#[cfg(feature = "parallel")]
+fn builder(threads: Option<usize>) -> rayon::ThreadPoolBuilder {
+    let builder = rayon::ThreadPoolBuilder::new();
+    match threads {
+        Some(n) => builder.num_threads(n),
+        None => builder,
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use crate::options::DayOptions;
+    use crate::output::Output;
+
+    #[test]
+    fn map_keeps_item_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let squares = with_threads(4, || map(&items, |n| n * n));
+        assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn pool_size_is_honored_and_answers_match() {
+        for size in [3, 1] {
+            let (pool, started) = counted_pool(size);
+            assert_eq!(pool.install(threads), size);
+            assert_eq!(started.load(Ordering::SeqCst), size);
+        }
+
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        for number in [2, 3, 4] {
+            let day = crate::day(number).unwrap();
+            let input = std::fs::read_to_string(root.join(format!("day{}.txt", number))).unwrap();
+            for part2 in [false, true] {
+                let solve = || (day.solve)(&input, part2, &DayOptions::default(), &mut Output::capture()).unwrap();
+                let default = solve();
+                assert_eq!(with_threads(1, solve), default, "day {} part2={}", number, part2);
+                assert_eq!(with_threads(2, solve), default, "day {} part2={}", number, part2);
+            }
+        }
+    }
+}
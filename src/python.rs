@@ -0,0 +1,70 @@
+// Python bindings
+//
+// With the `python` feature the cdylib is also a PyO3 extension module named
+// `adventcode` (maturin builds it from pyproject.toml). Solver errors become
+// Python exceptions carrying the same message the CLI prints, and solves run
+// with the GIL released so other Python threads keep going.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyOSError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::error::Error;
+use crate::input;
+use crate::options::DayOptions;
+use crate::output::Output;
+
+create_exception!(adventcode, AocError, PyException, "Base class for solver errors");
+create_exception!(adventcode, ParseError, AocError, "Malformed input lines, raised with strict=True");
+create_exception!(adventcode, InputError, AocError, "Input the day cannot use at all");
+
+/// Map a solver error to the matching exception, keeping its message
+fn to_py_err(e: Error) -> PyErr {
+    let message = e.to_string();
+    match e {
+        Error::Parse(_) => ParseError::new_err(message),
+        Error::Input(_) => InputError::new_err(message),
+        Error::Io(_) | Error::Export(..) => PyOSError::new_err(message),
+        Error::Cancelled => AocError::new_err(message),
+    }
+}
+
+/// solve(day, part, text, strict=False) -> str
+///
+/// Solve one part (1 or 2) of a day and return the answer as the CLI prints it
+#[pyfunction]
+#[pyo3(signature = (day, part, text, strict = false))]
+fn solve(py: Python<'_>, day: u8, part: u8, text: &str, strict: bool) -> PyResult<String> {
+    let solver = crate::day(day).ok_or_else(|| PyValueError::new_err(format!("Day {} is not implemented", day)))?;
+    let part2 = match part {
+        1 => false,
+        2 => true,
+        _ => return Err(PyValueError::new_err(format!("part must be 1 or 2, not {}", part))),
+    };
+
+    let opts = DayOptions::default().with_strict(strict);
+    py.detach(|| {
+        let text = input::normalize(text);
+        let mut out = Output::capture();
+        (solver.solve)(&text, part2, &opts, &mut out).map(|answer| answer.to_string())
+    })
+    .map_err(to_py_err)
+}
+
+/// days() -> list[tuple[int, str]]
+///
+/// Every implemented day as (number, title)
+#[pyfunction]
+fn days() -> Vec<(u8, String)> {
+    crate::DAYS.iter().map(|day| (day.number, day.meta().title)).collect()
+}
+
+#[pymodule]
+fn adventcode(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(days, m)?)?;
+    m.add("AocError", m.py().get_type::<AocError>())?;
+    m.add("ParseError", m.py().get_type::<ParseError>())?;
+    m.add("InputError", m.py().get_type::<InputError>())?;
+    Ok(())
+}
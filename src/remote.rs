@@ -0,0 +1,197 @@
+// Inputs from a URL (--input-url)
+//
+// `--input-url https://...` downloads the day's input instead of reading a
+// file: a 200 response's body is the input, and any other status, or a body
+// that is not UTF-8 text, is an error. Servers that want credentials get
+// them from adventcode.toml:
+//
+//     [input_url]
+//     bearer = "..."            # sent as `Authorization: Bearer ...`
+//     cookie = "session=..."    # sent as `Cookie: session=...`
+//
+// Downloads are kept in the data directory's inputs/ (the fetched inputs
+// `adventcode clean --fetched` deletes), named after the URL's hash, and
+// used again the next time the same URL is given; --no-cache downloads anew.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use adventcode::cache;
+
+/// The headers sent with every --input-url request, from `[input_url]`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Credentials {
+    pub bearer: Option<String>,
+    pub cookie: Option<String>,
+}
+
+impl Credentials {
+    /// The `[input_url]` table of the config file at `path`; a missing file
+    /// or table means no credentials
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Ok(Self::default());
+        };
+        let table: toml::Table =
+            text.parse().map_err(|e: toml::de::Error| format!("{} does not parse: {}", path.display(), e.message()))?;
+        let Some(input_url) = table.get("input_url") else {
+            return Ok(Self::default());
+        };
+        let input_url = input_url.as_table().ok_or_else(|| format!("{}: [input_url] must be a table", path.display()))?;
+        let mut credentials = Self::default();
+        for (key, value) in input_url {
+            let slot = match key.as_str() {
+                "bearer" => &mut credentials.bearer,
+                "cookie" => &mut credentials.cookie,
+                _ => return Err(format!("{}: [input_url] unknown key {}, expected bearer or cookie", path.display(), key)),
+            };
+            let value = value.as_str().ok_or_else(|| format!("{}: [input_url] {} must be a string", path.display(), key))?;
+            *slot = Some(value.to_string());
+        }
+        Ok(credentials)
+    }
+
+    /// (name, value) pairs to set on the request
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(token) = &self.bearer {
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+        if let Some(cookie) = &self.cookie {
+            headers.push(("Cookie", cookie.clone()));
+        }
+        headers
+    }
+}
+
+/// Where a run's input comes from with --input-url
+#[derive(Debug, Clone)]
+pub struct InputUrl {
+    pub url: String,
+    pub credentials: Credentials,
+    /// Where downloads are kept; None keeps nothing
+    pub cache_dir: Option<PathBuf>,
+    /// Download even when a kept copy exists (--no-cache)
+    pub refresh: bool,
+}
+
+impl InputUrl {
+    /// `<data dir>/inputs`, where fetched inputs live
+    pub fn default_cache_dir() -> Option<PathBuf> {
+        Some(cache::data_dir()?.join("inputs"))
+    }
+
+    /// The kept copy of this URL's input
+    pub fn cache_path(&self) -> Option<PathBuf> {
+        let dir = self.cache_dir.as_ref()?;
+        Some(dir.join(format!("url-{}.txt", cache::input_hash(&self.url))))
+    }
+
+    /// The input: the kept copy, or a fresh download that is then kept;
+    /// bodies over `limit_mb` (None = no limit) are refused
+    pub fn load(&self, limit_mb: Option<u64>) -> Result<String, String> {
+        let kept = self.cache_path();
+        if !self.refresh
+            && let Some(text) = kept.as_deref().and_then(|path| fs::read_to_string(path).ok())
+        {
+            return Ok(text);
+        }
+        let body = download(&self.url, &self.credentials.headers(), limit_mb)?;
+        let text = String::from_utf8(body).map_err(|e| {
+            format!("{} did not send UTF-8 text (invalid byte at offset {})", self.url, e.utf8_error().valid_up_to())
+        })?;
+        // Keeping a copy only saves the next download; failing to is no error
+        if let Some(path) = kept {
+            let _ = path.parent().map(fs::create_dir_all);
+            let _ = fs::write(path, &text);
+        }
+        Ok(text)
+    }
+}
+
+/// The body of GET `url` with `headers`, for a 200 response only
+#[cfg(feature = "fetch")]
+fn download(url: &str, headers: &[(&str, String)], limit_mb: Option<u64>) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent("github.com/zsh28/adventcode2025 (--input-url)")
+        .build();
+    let mut request = agent.get(url);
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            return Err(format!("{} answered HTTP {} {}", url, code, response.status_text()));
+        }
+        Err(e) => return Err(format!("could not download {}: {}", url, e)),
+    };
+    if response.status() != 200 {
+        return Err(format!("{} answered HTTP {} {}", url, response.status(), response.status_text()));
+    }
+
+    let limit = limit_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(limit.map_or(u64::MAX, |limit| limit + 1))
+        .read_to_end(&mut body)
+        .map_err(|e| format!("could not download {}: {}", url, e))?;
+    if let (Some(limit), Some(mb)) = (limit, limit_mb)
+        && body.len() as u64 > limit
+    {
+        return Err(format!("{} sent more than {} MB (pass --allow-large-input to load it anyway)", url, mb));
+    }
+    Ok(body)
+}
+
+#[cfg(not(feature = "fetch"))]
+fn download(_url: &str, _headers: &[(&str, String)], _limit_mb: Option<u64>) -> Result<Vec<u8>, String> {
+    Err("this build cannot download inputs (built without the `fetch` feature)".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_input_url_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("adventcode.toml");
+        assert_eq!(Credentials::load(&path), Ok(Credentials::default()));
+
+        fs::write(&path, "input_dir = \"inputs\"\n\n[input_url]\nbearer = \"t0ken\"\ncookie = \"session=abc\"\n").unwrap();
+        let credentials = Credentials::load(&path).unwrap();
+        assert_eq!(
+            credentials.headers(),
+            [("Authorization", "Bearer t0ken".to_string()), ("Cookie", "session=abc".to_string())]
+        );
+
+        fs::write(&path, "[input_url]\ntoken = \"x\"\n").unwrap();
+        assert!(Credentials::load(&path).unwrap_err().contains("unknown key token"));
+        fs::write(&path, "[input_url]\nbearer = 5\n").unwrap();
+        assert!(Credentials::load(&path).unwrap_err().contains("bearer must be a string"));
+    }
+
+    #[test]
+    fn a_kept_copy_is_used_unless_refreshing() {
+        let dir = tempfile::tempdir().unwrap();
+        // Nothing listens here, so only the kept copy can answer
+        let remote = InputUrl {
+            url: "http://127.0.0.1:9/day5.txt".to_string(),
+            credentials: Credentials::default(),
+            cache_dir: Some(dir.path().to_path_buf()),
+            refresh: false,
+        };
+        assert!(remote.load(None).is_err());
+
+        let kept = remote.cache_path().unwrap();
+        assert!(kept.file_name().unwrap().to_str().unwrap().starts_with("url-"));
+        fs::write(&kept, "3-5\n\n4\n").unwrap();
+        assert_eq!(remote.load(None).unwrap(), "3-5\n\n4\n");
+        assert!(InputUrl { refresh: true, ..remote }.load(None).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+// The REPL (`adventcode repl`)
+//
+// A prompt for trying a day over and over without restarting:
+//
+//     aoc> use day 3 part 2
+//     aoc> load day3.txt
+//     aoc> set top 5
+//     aoc> go
+//     Day 3 Part 2: Total joltage: 167384358365132 (41.2 ms)
+//
+// The input can also come from the clipboard (`paste`, as --paste does) or
+// be typed in after `input`, ending with a line holding only `.`. It is read
+// and normalized once and kept, so `go` after a `set` only solves again.
+// `set NAME [VALUE]` is a per-day option as passed after `--` on the command
+// line (`set top 5` is `-- --top 5`) and is checked against the day's list
+// the same way. Solves go through the captured Output, and are recorded in
+// the run history like any other run.
+//
+// Session turns each line into an Action and knows nothing of stdin, so
+// tests can script it. `run` is the interactive loop around it: a solve
+// runs on a thread of its own, and Ctrl-C gives up waiting for it and goes
+// back to the prompt instead of ending the program.
+
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use adventcode::history::{self, Record};
+use adventcode::input;
+use adventcode::options::DayOptions;
+use adventcode::output::{Answer, Output};
+
+use crate::clipboard::{self, Clipboard};
+
+const PROMPT: &str = "aoc> ";
+
+/// Ends input typed in after `input`
+const END_OF_INPUT: &str = ".";
+
+const HELP: &str = "\
+Commands:
+  use day N [part P]   pick the day (and part) to solve
+  use part P           pick the part, 1 or 2
+  load FILE            read the input from FILE
+  paste                take the input from the clipboard
+  input                type the input in; end it with a line holding only .
+  set NAME [VALUE]     pass the day option --NAME [VALUE]
+  unset NAME           drop option --NAME
+  show                 the day, part, input and options in use
+  go                   solve, and show the answer and the time taken
+  help                 this list
+  quit                 leave (so does Ctrl-D; Ctrl-C cancels a solve)";
+
+/// What a line asks of the loop
+#[derive(Debug)]
+pub enum Action {
+    /// Print this (nothing when empty)
+    Say(String),
+    Solve(Job),
+    Quit,
+}
+
+/// One solve, with everything it needs copied out of the session
+#[derive(Debug)]
+pub struct Job {
+    day: u8,
+    part2: bool,
+    input: Arc<str>,
+    options: DayOptions,
+}
+
+/// A job's answer, captured report lines and time, or what went wrong
+pub type Outcome = Result<(Answer, Output, f64), String>;
+
+impl Job {
+    /// Solve, catching a panic as an error
+    pub fn run(&self) -> Outcome {
+        let solver = crate::find_solver(self.day).ok_or_else(|| format!("Day {} is not implemented", self.day))?;
+        let mut output = Output::capture();
+        let start = Instant::now();
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+            (solver.solve)(&self.input, self.part2, &self.options, &mut output)
+        }))
+        .map_err(|payload| format!("panicked: {}", crate::panic_message(payload.as_ref())))?
+        .map_err(|e| e.to_string())?;
+        Ok((answer, output, start.elapsed().as_secs_f64() * 1000.0))
+    }
+}
+
+pub struct Session {
+    day: Option<u8>,
+    part2: bool,
+    /// The input, normalized, and where it came from
+    input: Option<(Arc<str>, String)>,
+    /// Per-day options, as they would follow `--`
+    options: Vec<(String, Option<String>)>,
+    /// Lines typed since `input`, until the line ending them
+    typing: Option<Vec<String>>,
+    /// Where solves are recorded; None records nothing
+    history: Option<PathBuf>,
+}
+
+impl Session {
+    pub fn new(history: Option<PathBuf>) -> Self {
+        Self { day: None, part2: false, input: None, options: Vec::new(), typing: None, history }
+    }
+
+    /// Whether lines are being taken as input rather than commands
+    pub fn is_typing(&self) -> bool {
+        self.typing.is_some()
+    }
+
+    /// Carry out one line
+    pub fn command(&mut self, line: &str, clipboard: &dyn Clipboard) -> Action {
+        if let Some(lines) = self.typing.as_mut() {
+            if line.trim_end() != END_OF_INPUT {
+                lines.push(line.to_string());
+                return Action::Say(String::new());
+            }
+            let text = self.typing.take().unwrap_or_default().join("\n") + "\n";
+            return Action::Say(self.take_input(&text, "typed in".to_string()));
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let say = |text: String| Action::Say(text);
+        match words.as_slice() {
+            [] => say(String::new()),
+            ["help" | "?"] => say(HELP.to_string()),
+            ["quit" | "exit"] => Action::Quit,
+            ["use", "day", day, rest @ ..] => {
+                let part = match rest {
+                    [] => None,
+                    ["part", part] => Some(*part),
+                    _ => return say("Usage: use day N [part P]".to_string()),
+                };
+                let Some(day) = day.parse().ok().filter(|day| (1..=25).contains(day)) else {
+                    return say(format!("{} is not a day (1-25)", day));
+                };
+                if crate::find_solver(day).is_none() {
+                    return say(format!("Day {} is not implemented", day));
+                }
+                if let Some(part) = part
+                    && let Err(e) = self.set_part(part)
+                {
+                    return say(e);
+                }
+                self.day = Some(day);
+                say(format!("Using Day {} Part {}", day, self.part()))
+            }
+            ["use", "part", part] => match self.set_part(part) {
+                Ok(()) => say(format!("Using Part {}", self.part())),
+                Err(e) => say(e),
+            },
+            ["load", ..] => {
+                let path = line.trim_start()["load".len()..].trim();
+                if path.is_empty() {
+                    return say("Usage: load FILE".to_string());
+                }
+                match input::load_file(Path::new(path), Some(input::DEFAULT_LIMIT_MB)) {
+                    Ok(text) => say(self.take_input(text.as_str(), path.to_string())),
+                    Err(e) => say(e.to_string()),
+                }
+            }
+            ["paste"] => match clipboard::paste(clipboard, None) {
+                Ok(text) => say(self.take_input(&text, "the clipboard".to_string())),
+                Err(e) => say(e),
+            },
+            ["input"] => {
+                self.typing = Some(Vec::new());
+                say(format!("Type the input; end it with a line holding only {}", END_OF_INPUT))
+            }
+            ["set", name, value @ ..] if value.len() <= 1 => {
+                let name = name.trim_start_matches('-').to_string();
+                let value = value.first().map(|value| value.to_string());
+                let mut options = self.options.clone();
+                options.retain(|(n, _)| *n != name);
+                options.push((name, value));
+                if let Some(day) = self.day
+                    && let Err(e) = check(day, &options)
+                {
+                    return say(e);
+                }
+                self.options = options;
+                say(format!("Options: {}", self.options_text()))
+            }
+            ["unset", name] => {
+                let name = name.trim_start_matches('-');
+                let before = self.options.len();
+                self.options.retain(|(n, _)| n != name);
+                if self.options.len() == before {
+                    return say(format!("--{} is not set", name));
+                }
+                say(format!("Options: {}", self.options_text()))
+            }
+            ["show"] => {
+                let day = self.day.map_or("none".to_string(), |day| day.to_string());
+                let input = match &self.input {
+                    Some((text, source)) => format!("{} ({} lines)", source, text.lines().count()),
+                    None => "none".to_string(),
+                };
+                say(format!("Day: {}\nPart: {}\nInput: {}\nOptions: {}", day, self.part(), input, self.options_text()))
+            }
+            ["go"] => match self.job() {
+                Ok(job) => Action::Solve(job),
+                Err(e) => say(e),
+            },
+            [command, ..] => say(format!("Unknown command '{}'; help lists them", command)),
+        }
+    }
+
+    /// The line to print for a finished `job`, recording it in the history
+    pub fn finish(&self, job: &Job, outcome: Outcome) -> String {
+        let heading = format!("Day {} Part {}", job.day, if job.part2 { 2 } else { 1 });
+        let (answer, output, millis) = match outcome {
+            Ok(done) => done,
+            Err(e) => {
+                if let Some(path) = &self.history {
+                    let _ = history::append(path, &Record::failed(job.day, job.part2, &e));
+                }
+                return format!("{} failed: {}", heading, e);
+            }
+        };
+        if let Some(path) = &self.history {
+            // A history that cannot be written is no reason to lose the answer
+            let value = answer.value.to_string();
+            let _ = history::append(path, &Record::now(job.day, job.part2, &job.input, &value, millis));
+        }
+        let mut lines: Vec<String> = output.lines().to_vec();
+        lines.extend(output.warnings().iter().map(|warning| format!("Warning: {}", warning)));
+        lines.push(format!("{}: {} ({})", heading, answer, history::millis(millis)));
+        lines.join("\n")
+    }
+
+    fn part(&self) -> u8 {
+        if self.part2 { 2 } else { 1 }
+    }
+
+    fn set_part(&mut self, part: &str) -> Result<(), String> {
+        match part {
+            "1" => self.part2 = false,
+            "2" => self.part2 = true,
+            _ => return Err(format!("{} is not a part (1 or 2)", part)),
+        }
+        Ok(())
+    }
+
+    /// Keep `text` as the input, normalized as a run would read it
+    fn take_input(&mut self, text: &str, source: String) -> String {
+        let text = input::normalize(text).into_owned();
+        let said = format!("Input: {} ({} lines)", source, text.lines().count());
+        self.input = Some((text.into(), source));
+        said
+    }
+
+    fn options_text(&self) -> String {
+        if self.options.is_empty() { "none".to_string() } else { args(&self.options).join(" ") }
+    }
+
+    fn job(&self) -> Result<Job, String> {
+        let day = self.day.ok_or("No day yet; use day N first")?;
+        let (input, _) = self.input.as_ref().ok_or("No input yet; load FILE, paste or input first")?;
+        let options = check(day, &self.options)?;
+        Ok(Job { day, part2: self.part2, input: Arc::clone(input), options })
+    }
+}
+
+/// The options as command-line arguments
+fn args(options: &[(String, Option<String>)]) -> Vec<String> {
+    options.iter().flat_map(|(name, value)| std::iter::once(format!("--{}", name)).chain(value.clone())).collect()
+}
+
+/// The options, checked against what `day` understands
+fn check(day: u8, options: &[(String, Option<String>)]) -> Result<DayOptions, String> {
+    let options = DayOptions::new(args(options));
+    let supported = crate::find_solver(day).map_or(&[][..], |solver| solver.options);
+    options.validate(day, supported)?;
+    Ok(options)
+}
+
+/// Prompt on stdout and read commands from stdin until quit or end of input
+pub fn run(history: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&interrupted);
+    ctrlc::set_handler(move || flag.store(true, Ordering::Relaxed))
+        .map_err(|e| format!("Could not catch Ctrl-C: {}", e))?;
+
+    let mut session = Session::new(history);
+    let mut stdout = io::stdout();
+    println!("adventcode repl; help lists the commands");
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if !session.is_typing() {
+            print!("{}", PROMPT);
+            stdout.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(());
+        };
+        match session.command(&line, &clipboard::System) {
+            Action::Say(text) if text.is_empty() => {}
+            Action::Say(text) => println!("{}", text),
+            Action::Quit => return Ok(()),
+            Action::Solve(job) => {
+                let job = Arc::new(job);
+                interrupted.store(false, Ordering::Relaxed);
+                let (tx, rx) = mpsc::channel();
+                let worker = Arc::clone(&job);
+                thread::spawn(move || {
+                    let _ = tx.send(worker.run());
+                });
+                // A solve cannot be stopped from outside; a cancelled one
+                // runs on, and its answer is dropped
+                loop {
+                    match rx.recv_timeout(Duration::from_millis(50)) {
+                        Ok(outcome) => {
+                            println!("{}", session.finish(&job, outcome));
+                            break;
+                        }
+                        Err(mpsc::RecvTimeoutError::Timeout) if interrupted.swap(false, Ordering::Relaxed) => {
+                            println!("Cancelled");
+                            break;
+                        }
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        Err(mpsc::RecvTimeoutError::Disconnected) => {
+                            println!("The solve ended without an answer");
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clipboard holding fixed text
+    struct Fixed(&'static str);
+
+    impl Clipboard for Fixed {
+        fn read(&self) -> Result<Vec<u8>, String> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    /// Feed `lines` to a session, solving in place, and return what it
+    /// printed with the times taken out
+    fn transcript(session: &mut Session, lines: &[&str]) -> String {
+        let clipboard = Fixed("3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n");
+        let mut said = Vec::new();
+        for line in lines {
+            said.push(format!("{}{}", if session.is_typing() { "" } else { PROMPT }, line));
+            match session.command(line, &clipboard) {
+                Action::Say(text) if text.is_empty() => {}
+                Action::Say(text) => said.push(text),
+                Action::Quit => break,
+                Action::Solve(job) => {
+                    let outcome = job.run();
+                    let line = session.finish(&job, outcome);
+                    said.push(line.rsplit_once(" (").map_or(line.clone(), |(answer, _)| answer.to_string()));
+                }
+            }
+        }
+        said.join("\n")
+    }
+
+    #[test]
+    fn a_scripted_session_solves_and_solves_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        std::fs::write(&path, "L68\r\nL30\r\nR48\r\n").unwrap();
+        let history_path = dir.path().join("history.jsonl");
+        let mut session = Session::new(Some(history_path.clone()));
+
+        let load = format!("load {}", path.display());
+        let said = transcript(&mut session, &["go", "use day 1", "go", &load, "go", "set verbos", "use part 3"]);
+        let expected = format!(
+            "aoc> go\nNo day yet; use day N first\n\
+             aoc> use day 1\nUsing Day 1 Part 1\n\
+             aoc> go\nNo input yet; load FILE, paste or input first\n\
+             aoc> {}\nInput: {} (3 lines)\n\
+             aoc> go\nDay 1 Part 1: Password: 1\n\
+             aoc> set verbos\nDay 1 does not understand '--verbos' (supported: --verbose, --export-path <FILE>)\n\
+             aoc> use part 3\n3 is not a part (1 or 2)",
+            load,
+            path.display()
+        );
+        assert_eq!(said, expected);
+
+        // Typed-in input ends at the lone dot; the same day then runs on
+        // pasted text with an option, twice without reading it again
+        let said = transcript(
+            &mut session,
+            &["input", "L68", "L30", "R48", "L5", ".", "show", "use day 5 part 2", "paste", "go", "set show-merged", "go", "quit", "go"],
+        );
+        assert!(said.contains("aoc> input\nType the input; end it with a line holding only .\nL68\nL30\nR48\nL5\n.\nInput: typed in (4 lines)\n"), "{}", said);
+        assert!(said.contains("aoc> show\nDay: 1\nPart: 1\nInput: typed in (4 lines)\nOptions: none\n"), "{}", said);
+        assert!(said.contains("aoc> paste\nInput: the clipboard (11 lines)\naoc> go\nDay 5 Part 2: "), "{}", said);
+        assert!(said.contains("aoc> set show-merged\nOptions: --show-merged\naoc> go\n"), "{}", said);
+        assert!(said.ends_with("aoc> quit"), "{}", said);
+        assert_eq!(said.matches("Day 5 Part 2: ").count(), 2, "{}", said);
+        assert!(said.lines().filter(|line| line.starts_with("Day 5 Part 2: ")).all(|line| line.ends_with(": 14")), "{}", said);
+
+        // Every solve went into the run history
+        let records = history::load(&history_path);
+        assert_eq!(records.iter().map(|r| (r.day, r.part, r.answer.as_str())).collect::<Vec<_>>(), [(1, 1, "1"), (5, 2, "14"), (5, 2, "14")]);
+    }
+}
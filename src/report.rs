@@ -0,0 +1,227 @@
+// Structured reports for --output-format
+//
+// `--output-format json|jsonl|csv|md` replaces the banner, result lines and
+// --all summary with one document holding a row per part: what ran, how it
+// went, the answer's value and how long it took. Scripts parse these, so the
+// serializers are plain functions of a `Report` and their exact output is
+// pinned by snapshot tests; changing a format means reviewing a snapshot.
+
+use std::fmt::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The document shapes --output-format can print
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// One JSON object with a `results` array
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    /// A header line, then one row per part
+    Csv,
+    /// A Markdown table
+    Md,
+}
+
+/// How one part went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Failed,
+    Skipped,
+    NotImplemented,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Failed => "failed",
+            Status::Skipped => "skipped",
+            Status::NotImplemented => "not_implemented",
+        }
+    }
+}
+
+/// One part's row; the field names are the JSON keys and CSV columns
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub title: String,
+    pub status: Status,
+    /// Just the value, e.g. "984"; None unless the part was solved
+    pub answer: Option<String>,
+    pub duration_ms: Option<f64>,
+    /// Where the input came from: a path, "example", "embedded" or "stdin"
+    pub input: Option<String>,
+    /// Why it failed or was skipped
+    pub error: Option<String>,
+}
+
+impl Row {
+    /// With the answer's value, how long it took and where its input came from
+    pub fn solved(self, answer: &str, duration_ms: f64, input: &str) -> Self {
+        Self {
+            answer: Some(answer.to_string()),
+            duration_ms: Some(duration_ms),
+            input: Some(input.to_string()),
+            ..self
+        }
+    }
+
+    /// With the reason it failed or was skipped
+    pub fn error(self, error: &str) -> Self {
+        Self { error: Some(error.to_string()), ..self }
+    }
+}
+
+/// Column order for CSV and Markdown, the same as the JSON field order
+const COLUMNS: [&str; 8] = ["day", "part", "title", "status", "answer", "duration_ms", "input", "error"];
+
+/// Every row of a run, in the order the parts ran
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    pub results: Vec<Row>,
+}
+
+/// `report` as a `format` document, ending with a newline
+pub fn render(format: Format, report: &Report) -> String {
+    match format {
+        Format::Json => json(report),
+        Format::Jsonl => jsonl(report),
+        Format::Csv => csv(report),
+        Format::Md => markdown(report),
+    }
+}
+
+fn json(report: &Report) -> String {
+    serde_json::to_string_pretty(report).expect("reports always serialize") + "\n"
+}
+
+fn jsonl(report: &Report) -> String {
+    report.results.iter().map(|row| serde_json::to_string(row).expect("rows always serialize") + "\n").collect()
+}
+
+fn csv(report: &Report) -> String {
+    let mut text = COLUMNS.join(",") + "\n";
+    for row in &report.results {
+        let fields = cells(row).map(|cell| csv_field(&cell));
+        writeln!(text, "{}", fields.join(",")).unwrap();
+    }
+    text
+}
+
+fn markdown(report: &Report) -> String {
+    let mut text = format!("| {} |\n", COLUMNS.join(" | "));
+    writeln!(text, "|{}", "---|".repeat(COLUMNS.len())).unwrap();
+    for row in &report.results {
+        let cells = cells(row).map(|cell| cell.replace('|', "\\|").replace('\n', " "));
+        writeln!(text, "| {} |", cells.join(" | ")).unwrap();
+    }
+    text
+}
+
+/// A row's fields as text, in `COLUMNS` order; missing values are empty
+fn cells(row: &Row) -> [String; 8] {
+    [
+        row.day.to_string(),
+        row.part.to_string(),
+        row.title.clone(),
+        row.status.name().to_string(),
+        row.answer.clone().unwrap_or_default(),
+        row.duration_ms.map(|ms| format!("{:.3}", ms)).unwrap_or_default(),
+        row.input.clone().unwrap_or_default(),
+        row.error.clone().unwrap_or_default(),
+    ]
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break (RFC 4180)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A solved part, a text answer, a failure and a skipped part
+    fn sample() -> Report {
+        let row = |day, part, title: &str, status| Row {
+            day,
+            part,
+            title: title.to_string(),
+            status,
+            answer: None,
+            duration_ms: None,
+            input: None,
+            error: None,
+        };
+        Report {
+            results: vec![
+                Row {
+                    answer: Some("984".to_string()),
+                    duration_ms: Some(1.25),
+                    input: Some("day1.txt".to_string()),
+                    ..row(1, 1, "SECRET ENTRANCE", Status::Ok)
+                },
+                Row {
+                    answer: Some("ab,c \"d\"".to_string()),
+                    duration_ms: Some(0.0421),
+                    input: Some("example".to_string()),
+                    ..row(2, 2, "GIFT SHOP", Status::Ok)
+                },
+                Row {
+                    error: Some("line 3: malformed fresh range | expected start-end".to_string()),
+                    ..row(5, 1, "CAFETERIA", Status::Failed)
+                },
+                Row { error: Some("no input".to_string()), ..row(6, 1, "Day 6", Status::Skipped) },
+            ],
+        }
+    }
+
+    #[test]
+    fn json_snapshot() {
+        insta::assert_snapshot!(render(Format::Json, &sample()));
+    }
+
+    #[test]
+    fn jsonl_snapshot() {
+        insta::assert_snapshot!(render(Format::Jsonl, &sample()));
+    }
+
+    #[test]
+    fn csv_snapshot() {
+        insta::assert_snapshot!(render(Format::Csv, &sample()));
+    }
+
+    #[test]
+    fn markdown_snapshot() {
+        insta::assert_snapshot!(render(Format::Md, &sample()));
+    }
+
+    #[test]
+    fn json_field_names_are_stable() {
+        let value: serde_json::Value = serde_json::from_str(&render(Format::Json, &sample())).unwrap();
+        let object = value.as_object().unwrap();
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["results"]);
+        for row in object["results"].as_array().unwrap() {
+            // Every key on every row, even when its value is null
+            let keys: Vec<&str> = row.as_object().unwrap().keys().map(String::as_str).collect();
+            let mut expected = COLUMNS.to_vec();
+            expected.sort_unstable();
+            assert_eq!(keys, expected);
+        }
+
+        // JSON Lines rows are the same objects
+        for (line, row) in render(Format::Jsonl, &sample()).lines().zip(object["results"].as_array().unwrap()) {
+            assert_eq!(&serde_json::from_str::<serde_json::Value>(line).unwrap(), row);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// `adventcode new`
+//
+// Scaffolds a day in the crate at the current directory: src/dayN.rs from a
+// template whose header has the `DAY N: TITLE` line discovery reads and a
+// stub solver, plus the day's `pub mod` line and DAYS entry in src/lib.rs,
+// the one place days are registered. An existing dayN.rs, or a lib.rs that
+// already names the day, stops the command before anything is written.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// src/dayN.rs for a new day: the header, OPTIONS and a `solve` that
+/// counts the input's lines (part 1) and characters (part 2) until it is
+/// written
+pub fn source(day: u8, title: &str) -> String {
+    let rule = "=".repeat(76);
+    format!(
+        "\
+// {rule}
+// DAY {day}: {title}
+// {rule}
+//
+// PROBLEM OVERVIEW:
+// -----------------
+// Description of the problem...
+//
+// Part 1: What part 1 asks for
+// Part 2: What part 2 asks for
+//
+// {rule}
+
+use crate::error::Error;
+use crate::options::DayOptions;
+use crate::output::{{Answer, Output}};
+
+/// Extra options understood by Day {day} (passed after `--`)
+pub const OPTIONS: &[&str] = &[];
+
+pub fn solve(input: &str, part2: bool, _opts: &DayOptions, _out: &mut Output) -> Result<Answer, Error> {{
+    let lines = input.lines().filter(|line| !line.trim().is_empty());
+    if part2 {{
+        // Part 2 logic
+        Ok(Answer::new(\"Characters\", lines.map(str::len).sum::<usize>()))
+    }} else {{
+        // Part 1 logic
+        Ok(Answer::new(\"Lines\", lines.count()))
+    }}
+}}
+"
+    )
+}
+
+/// The number in a `pub mod dayN;` line
+fn module_day(line: &str) -> Option<u8> {
+    line.strip_prefix("pub mod day")?.strip_suffix(';')?.parse().ok()
+}
+
+/// The number in a `Day { number: N, ... }` entry of DAYS
+fn entry_day(line: &str) -> Option<u8> {
+    let rest = line.trim_start().strip_prefix("Day { number: ")?;
+    rest[..rest.find(',')?].parse().ok()
+}
+
+/// Where a line for `day` goes among `lines`: after the last of the lines
+/// `number` recognizes with a smaller day, else before the first one; None
+/// when there are none
+fn insert_at(lines: &[&str], range: std::ops::Range<usize>, day: u8, number: fn(&str) -> Option<u8>) -> Option<usize> {
+    let days: Vec<(usize, u8)> = range.filter_map(|i| Some((i, number(lines[i])?))).collect();
+    let (first, _) = *days.first()?;
+    Some(days.iter().rev().find(|&&(_, n)| n < day).map_or(first, |&(i, _)| i + 1))
+}
+
+/// `lib` with `day` registered: its `pub mod` line and DAYS entry, each
+/// placed in day order
+pub fn register(lib: &str, day: u8) -> Result<String, String> {
+    let lines: Vec<&str> = lib.lines().collect();
+    if lines.iter().any(|&line| module_day(line) == Some(day)) {
+        return Err(format!("src/lib.rs already declares day{}", day));
+    }
+    if lines.iter().any(|&line| entry_day(line) == Some(day)) {
+        return Err(format!("src/lib.rs already has a DAYS entry for Day {}", day));
+    }
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .ok_or("src/lib.rs has no `pub const DAYS` table")?;
+    let end = start
+        + lines[start..].iter().position(|line| line.starts_with("];")).ok_or("the DAYS table in src/lib.rs never ends")?;
+    let entry_at = insert_at(&lines, start + 1..end, day, entry_day).unwrap_or(end);
+    let module_at = insert_at(&lines, 0..start, day, module_day).ok_or("src/lib.rs has no `pub mod dayN;` lines")?;
+
+    let entry = format!(
+        "    Day {{ number: {day}, options: day{day}::OPTIONS, solve: day{day}::solve, stream: None, source: include_str!(\"day{day}.rs\") }},"
+    );
+    let module = format!("pub mod day{};", day);
+    let mut out: Vec<&str> = Vec::with_capacity(lines.len() + 2);
+    for (i, &line) in lines.iter().enumerate() {
+        if i == module_at {
+            out.push(&module);
+        }
+        if i == entry_at {
+            out.push(&entry);
+        }
+        out.push(line);
+    }
+    Ok(out.join("\n") + "\n")
+}
+
+/// Write src/dayN.rs under `root` and register it in src/lib.rs; the paths
+/// written, in order
+pub fn create(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>, String> {
+    let title = title.trim();
+    if title.is_empty() || title.contains('\n') {
+        return Err("--title must be one non-empty line".into());
+    }
+    let lib_path = root.join("src/lib.rs");
+    let day_path = root.join(format!("src/day{}.rs", day));
+    if day_path.exists() {
+        return Err(format!("{} already exists; not overwriting it", day_path.display()));
+    }
+    let lib = fs::read_to_string(&lib_path).map_err(|e| {
+        format!("Could not read {} ({}); run `adventcode new` from the crate root", lib_path.display(), e)
+    })?;
+    let lib = register(&lib, day)?;
+
+    // create_new rather than the check above alone, so a file that appears
+    // in between is not clobbered either
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&day_path)
+        .and_then(|mut file| file.write_all(source(day, title).as_bytes()));
+    written.map_err(|e| match e.kind() {
+        io::ErrorKind::AlreadyExists => format!("{} already exists; not overwriting it", day_path.display()),
+        _ => format!("Could not write {}: {}", day_path.display(), e),
+    })?;
+    fs::write(&lib_path, lib).map_err(|e| format!("Could not write {}: {}", lib_path.display(), e))?;
+    Ok(vec![day_path, lib_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod bench;
+pub mod day1;
+pub mod day5;
+pub mod diagnostics;
+
+pub const DAYS: &[Day] = &[
+    Day { number: 1, options: day1::OPTIONS, solve: day1::solve, stream: None, source: include_str!(\"day1.rs\") },
+    Day { number: 5, options: day5::OPTIONS, solve: day5::solve, stream: None, source: include_str!(\"day5.rs\") },
+];
+";
+
+    #[test]
+    fn days_are_registered_in_order_once() {
+        let lib = register(LIB, 3).unwrap();
+        let lines: Vec<&str> = lib.lines().collect();
+        assert_eq!(lines[1..5], ["pub mod day1;", "pub mod day3;", "pub mod day5;", "pub mod diagnostics;"]);
+        assert!(lines[8].starts_with("    Day { number: 3, options: day3::OPTIONS, solve: day3::solve,"));
+        assert!(lines[9].starts_with("    Day { number: 5,"));
+
+        let lib = register(&lib, 12).unwrap();
+        assert!(lib.contains("pub mod day5;\npub mod day12;\npub mod diagnostics;"));
+        assert!(lib.contains("include_str!(\"day12.rs\") },\n];"));
+
+        assert_eq!(register(&lib, 12).unwrap_err(), "src/lib.rs already declares day12");
+        assert!(register("pub mod day1;\n", 2).unwrap_err().contains("no `pub const DAYS`"));
+    }
+
+    #[test]
+    fn the_template_has_a_header_discovery_reads() {
+        let meta = adventcode::meta::parse(6, &source(6, "Warehouse"));
+        assert_eq!(meta.title, "Warehouse");
+        assert_eq!(meta.overview[0], "Description of the problem...");
+    }
+}
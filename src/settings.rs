@@ -0,0 +1,546 @@
+// The TUI's settings screen (S)
+//
+// Lists the effective configuration: the top-level settings of
+// adventcode.toml (config.rs) and the session token, which lives in the user
+// config directory and is shown masked. Enter edits the selected field in a
+// small input box and Enter again checks the value and applies it, so a new
+// theme or key binding profile shows at once; s writes everything to the
+// files. A field the command line set for this session (--part2,
+// --notify-after) is marked as overridden and cannot be edited here.
+// Accessible mode (--accessible, or `accessible` in the file) forces the
+// high-contrast theme whatever the Theme field says.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::config::{Config, Overrides};
+use crate::init;
+use crate::notify;
+use crate::theme::{self, Theme};
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 18;
+
+/// Key bindings on top of the ones every screen has
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Keys {
+    #[default]
+    Default,
+    /// Ctrl-N/Ctrl-P move down and up, Ctrl-F/Ctrl-B right and left, and
+    /// Ctrl-G backs out like Esc
+    Emacs,
+}
+
+impl Keys {
+    pub const NAMES: &'static str = "default, emacs";
+
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Keys::Default),
+            "emacs" => Some(Keys::Emacs),
+            _ => None,
+        }
+    }
+
+    /// The key the screens see for `key`
+    pub fn translate(self, key: KeyEvent) -> KeyEvent {
+        if self == Keys::Default || !key.modifiers.contains(KeyModifiers::CONTROL) {
+            return key;
+        }
+        let code = match key.code {
+            KeyCode::Char('n') => KeyCode::Down,
+            KeyCode::Char('p') => KeyCode::Up,
+            KeyCode::Char('f') => KeyCode::Right,
+            KeyCode::Char('b') => KeyCode::Left,
+            KeyCode::Char('g') => KeyCode::Esc,
+            _ => return key,
+        };
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    InputDir,
+    Answers,
+    Theme,
+    Keys,
+    Session,
+    DefaultPart,
+    NotifyAfter,
+}
+
+impl Field {
+    pub const ALL: [Field; 7] = [
+        Field::InputDir,
+        Field::Answers,
+        Field::Theme,
+        Field::Keys,
+        Field::Session,
+        Field::DefaultPart,
+        Field::NotifyAfter,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Field::InputDir => "Input directory",
+            Field::Answers => "Answers file",
+            Field::Theme => "Theme",
+            Field::Keys => "Key bindings",
+            Field::Session => "Session token",
+            Field::DefaultPart => "Default part",
+            Field::NotifyAfter => "Notify after (s)",
+        }
+    }
+
+    /// What the field takes, under the input box
+    fn hint(self) -> String {
+        match self {
+            Field::InputDir => "a directory that exists or can be created".to_string(),
+            Field::Answers => "a file in a directory that exists or can be created".to_string(),
+            Field::Theme => theme::names(),
+            Field::Keys => Keys::NAMES.to_string(),
+            Field::Session => "the `session` cookie from adventofcode.com".to_string(),
+            Field::DefaultPart => "1 or 2".to_string(),
+            Field::NotifyAfter => "seconds, e.g. 10 or 2.5".to_string(),
+        }
+    }
+}
+
+/// Check `text` as a value for `field`, returning it trimmed
+pub fn validate(field: Field, text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(format!("{} cannot be blank", field.label()));
+    }
+    match field {
+        Field::InputDir => {
+            let path = Path::new(text);
+            if path.exists() && !path.is_dir() {
+                return Err(format!("{} is not a directory", text));
+            }
+            creatable(path)?;
+        }
+        Field::Answers => {
+            let path = Path::new(text);
+            if path.is_dir() {
+                return Err(format!("{} is a directory", text));
+            }
+            if let Some(dir) = path.parent() {
+                creatable(dir)?;
+            }
+        }
+        Field::Theme if theme::named(text).is_none() => {
+            return Err(format!("no theme called {}; try {}", text, theme::names()));
+        }
+        Field::Keys if Keys::named(text).is_none() => {
+            return Err(format!("no key bindings called {}; try {}", text, Keys::NAMES));
+        }
+        Field::Session if text.contains(char::is_whitespace) => {
+            return Err("a session token has no spaces".to_string());
+        }
+        Field::DefaultPart if !matches!(text, "1" | "2") => return Err("the default part is 1 or 2".to_string()),
+        Field::NotifyAfter if !text.parse::<f64>().is_ok_and(|secs| secs >= 0.0 && secs.is_finite()) => {
+            return Err(format!("{} is not a number of seconds", text));
+        }
+        _ => {}
+    }
+    Ok(text.to_string())
+}
+
+/// Whether `dir` exists or could be made: its nearest existing ancestor has
+/// to be a directory
+fn creatable(dir: &Path) -> Result<(), String> {
+    let existing = dir.ancestors().find(|ancestor| ancestor.as_os_str().is_empty() || ancestor.exists());
+    match existing {
+        Some(ancestor) if ancestor.as_os_str().is_empty() || ancestor.is_dir() => Ok(()),
+        Some(ancestor) => Err(format!("{} is not a directory", ancestor.display())),
+        None => Err(format!("{} cannot be created", dir.display())),
+    }
+}
+
+/// "****1234": only the end of a token shows
+fn mask(token: &str) -> String {
+    let shown: String = token.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("{}{}", "*".repeat(token.chars().count().saturating_sub(4).min(12)), shown)
+}
+
+pub struct Settings {
+    /// The settings as they will be saved
+    config: Config,
+    config_path: PathBuf,
+    /// The user config, for what config leaves out; never saved from here
+    user: Config,
+    /// The session token, and the file it lives in (None: no config dir)
+    session: Option<String>,
+    session_path: Option<PathBuf>,
+    overrides: Overrides,
+    /// Edits not yet written to the files
+    dirty: bool,
+    session_dirty: bool,
+    selected: usize,
+    /// The input box's text, while a field is being edited
+    editing: Option<String>,
+    /// The last edit's or save's outcome, and whether it was an error
+    message: Option<(String, bool)>,
+}
+
+impl Settings {
+    /// The screen for `config` (read from `config_path`), the session token
+    /// at `session_path`, and what the command line overrode
+    pub fn new(config: Config, config_path: PathBuf, session_path: Option<PathBuf>, overrides: Overrides) -> Self {
+        let session = session_path
+            .as_deref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        Self {
+            config,
+            config_path,
+            user: Config::default(),
+            session,
+            session_path,
+            overrides,
+            dirty: false,
+            session_dirty: false,
+            selected: 0,
+            editing: None,
+            message: None,
+        }
+    }
+
+    /// Fall back on the user config's settings where config has none
+    pub fn with_user(mut self, user: Config) -> Self {
+        self.user = user;
+        self
+    }
+
+    /// The colors the config asks for; an unknown name gets the default,
+    /// and accessible mode always gets high contrast
+    pub fn theme(&self) -> &'static Theme {
+        if self.accessible() {
+            return theme::high_contrast();
+        }
+        self.theme_name().and_then(theme::named).unwrap_or_else(theme::default)
+    }
+
+    /// Accessible mode, from the command line or the config
+    pub fn accessible(&self) -> bool {
+        self.overrides.accessible || self.config.accessible.or(self.user.accessible) == Some(true)
+    }
+
+    pub fn keys(&self) -> Keys {
+        self.keys_name().and_then(Keys::named).unwrap_or_default()
+    }
+
+    /// 1 or 2, from the command line or the config
+    pub fn default_part(&self) -> u8 {
+        self.overrides.default_part.or(self.config.default_part).or(self.user.default_part).unwrap_or(1)
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        PathBuf::from(self.config.answers.as_deref().or(self.user.answers.as_deref()).unwrap_or(init::ANSWERS_FILE))
+    }
+
+    fn theme_name(&self) -> Option<&str> {
+        self.config.theme.as_deref().or(self.user.theme.as_deref())
+    }
+
+    fn keys_name(&self) -> Option<&str> {
+        self.config.keys.as_deref().or(self.user.keys.as_deref())
+    }
+
+    /// A theme or key binding profile in the config that does not exist
+    pub fn unknown_names(&self) -> Vec<String> {
+        let mut unknown = Vec::new();
+        if let Some(name) = self.theme_name().filter(|name| theme::named(name).is_none()) {
+            unknown.push(format!("Unknown theme {}, using the default", name));
+        }
+        if let Some(name) = self.keys_name().filter(|name| Keys::named(name).is_none()) {
+            unknown.push(format!("Unknown key bindings {}, using the default", name));
+        }
+        unknown
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn field(&self) -> Field {
+        Field::ALL[self.selected]
+    }
+
+    /// The command-line flag that set `field` for this session
+    fn overridden_by(&self, field: Field) -> Option<&'static str> {
+        match field {
+            Field::DefaultPart => self.overrides.default_part.map(|_| "--part"),
+            Field::NotifyAfter => self.overrides.notify_after.map(|_| "--notify-after"),
+            _ => None,
+        }
+    }
+
+    /// The value in effect, and where it comes from when not the config
+    fn shown(&self, field: Field) -> (String, Option<String>) {
+        if let Some(flag) = self.overridden_by(field) {
+            let value = match field {
+                Field::DefaultPart => self.default_part().to_string(),
+                _ => self.overrides.notify_after.unwrap_or_default().to_string(),
+            };
+            return (value, Some(format!("set by {} for this session", flag)));
+        }
+        let default = || Some("default".to_string());
+        if field == Field::Theme && self.accessible() {
+            return (theme::high_contrast().name.to_string(), Some("forced by accessible mode".to_string()));
+        }
+        match field {
+            Field::InputDir => match &self.config.input_dir {
+                Some(dir) => (dir.clone(), None),
+                None => (".".to_string(), default()),
+            },
+            Field::Answers => match &self.config.answers {
+                Some(path) => (path.clone(), None),
+                None => (init::ANSWERS_FILE.to_string(), default()),
+            },
+            Field::Theme => match &self.config.theme {
+                Some(name) if theme::named(name).is_some() => (name.clone(), None),
+                Some(name) => (theme::default().name.to_string(), Some(format!("{} is unknown", name))),
+                None => (theme::default().name.to_string(), default()),
+            },
+            Field::Keys => match &self.config.keys {
+                Some(name) if Keys::named(name).is_some() => (name.clone(), None),
+                Some(name) => ("default".to_string(), Some(format!("{} is unknown", name))),
+                None => ("default".to_string(), default()),
+            },
+            Field::Session => match (&self.session, &self.session_path) {
+                (Some(token), _) => (mask(token), None),
+                (None, Some(_)) => ("not set".to_string(), None),
+                (None, None) => ("not set".to_string(), Some("no config directory".to_string())),
+            },
+            Field::DefaultPart => match self.config.default_part {
+                Some(part) => (part.to_string(), None),
+                None => ("1".to_string(), default()),
+            },
+            Field::NotifyAfter => match self.config.notify_after {
+                Some(secs) => (secs.to_string(), None),
+                None => (notify::DEFAULT_THRESHOLD_SECS.to_string(), default()),
+            },
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1).min(Field::ALL.len() - 1);
+    }
+
+    /// Open the input box on the selected field, unless the command line
+    /// set it; a token starts blank rather than in the clear
+    pub fn edit(&mut self) {
+        let field = self.field();
+        if let Some(flag) = self.overridden_by(field) {
+            self.message = Some((format!("{} is set by {} for this session", field.label(), flag), true));
+            return;
+        }
+        if field == Field::Session && self.session_path.is_none() {
+            self.message = Some(("There is no config directory to keep a session token in".to_string(), true));
+            return;
+        }
+        let current = match field {
+            Field::Session => String::new(),
+            _ => self.shown(field).0,
+        };
+        self.editing = Some(current);
+        self.message = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.editing = None;
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        if let Some(text) = self.editing.as_mut() {
+            text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.editing.as_mut() {
+            text.pop();
+        }
+    }
+
+    /// Check the input box and take its value; true when it was taken, so
+    /// the caller can put it to use
+    pub fn apply(&mut self) -> bool {
+        let (Some(text), field) = (self.editing.as_deref(), self.field()) else {
+            return false;
+        };
+        let value = match validate(field, text) {
+            Ok(value) => value,
+            Err(e) => {
+                self.message = Some((e, true));
+                return false;
+            }
+        };
+        match field {
+            Field::InputDir => self.config.input_dir = Some(value.clone()),
+            Field::Answers => self.config.answers = Some(value.clone()),
+            Field::Theme => self.config.theme = Some(value.clone()),
+            Field::Keys => self.config.keys = Some(value.clone()),
+            Field::Session => {
+                self.session = Some(value.clone());
+                self.session_dirty = true;
+            }
+            Field::DefaultPart => self.config.default_part = value.parse().ok(),
+            Field::NotifyAfter => self.config.notify_after = value.parse().ok(),
+        }
+        self.dirty |= field != Field::Session;
+        let shown = if field == Field::Session { mask(&value) } else { value };
+        self.message = Some((format!("{} is now {}; s saves it", field.label(), shown), false));
+        self.editing = None;
+        true
+    }
+
+    /// Write the config file (creating the input directory) and the session
+    /// token, whichever changed
+    pub fn save(&mut self) {
+        let mut saved = Vec::new();
+        let result = (|| {
+            if self.dirty {
+                if let Some(dir) = &self.config.input_dir {
+                    fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir, e))?;
+                }
+                self.config.save(&self.config_path)?;
+                self.dirty = false;
+                saved.push(self.config_path.display().to_string());
+            }
+            if self.session_dirty
+                && let (Some(path), Some(token)) = (&self.session_path, &self.session)
+            {
+                init::save_session(path, token).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                self.session_dirty = false;
+                saved.push(path.display().to_string());
+            }
+            Ok::<(), String>(())
+        })();
+        self.message = Some(match result {
+            Err(e) => (format!("Save failed: {}", e), true),
+            Ok(()) if saved.is_empty() => ("Nothing to save".to_string(), false),
+            Ok(()) => (format!("Saved {}", saved.join(" and ")), false),
+        });
+    }
+
+    /// Unsaved edits
+    pub fn is_dirty(&self) -> bool {
+        self.dirty || self.session_dirty
+    }
+}
+
+/// Draw the field list, with the input box below it while editing; with a
+/// `marker` (accessible mode) the selected field and errors say so in words
+/// too, not only in color
+pub fn render(f: &mut Frame, area: Rect, settings: &Settings, theme: &Theme, marker: Option<&str>, block: Block<'static>) {
+    let modified = if settings.is_dirty() { " [modified]" } else { "" };
+    let block = block.title(format!(" Settings ({}){} ", settings.config_path.display(), modified));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(Field::ALL.len() as u16 + 1), Constraint::Min(0)])
+        .split(inner);
+
+    let mut lines = vec![Line::from("")];
+    for (i, field) in Field::ALL.into_iter().enumerate() {
+        let (value, note) = settings.shown(field);
+        let selected = i == settings.selected;
+        let style = if selected {
+            Style::default().fg(theme.highlight).bg(theme.selection).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled(format!(" {:<width$}", field.label(), width = LABEL_WIDTH), Style::default().fg(theme.accent)),
+            Span::styled(value, style),
+        ];
+        if let Some(marker) = marker {
+            let shown = if selected { marker.to_string() } else { " ".repeat(marker.len()) };
+            spans.insert(0, Span::styled(shown, style));
+        }
+        if let Some(note) = note {
+            spans.push(Span::styled(format!(" ({})", note), Style::default().fg(theme.muted)));
+        }
+        lines.push(Line::from(spans));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let mut below = Vec::new();
+    if let Some(text) = &settings.editing {
+        let field = settings.field();
+        let shown = if field == Field::Session { "*".repeat(text.chars().count()) } else { text.clone() };
+        below.push(Line::from(""));
+        below.push(Line::from(vec![
+            Span::styled(format!(" {}: ", field.label()), Style::default().fg(theme.accent)),
+            Span::styled(format!("{}_", shown), Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+        ]));
+        below.push(Line::from(Span::styled(format!(" {}", field.hint()), Style::default().fg(theme.muted))));
+    }
+    if let Some((message, error)) = &settings.message {
+        let color = if *error { ratatui::style::Color::Red } else { theme.frame };
+        let label = if *error && marker.is_some() { "Error: " } else { "" };
+        below.push(Line::from(""));
+        below.push(Line::from(Span::styled(format!(" {}{}", label, message), Style::default().fg(color))));
+    }
+    f.render_widget(Paragraph::new(below), chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_checked_before_they_are_taken() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("answers.toml");
+        fs::write(&file, "").unwrap();
+        let path = |p: &Path| p.to_str().unwrap().to_string();
+
+        assert_eq!(validate(Field::InputDir, &path(&dir.path().join("new/inputs"))), Ok(path(&dir.path().join("new/inputs"))));
+        assert!(validate(Field::InputDir, &path(&file)).unwrap_err().ends_with("is not a directory"));
+        assert!(validate(Field::InputDir, &path(&file.join("inputs"))).unwrap_err().ends_with("is not a directory"));
+        assert!(validate(Field::Answers, &path(&file)).is_ok());
+        assert!(validate(Field::Answers, &path(dir.path())).unwrap_err().ends_with("is a directory"));
+
+        assert_eq!(validate(Field::Theme, " light "), Ok("light".to_string()));
+        assert!(validate(Field::Theme, "solarized").unwrap_err().contains("try default, light, mono"));
+        assert!(validate(Field::Keys, "emacs").is_ok());
+        assert!(validate(Field::Keys, "vi").is_err());
+        assert!(validate(Field::Session, "abc def").is_err());
+        assert!(validate(Field::DefaultPart, "2").is_ok());
+        assert!(validate(Field::DefaultPart, "3").is_err());
+        assert!(validate(Field::NotifyAfter, "2.5").is_ok());
+        assert!(validate(Field::NotifyAfter, "-1").is_err());
+        assert!(validate(Field::Theme, "").unwrap_err().contains("cannot be blank"));
+    }
+
+    #[test]
+    fn emacs_keys_map_onto_the_arrows() {
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(Keys::Emacs.translate(ctrl('n')).code, KeyCode::Down);
+        assert_eq!(Keys::Emacs.translate(ctrl('g')).code, KeyCode::Esc);
+        assert_eq!(Keys::Emacs.translate(ctrl('s')), ctrl('s'));
+        assert_eq!(Keys::Default.translate(ctrl('n')), ctrl('n'));
+        assert_eq!(mask("53616c7465645f5f"), "************5f5f");
+    }
+}
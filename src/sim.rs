@@ -0,0 +1,127 @@
+// Fixed-point simulation
+//
+// "Repeat until nothing changes" comes up in AoC again and again (day 4's
+// roll removal is the first this year). `run_until_stable` drives such a
+// loop: the step function updates the state in place and says whether
+// anything changed. A cap on the number of rounds turns a simulation that
+// never settles into a Runaway error instead of a hang. `rounds` yields
+// every intermediate state, for visualizing the process.
+
+use std::fmt;
+
+/// Default cap on changing rounds, far more than any puzzle needs
+pub const DEFAULT_MAX_ROUNDS: usize = 1_000_000;
+
+/// The simulation was still changing when it hit the round cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Runaway {
+    pub max_rounds: usize,
+}
+
+impl fmt::Display for Runaway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "simulation still changing after {} rounds", self.max_rounds)
+    }
+}
+
+impl std::error::Error for Runaway {}
+
+/// Step `state` until a round changes nothing, with the default cap
+///
+/// Returns the stable state and the number of rounds that changed it
+/// (0 if it was stable to begin with).
+pub fn run_until_stable<S>(state: S, step: impl FnMut(&mut S) -> bool) -> Result<(S, usize), Runaway> {
+    run_until_stable_capped(state, DEFAULT_MAX_ROUNDS, step)
+}
+
+/// `run_until_stable`, allowing at most `max_rounds` changing rounds
+pub fn run_until_stable_capped<S>(
+    mut state: S,
+    max_rounds: usize,
+    mut step: impl FnMut(&mut S) -> bool,
+) -> Result<(S, usize), Runaway> {
+    let mut rounds = 0;
+    while step(&mut state) {
+        rounds += 1;
+        if rounds > max_rounds {
+            return Err(Runaway { max_rounds });
+        }
+    }
+    Ok((state, rounds))
+}
+
+/// Every state after a changing round, in order, ending at the stable one
+///
+/// The initial state is not yielded, so a state that is already stable
+/// yields nothing. Past `max_rounds` changing rounds the iterator yields
+/// one Runaway error and stops.
+pub fn rounds<S: Clone, F: FnMut(&mut S) -> bool>(state: S, max_rounds: usize, step: F) -> Rounds<S, F> {
+    Rounds { state, step, rounds: 0, max_rounds, done: false }
+}
+
+/// Iterator returned by `rounds`
+pub struct Rounds<S, F> {
+    state: S,
+    step: F,
+    rounds: usize,
+    max_rounds: usize,
+    done: bool,
+}
+
+impl<S: Clone, F: FnMut(&mut S) -> bool> Iterator for Rounds<S, F> {
+    type Item = Result<S, Runaway>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || !(self.step)(&mut self.state) {
+            self.done = true;
+            return None;
+        }
+        self.rounds += 1;
+        if self.rounds > self.max_rounds {
+            self.done = true;
+            return Some(Err(Runaway { max_rounds: self.max_rounds }));
+        }
+        Some(Ok(self.state.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Halve until zero: n changes ceil(log2(n + 1)) times
+    fn halve(n: &mut u32) -> bool {
+        let before = *n;
+        *n /= 2;
+        *n != before
+    }
+
+    #[test]
+    fn stable_state_takes_zero_rounds() {
+        assert_eq!(run_until_stable(0, halve), Ok((0, 0)));
+        assert_eq!(rounds(0, 10, halve).count(), 0);
+    }
+
+    #[test]
+    fn counts_rounds_and_yields_each_state() {
+        assert_eq!(run_until_stable(20, halve), Ok((0, 5)));
+        let states: Vec<u32> = rounds(20, 10, halve).map(Result::unwrap).collect();
+        assert_eq!(states, [10, 5, 2, 1, 0]);
+    }
+
+    #[test]
+    fn cap_turns_a_runaway_into_an_error() {
+        let forever = |n: &mut u32| {
+            *n += 1;
+            true
+        };
+        assert_eq!(run_until_stable_capped(0, 100, forever), Err(Runaway { max_rounds: 100 }));
+        // Exactly max_rounds changes is still fine
+        assert_eq!(run_until_stable_capped(20, 5, halve), Ok((0, 5)));
+        assert_eq!(run_until_stable_capped(20, 4, halve), Err(Runaway { max_rounds: 4 }));
+
+        let last = rounds(0, 3, forever).last();
+        assert_eq!(last, Some(Err(Runaway { max_rounds: 3 })));
+        assert_eq!(rounds(0, 3, forever).count(), 4);
+    }
+}
@@ -0,0 +1,101 @@
+// Saved TUI state
+//
+// What the TUI remembers between sessions, as JSON in state.json under the
+// data directory (the file `adventcode clean` deletes as "TUI state"). A
+// missing, unreadable or outdated file just means the defaults; a field this
+// version does not know is dropped on the next save. Only the split between
+// the day list and the details pane is kept so far.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use adventcode::cache;
+
+/// The state file's name in the data directory
+pub const FILE: &str = "state.json";
+
+/// The day list's share of the width, in percent, before any change
+pub const DEFAULT_SPLIT: u16 = 60;
+
+/// How far one `<` or `>` moves the split
+pub const SPLIT_STEP: u16 = 5;
+
+/// The narrowest and widest the day list may get
+pub const SPLIT_MIN: u16 = 20;
+pub const SPLIT_MAX: u16 = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// The day list's share of the width, in percent
+    pub split: u16,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self { split: DEFAULT_SPLIT }
+    }
+}
+
+impl State {
+    /// `<data dir>/state.json`
+    pub fn default_path() -> Option<PathBuf> {
+        Some(cache::data_dir()?.join(FILE))
+    }
+
+    /// The state saved at `path`, or the defaults when there is none to
+    /// read; a split outside the bounds is pulled back inside them
+    pub fn load(path: &Path) -> Self {
+        let state: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { split: state.split.clamp(SPLIT_MIN, SPLIT_MAX) }
+    }
+
+    /// Write the state to `path`, creating the data directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Move the split by `steps` of SPLIT_STEP, within the bounds
+    pub fn nudge_split(&mut self, steps: i16) {
+        let split = self.split as i16 + steps * SPLIT_STEP as i16;
+        self.split = (split.max(0) as u16).clamp(SPLIT_MIN, SPLIT_MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_split_saves_reloads_and_stays_in_bounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join(FILE);
+        assert_eq!(State::load(&path), State::default());
+
+        let mut state = State::default();
+        state.nudge_split(1);
+        assert_eq!(state.split, 65);
+        state.save(&path).unwrap();
+        assert_eq!(State::load(&path).split, 65);
+
+        state.nudge_split(10);
+        assert_eq!(state.split, SPLIT_MAX);
+        state.nudge_split(-20);
+        assert_eq!(state.split, SPLIT_MIN);
+
+        fs::write(&path, r#"{"split": 95, "theme": "dark"}"#).unwrap();
+        assert_eq!(State::load(&path).split, SPLIT_MAX);
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(State::load(&path), State::default());
+    }
+}
@@ -0,0 +1,95 @@
+// Color in the plain (non-TUI) output
+//
+// The result line's answer is bold green, --expected's PASS and FAIL are
+// green and red, warnings are yellow and the banner's title is bold. Color
+// is only ever added for a terminal: stdout and stderr are judged apart, so
+// `adventcode -d 1 | tee answers.txt` gets a plain answer while its warnings
+// on the terminal stay yellow. --no-color, a non-empty NO_COLOR
+// (https://no-color.org) or TERM=dumb turn it off everywhere.
+
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+
+/// Which stream a piece of text is printed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether each stream gets color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    stdout: bool,
+    stderr: bool,
+}
+
+const BOLD: &str = "1";
+const BOLD_GREEN: &str = "1;32";
+const RED: &str = "31";
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+
+impl Style {
+    /// Color for the streams that are terminals, unless `no_color`
+    /// (--no-color) or the environment says otherwise
+    pub fn detect(no_color: bool) -> Self {
+        let var = |name: &str| std::env::var_os(name).unwrap_or_default();
+        let allowed = !no_color && var("NO_COLOR").is_empty() && var("TERM") != "dumb";
+        Self { stdout: allowed && io::stdout().is_terminal(), stderr: allowed && io::stderr().is_terminal() }
+    }
+
+    /// Color on both streams; for tests
+    #[cfg(test)]
+    pub fn always() -> Self {
+        Self { stdout: true, stderr: true }
+    }
+
+    /// `text` in the SGR color `code`, when `stream` gets color
+    fn paint(self, stream: Stream, code: &str, text: impl Display) -> String {
+        let on = match stream {
+            Stream::Stdout => self.stdout,
+            Stream::Stderr => self.stderr,
+        };
+        if on { format!("\x1b[{}m{}\x1b[0m", code, text) } else { text.to_string() }
+    }
+
+    /// The answer in the result line
+    pub fn answer(self, text: impl Display) -> String {
+        self.paint(Stream::Stdout, BOLD_GREEN, text)
+    }
+
+    /// A banner or summary title
+    pub fn heading(self, text: impl Display) -> String {
+        self.paint(Stream::Stdout, BOLD, text)
+    }
+
+    /// --expected's verdict: green when it passed, red when it failed
+    pub fn verdict(self, stream: Stream, passed: bool, text: impl Display) -> String {
+        self.paint(stream, if passed { GREEN } else { RED }, text)
+    }
+
+    /// A warning line, with its glyph
+    pub fn warning(self, stream: Stream, text: impl Display) -> String {
+        self.paint(stream, YELLOW, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_streams_with_color_get_escapes() {
+        let style = Style::always();
+        assert_eq!(style.answer(357), "\x1b[1;32m357\x1b[0m");
+        assert_eq!(style.verdict(Stream::Stderr, false, "FAIL"), "\x1b[31mFAIL\x1b[0m");
+
+        let piped = Style { stdout: false, stderr: true };
+        assert_eq!(piped.answer(357), "357");
+        assert_eq!(piped.warning(Stream::Stdout, "! Diff"), "! Diff");
+        assert_eq!(piped.warning(Stream::Stderr, "! slow"), "\x1b[33m! slow\x1b[0m");
+        assert_eq!(Style::default().heading("Summary"), "Summary");
+        assert_eq!(Style::detect(true), Style::default());
+    }
+}
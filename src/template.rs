@@ -0,0 +1,170 @@
+// Output templates for --format
+//
+// `--format "day {day} part {part}: {answer}"` replaces the banner and result
+// lines with one line per completed part, for scripts that want their own
+// shape. The template is parsed when the arguments are, so a typo in a
+// placeholder fails before anything runs; `{{` and `}}` stand for literal
+// braces.
+
+use std::str::FromStr;
+
+/// Something a template can fill in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Day,
+    Part,
+    Title,
+    Answer,
+    DurationMs,
+    Input,
+}
+
+impl Field {
+    const ALL: [Field; 6] = [Field::Day, Field::Part, Field::Title, Field::Answer, Field::DurationMs, Field::Input];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Day => "day",
+            Field::Part => "part",
+            Field::Title => "title",
+            Field::Answer => "answer",
+            Field::DurationMs => "duration_ms",
+            Field::Input => "input",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// A parsed --format template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+/// What one completed part fills a template with
+pub struct Values<'a> {
+    pub day: u8,
+    pub part2: bool,
+    pub title: &'a str,
+    /// Just the value, e.g. "984"
+    pub answer: &'a str,
+    pub millis: f64,
+    /// Where the input came from: a path, "example", "embedded" or "stdin"
+    pub input: &'a str,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(template: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unclosed placeholder {{{} (write {{{{ for a literal brace)", name)),
+                        }
+                    }
+                    let Some(field) = Field::ALL.into_iter().find(|f| f.name() == name) else {
+                        let known: Vec<String> = Field::ALL.iter().map(|f| format!("{{{}}}", f.name())).collect();
+                        return Err(format!("unknown placeholder {{{}}}; expected one of {}", name, known.join(", ")));
+                    };
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                }
+                '}' => return Err("unmatched } (write }} for a literal brace)".to_string()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self { pieces })
+    }
+}
+
+impl Template {
+    /// The template's line for one completed part
+    pub fn render(&self, values: &Values) -> String {
+        self.pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => text.clone(),
+                Piece::Field(Field::Day) => values.day.to_string(),
+                Piece::Field(Field::Part) => if values.part2 { "2" } else { "1" }.to_string(),
+                Piece::Field(Field::Title) => values.title.to_string(),
+                Piece::Field(Field::Answer) => values.answer.to_string(),
+                Piece::Field(Field::DurationMs) => format!("{:.3}", values.millis),
+                Piece::Field(Field::Input) => values.input.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: Values = Values {
+        day: 5,
+        part2: true,
+        title: "CAFETERIA",
+        answer: "14",
+        millis: 1.25,
+        input: "day5.txt",
+    };
+
+    fn render(template: &str) -> String {
+        template.parse::<Template>().unwrap().render(&VALUES)
+    }
+
+    #[test]
+    fn fills_every_placeholder() {
+        assert_eq!(
+            render("{day}/{part} {title}: {answer} in {duration_ms}ms from {input}"),
+            "5/2 CAFETERIA: 14 in 1.250ms from day5.txt"
+        );
+        assert_eq!(render("{answer}"), "14");
+        assert_eq!(render("no placeholders"), "no placeholders");
+        assert_eq!(render(""), "");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{day}} = {day}"), "{day} = 5");
+        assert_eq!(render("{{{answer}}}"), "{14}");
+        assert_eq!(render("}}{{"), "}{");
+    }
+
+    #[test]
+    fn bad_templates_name_the_problem() {
+        let err = "{day} {anwser}".parse::<Template>().unwrap_err();
+        assert!(err.starts_with("unknown placeholder {anwser}"), "{}", err);
+        assert!(err.contains("{duration_ms}"));
+        assert!("{}".parse::<Template>().unwrap_err().contains("unknown placeholder {}"));
+        assert!("{day".parse::<Template>().unwrap_err().starts_with("unclosed placeholder {day"));
+        assert!("day}".parse::<Template>().unwrap_err().starts_with("unmatched }"));
+    }
+}
@@ -0,0 +1,231 @@
+// Differential testing
+//
+// Naive reference implementations ("oracles") for days with optimised
+// solvers, and a harness that runs both on seeded random inputs and checks
+// they agree. A mismatch panics with the seed and a minimized input; set
+// ORACLE_SEED=<seed> to replay just that case.
+
+use std::collections::BTreeSet;
+
+use fastrand::Rng;
+
+use crate::options::DayOptions;
+use crate::output::{Output, Value};
+
+/// Random cases checked per day and part
+const CASES: u64 = 300;
+
+/// A generated input, kept as separate pieces (ranges for day 2, rows for
+/// day 4) so a failing case can be shrunk by dropping pieces
+pub struct Case {
+    pub parts: Vec<String>,
+    pub separator: &'static str,
+}
+
+impl Case {
+    fn render(&self) -> String {
+        self.parts.join(self.separator)
+    }
+}
+
+/// Builds a random input for one day from a seeded generator
+pub type Generator = fn(&mut Rng) -> Case;
+
+/// Naive answer for an input, computed the obvious way
+pub type Oracle = fn(&str, bool) -> u128;
+
+/// Compare the registered solver for `day` against `oracle` on `CASES`
+/// seeded inputs (or only ORACLE_SEED when it is set)
+pub fn differential(day: u8, part2: bool, generate: Generator, oracle: Oracle) {
+    let seeds = match std::env::var("ORACLE_SEED") {
+        Ok(seed) => {
+            let seed = seed.parse().expect("ORACLE_SEED must be a number");
+            seed..seed + 1
+        }
+        Err(_) => 0..CASES,
+    };
+
+    for seed in seeds {
+        let mut case = generate(&mut Rng::with_seed(seed));
+        if mismatch(day, part2, oracle, &case.render()).is_none() {
+            continue;
+        }
+
+        minimize(&mut case, |input| mismatch(day, part2, oracle, input).is_some());
+        let input = case.render();
+        let detail = mismatch(day, part2, oracle, &input).unwrap_or_default();
+        panic!(
+            "day {} part {} disagrees with the oracle (seed {}): {}\nminimized input:\n{}",
+            day,
+            if part2 { 2 } else { 1 },
+            seed,
+            detail,
+            input
+        );
+    }
+}
+
+/// Describe how the solver and oracle differ on `input`, if they do
+fn mismatch(day: u8, part2: bool, oracle: Oracle, input: &str) -> Option<String> {
+    let solver = crate::day(day).expect("day is not registered");
+    let expected = oracle(input, part2);
+    match (solver.solve)(input, part2, &DayOptions::default(), &mut Output::capture()) {
+        Ok(answer) if answer.value == Value::Number(expected) => None,
+        Ok(answer) => Some(format!("solver gave {}, oracle gave {}", answer.value, expected)),
+        Err(e) => Some(format!("solver failed ({}), oracle gave {}", e, expected)),
+    }
+}
+
+/// Greedily drop pieces while the case still fails
+fn minimize(case: &mut Case, fails: impl Fn(&str) -> bool) {
+    let mut i = 0;
+    while i < case.parts.len() && case.parts.len() > 1 {
+        let removed = case.parts.remove(i);
+        if fails(&case.render()) {
+            // Still failing without this piece; retry from the same index
+            continue;
+        }
+        case.parts.insert(i, removed);
+        i += 1;
+    }
+}
+
+// ============================================================================
+// DAY 2 ORACLE
+// ============================================================================
+
+/// 1-5 comma-separated ranges, up to 2000 IDs each, with starts of varying
+/// digit counts so both odd and even lengths show up
+pub fn day2_input(rng: &mut Rng) -> Case {
+    let parts = (0..rng.usize(1..=5))
+        .map(|_| {
+            let digits = rng.u32(1..=6);
+            let start = rng.u64(1..10u64.pow(digits));
+            let end = start + rng.u64(0..2000);
+            format!("{}-{}", start, end)
+        })
+        .collect();
+    Case { parts, separator: "," }
+}
+
+/// Visit every ID in the union of the ranges and test its digits directly
+pub fn day2_naive(input: &str, part2: bool) -> u128 {
+    let mut ids = BTreeSet::new();
+    for token in input.split(',') {
+        let (start, end) = token.trim().split_once('-').expect("generated range");
+        let start: u64 = start.parse().expect("generated start");
+        let end: u64 = end.parse().expect("generated end");
+        ids.extend(start..=end);
+    }
+
+    ids.into_iter()
+        .filter(|id| {
+            let s = id.to_string();
+            let len = s.len();
+            if part2 {
+                // Some shorter prefix repeated at least twice spells the whole ID
+                (1..len).any(|p| len % p == 0 && s[..p].repeat(len / p) == s)
+            } else {
+                len % 2 == 0 && s[..len / 2] == s[len / 2..]
+            }
+        })
+        .map(|id| id as u128)
+        .sum()
+}
+
+// ============================================================================
+// DAY 4 ORACLE
+// ============================================================================
+
+/// A rectangular grid of 1-12 rows and columns at a random roll density
+pub fn day4_input(rng: &mut Rng) -> Case {
+    let rows = rng.usize(1..=12);
+    let cols = rng.usize(1..=12);
+    let density = rng.u8(0..=100);
+    let parts = (0..rows)
+        .map(|_| {
+            (0..cols)
+                .map(|_| if rng.u8(0..100) < density { '@' } else { '.' })
+                .collect()
+        })
+        .collect();
+    Case { parts, separator: "\n" }
+}
+
+/// Part 1 checks each roll's neighbours; part 2 removes accessible rolls
+/// one at a time (rather than in batches) until none are left
+pub fn day4_naive(input: &str, part2: bool) -> u128 {
+    let mut grid: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+
+    let accessible = |grid: &[Vec<u8>], r: usize, c: usize| {
+        let mut neighbours = 0;
+        for nr in r.saturating_sub(1)..=r + 1 {
+            for nc in c.saturating_sub(1)..=c + 1 {
+                if (nr, nc) != (r, c) && grid.get(nr).and_then(|row| row.get(nc)) == Some(&b'@') {
+                    neighbours += 1;
+                }
+            }
+        }
+        grid[r][c] == b'@' && neighbours < 4
+    };
+
+    let cells: Vec<(usize, usize)> = (0..grid.len())
+        .flat_map(|r| (0..grid[r].len()).map(move |c| (r, c)))
+        .collect();
+
+    if !part2 {
+        return cells.iter().filter(|&&(r, c)| accessible(&grid, r, c)).count() as u128;
+    }
+
+    let mut removed = 0;
+    while let Some(&(r, c)) = cells.iter().find(|&&(r, c)| accessible(&grid, r, c)) {
+        grid[r][c] = b'.';
+        removed += 1;
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oracles_match_the_examples() {
+        let day2 = std::fs::read_to_string("tests/fixtures/day2_example.txt").unwrap();
+        assert_eq!(day2_naive(&day2, false), 1227775554);
+        assert_eq!(day2_naive(&day2, true), 4174379265);
+
+        let day4 = std::fs::read_to_string("tests/fixtures/day4_example.txt").unwrap();
+        assert_eq!(day4_naive(&day4, false), 13);
+        assert_eq!(day4_naive(&day4, true), 43);
+    }
+
+    #[test]
+    fn day2_matches_oracle() {
+        differential(2, false, day2_input, day2_naive);
+        differential(2, true, day2_input, day2_naive);
+    }
+
+    #[test]
+    fn day4_matches_oracle() {
+        differential(4, false, day4_input, day4_naive);
+        differential(4, true, day4_input, day4_naive);
+    }
+
+    #[test]
+    #[should_panic(expected = "minimized input:")]
+    fn mismatches_report_a_minimized_input() {
+        // Wrong on purpose: claims every input has an answer of 1
+        differential(2, false, day2_input, |_, _| 1);
+    }
+
+    #[test]
+    fn minimize_keeps_only_the_failing_piece() {
+        let mut case = Case {
+            parts: vec!["1-2".into(), "bad".into(), "3-4".into()],
+            separator: ",",
+        };
+        minimize(&mut case, |input| input.contains("bad"));
+        assert_eq!(case.render(), "bad");
+    }
+}
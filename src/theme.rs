@@ -0,0 +1,93 @@
+// The TUI's colors
+//
+// The picker's own colors (header, panes, footer, part selection and the
+// settings screen) come from the current Theme, picked by name with `theme`
+// in adventcode.toml or on the settings screen, where a change shows at once. "default" is the original cyan and green on a dark
+// terminal, "light" keeps to colors that read on a light background,
+// "mono" leaves nearly everything in the terminal's own foreground, and
+// "high-contrast" keeps to bright white and yellow on black. Accessible mode
+// always uses "high-contrast".
+
+use ratatui::style::Color;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Titles, labels and the outer frame
+    pub accent: Color,
+    /// The panes' borders and the good news
+    pub frame: Color,
+    /// The selection, key hints and the focused pane
+    pub highlight: Color,
+    /// Hints and secondary text
+    pub muted: Color,
+    /// Full-screen views and pickers
+    pub special: Color,
+    /// Behind the selection
+    pub selection: Color,
+    /// Plain text in the part picker
+    pub text: Color,
+}
+
+pub const THEMES: [Theme; 4] = [
+    Theme {
+        name: "default",
+        accent: Color::Cyan,
+        frame: Color::Green,
+        highlight: Color::Yellow,
+        muted: Color::Gray,
+        special: Color::Magenta,
+        selection: Color::DarkGray,
+        text: Color::White,
+    },
+    Theme {
+        name: "light",
+        accent: Color::Blue,
+        frame: Color::Green,
+        highlight: Color::Red,
+        muted: Color::DarkGray,
+        special: Color::Magenta,
+        selection: Color::Gray,
+        text: Color::Black,
+    },
+    Theme {
+        name: "mono",
+        accent: Color::Reset,
+        frame: Color::Reset,
+        highlight: Color::Reset,
+        muted: Color::Reset,
+        special: Color::Reset,
+        selection: Color::DarkGray,
+        text: Color::Reset,
+    },
+    Theme {
+        name: "high-contrast",
+        accent: Color::White,
+        frame: Color::White,
+        highlight: Color::LightYellow,
+        muted: Color::White,
+        special: Color::White,
+        selection: Color::Black,
+        text: Color::White,
+    },
+];
+
+/// The theme called `name`
+pub fn named(name: &str) -> Option<&'static Theme> {
+    THEMES.iter().find(|theme| theme.name == name)
+}
+
+/// The theme used when none is chosen
+pub fn default() -> &'static Theme {
+    &THEMES[0]
+}
+
+/// The theme accessible mode forces
+pub fn high_contrast() -> &'static Theme {
+    &THEMES[3]
+}
+
+/// "default, light, mono, high-contrast"
+pub fn names() -> String {
+    THEMES.iter().map(|theme| theme.name).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,139 @@
+// TUI timings screen
+//
+// A horizontal bar chart of how long each day's parts took, part 1 and
+// part 2 side by side under their day. Each bar is the latest time for its
+// day and part: runs from this session win over the run history on disk.
+// Bars scale to the slowest one; the selected bar is highlighted so Enter
+// can jump to it.
+
+use std::collections::BTreeMap;
+
+use ratatui::{
+    layout::{Direction, Rect},
+    style::{Color, Modifier, Style},
+    symbols::bar,
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Paragraph, Wrap},
+    Frame,
+};
+
+use adventcode::history::{self, Record};
+
+/// Bars drawn with # for ASCII mode
+const ASCII_BAR: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: "#",
+    half: "#",
+    three_eighths: "#",
+    one_quarter: "#",
+    one_eighth: "#",
+    empty: " ",
+};
+
+/// Shown when nothing has been timed yet
+const EMPTY_HINT: &str = "No timings yet. Press 1 or 2 on a day to solve it in the background, \
+                          or run adventcode --day N; finished runs show up here.";
+
+/// The latest time for one day and part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub day: u8,
+    pub part2: bool,
+    pub millis: f64,
+}
+
+/// One timing per day and part, sorted by day then part: the last of
+/// `session` for it, else the last successful run in `recorded`
+pub fn collect(recorded: &[Record], session: impl IntoIterator<Item = Timing>) -> Vec<Timing> {
+    let mut latest = BTreeMap::new();
+    for record in recorded.iter().filter(|record| record.error.is_none()) {
+        latest.insert((record.day, record.part == 2), record.millis);
+    }
+    for timing in session {
+        latest.insert((timing.day, timing.part2), timing.millis);
+    }
+    latest.into_iter().map(|((day, part2), millis)| Timing { day, part2, millis }).collect()
+}
+
+/// Draw `timings` into `area`, highlighting the bar at `selected`; with a
+/// `marker` (accessible mode) its label starts with that too
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    timings: &[Timing],
+    selected: usize,
+    ascii: bool,
+    marker: Option<&str>,
+    block: Block<'static>,
+) {
+    if timings.is_empty() {
+        let hint = Paragraph::new(EMPTY_HINT).style(Style::default().fg(Color::Gray)).wrap(Wrap { trim: true });
+        f.render_widget(hint.block(block), area);
+        return;
+    }
+
+    // A day takes a row per bar plus one for its label; start late enough
+    // that the selected bar is on screen
+    let groups = group(timings);
+    let rows = |groups: &[(u8, Vec<(usize, &Timing)>)]| groups.iter().map(|(_, bars)| bars.len() + 1).sum::<usize>();
+    let height = area.height.saturating_sub(2) as usize;
+    let selected_group = groups.iter().position(|(_, bars)| bars.iter().any(|(i, _)| *i == selected)).unwrap_or(0);
+    let mut first = 0;
+    while first < selected_group && rows(&groups[first..=selected_group]) > height {
+        first += 1;
+    }
+
+    let mut chart = BarChart::default()
+        .block(block)
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .group_gap(1)
+        // Bars scale to the slowest entry, even one scrolled out of view
+        .max(timings.iter().map(|t| micros(t.millis)).max().unwrap_or(1))
+        .bar_set(if ascii { ASCII_BAR } else { bar::NINE_LEVELS });
+    for (day, bars) in &groups[first..] {
+        let bars: Vec<Bar> = bars
+            .iter()
+            .map(|(i, timing)| {
+                let style = if *i == selected {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                let part = if timing.part2 { "Part 2" } else { "Part 1" };
+                let label = match marker.filter(|_| *i == selected) {
+                    Some(marker) => format!("{}{}", marker, part),
+                    None => part.to_string(),
+                };
+                Bar::default()
+                    .label(Line::from(label))
+                    .value(micros(timing.millis))
+                    .text_value(history::millis(timing.millis))
+                    .style(style)
+                    .value_style(style.add_modifier(Modifier::REVERSED))
+            })
+            .collect();
+        chart = chart.data(BarGroup::default().label(Line::from(format!("Day {}", day))).bars(&bars));
+    }
+    f.render_widget(chart, area);
+}
+
+/// Timings grouped by day, each with its index into `timings`
+fn group(timings: &[Timing]) -> Vec<(u8, Vec<(usize, &Timing)>)> {
+    let mut groups: Vec<(u8, Vec<(usize, &Timing)>)> = Vec::new();
+    for (i, timing) in timings.iter().enumerate() {
+        match groups.last_mut() {
+            Some((day, bars)) if *day == timing.day => bars.push((i, timing)),
+            _ => groups.push((timing.day, vec![(i, timing)])),
+        }
+    }
+    groups
+}
+
+/// Bar length in microseconds, so sub-millisecond runs still get a bar
+fn micros(millis: f64) -> u64 {
+    ((millis * 1000.0).round() as u64).max(1)
+}
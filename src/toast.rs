@@ -0,0 +1,103 @@
+// TUI toasts
+//
+// Short notices stacked in the top-right corner, such as "Day 2 Part 2
+// finished: 123456 (41.2 ms)". The TUI's tick loop expires them after a few
+// seconds. They are drawn over whatever screen is showing and never take the
+// keyboard. Only the newest few are kept; a new one pushes out the oldest.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+/// How long a toast stays up unless the App says otherwise
+pub const DEFAULT_TTL: Duration = Duration::from_secs(4);
+
+/// Toasts on screen at once
+pub const MAX: usize = 4;
+
+/// Widest a toast gets, borders included
+const WIDTH: u16 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub kind: Kind,
+    /// The day it is about, for jumping there
+    pub day: Option<u8>,
+    shown: Instant,
+}
+
+impl Toast {
+    pub fn new(message: String, kind: Kind, day: Option<u8>, now: Instant) -> Self {
+        Self { message, kind, day, shown: now }
+    }
+}
+
+/// The queue of toasts on screen, oldest first
+#[derive(Debug)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+    ttl: Duration,
+}
+
+impl Toasts {
+    pub fn new(ttl: Duration) -> Self {
+        Self { queue: VecDeque::new(), ttl }
+    }
+
+    pub fn push(&mut self, toast: Toast) {
+        if self.queue.len() == MAX {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(toast);
+    }
+
+    /// Drop every toast that has been up for the whole ttl at `now`
+    pub fn expire(&mut self, now: Instant) {
+        let ttl = self.ttl;
+        self.queue.retain(|toast| now.saturating_duration_since(toast.shown) < ttl);
+    }
+
+    pub fn newest(&self) -> Option<&Toast> {
+        self.queue.back()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Draw the toasts down from the top-right corner of `area`, each in a
+    /// copy of `block`
+    pub fn render(&self, f: &mut Frame, area: Rect, block: &Block<'static>) {
+        let width = WIDTH.min(area.width);
+        let mut y = area.y;
+        for toast in &self.queue {
+            if y + 3 > area.bottom() {
+                break;
+            }
+            let rect = Rect { x: area.right() - width, y, width, height: 3 };
+            let color = match toast.kind {
+                Kind::Info => Color::Green,
+                Kind::Error => Color::Red,
+            };
+            f.render_widget(Clear, rect);
+            f.render_widget(
+                Paragraph::new(toast.message.as_str()).block(block.clone().border_style(Style::default().fg(color))),
+                rect,
+            );
+            y += 3;
+        }
+    }
+}
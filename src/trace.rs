@@ -0,0 +1,208 @@
+// --trace-json: run timelines for chrome://tracing and Perfetto
+//
+// The run and the days mark their phases with `tracing` spans: reading the
+// input, each part's solve, the days' parsing, and day 4's removal rounds.
+// Nothing listens to them normally. With --trace-json a Recorder's layer
+// goes into the global subscriber (beside --verbose's, see verbose.rs). It
+// notes when each span is entered
+// and exited, on whichever thread, and at exit writes them as "complete"
+// events ("ph": "X") in the Trace Event Format: a JSON array with
+// timestamps and durations in microseconds since the run started.
+
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+use crate::verbose::VerboseLayer;
+
+/// One span, as a Trace Event Format "complete" event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Event {
+    pub name: String,
+    pub cat: &'static str,
+    pub ph: &'static str,
+    /// Start and length, in microseconds
+    pub ts: f64,
+    pub dur: f64,
+    pub pid: u32,
+    pub tid: u64,
+    /// The span's fields, such as a round number
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub args: Map<String, Value>,
+}
+
+/// The events recorded so far; clones share them
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+/// Set the global subscriber for the run: `recorder` records every span
+/// from here on, on every thread, and `verbose` prints the events; with
+/// neither, nothing is set and the spans and events cost next to nothing
+pub fn install(recorder: Option<&Recorder>, verbose: Option<VerboseLayer>) {
+    if recorder.is_none() && verbose.is_none() {
+        return;
+    }
+    let subscriber = Registry::default().with(recorder.map(Recorder::layer)).with(verbose);
+    // This only fails when a global subscriber is already set, and nothing
+    // else in the program sets one
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+impl Recorder {
+    /// The layer that fills this recorder, for a subscriber of one's own
+    pub fn layer(&self) -> SpanLayer {
+        SpanLayer { start: Instant::now(), events: Arc::clone(&self.events) }
+    }
+
+    /// The events recorded so far, in the order their spans closed
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Write the events to `path` as a JSON array
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.events()).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+}
+
+/// Collects a Recorder's events from the spans it sees
+pub struct SpanLayer {
+    start: Instant,
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+/// What the layer keeps on each span while it is open
+struct Open {
+    args: Map<String, Value>,
+    entered: Option<Instant>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut args = Args(Map::new());
+        attrs.record(&mut args);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Open { args: args.0, entered: None });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(open) = span.extensions_mut().get_mut::<Open>()
+        {
+            open.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let now = Instant::now();
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(open) = extensions.get_mut::<Open>() else {
+            return;
+        };
+        let Some(entered) = open.entered.take() else {
+            return;
+        };
+        let event = Event {
+            name: span.name().to_string(),
+            cat: "adventcode",
+            ph: "X",
+            ts: micros(entered.saturating_duration_since(self.start)),
+            dur: micros(now - entered),
+            pid: std::process::id(),
+            tid: thread_number(),
+            args: open.args.clone(),
+        };
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+    }
+}
+
+fn micros(duration: std::time::Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+/// A small number for the current thread, 1 for the first one seen; the
+/// viewers draw one track per number
+fn thread_number() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static NUMBER: Cell<u64> = const { Cell::new(0) };
+    }
+    NUMBER.with(|number| {
+        if number.get() == 0 {
+            number.set(NEXT.fetch_add(1, Ordering::Relaxed));
+        }
+        number.get()
+    })
+}
+
+/// A span's fields as JSON values
+struct Args(Map<String, Value>);
+
+impl Visit for Args {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_spans_become_complete_events() {
+        let recorder = Recorder::default();
+        let subscriber = Registry::default().with(recorder.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let _outer = tracing::info_span!("solve part 2", day = 4u8).entered();
+            for round in 1..=2u64 {
+                let _round = tracing::info_span!("round", round).entered();
+            }
+        });
+
+        let events = recorder.events();
+        let names: Vec<&str> = events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["round", "round", "solve part 2"]);
+        let outer = &events[2];
+        assert_eq!(outer.args["day"], 4);
+        assert_eq!(events[1].args["round"], 2);
+        for round in &events[..2] {
+            assert!(round.ts >= outer.ts && round.ts + round.dur <= outer.ts + outer.dur);
+            assert_eq!((round.ph, round.tid), ("X", outer.tid));
+        }
+    }
+}
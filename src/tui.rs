@@ -0,0 +1,2090 @@
+// Interactive day picker
+//
+// The ratatui front end shown when no --day is given. It only picks a day
+// and part; main.rs runs the selection. Built with the default `tui`
+// feature, so CLI-only builds skip ratatui and crossterm entirely.
+//
+// Beside the day list sit the day's details and a preview of its input.
+// Tab moves focus to the preview, where E opens the input in a small editor
+// (editor.rs); saving writes dayN.txt, so the run picked afterwards sees the
+// new text.
+//
+// 1 and 2 solve the selected day's part in a background thread instead, so
+// browsing carries on; the tick loop collects finished runs into the details
+// pane and announces them as toasts (toast.rs). t charts the times of
+// every part run so far (timings.rs), and v draws the selected day's input
+// where the day has a view for it (day 2's number line, numberline.rs; day
+// 3's battery picks, batteries.rs).
+//
+// n opens the selected day's note (notes.rs) in the same editor; its first
+// line shows in the details, and saving it blank deletes it.
+//
+// w watches the selected day's input (watch.rs) until another day is
+// selected: a change is sent down the same channel as finished runs, and
+// the tick loop reruns the parts whose results the details pane shows.
+//
+// A Guard puts the terminal back however the TUI ends: on return, on an
+// error, or on a panic, whose message then prints on a usable screen.
+//
+// The header counts the season's progress: days with a solver, inputs on
+// disk, and days whose answers.toml has both parts. The counts are worked out
+// when the TUI starts and again whenever a background run finishes or an
+// input is saved, so files fetched or answers written meanwhile show up.
+//
+// The day list takes 60% of the width beside the details; < and > move the
+// split in 5% steps and 0 puts it back, and the choice is kept in the TUI
+// state file (state.rs). Below NARROW_WIDTH columns only the focused pane is
+// drawn, and Tab switches between them.
+//
+// S opens the settings screen (settings.rs), whose theme (theme.rs), key
+// bindings and answers file take effect as soon as they are applied.
+//
+// Decorations come from the Glyphs set, borders included, so ASCII mode
+// draws nothing outside ASCII. Terminals without an alternate screen get the
+// picker drawn inline instead.
+//
+// Accessible mode (the WORDS glyph set) is meant for screen readers: the
+// selection, the focused pane and errors are marked in words as well as in
+// color, the theme is high-contrast, and notices become a status line under
+// the key hints that stays until the next one instead of toasts popping up
+// over the screen and vanishing.
+
+use crossterm::{
+    cursor::Show,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal, TerminalOptions, Viewport,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use adventcode::embedded;
+use adventcode::history::{self, Record};
+use adventcode::input::{self, InputReader, InputText};
+use adventcode::options::DayOptions;
+use adventcode::output::Output;
+
+use crate::batteries::{self, BatteryView};
+use crate::doctor;
+use crate::editor::Editor;
+use crate::external;
+use crate::glyphs::Glyphs;
+use crate::notes;
+use crate::numberline::{self, NumberLine};
+use crate::settings::{self, Keys, Settings};
+use crate::state::State;
+use crate::theme::{self, Theme};
+use crate::timings::{self, Timing};
+use crate::toast::{self, Kind, Toast, Toasts};
+use crate::watch::{self, Watcher};
+use crate::DayInfo;
+
+/// Borders drawn with + - | for ASCII mode
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Rows used by the picker when it has to draw inline
+const INLINE_HEIGHT: u16 = 24;
+
+/// Below this many columns the day screen shows one pane at a time
+const NARROW_WIDTH: u16 = 80;
+
+/// Lines of an input kept for its preview
+const PREVIEW_LINES: usize = 200;
+
+/// How often the event loop wakes without a key, to collect finished runs
+/// and expire toasts
+const TICK: Duration = Duration::from_millis(100);
+
+/// Which pane of the day screen takes the keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Days,
+    Preview,
+}
+
+/// A day's view of its input (v)
+enum Visualization {
+    /// Day 2's ranges on a number line
+    NumberLine(NumberLine),
+    /// Day 3's banks with their picked batteries
+    Batteries(BatteryView),
+}
+
+/// The start of an input file and its size, read once per day
+struct Preview {
+    lines: Vec<String>,
+    line_count: usize,
+    bytes: u64,
+}
+
+impl Preview {
+    /// None when the file is missing or unreadable
+    fn load(day: &DayInfo) -> Option<Self> {
+        let bytes = fs::metadata(&day.input_path).ok()?.len();
+        let mut reader = InputReader::open(&day.input_path).ok()?;
+        let mut lines = Vec::new();
+        let mut line_count = 0;
+        for line in reader.lines() {
+            let line = line.ok()?;
+            if lines.len() < PREVIEW_LINES {
+                lines.push(line);
+            }
+            line_count += 1;
+        }
+        Some(Self { lines, line_count, bytes })
+    }
+}
+
+/// A background solve that has finished
+#[derive(Debug, Clone)]
+struct Finished {
+    day: u8,
+    part2: bool,
+    /// The answer's value and the solve time in ms, or what went wrong
+    result: Result<(String, f64), String>,
+}
+
+impl Finished {
+    /// e.g. "Day 2 Part 2 finished: 123456 (41.2 ms)"
+    fn message(&self) -> String {
+        let part = if self.part2 { 2 } else { 1 };
+        match &self.result {
+            Ok((value, millis)) => format!("Day {} Part {} finished: {} ({})", self.day, part, value, history::millis(*millis)),
+            Err(e) => format!("Day {} Part {} failed: {}", self.day, part, e),
+        }
+    }
+}
+
+/// What background threads tell the tick loop, over one channel
+#[derive(Debug)]
+enum Background {
+    Finished(Finished),
+    /// The watched input of this day changed (w)
+    InputChanged(u8),
+}
+
+/// A day's input as a plain run would read it (the file, else the embedded
+/// copy), normalized
+fn load_input(day: &DayInfo) -> Result<String, String> {
+    let text = if day.input_path.exists() {
+        input::load_file(&day.input_path, Some(input::DEFAULT_LIMIT_MB)).map_err(|e| e.to_string())?
+    } else if let Some(text) = embedded::input(day.number) {
+        InputText::Owned(text.to_string())
+    } else {
+        return Err(format!("no input file ({})", day.input_path.display()));
+    };
+    Ok(input::normalize(text.as_str()).into_owned())
+}
+
+/// Solve one part the way a plain run would, without printing anything,
+/// and record it in the run history at `history`
+fn solve(day: &DayInfo, part2: bool, history: Option<&Path>) -> Result<(String, f64), String> {
+    if !day.implemented() {
+        return Err("not implemented yet".to_string());
+    }
+    let input = load_input(day)?;
+
+    let start = Instant::now();
+    let answer = match (&day.external, crate::find_solver(day.number)) {
+        (Some(command), _) => external::solve(command, part2, &input, None)?,
+        (None, Some(solver)) => panic::catch_unwind(AssertUnwindSafe(|| {
+            (solver.solve)(&input, part2, &DayOptions::default(), &mut Output::capture())
+        }))
+        .map_err(|payload| format!("panicked: {}", crate::panic_message(payload.as_ref())))?
+        .map_err(|e| e.to_string())?,
+        (None, None) => unreachable!("checked above"),
+    };
+    let millis = start.elapsed().as_secs_f64() * 1000.0;
+
+    let value = answer.value.to_string();
+    if let Some(path) = history {
+        // A history that cannot be written is no reason to lose the answer
+        let _ = history::append(path, &Record::now(day.number, part2, &input, &value, millis));
+    }
+    Ok((value, millis))
+}
+
+/// What a key press asks of the event loop
+#[derive(Debug)]
+enum Step {
+    Continue,
+    Quit,
+    Run(Box<DayInfo>, bool),
+}
+
+struct App {
+    days: Vec<DayInfo>,
+    glyphs: &'static Glyphs,
+    selected_day: ListState,
+    selected_part: usize, // 0 for Part 1, 1 for Part 2
+    in_part_selection: bool,
+    focus: Focus,
+    /// Input previews by day number, dropped when the file is saved
+    previews: HashMap<u8, Option<Preview>>,
+    preview_scroll: u16,
+    /// Edit mode, when open
+    editor: Option<Editor>,
+    /// Esc was pressed with unsaved edits; waiting for y or n
+    confirm_discard: bool,
+    /// The last save's outcome (or why the editor would not open)
+    status: Option<String>,
+    /// Where background solves are recorded; None without a data directory
+    history: Option<PathBuf>,
+    /// Background solves still going, as (day, part2)
+    running: Vec<(u8, bool)>,
+    /// Background solves finished this session, oldest first
+    results: Vec<Finished>,
+    /// Finished solves and input changes, from their threads
+    events_tx: Sender<Background>,
+    events_rx: Receiver<Background>,
+    /// The day whose input is being watched, and its watcher (w)
+    watching: Option<(u8, Watcher)>,
+    toasts: Toasts,
+    /// The timings screen is up
+    show_timings: bool,
+    /// Index of the highlighted bar on the timings screen
+    selected_timing: usize,
+    /// The run history on disk, read when the timings screen opens
+    recorded: Vec<Record>,
+    /// The selected day's visualization, when open (v)
+    visualization: Option<Visualization>,
+    /// What is kept between sessions, and where; None saves nothing
+    state: State,
+    state_path: Option<PathBuf>,
+    /// The terminal is narrower than NARROW_WIDTH, as of its last resize
+    narrow: bool,
+    /// answers.toml, and the days it has both parts for
+    answers_path: Option<PathBuf>,
+    answered: Vec<u8>,
+    /// Where notes are kept (None: no data directory), and the first line
+    /// of each day's note
+    notes_dir: Option<PathBuf>,
+    notes: HashMap<u8, String>,
+    /// The colors and key bindings in use, as the settings say
+    theme: &'static Theme,
+    keys: Keys,
+    /// The settings screen's state; None leaves S doing nothing
+    settings: Option<Settings>,
+    /// The settings screen is up
+    show_settings: bool,
+}
+
+impl App {
+    fn new(days: Vec<DayInfo>, glyphs: &'static Glyphs) -> Self {
+        let mut selected_day = ListState::default();
+        if !days.is_empty() {
+            selected_day.select(Some(0));
+        }
+        let (events_tx, events_rx) = mpsc::channel();
+        Self {
+            days,
+            glyphs,
+            selected_day,
+            selected_part: 0,
+            in_part_selection: false,
+            focus: Focus::Days,
+            previews: HashMap::new(),
+            preview_scroll: 0,
+            editor: None,
+            confirm_discard: false,
+            status: None,
+            history: None,
+            running: Vec::new(),
+            results: Vec::new(),
+            events_tx,
+            events_rx,
+            watching: None,
+            // Accessible mode keeps the last notice in the status line
+            toasts: Toasts::new(if glyphs.words { Duration::MAX } else { toast::DEFAULT_TTL }),
+            show_timings: false,
+            selected_timing: 0,
+            recorded: Vec::new(),
+            visualization: None,
+            state: State::default(),
+            state_path: None,
+            narrow: false,
+            answers_path: None,
+            answered: Vec::new(),
+            notes_dir: None,
+            notes: HashMap::new(),
+            theme: theme::default(),
+            keys: Keys::Default,
+            settings: None,
+            show_settings: false,
+        }
+    }
+
+    fn next_day(&mut self) {
+        let i = match self.selected_day.selected() {
+            Some(i) => {
+                if i >= self.days.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.selected_day.select(Some(i));
+        self.preview_scroll = 0;
+        self.status = None;
+    }
+
+    fn previous_day(&mut self) {
+        let i = match self.selected_day.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.days.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.selected_day.select(Some(i));
+        self.preview_scroll = 0;
+        self.status = None;
+    }
+
+    fn toggle_part(&mut self) {
+        self.selected_part = 1 - self.selected_part;
+    }
+
+    fn get_selected_day(&self) -> Option<&DayInfo> {
+        self.selected_day.selected().and_then(|i| self.days.get(i))
+    }
+
+    /// The selected day's input preview, read on first use
+    fn preview(&mut self) -> Option<&Preview> {
+        let day = self.selected_day.selected().and_then(|i| self.days.get(i))?;
+        self.previews.entry(day.number).or_insert_with(|| Preview::load(day)).as_ref()
+    }
+
+    /// Check again which inputs exist and which days answers.toml covers
+    fn refresh_progress(&mut self) {
+        for day in &mut self.days {
+            day.has_input = day.input_path.exists();
+        }
+        self.answered = self.answers_path.as_deref().map(doctor::answered_days).unwrap_or_default();
+    }
+
+    /// The header's progress line, e.g. "5/25 days implemented · 4 inputs
+    /// present · 3 days verified"
+    fn progress(&self) -> Line<'static> {
+        let implemented = self.days.iter().filter(|day| day.implemented()).count();
+        let inputs = self.days.iter().filter(|day| day.has_input).count();
+        let verified = self.days.iter().filter(|day| self.answered.contains(&day.number)).count();
+        let count = |n: usize, noun: &str| format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" });
+        let dot = Span::styled(format!(" {} ", self.glyphs.dot), Style::default().fg(self.theme.muted));
+        Line::from(vec![
+            Span::styled(format!("{}/25 days implemented", implemented), Style::default().fg(self.theme.accent)),
+            dot.clone(),
+            Span::styled(format!("{} present", count(inputs, "input")), Style::default().fg(self.theme.highlight)),
+            dot,
+            Span::styled(format!("{} verified", count(verified, "day")), Style::default().fg(self.theme.frame)),
+        ])
+        .alignment(Alignment::Center)
+    }
+
+    /// Follow a change in the terminal's width
+    fn on_resize(&mut self, width: u16) {
+        self.narrow = width < NARROW_WIDTH;
+    }
+
+    /// Move the split between the day list and the details by `steps` (or
+    /// back to the default with None), and save it
+    fn set_split(&mut self, steps: Option<i16>, now: Instant) {
+        match steps {
+            Some(steps) => self.state.nudge_split(steps),
+            None => self.state = State::default(),
+        }
+        if let Some(path) = &self.state_path
+            && let Err(e) = self.state.save(path)
+        {
+            self.toasts.push(Toast::new(format!("Cannot save the layout: {}", e), Kind::Error, None, now));
+        }
+    }
+
+    /// The day list's and the details' areas within `area`, from the saved
+    /// split; on a narrow terminal only the focused one gets an area
+    fn panes(&self, area: Rect) -> (Option<Rect>, Option<Rect>) {
+        if self.narrow {
+            return match self.focus {
+                Focus::Days => (Some(area), None),
+                Focus::Preview => (None, Some(area)),
+            };
+        }
+        let split = self.state.split;
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(split), Constraint::Percentage(100 - split)])
+            .split(area);
+        (Some(chunks[0]), Some(chunks[1]))
+    }
+
+    /// Open the selected day's input in the editor
+    fn start_editing(&mut self) {
+        let Some(day) = self.get_selected_day() else {
+            return;
+        };
+        match Editor::open(&day.input_path) {
+            Ok(editor) => {
+                self.editor = Some(editor);
+                self.status = None;
+            }
+            Err(e) => self.status = Some(format!("Cannot edit: {}", e)),
+        }
+    }
+
+    /// Open the selected day's note in the editor
+    fn start_note(&mut self) {
+        let Some(day) = self.get_selected_day().map(|day| day.number) else {
+            return;
+        };
+        let Some(dir) = self.notes_dir.clone() else {
+            self.status = Some("Cannot keep notes: no data directory".to_string());
+            return;
+        };
+        match fs::create_dir_all(&dir).and_then(|()| Editor::open(&notes::path(&dir, day))) {
+            Ok(editor) => {
+                self.editor = Some(editor);
+                self.status = None;
+            }
+            Err(e) => self.status = Some(format!("Cannot edit the note: {}", e)),
+        }
+    }
+
+    /// Read the first line of every day's note again
+    fn refresh_notes(&mut self) {
+        let Some(dir) = &self.notes_dir else {
+            return;
+        };
+        self.notes = self
+            .days
+            .iter()
+            .filter_map(|day| {
+                let note = notes::read(dir, day.number).ok().flatten()?;
+                Some((day.number, notes::first_line(&note).to_string()))
+            })
+            .collect();
+    }
+
+    /// Write the editor's buffer, then refresh what the day screen knows
+    /// about the file
+    fn save(&mut self) {
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+        let path = editor.path().to_path_buf();
+        let note = self.notes_dir.as_deref().and_then(|dir| Some((dir, notes::day_of(dir, &path)?)));
+        self.status = Some(match (note, editor.save()) {
+            (_, Err(e)) => format!("Save failed: {}", e),
+            (Some((dir, day)), Ok(_)) if editor.contents().trim().is_empty() => match notes::delete(dir, day) {
+                Ok(_) => format!("Deleted the note for Day {}", day),
+                Err(e) => format!("Cannot delete the note: {}", e),
+            },
+            (_, Ok(bytes)) => format!("Saved {} ({} bytes)", path.display(), bytes),
+        });
+        if note.is_some() {
+            self.refresh_notes();
+        }
+        for day in self.days.iter().filter(|day| day.input_path == path) {
+            self.previews.remove(&day.number);
+        }
+        self.refresh_progress();
+    }
+
+    /// Start on `day`'s part selection with `part2` chosen (`--tui --day N`);
+    /// a day that cannot run leaves the list as it is and says why in a toast
+    fn preselect(&mut self, day: u8, part2: bool, now: Instant) {
+        self.selected_part = usize::from(part2);
+        let listed = self.days.iter().position(|info| info.number == day);
+        if let Some(i) = listed {
+            self.selected_day.select(Some(i));
+        }
+        if listed.is_some_and(|i| self.days[i].implemented()) {
+            self.in_part_selection = true;
+        } else {
+            let message = format!("Day {} is not implemented yet", day);
+            self.toasts.push(Toast::new(message, Kind::Error, listed.map(|_| day), now));
+        }
+    }
+
+    /// Solve a part of the selected day on a thread of its own
+    fn run_in_background(&mut self, part2: bool) {
+        if let Some(day) = self.get_selected_day().cloned() {
+            self.start_run(day, part2);
+        }
+    }
+
+    fn start_run(&mut self, day: DayInfo, part2: bool) {
+        if self.running.contains(&(day.number, part2)) {
+            return;
+        }
+        self.running.push((day.number, part2));
+        let (tx, history) = (self.events_tx.clone(), self.history.clone());
+        thread::spawn(move || {
+            let result = solve(&day, part2, history.as_deref());
+            // The TUI may have quit in the meantime; nobody is left to tell
+            let _ = tx.send(Background::Finished(Finished { day: day.number, part2, result }));
+        });
+    }
+
+    /// Start or stop watching the selected day's input file
+    fn toggle_watch(&mut self, now: Instant) {
+        let Some((number, path)) = self.get_selected_day().map(|day| (day.number, day.input_path.clone())) else {
+            return;
+        };
+        if self.watching.take().is_some_and(|(watched, _)| watched == number) {
+            self.toasts.push(Toast::new(format!("Stopped watching Day {}'s input", number), Kind::Info, Some(number), now));
+            return;
+        }
+        let tx = self.events_tx.clone();
+        let message = format!("Watching {} for changes", path.display());
+        let watcher = Watcher::spawn(path, watch::DEFAULT_DEBOUNCE, move || {
+            let _ = tx.send(Background::InputChanged(number));
+        });
+        self.watching = Some((number, watcher));
+        self.toasts.push(Toast::new(message, Kind::Info, Some(number), now));
+    }
+
+    /// A watch only lasts while its day stays selected
+    fn unwatch_if_moved(&mut self) {
+        let selected = self.get_selected_day().map(|day| day.number);
+        if self.watching.as_ref().is_some_and(|(watched, _)| Some(*watched) != selected) {
+            self.watching = None;
+        }
+    }
+
+    /// The watched input changed: drop the stale preview, and run again the
+    /// parts whose results are on show
+    fn on_input_changed(&mut self, number: u8, now: Instant) {
+        self.previews.remove(&number);
+        self.toasts.push(Toast::new(format!("Day {}'s input changed", number), Kind::Info, Some(number), now));
+        let Some(day) = self.days.iter().find(|day| day.number == number).cloned() else {
+            return;
+        };
+        for part2 in [false, true] {
+            if self.results.iter().any(|r| r.day == number && r.part2 == part2) {
+                self.start_run(day.clone(), part2);
+            }
+        }
+    }
+
+    /// Collect finished runs and input changes, and expire toasts (once per
+    /// tick)
+    fn tick(&mut self, now: Instant) {
+        let mut any = false;
+        while let Ok(event) = self.events_rx.try_recv() {
+            match event {
+                Background::Finished(finished) => {
+                    self.running.retain(|&run| run != (finished.day, finished.part2));
+                    let kind = if finished.result.is_ok() { Kind::Info } else { Kind::Error };
+                    self.toasts.push(Toast::new(finished.message(), kind, Some(finished.day), now));
+                    self.results.push(finished);
+                }
+                Background::InputChanged(number) => self.on_input_changed(number, now),
+            }
+            any = true;
+        }
+        if any {
+            self.refresh_progress();
+        }
+        self.toasts.expire(now);
+    }
+
+    /// Select the day the newest toast is about
+    fn jump_to_toast(&mut self) {
+        let Some(number) = self.toasts.newest().and_then(|toast| toast.day) else {
+            return;
+        };
+        if let Some(i) = self.days.iter().position(|day| day.number == number) {
+            self.selected_day.select(Some(i));
+            self.preview_scroll = 0;
+            self.in_part_selection = false;
+            self.focus = Focus::Days;
+        }
+    }
+
+    /// Every part timed so far, this session's runs over the history's
+    fn timings(&self) -> Vec<Timing> {
+        let session = self.results.iter().filter_map(|r| {
+            let (_, millis) = r.result.as_ref().ok()?;
+            Some(Timing { day: r.day, part2: r.part2, millis: *millis })
+        });
+        let mut timings = timings::collect(&self.recorded, session);
+        // Only days in the list can be jumped to
+        timings.retain(|t| self.days.iter().any(|day| day.number == t.day));
+        timings
+    }
+
+    fn open_timings(&mut self) {
+        self.recorded = self.history.as_deref().map(history::load).unwrap_or_default();
+        self.selected_timing = 0;
+        self.show_timings = true;
+    }
+
+    fn on_timings_key(&mut self, key: KeyEvent) -> Step {
+        let count = self.timings().len();
+        match key.code {
+            KeyCode::Char('q') => return Step::Quit,
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('t') => self.show_timings = false,
+            KeyCode::Down | KeyCode::Char('j') if self.selected_timing + 1 < count => self.selected_timing += 1,
+            KeyCode::Up | KeyCode::Char('k') => self.selected_timing = self.selected_timing.saturating_sub(1),
+            KeyCode::Enter => {
+                let Some(timing) = self.timings().get(self.selected_timing).copied() else {
+                    return Step::Continue;
+                };
+                if let Some(i) = self.days.iter().position(|day| day.number == timing.day) {
+                    self.selected_day.select(Some(i));
+                    self.selected_part = usize::from(timing.part2);
+                    self.in_part_selection = true;
+                    self.show_timings = false;
+                }
+            }
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    /// Open the selected day's visualization, for the days that have one;
+    /// anything else (or an input that cannot be read) gets a toast
+    fn open_visualization(&mut self, now: Instant) {
+        let Some(day) = self.get_selected_day() else {
+            return;
+        };
+        let number = day.number;
+        let opened = match number {
+            2 => load_input(day).map(|input| NumberLine::new(&input, self.selected_part == 1)).map(Visualization::NumberLine),
+            3 => load_input(day).map(|input| BatteryView::new(&input, self.selected_part == 1)).map(Visualization::Batteries),
+            _ => Err("it has no visualization".to_string()),
+        };
+        match opened {
+            Ok(view) => self.visualization = Some(view),
+            Err(e) => {
+                self.toasts.push(Toast::new(format!("Cannot show Day {}: {}", number, e), Kind::Error, Some(number), now))
+            }
+        }
+    }
+
+    fn on_visualization_key(&mut self, key: KeyEvent) -> Step {
+        match key.code {
+            KeyCode::Char('q') => return Step::Quit,
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('v') => self.visualization = None,
+            _ => match self.visualization.as_mut() {
+                Some(Visualization::NumberLine(line)) => match key.code {
+                    KeyCode::Char('h') => line.pan(-1),
+                    KeyCode::Char('l') => line.pan(1),
+                    KeyCode::Char('+' | '=') => line.zoom_in(),
+                    KeyCode::Char('-') => line.zoom_out(),
+                    KeyCode::Char('0') => line.reset(),
+                    KeyCode::Left => line.select(false),
+                    KeyCode::Right => line.select(true),
+                    KeyCode::Tab => line.part2 = !line.part2,
+                    _ => {}
+                },
+                Some(Visualization::Batteries(view)) => match key.code {
+                    KeyCode::Down | KeyCode::Char('j') => view.down(1),
+                    KeyCode::Up | KeyCode::Char('k') => view.up(1),
+                    KeyCode::PageDown => view.page_down(),
+                    KeyCode::PageUp => view.page_up(),
+                    KeyCode::Left | KeyCode::Char('h') => view.left(),
+                    KeyCode::Right | KeyCode::Char('l') => view.right(),
+                    KeyCode::Tab => view.part2 = !view.part2,
+                    _ => {}
+                },
+                None => {}
+            },
+        }
+        Step::Continue
+    }
+
+    /// Put the settings' theme, key bindings and answers file to use
+    fn apply_settings(&mut self) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        self.theme = settings.theme();
+        self.keys = settings.keys();
+        self.answers_path = Some(settings.answers_path());
+        self.refresh_progress();
+    }
+
+    fn on_settings_key(&mut self, key: KeyEvent) -> Step {
+        let Some(settings) = self.settings.as_mut() else {
+            self.show_settings = false;
+            return Step::Continue;
+        };
+        if settings.is_editing() {
+            match key.code {
+                KeyCode::Enter if settings.apply() => self.apply_settings(),
+                KeyCode::Esc => settings.cancel(),
+                KeyCode::Backspace => settings.backspace(),
+                KeyCode::Char(c) => settings.type_char(c),
+                _ => {}
+            }
+            return Step::Continue;
+        }
+        match key.code {
+            KeyCode::Char('q') => return Step::Quit,
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('S') => self.show_settings = false,
+            KeyCode::Down | KeyCode::Char('j') => settings.down(),
+            KeyCode::Up | KeyCode::Char('k') => settings.up(),
+            KeyCode::Enter | KeyCode::Char('e') => settings.edit(),
+            KeyCode::Char('s') => settings.save(),
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    /// Apply one key press
+    fn on_key(&mut self, key: KeyEvent) -> Step {
+        let step = self.handle_key(self.keys.translate(key));
+        self.unwatch_if_moved();
+        step
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Step {
+        if self.editor.is_some() {
+            self.on_editor_key(key);
+            return Step::Continue;
+        }
+        if self.show_timings {
+            return self.on_timings_key(key);
+        }
+        if self.visualization.is_some() {
+            return self.on_visualization_key(key);
+        }
+        if self.show_settings {
+            return self.on_settings_key(key);
+        }
+
+        if self.in_part_selection {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Step::Quit,
+                KeyCode::Up | KeyCode::Char('k') => self.toggle_part(),
+                KeyCode::Down | KeyCode::Char('j') => self.toggle_part(),
+                KeyCode::Enter => {
+                    if let Some(day) = self.get_selected_day() {
+                        return Step::Run(Box::new(day.clone()), self.selected_part == 1);
+                    }
+                }
+                KeyCode::Backspace => self.in_part_selection = false,
+                KeyCode::Char('g') => self.jump_to_toast(),
+                _ => {}
+            }
+            return Step::Continue;
+        }
+
+        match (self.focus, key.code) {
+            (_, KeyCode::Char('q') | KeyCode::Esc) => return Step::Quit,
+            (_, KeyCode::Enter) => self.in_part_selection = true,
+            (Focus::Days, KeyCode::Tab) => self.focus = Focus::Preview,
+            (Focus::Preview, KeyCode::Tab) => self.focus = Focus::Days,
+            (Focus::Days, KeyCode::Down | KeyCode::Char('j')) => self.next_day(),
+            (Focus::Days, KeyCode::Up | KeyCode::Char('k')) => self.previous_day(),
+            (Focus::Preview, KeyCode::Down | KeyCode::Char('j')) => {
+                self.preview_scroll = self.preview_scroll.saturating_add(1)
+            }
+            (Focus::Preview, KeyCode::Up | KeyCode::Char('k')) => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(1)
+            }
+            (Focus::Preview, KeyCode::Char('E' | 'e')) => self.start_editing(),
+            (_, KeyCode::Char('1')) => self.run_in_background(false),
+            (_, KeyCode::Char('2')) => self.run_in_background(true),
+            (_, KeyCode::Char('g')) => self.jump_to_toast(),
+            (_, KeyCode::Char('t')) => self.open_timings(),
+            (_, KeyCode::Char('w')) => self.toggle_watch(Instant::now()),
+            (_, KeyCode::Char('n')) => self.start_note(),
+            (_, KeyCode::Char('S')) => self.show_settings = self.settings.is_some(),
+            (_, KeyCode::Char('v')) => self.open_visualization(Instant::now()),
+            (_, KeyCode::Char('<')) => self.set_split(Some(-1), Instant::now()),
+            (_, KeyCode::Char('>')) => self.set_split(Some(1), Instant::now()),
+            (_, KeyCode::Char('0')) => self.set_split(None, Instant::now()),
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    fn on_editor_key(&mut self, key: KeyEvent) {
+        if self.confirm_discard {
+            self.confirm_discard = false;
+            if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                self.editor = None;
+                self.status = None;
+            }
+            return;
+        }
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('s') => self.save(),
+                KeyCode::Char('z') => editor.undo(),
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Esc if editor.is_dirty() => self.confirm_discard = true,
+            KeyCode::Esc => self.editor = None,
+            KeyCode::Left => editor.left(),
+            KeyCode::Right => editor.right(),
+            KeyCode::Up => editor.up(),
+            KeyCode::Down => editor.down(),
+            KeyCode::Home => editor.home(),
+            KeyCode::End => editor.end(),
+            KeyCode::Enter => editor.split_line(),
+            KeyCode::Backspace => editor.backspace(),
+            KeyCode::Delete => editor.delete(),
+            KeyCode::Char(c) => editor.insert(c),
+            _ => {}
+        }
+    }
+
+    /// `title`, marked "[focused]" in accessible mode when `pane` has focus
+    fn pane_title(&self, pane: Focus, title: &str) -> String {
+        if self.glyphs.words && self.focus == pane {
+            let text = title.trim_start();
+            format!("{}[focused] {}", &title[..title.len() - text.len()], text)
+        } else {
+            title.to_string()
+        }
+    }
+
+    /// Accessible mode's status line: the newest notice, else what is
+    /// running
+    fn status_line(&self) -> String {
+        if let Some(toast) = self.toasts.newest() {
+            let label = match toast.kind {
+                Kind::Info => "Notice",
+                Kind::Error => "Error",
+            };
+            return format!("{}: {}", label, toast.message);
+        }
+        let running: Vec<String> = self
+            .running
+            .iter()
+            .map(|&(day, part2)| format!("Day {} Part {}", day, if part2 { 2 } else { 1 }))
+            .collect();
+        if running.is_empty() { "Status: ready".to_string() } else { format!("Running: {}", running.join(", ")) }
+    }
+
+    /// A block with all borders, in the glyph set's style
+    fn bordered(&self) -> Block<'static> {
+        let set = if self.glyphs.ascii { ASCII_BORDER } else { border::PLAIN };
+        Block::default().borders(Borders::ALL).border_set(set)
+    }
+}
+
+/// Something that can put the terminal back the way the shell expects
+trait Restore {
+    /// Best effort: a step that fails must not stop the others
+    fn restore(&mut self);
+}
+
+/// The real terminal, set up by `run_tui`
+#[derive(Clone, Copy)]
+struct Crossterm {
+    /// Drawn inline, so there is no alternate screen to leave
+    inline: bool,
+}
+
+impl Restore for Crossterm {
+    fn restore(&mut self) {
+        let _ = disable_raw_mode();
+        let mut stdout = io::stdout();
+        let _ = execute!(stdout, DisableMouseCapture);
+        if !self.inline {
+            let _ = execute!(stdout, LeaveAlternateScreen);
+        }
+        let _ = execute!(stdout, Show);
+    }
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Restores the terminal when dropped, including while unwinding
+struct Guard<R: Restore> {
+    handle: R,
+    /// The panic hook to put back, when `with_panic_hook` replaced it
+    previous_hook: Option<Arc<PanicHook>>,
+}
+
+impl<R: Restore> Guard<R> {
+    fn new(handle: R) -> Self {
+        Self { handle, previous_hook: None }
+    }
+}
+
+impl Guard<Crossterm> {
+    /// Also restore the terminal before a panic on this thread is printed
+    ///
+    /// Background solves catch their own panics and report them as toasts,
+    /// so their messages are not printed over the TUI at all.
+    fn with_panic_hook(handle: Crossterm) -> Self {
+        let previous: Arc<PanicHook> = Arc::new(panic::take_hook());
+        let tui_thread: ThreadId = thread::current().id();
+        let hook = Arc::clone(&previous);
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().id() == tui_thread {
+                let mut terminal = handle;
+                terminal.restore();
+                hook(info);
+            }
+        }));
+        Self { previous_hook: Some(previous), ..Self::new(handle) }
+    }
+}
+
+impl<R: Restore> Drop for Guard<R> {
+    fn drop(&mut self) {
+        self.handle.restore();
+        // The hook cannot be swapped mid-panic; the process is ending anyway
+        if let Some(previous) = self.previous_hook.take()
+            && !thread::panicking()
+        {
+            panic::set_hook(Box::new(move |info| previous(info)));
+        }
+    }
+}
+
+/// Run the TUI and return the selected day and part
+/// Background solves are recorded in the run history at `history`, the
+/// layout is kept in the state file at `state`, and day notes in the
+/// directory `notes`; the header counts the days the answers file named in
+/// `settings` verifies, and `settings` also picks the colors, key bindings
+/// and first part. With `preselect` (day, part2) it
+/// opens on that day's part selection
+pub fn run_tui(
+    days: Vec<DayInfo>,
+    glyphs: &'static Glyphs,
+    history: Option<PathBuf>,
+    state: Option<PathBuf>,
+    notes: Option<PathBuf>,
+    settings: Settings,
+    preselect: Option<(u8, bool)>,
+) -> Result<(DayInfo, bool), Box<dyn std::error::Error>> {
+    // Setup terminal; without an alternate screen, draw below the prompt instead
+    let mut stdout = io::stdout();
+    let inline = execute!(stdout, EnterAlternateScreen).is_err();
+    if inline {
+        eprintln!("{} Terminal has no alternate screen, drawing the picker inline", glyphs.warning);
+    }
+    // From here on, every way out restores the terminal
+    let _guard = Guard::with_panic_hook(Crossterm { inline });
+    enable_raw_mode()?;
+    // Mouse capture is optional; we only read keys
+    let _ = execute!(stdout, EnableMouseCapture);
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = if inline {
+        Terminal::with_options(backend, TerminalOptions { viewport: Viewport::Inline(INLINE_HEIGHT) })?
+    } else {
+        Terminal::new(backend)?
+    };
+
+    // Create app state
+    let mut app = App { history, notes_dir: notes, ..App::new(days, glyphs) };
+    app.refresh_notes();
+    if let Some(path) = state {
+        app.state = State::load(&path);
+        app.state_path = Some(path);
+    }
+    app.on_resize(terminal.size()?.width);
+    app.selected_part = usize::from(settings.default_part() == 2);
+    for unknown in settings.unknown_names() {
+        app.toasts.push(Toast::new(unknown, Kind::Error, None, Instant::now()));
+    }
+    app.settings = Some(settings);
+    app.apply_settings();
+    if let Some((day, part2)) = preselect {
+        app.preselect(day, part2, Instant::now());
+    }
+    let result = run_app(&mut terminal, &mut app)?;
+
+    result.ok_or_else(|| "No selection made".into())
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> Result<Option<(DayInfo, bool)>, Box<dyn std::error::Error>> {
+    loop {
+        terminal.draw(|f| ui(f, app))?;
+
+        if event::poll(TICK)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => match app.on_key(key) {
+                    Step::Continue => {}
+                    Step::Quit => return Ok(None),
+                    Step::Run(day, part2) => return Ok(Some((*day, part2))),
+                },
+                Event::Resize(width, _) => app.on_resize(width),
+                _ => {}
+            }
+        }
+        app.tick(Instant::now());
+    }
+}
+
+fn ui(f: &mut Frame, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),  // Header
+            Constraint::Min(10),     // Main content
+            Constraint::Length(if app.glyphs.words { 4 } else { 3 }),   // Footer, and the status line
+        ])
+        .split(f.area());
+
+    // Header
+    let title = Span::styled(
+        "Advent of Code Runner",
+        Style::default()
+            .fg(app.theme.accent)
+            .add_modifier(Modifier::BOLD),
+    );
+    let title = if app.glyphs.tree.is_empty() {
+        vec![Span::raw("  "), title]
+    } else {
+        vec![
+            Span::raw("  "),
+            Span::styled(format!("{} ", app.glyphs.tree), Style::default().fg(app.theme.frame)),
+            title,
+            Span::styled(format!(" {}", app.glyphs.tree), Style::default().fg(app.theme.frame)),
+        ]
+    };
+    let header = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(title).alignment(Alignment::Center),
+        Line::from(""),
+        Line::from("  Select a day to run")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(app.theme.muted)),
+        app.progress(),
+    ])
+    .block(
+        app.bordered()
+            .border_style(Style::default().fg(app.theme.accent)),
+    );
+    f.render_widget(header, chunks[0]);
+
+    if app.editor.is_some() {
+        render_editor(f, app, chunks[1]);
+    } else if app.show_timings {
+        let block = app.bordered().title(" Timings ").border_style(Style::default().fg(app.theme.special));
+        let marker = app.glyphs.words.then_some(app.glyphs.pointer);
+        timings::render(f, chunks[1], &app.timings(), app.selected_timing, app.glyphs.ascii, marker, block);
+    } else if app.show_settings
+        && let Some(settings) = &app.settings
+    {
+        let block = app.bordered().border_style(Style::default().fg(app.theme.special));
+        settings::render(f, chunks[1], settings, app.theme, app.glyphs.words.then_some(app.glyphs.pointer), block);
+    } else if app.visualization.is_some() {
+        let block = app.bordered().border_style(Style::default().fg(app.theme.special));
+        let ascii = app.glyphs.ascii;
+        match app.visualization.as_mut() {
+            Some(Visualization::NumberLine(line)) => {
+                let part = if line.part2 { 2 } else { 1 };
+                let title = format!(" Day 2 number line (Part {} invalid IDs) ", part);
+                numberline::render(f, chunks[1], line, ascii, block.title(title));
+            }
+            Some(Visualization::Batteries(view)) => {
+                let title = if view.part2 { " Day 3 batteries (Part 2: twelve per bank) " } else { " Day 3 batteries (Part 1: two per bank) " };
+                batteries::render(f, chunks[1], view, block.title(title));
+            }
+            None => {}
+        }
+    } else if !app.in_part_selection {
+        // Day selection, with details above the input preview
+        let (list, side) = app.panes(chunks[1]);
+        if let Some(list) = list {
+            render_day_list(f, app, list);
+        }
+        if let Some(side) = side {
+            let side_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(side);
+            render_day_info(f, app, side_chunks[0]);
+            render_preview(f, app, side_chunks[1]);
+        }
+    } else {
+        // Part selection
+        render_part_selection(f, app, chunks[1]);
+    }
+
+    // Footer
+    let footer_text = if app.confirm_discard {
+        "Discard unsaved changes? y: Discard | n: Keep editing".to_string()
+    } else if app.editor.is_some() {
+        "Ctrl-S: Save | Ctrl-Z: Undo | Esc: Close".to_string()
+    } else if app.show_settings && app.settings.as_ref().is_some_and(Settings::is_editing) {
+        "Enter: Apply | Esc: Cancel".to_string()
+    } else if app.show_settings {
+        format!("{}: Select | Enter: Edit | s: Save | Esc: Back | q: Quit", app.glyphs.up_down)
+    } else if app.show_timings {
+        format!("{}: Select | Enter: Go to day and part | t/Esc: Back | q: Quit", app.glyphs.up_down)
+    } else if let Some(Visualization::NumberLine(_)) = app.visualization {
+        "h/l: Pan | +/-: Zoom | 0: Fit | Left/Right: Select ID | Tab: Part | v/Esc: Back | q: Quit".to_string()
+    } else if let Some(Visualization::Batteries(_)) = app.visualization {
+        "j/k: Scroll | PgUp/PgDn: Page | h/l: Scroll sideways | Tab: Part | v/Esc: Back | q: Quit".to_string()
+    } else if app.in_part_selection {
+        format!("{}: Select Part | Enter: Run | Backspace: Back | q: Quit", app.glyphs.up_down)
+    } else if app.focus == Focus::Preview {
+        format!("{}: Scroll | E: Edit input | n: Note | w: Watch input | 1/2: Run in background | Tab: Days | Enter: Select | q: Quit", app.glyphs.up_down)
+    } else if app.narrow {
+        format!("{}: Navigate | 1/2: Run in background | Tab: Details | Enter: Select | q: Quit", app.glyphs.up_down)
+    } else {
+        format!(
+            "{}: Move | 1/2: Run | t: Timings | v: Visualize | S: Settings | </>/0: Split | Tab: Preview | Enter: Select | q: Quit",
+            app.glyphs.up_down
+        )
+    };
+    let footer_text = if app.toasts.is_empty() || app.editor.is_some() {
+        footer_text
+    } else {
+        format!("{} | g: Go to notice", footer_text)
+    };
+
+    let mut footer_lines = vec![Line::from(footer_text)];
+    if app.glyphs.words {
+        footer_lines.push(Line::from(app.status_line()));
+    }
+
+    let footer = Paragraph::new(footer_lines)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(app.theme.highlight))
+        .block(
+            app.bordered()
+                .border_style(Style::default().fg(app.theme.accent)),
+        );
+    f.render_widget(footer, chunks[2]);
+
+    // Toasts go last, over whichever screen is up; accessible mode has the
+    // status line instead
+    if !app.glyphs.words {
+        app.toasts.render(f, f.area(), &app.bordered());
+    }
+}
+
+fn render_day_list(f: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .days
+        .iter()
+        .map(|day| {
+            ListItem::new(list_label(day, app.glyphs))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            app.bordered()
+                .title(app.pane_title(Focus::Days, "Available Days"))
+                .border_style(Style::default().fg(app.theme.frame)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection)
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(app.glyphs.pointer);
+
+    f.render_stateful_widget(list, area, &mut app.selected_day);
+}
+
+/// The day's line in the list of days
+fn list_label(day: &DayInfo, glyphs: &Glyphs) -> String {
+    if glyphs.words {
+        // Every part of the line says what it is
+        let input = if day.has_input {
+            glyphs.has_input
+        } else if day.embedded {
+            "input: embedded"
+        } else {
+            glyphs.no_input
+        };
+        let solver = if day.external.is_some() {
+            "solver: external command"
+        } else if day.implemented() {
+            "solver: built in"
+        } else {
+            "solver: not implemented"
+        };
+        let example = if day.example_path.is_some() { ", example: present" } else { "" };
+        return format!("Day {}: {}, {}{}, {}", day.number, day.meta.title, input, example, solver);
+    }
+    let status = if day.has_input {
+        glyphs.has_input.to_string()
+    } else if day.embedded {
+        format!("{} (embedded)", glyphs.has_input)
+    } else {
+        glyphs.no_input.to_string()
+    };
+    let external = if day.external.is_some() { " (external)" } else { "" };
+    let example = if day.example_path.is_some() { " (example file)" } else { "" };
+    format!("Day {:2}: {}{} [{}]{}", day.number, day.meta.title, external, status, example)
+}
+
+fn render_day_info(f: &mut Frame, app: &App, area: Rect) {
+    let info_text = if let Some(day) = app.get_selected_day() {
+        // The words after the mark say it all, so accessible mode drops it
+        let mark = |glyph: &str| if app.glyphs.words { String::new() } else { format!("{} ", glyph) };
+        let input_status = if day.has_input {
+            format!("{}Input file: {}", mark(app.glyphs.has_input), day.input_path.display())
+        } else if day.embedded {
+            format!("{}Input: embedded in this build ({} missing)", mark(app.glyphs.has_input), day.input_path.display())
+        } else {
+            format!("{}No input file ({} missing)", mark(app.glyphs.no_input), day.input_path.display())
+        };
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Day: ", Style::default().fg(app.theme.accent)),
+                Span::styled(
+                    day.number.to_string(),
+                    Style::default()
+                        .fg(app.theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Title: ", Style::default().fg(app.theme.accent)),
+                Span::raw(&day.meta.title),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Status: ", Style::default().fg(app.theme.accent)),
+                Span::raw(input_status),
+            ]),
+            Line::from(""),
+        ];
+        if let Some(path) = &day.example_path {
+            let example = Line::from(vec![
+                Span::styled("Example: ", Style::default().fg(app.theme.accent)),
+                Span::raw(format!("{} (--example runs it)", path.display())),
+            ]);
+            lines.insert(lines.len() - 1, example);
+        }
+        if app.notes_dir.is_some() {
+            let note = match app.notes.get(&day.number) {
+                Some(line) => Span::raw(line.clone()),
+                None => Span::styled("none (n writes one)", Style::default().fg(app.theme.muted)),
+            };
+            lines.insert(lines.len() - 1, Line::from(vec![Span::styled("Note: ", Style::default().fg(app.theme.accent)), note]));
+        }
+        if app.watching.as_ref().is_some_and(|(watched, _)| *watched == day.number) {
+            let watch = Line::from(vec![
+                Span::styled("Watch: ", Style::default().fg(app.theme.accent)),
+                Span::raw("reruns shown parts when the input changes (w stops)"),
+            ]);
+            lines.insert(lines.len() - 1, watch);
+        }
+
+        // Background runs of this day, newest result per part
+        for part2 in [false, true] {
+            let part = if part2 { 2 } else { 1 };
+            let result = if app.running.contains(&(day.number, part2)) {
+                Some("running...".to_string())
+            } else {
+                app.results.iter().rev().find(|r| r.day == day.number && r.part2 == part2).map(|r| match &r.result {
+                    Ok((value, millis)) => format!("{} ({})", value, history::millis(*millis)),
+                    Err(e) => format!("failed: {}", e),
+                })
+            };
+            if let Some(result) = result {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("Part {}: ", part), Style::default().fg(app.theme.accent)),
+                    Span::raw(result),
+                ]));
+            }
+        }
+
+        // Overview from the header comment, when the day has one
+        if day.meta.overview.is_empty() {
+            lines.push(Line::from(""));
+        } else {
+            lines.push(Line::from(Span::styled("Overview:", Style::default().fg(app.theme.accent))));
+            lines.extend(day.meta.overview.iter().map(|line| Line::from(line.as_str())));
+            lines.push(Line::from(""));
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled("Press ", Style::default().fg(app.theme.muted)),
+            Span::styled("Enter", Style::default().fg(app.theme.frame).add_modifier(Modifier::BOLD)),
+            Span::styled(" to continue", Style::default().fg(app.theme.muted)),
+        ]));
+        lines
+    } else {
+        vec![Line::from("No day selected")]
+    };
+
+    let info = Paragraph::new(info_text)
+        .block(
+            app.bordered()
+                .title("Details")
+                .border_style(Style::default().fg(app.theme.frame)),
+        )
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(info, area);
+}
+
+fn render_preview(f: &mut Frame, app: &mut App, area: Rect) {
+    let focused = app.focus == Focus::Preview;
+    let scroll = app.preview_scroll;
+    let (title, text) = match app.preview() {
+        Some(preview) => (
+            format!(" Input ({} lines, {} bytes) ", preview.line_count, preview.bytes),
+            preview.lines.iter().map(|line| Line::from(line.clone())).collect(),
+        ),
+        None => (" Input ".to_string(), vec![Line::from("No input file; press E here to create it")]),
+    };
+    let mut text: Vec<Line> = text;
+    if let Some(status) = &app.status {
+        text.insert(0, Line::from(Span::styled(status.clone(), Style::default().fg(app.theme.highlight))));
+    }
+
+    let border = if focused { app.theme.highlight } else { app.theme.frame };
+    let preview = Paragraph::new(text)
+        .scroll((scroll, 0))
+        .block(app.bordered().title(app.pane_title(Focus::Preview, &title)).border_style(Style::default().fg(border)));
+    f.render_widget(preview, area);
+}
+
+fn render_editor(f: &mut Frame, app: &mut App, area: Rect) {
+    let block = app.bordered().border_style(Style::default().fg(app.theme.highlight));
+    let inner = block.inner(area);
+    let Some(editor) = app.editor.as_mut() else {
+        return;
+    };
+
+    let (top, left) = editor.scroll(inner.height as usize, inner.width as usize);
+    let (row, col) = editor.cursor();
+    let text: Vec<Line> = editor
+        .lines()
+        .iter()
+        .skip(top)
+        .take(inner.height as usize)
+        .map(|line| Line::from(line.chars().skip(left).take(inner.width as usize).collect::<String>()))
+        .collect();
+
+    let modified = if editor.is_dirty() { " [modified]" } else { "" };
+    let mut title = format!(" Editing {}{} - line {}, column {} ", editor.path().display(), modified, row + 1, col + 1);
+    if let Some(status) = &app.status {
+        title = format!("{}- {} ", title, status);
+    }
+
+    f.render_widget(Paragraph::new(text).block(block.title(title)), area);
+    f.set_cursor_position((inner.x + (col - left) as u16, inner.y + (row - top) as u16));
+}
+
+fn render_part_selection(f: &mut Frame, app: &App, area: Rect) {
+    let day = app.get_selected_day().unwrap();
+
+    // Center the selection box
+    let vertical_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+        ])
+        .split(area);
+
+    let horizontal_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .split(vertical_chunks[1]);
+
+    let center_area = horizontal_chunks[1];
+
+    let inner_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),  // Title
+            Constraint::Length(9),  // Part selection
+        ])
+        .split(center_area);
+
+    // Title
+    let title = Paragraph::new(format!("Day {}: {}", day.number, day.meta.title))
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(
+            app.bordered()
+                .border_style(Style::default().fg(app.theme.frame)),
+        );
+    f.render_widget(title, inner_chunks[0]);
+
+    // Part selection with clear visual separation
+    let part1_style = if app.selected_part == 0 {
+        Style::default()
+            .fg(app.theme.highlight)
+            .add_modifier(Modifier::BOLD)
+            .bg(app.theme.selection)
+    } else {
+        Style::default().fg(app.theme.text)
+    };
+
+    let part2_style = if app.selected_part == 1 {
+        Style::default()
+            .fg(app.theme.highlight)
+            .add_modifier(Modifier::BOLD)
+            .bg(app.theme.selection)
+    } else {
+        Style::default().fg(app.theme.text)
+    };
+
+    // The pointer marks the selection; unselected parts get matching padding
+    let blank = " ".repeat(app.glyphs.pointer.chars().count());
+    let part1_symbol = if app.selected_part == 0 { app.glyphs.pointer } else { &blank };
+    let part2_symbol = if app.selected_part == 1 { app.glyphs.pointer } else { &blank };
+
+    let text = vec![
+        Line::from(""),
+        Line::from("  Select which part to run:")
+            .style(Style::default().fg(app.theme.muted)),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("      "),
+            Span::styled(part1_symbol, part1_style),
+            Span::styled("Part 1", part1_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("      "),
+            Span::styled(part2_symbol, part2_style),
+            Span::styled("Part 2", part2_style),
+        ]),
+    ];
+
+    let parts_widget = Paragraph::new(text)
+        .block(
+            app.bordered()
+                .title(" Select Part ")
+                .border_style(Style::default().fg(app.theme.special)),
+        );
+    f.render_widget(parts_widget, inner_chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyphs;
+    use crate::Catalog;
+    use ratatui::backend::TestBackend;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Draw the picker into an in-memory buffer and return its text
+    fn render(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn ascii_mode_renders_only_ascii() {
+        // Unit tests run from the crate root, where src/dayN.rs lives
+        let mut app = App::new(Catalog::discover().days, &glyphs::ASCII);
+
+        let day_list = render(&mut app);
+        assert!(day_list.contains("Available Days"));
+        assert!(day_list.is_ascii(), "non-ASCII in day list: {:?}", day_list.chars().filter(|c| !c.is_ascii()).collect::<String>());
+
+        app.in_part_selection = true;
+        let parts = render(&mut app);
+        assert!(parts.contains("> Part 1"));
+        assert!(parts.is_ascii(), "non-ASCII in part selection: {:?}", parts.chars().filter(|c| !c.is_ascii()).collect::<String>());
+
+        // The Unicode set really does differ
+        let mut app = App::new(Catalog::discover().days, &glyphs::UNICODE);
+        assert!(!render(&mut app).is_ascii());
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// A picker over one day whose input lives in a temp directory
+    fn app_with_input(text: &str) -> (tempfile::TempDir, App) {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("day1.txt");
+        fs::write(&input_path, text).unwrap();
+        let day = DayInfo {
+            number: 1,
+            meta: adventcode::meta::parse(1, ""),
+            has_input: true,
+            input_path,
+            embedded: false,
+            example_path: None,
+            external: None,
+        };
+        (dir, App::new(vec![day], &glyphs::ASCII))
+    }
+
+    #[test]
+    fn editing_a_character_and_saving_updates_the_file() {
+        let (dir, mut app) = app_with_input("L68\nL30\n");
+        assert!(render(&mut app).contains("Input (2 lines, 8 bytes)"));
+
+        // E only opens the editor from the preview
+        app.on_key(key(KeyCode::Char('E')));
+        assert!(app.editor.is_none());
+        app.on_key(key(KeyCode::Tab));
+        app.on_key(key(KeyCode::Char('E')));
+        assert!(render(&mut app).contains("Editing"));
+
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Delete));
+        app.on_key(key(KeyCode::Char('R')));
+        let screen = render(&mut app);
+        assert!(screen.contains("[modified]"), "{}", screen);
+        assert!(screen.contains("R30"));
+
+        app.on_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(fs::read_to_string(dir.path().join("day1.txt")).unwrap(), "L68\nR30\n");
+        assert!(!render(&mut app).contains("[modified]"));
+
+        // Back on the day screen the preview shows the saved text
+        app.on_key(key(KeyCode::Esc));
+        assert!(app.editor.is_none());
+        let screen = render(&mut app);
+        assert!(screen.contains("Saved "), "{}", screen);
+        assert!(screen.contains("R30"));
+
+        // And the run picked next is the same day, reading the same file
+        app.on_key(key(KeyCode::Enter));
+        let Step::Run(day, false) = app.on_key(key(KeyCode::Enter)) else {
+            panic!("expected a part 1 run");
+        };
+        assert_eq!(fs::read_to_string(day.input_path).unwrap(), "L68\nR30\n");
+    }
+
+    #[test]
+    fn discarding_unsaved_edits_asks_first() {
+        let (dir, mut app) = app_with_input("L68\n");
+        app.on_key(key(KeyCode::Tab));
+        app.on_key(key(KeyCode::Char('e')));
+        app.on_key(key(KeyCode::Char('x')));
+
+        app.on_key(key(KeyCode::Esc));
+        assert!(render(&mut app).contains("Discard unsaved changes?"));
+        app.on_key(key(KeyCode::Char('n')));
+        assert!(app.editor.as_ref().is_some_and(|editor| editor.is_dirty()));
+
+        app.on_key(key(KeyCode::Esc));
+        app.on_key(key(KeyCode::Char('y')));
+        assert!(app.editor.is_none());
+        assert_eq!(fs::read_to_string(dir.path().join("day1.txt")).unwrap(), "L68\n");
+    }
+
+    #[test]
+    fn saving_a_missing_input_creates_it() {
+        let (dir, mut app) = app_with_input("");
+        let path = dir.path().join("day1.txt");
+        fs::remove_file(&path).unwrap();
+        app.days[0].has_input = false;
+        assert!(render(&mut app).contains("No input file"));
+
+        app.on_key(key(KeyCode::Tab));
+        app.on_key(key(KeyCode::Char('E')));
+        app.on_key(key(KeyCode::Char('7')));
+        app.on_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(app.days[0].has_input);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "7\n");
+    }
+
+    #[test]
+    fn the_details_show_the_input_path_a_run_reads() {
+        let (_dir, mut app) = app_with_input("L68\n");
+        app.days[0].has_input = false;
+        app.days[0].input_path = PathBuf::from("inputs/day1.txt");
+        let screen = render(&mut app);
+        assert!(screen.contains("No input file (inputs/day1.txt"), "{}", screen);
+    }
+
+    #[test]
+    fn an_example_file_is_shown_in_the_details() {
+        let (_dir, mut app) = app_with_input("L68\n");
+        assert!(!render(&mut app).contains("Example:"));
+        app.days[0].example_path = Some(PathBuf::from("day1_example.txt"));
+        assert!(render(&mut app).contains("day1_example.txt (--example runs it)"));
+    }
+
+    #[test]
+    fn notes_are_edited_shown_and_deleted() {
+        let (dir, mut app) = app_with_input("L68\n");
+        let notes_dir = dir.path().join("data/notes");
+        app.notes_dir = Some(notes_dir.clone());
+        assert!(render(&mut app).contains("Note: none (n writes one)"));
+
+        app.on_key(key(KeyCode::Char('n')));
+        assert!(render(&mut app).contains("day1.md"));
+        for c in "too low twice".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        app.on_key(key(KeyCode::Enter));
+        for c in "off by one?".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        app.on_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        app.on_key(key(KeyCode::Esc));
+        assert_eq!(fs::read_to_string(notes::path(&notes_dir, 1)).unwrap(), "too low twice\noff by one?\n");
+        let screen = render(&mut app);
+        assert!(screen.contains("Note: too low twice"), "{}", screen);
+        assert!(!screen.contains("off by one?"), "{}", screen);
+        // The input is untouched
+        assert_eq!(fs::read_to_string(dir.path().join("day1.txt")).unwrap(), "L68\n");
+
+        // Reopened, it holds the note; emptied and saved, it is gone
+        app.on_key(key(KeyCode::Char('n')));
+        assert!(render(&mut app).contains("off by one?"));
+        for _ in 0..2 {
+            app.on_key(key(KeyCode::End));
+            for _ in 0..20 {
+                app.on_key(key(KeyCode::Backspace));
+            }
+            app.on_key(key(KeyCode::Down));
+        }
+        app.on_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        app.on_key(key(KeyCode::Esc));
+        assert!(!notes::path(&notes_dir, 1).exists());
+        let screen = render(&mut app);
+        assert!(screen.contains("Deleted the note for Day 1"), "{}", screen);
+        assert!(screen.contains("Note: none"), "{}", screen);
+    }
+
+    /// Tick until `count` background runs have finished
+    fn wait_for_results(app: &mut App, count: usize) {
+        let start = Instant::now();
+        while app.results.len() < count {
+            assert!(start.elapsed() < Duration::from_secs(30), "background run never finished");
+            thread::sleep(Duration::from_millis(5));
+            app.tick(Instant::now());
+        }
+    }
+
+    #[test]
+    fn preselection_opens_on_the_chosen_part() {
+        let mut app = App::new(Catalog::discover().days, &glyphs::ASCII);
+        app.preselect(4, true, Instant::now());
+        let position = app.days.iter().position(|day| day.number == 4);
+        assert_eq!(app.selected_day.selected(), position);
+        assert_eq!(app.selected_part, 1);
+        assert!(app.in_part_selection);
+        let screen = render(&mut app);
+        assert!(screen.contains("> Part 2"), "{}", screen);
+        assert!(app.toasts.is_empty());
+
+        // Enter runs it straight away
+        let Step::Run(day, part2) = app.on_key(key(KeyCode::Enter)) else {
+            panic!("Enter should run the preselected part");
+        };
+        assert_eq!((day.number, part2), (4, true));
+    }
+
+    #[test]
+    fn preselecting_a_missing_day_opens_the_list_with_a_toast() {
+        let mut app = App::new(Catalog::discover().days, &glyphs::ASCII);
+        app.preselect(24, false, Instant::now());
+        assert_eq!(app.selected_day.selected(), Some(0));
+        assert_eq!(app.selected_part, 0);
+        assert!(!app.in_part_selection);
+        let screen = render(&mut app);
+        assert!(screen.contains("Available Days"));
+        assert!(screen.contains("Day 24 is not implemented yet"), "{}", screen);
+        assert_eq!(app.toasts.newest().unwrap().kind, Kind::Error);
+    }
+
+    #[test]
+    fn v_opens_day_2_and_3s_views_and_toasts_elsewhere() {
+        let (_dir, mut app) = app_with_input("L68\n");
+        app.on_key(key(KeyCode::Char('v')));
+        assert!(app.visualization.is_none());
+        assert!(app.toasts.newest().unwrap().message.contains("Day 1: it has no visualization"));
+
+        let (dir, mut app) = app_with_input("11-22,95-115\n");
+        let day2 = dir.path().join("day2.txt");
+        fs::rename(&app.days[0].input_path, &day2).unwrap();
+        app.days[0] = DayInfo { number: 2, input_path: day2, ..app.days[0].clone() };
+        app.on_key(key(KeyCode::Char('v')));
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 2 number line (Part 1 invalid IDs)"), "{}", screen);
+        assert!(screen.contains("merged"));
+        app.on_key(key(KeyCode::Tab));
+        assert!(render(&mut app).contains("(Part 2 invalid IDs)"));
+        app.on_key(key(KeyCode::Esc));
+        assert!(app.visualization.is_none());
+
+        let (dir, mut app) = app_with_input("987654321111111\n818181911112111\n");
+        let day3 = dir.path().join("day3.txt");
+        fs::rename(&app.days[0].input_path, &day3).unwrap();
+        app.days[0] = DayInfo { number: 3, input_path: day3, ..app.days[0].clone() };
+        app.on_key(key(KeyCode::Char('v')));
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 3 batteries (Part 1: two per bank)"), "{}", screen);
+        assert!(screen.contains("Total: 190"));
+        app.on_key(key(KeyCode::Tab));
+        assert!(render(&mut app).contains("(Part 2: twelve per bank)"));
+        app.on_key(key(KeyCode::Char('v')));
+        assert!(app.visualization.is_none());
+    }
+
+    #[test]
+    fn split_keys_move_save_and_reset_the_panes() {
+        let (dir, mut app) = app_with_input("L68\n");
+        let path = dir.path().join("data").join(crate::state::FILE);
+        app.state_path = Some(path.clone());
+        let area = Rect::new(0, 0, 100, 20);
+        let widths = |app: &App| {
+            let (list, side) = app.panes(area);
+            (list.map(|r| r.width), side.map(|r| r.width))
+        };
+        assert_eq!(widths(&app), (Some(60), Some(40)));
+
+        app.on_key(key(KeyCode::Char('>')));
+        app.on_key(key(KeyCode::Char('>')));
+        assert_eq!(widths(&app), (Some(70), Some(30)));
+        assert_eq!(State::load(&path).split, 70);
+        for _ in 0..20 {
+            app.on_key(key(KeyCode::Char('<')));
+        }
+        assert_eq!(widths(&app), (Some(20), Some(80)));
+        app.on_key(key(KeyCode::Char('0')));
+        assert_eq!(widths(&app), (Some(60), Some(40)));
+        assert_eq!(State::load(&path).split, 60);
+
+        // Narrow: one pane at a time, and Tab swaps them
+        app.on_resize(60);
+        assert_eq!(widths(&app), (Some(100), None));
+        let screen = render(&mut app);
+        // "Details" only in the footer, not as a pane's title
+        assert!(screen.contains("Available Days"), "{}", screen);
+        assert_eq!(screen.matches("Details").count(), 1, "{}", screen);
+        assert!(screen.contains("Tab: Details"));
+        app.on_key(key(KeyCode::Tab));
+        assert_eq!(widths(&app), (None, Some(100)));
+        assert!(!render(&mut app).contains("Available Days"));
+        app.on_resize(120);
+        assert_eq!(widths(&app), (Some(60), Some(40)));
+    }
+
+    /// The foreground of the header's title, as drawn
+    fn title_color(app: &mut App) -> ratatui::style::Color {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..120).map(|x| buffer[(x, 2)].symbol()).collect();
+        let x = row.find("Advent of Code Runner").expect("no title") as u16;
+        buffer[(x, 2)].fg
+    }
+
+    #[test]
+    fn settings_screen_applies_a_theme_at_once_and_saves_it() {
+        let (dir, mut app) = app_with_input("L68\n");
+        let config_path = dir.path().join("adventcode.toml");
+        fs::write(&config_path, "# mine\nnotify_after = 5\n").unwrap();
+        let config = crate::config::Config::load(&config_path).unwrap();
+        let overrides = crate::config::Overrides { default_part: Some(2), ..Default::default() };
+        app.settings = Some(Settings::new(config, config_path.clone(), Some(dir.path().join("session")), overrides));
+        app.apply_settings();
+        assert_eq!(title_color(&mut app), ratatui::style::Color::Cyan);
+
+        app.on_key(key(KeyCode::Char('S')));
+        assert!(render(&mut app).contains("Settings ("));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Enter));
+        for _ in "default".chars() {
+            app.on_key(key(KeyCode::Backspace));
+        }
+        for c in "light".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        app.on_key(key(KeyCode::Enter));
+        assert_eq!(title_color(&mut app), ratatui::style::Color::Blue);
+        assert!(render(&mut app).contains("[modified]"));
+
+        app.on_key(key(KeyCode::Char('s')));
+        let text = fs::read_to_string(&config_path).unwrap();
+        assert!(text.starts_with("# mine\nnotify_after = 5\n"), "{}", text);
+        assert!(text.contains("theme = \"light\""), "{}", text);
+
+        // --part 2 set the default part, so it cannot be edited here
+        for _ in 0..3 {
+            app.on_key(key(KeyCode::Down));
+        }
+        app.on_key(key(KeyCode::Enter));
+        assert!(!app.settings.as_ref().unwrap().is_editing());
+        assert!(render(&mut app).contains("Default part is set by --part for this session"));
+
+        app.on_key(key(KeyCode::Esc));
+        assert!(!app.show_settings);
+        assert!(render(&mut app).contains("Available Days"));
+    }
+
+    #[test]
+    fn accessible_mode_says_in_words_what_colors_show() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(Catalog::discover().days, &glyphs::WORDS);
+        let overrides = crate::config::Overrides { accessible: true, ..Default::default() };
+        let config = crate::config::Config { theme: Some("light".into()), ..Default::default() };
+        app.settings = Some(Settings::new(config, dir.path().join("adventcode.toml"), None, overrides));
+        app.apply_settings();
+        // The configured theme gives way to high contrast
+        assert_eq!(title_color(&mut app), ratatui::style::Color::White);
+
+        let screen = render(&mut app);
+        assert!(screen.is_ascii(), "{:?}", screen.chars().filter(|c| !c.is_ascii()).collect::<String>());
+        assert!(screen.contains("[selected] Day 1: "), "{}", screen);
+        assert!(screen.contains(", solver: built in"), "{}", screen);
+        assert!(screen.contains("[focused] Available Days"), "{}", screen);
+        assert!(screen.contains("Status: ready"), "{}", screen);
+
+        // Moving the selection or the focus moves the words, not just colors
+        app.on_key(key(KeyCode::Down));
+        let screen = render(&mut app);
+        assert!(screen.contains("[selected] Day 2: ") && !screen.contains("[selected] Day 1: "), "{}", screen);
+        app.on_key(key(KeyCode::Tab));
+        let screen = render(&mut app);
+        assert!(screen.contains("[focused] Input") && !screen.contains("[focused] Available Days"), "{}", screen);
+
+        // A notice is a status line that stays, not a toast drawn over the screen
+        app.preselect(24, false, Instant::now());
+        app.tick(Instant::now() + Duration::from_secs(60));
+        let screen = render(&mut app);
+        assert_eq!(screen.matches("Day 24 is not implemented yet").count(), 1, "{}", screen);
+        assert!(screen.contains("Error: Day 24 is not implemented yet"), "{}", screen);
+
+        app.on_key(key(KeyCode::Enter));
+        assert!(render(&mut app).contains("[selected] Part 1"));
+        app.on_key(key(KeyCode::Backspace));
+        app.on_key(key(KeyCode::Char('S')));
+        let screen = render(&mut app);
+        assert!(screen.contains("[selected]  Input directory"), "{}", screen);
+        assert!(screen.contains("high-contrast (forced by accessible mode)"), "{}", screen);
+    }
+
+    #[test]
+    fn header_counts_progress_and_refreshes_after_runs() {
+        // Days 1-4 are listed, with a source but no solver for day 9
+        let dir = tempfile::tempdir().unwrap();
+        let days: Vec<DayInfo> = [1, 2, 3, 4, 9]
+            .into_iter()
+            .map(|number| DayInfo {
+                number,
+                meta: adventcode::meta::parse(number, ""),
+                has_input: false,
+                input_path: dir.path().join(format!("day{}.txt", number)),
+                embedded: false,
+                example_path: None,
+                external: None,
+            })
+            .collect();
+        for number in [1, 2, 9] {
+            fs::write(dir.path().join(format!("day{}.txt", number)), "L68\n").unwrap();
+        }
+        let answers = dir.path().join("answers.toml");
+        fs::write(&answers, "[day1]\npart1 = \"3\"\npart2 = \"6\"\n\n[day2]\npart1 = \"1\"\n").unwrap();
+        let mut app = App { answers_path: Some(answers.clone()), ..App::new(days, &glyphs::ASCII) };
+        app.refresh_progress();
+        let screen = render(&mut app);
+        assert!(screen.contains("4/25 days implemented - 3 inputs present - 1 day verified"), "{}", screen);
+
+        // A new input and answer show up once a background run finishes
+        fs::write(dir.path().join("day3.txt"), "L68\n").unwrap();
+        fs::write(&answers, "[day1]\npart1 = \"3\"\npart2 = \"6\"\n\n[day2]\npart1 = \"1\"\npart2 = \"2\"\n").unwrap();
+        app.on_key(key(KeyCode::Char('1')));
+        wait_for_results(&mut app, 1);
+        assert!(render(&mut app).contains("4/25 days implemented - 4 inputs present - 2 days verified"));
+    }
+
+    #[test]
+    fn toasts_stack_over_any_screen_and_expire() {
+        let (_dir, mut app) = app_with_input("L68\n");
+        let now = Instant::now();
+        for n in 1..=toast::MAX + 1 {
+            app.toasts.push(Toast::new(format!("notice {}", n), Kind::Info, None, now));
+        }
+        app.in_part_selection = true;
+        let screen = render(&mut app);
+        assert!(screen.contains("Select Part"));
+        // The oldest made room for the newest
+        assert!(!screen.contains("notice 1"));
+        for n in 2..=toast::MAX + 1 {
+            assert!(screen.contains(&format!("notice {}", n)), "{}", screen);
+        }
+
+        // Keys still reach the screen underneath
+        app.on_key(key(KeyCode::Down));
+        assert_eq!(app.selected_part, 1);
+
+        app.tick(now + toast::DEFAULT_TTL - Duration::from_millis(1));
+        assert!(render(&mut app).contains("notice 5"));
+        app.tick(now + toast::DEFAULT_TTL);
+        assert!(app.toasts.is_empty());
+        assert!(!render(&mut app).contains("notice"));
+    }
+
+    #[test]
+    fn background_runs_end_in_toasts() {
+        let (dir, mut app) = app_with_input("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n");
+        let input_path = dir.path().join("day90.txt");
+        fs::write(&input_path, "1\n").unwrap();
+        app.days.push(DayInfo {
+            number: crate::tests::PANICKING_DAY,
+            meta: adventcode::meta::parse(crate::tests::PANICKING_DAY, ""),
+            has_input: true,
+            input_path,
+            embedded: false,
+            example_path: None,
+            external: None,
+        });
+
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Char('1')));
+        wait_for_results(&mut app, 1);
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 90 Part 1 failed: panicked: index out of bounds"), "{}", screen);
+
+        app.on_key(key(KeyCode::Up));
+        app.on_key(key(KeyCode::Char('1')));
+        wait_for_results(&mut app, 2);
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 1 Part 1 finished: 3 ("), "{}", screen);
+        assert!(screen.contains("Part 1: 3 ("), "{}", screen);
+
+        // g jumps to the day of the newest toast
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Char('g')));
+        assert_eq!(app.get_selected_day().unwrap().number, 1);
+    }
+
+    #[test]
+    fn watched_inputs_rerun_the_results_on_show() {
+        let (dir, mut app) = app_with_input("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n");
+        app.on_key(key(KeyCode::Char('1')));
+        wait_for_results(&mut app, 1);
+
+        app.on_key(key(KeyCode::Char('w')));
+        assert!(render(&mut app).contains("Watch: reruns shown parts"));
+        fs::write(dir.path().join("day1.txt"), "L68\nL30\nR48\n").unwrap();
+        wait_for_results(&mut app, 2);
+        assert_eq!(app.results[1].result.as_ref().map(|(value, _)| value.as_str()), Ok("1"));
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 1's input changed"), "{}", screen);
+        assert!(screen.contains("Part 1: 1 ("), "{}", screen);
+        // Part 2 was never shown, so it was not run
+        assert!(app.results.iter().all(|r| !r.part2));
+
+        // Moving to another day stops the watch
+        let other = dir.path().join("day2.txt");
+        app.days.push(DayInfo { number: 2, input_path: other, ..app.days[0].clone() });
+        app.on_key(key(KeyCode::Down));
+        assert!(app.watching.is_none());
+        app.on_key(key(KeyCode::Up));
+        app.on_key(key(KeyCode::Char('w')));
+        app.on_key(key(KeyCode::Char('w')));
+        assert!(app.watching.is_none());
+    }
+
+    fn finished(day: u8, part2: bool, millis: f64) -> Finished {
+        Finished { day, part2, result: Ok((day.to_string(), millis)) }
+    }
+
+    #[test]
+    fn timings_chart_scales_to_the_slowest_and_jumps() {
+        let (_dir, mut app) = app_with_input("L68\n");
+        app.days.push(DayInfo { number: 5, ..app.days[0].clone() });
+        app.on_key(key(KeyCode::Char('t')));
+        let screen = render(&mut app);
+        assert!(screen.contains("No timings yet"), "{}", screen);
+        app.on_key(key(KeyCode::Esc));
+
+        // The history's day 1 part 1 time is older than this session's
+        app.recorded = vec![Record { at: 0, ..Record::now(1, false, "", "1", 99.0) }];
+        app.results = vec![finished(1, false, 2.0), finished(1, true, 8.0), finished(5, true, 0.5)];
+        app.show_timings = true;
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 1") && screen.contains("Day 5"), "{}", screen);
+        assert!(screen.contains("2.0 ms") && screen.contains("8.0 ms") && screen.contains("0.500 ms"), "{}", screen);
+        assert!(!screen.contains("99.0 ms"));
+        assert!(screen.is_ascii());
+
+        // Down stops at the last bar, day 5 part 2; Enter opens its part selection
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Enter));
+        assert!(!app.show_timings);
+        assert!(app.in_part_selection);
+        assert_eq!(app.get_selected_day().unwrap().number, 5);
+        assert_eq!(app.selected_part, 1);
+    }
+
+    /// Counts restores instead of touching the real terminal
+    struct FakeTerminal(Rc<Cell<usize>>);
+
+    impl Restore for FakeTerminal {
+        fn restore(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn guard_restores_on_return_and_on_panic() {
+        let restores = Rc::new(Cell::new(0));
+        {
+            let _guard = Guard::new(FakeTerminal(Rc::clone(&restores)));
+            assert_eq!(restores.get(), 0);
+        }
+        assert_eq!(restores.get(), 1);
+
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard = Guard::new(FakeTerminal(Rc::clone(&restores)));
+            panic!("render bug");
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(restores.get(), 2);
+    }
+}
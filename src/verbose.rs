@@ -0,0 +1,124 @@
+// --verbose: what the run found and chose, on stderr
+//
+// Discovery, the config files, input resolution and the days' parsers note
+// what they did as `tracing` events: which day modules and input files were
+// probed, which header line gave a day its title, where the input came
+// from and why, how much of it there was, and which way a parse went. With
+// -v the debug events are printed to stderr as they happen, one line each:
+//
+//     debug: reading the input day=5 source=day5.txt why="the input file"
+//
+// -vv adds the trace events (every file probed, not only those found).
+// Without the flag no subscriber listens for them, so nothing is printed.
+
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Prints the events up to a level of detail, one line each
+pub struct VerboseLayer {
+    max: Level,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+/// The layer for -v given `count` times, printing to stderr; None without it
+pub fn layer(count: u8) -> Option<VerboseLayer> {
+    let max = match count {
+        0 => return None,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    Some(VerboseLayer { max, out: Mutex::new(Box::new(io::stderr())) })
+}
+
+impl<S: Subscriber> Layer<S> for VerboseLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // Only our own events; a less detailed level compares lower
+        if *metadata.level() > self.max || !metadata.target().starts_with("adventcode") {
+            return;
+        }
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(out, "{}", line(event));
+    }
+}
+
+/// "debug: message key=value ..."
+fn line(event: &Event<'_>) -> String {
+    let mut fields = Fields::default();
+    event.record(&mut fields);
+    format!("{}: {}{}", event.metadata().level().as_str().to_lowercase(), fields.message, fields.rest)
+}
+
+/// An event's message, and its other fields as " key=value"
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else if value.contains(char::is_whitespace) || value.is_empty() {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        } else {
+            let _ = write!(self.rest, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::registry::Registry;
+
+    /// A Write whose bytes the test keeps
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn printed(count: u8) -> String {
+        let shared = Shared::default();
+        let mut layer = layer(count).unwrap();
+        layer.out = Mutex::new(Box::new(shared.clone()));
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(day = 5u8, source = "day5.txt", why = "the input file", "reading the input");
+            tracing::trace!(path = %std::path::Path::new("src/day7.rs").display(), "no day module");
+            tracing::debug!(target: "ureq", "not ours");
+        });
+        String::from_utf8(shared.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn events_become_one_line_each_up_to_the_level() {
+        assert!(layer(0).is_none());
+        let debug = "debug: reading the input day=5 source=day5.txt why=\"the input file\"\n";
+        assert_eq!(printed(1), debug);
+        assert_eq!(printed(2), format!("{}trace: no day module path=src/day7.rs\n", debug));
+    }
+}
@@ -0,0 +1,58 @@
+// JavaScript bindings
+//
+// With the `wasm` feature (and the TUI off) the library builds for
+// wasm32-unknown-unknown, so a web page can hand a pasted puzzle input to the
+// solvers. Everything goes through Output::capture: nothing touches the file
+// system, prints, or spawns threads.
+
+use wasm_bindgen::prelude::*;
+
+use crate::input;
+use crate::options::DayOptions;
+use crate::output::Output;
+
+/// Solve one part of a day; `part` is 1 or 2
+///
+/// Returns the answer as the CLI prints it (e.g. "Password: 3"); parse
+/// errors and unknown days come back as a rejected JS string.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsValue> {
+    solve_text(day, part, input).map_err(|e| JsValue::from_str(&e))
+}
+
+/// The day numbers this build can solve
+#[wasm_bindgen]
+pub fn days() -> Vec<u8> {
+    crate::DAYS.iter().map(|day| day.number).collect()
+}
+
+/// The body of `solve`, kept free of JsValue so native tests can call it
+fn solve_text(day: u8, part: u8, text: &str) -> Result<String, String> {
+    let solver = crate::day(day).ok_or_else(|| format!("Day {} is not implemented", day))?;
+    let part2 = match part {
+        1 => false,
+        2 => true,
+        _ => return Err(format!("part must be 1 or 2, not {}", part)),
+    };
+
+    // Pasted text may still carry CRLF endings or a byte order mark
+    let text = input::normalize(text);
+    let mut out = Output::capture();
+    (solver.solve)(&text, part2, &DayOptions::default(), &mut out)
+        .map(|answer| answer.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_by_part_number() {
+        let example = include_str!("../tests/fixtures/day5_example.txt");
+        assert_eq!(solve_text(5, 1, example).unwrap(), "3");
+        assert_eq!(solve_text(5, 2, example).unwrap(), "14");
+        assert!(solve_text(5, 3, example).unwrap_err().contains("part must be 1 or 2"));
+        assert!(solve_text(9, 1, example).unwrap_err().contains("Day 9"));
+    }
+}
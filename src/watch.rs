@@ -0,0 +1,100 @@
+// Watching an input file for changes
+//
+// A Watcher polls one file's modification time and size on a thread of its
+// own and calls back once the file has changed and then stayed put for the
+// debounce time, so an editor that saves in several writes (or a burst of
+// saves) causes one callback, not one per write. A file that appears or
+// disappears counts as a change. Dropping the Watcher stops the thread.
+//
+// Polling rather than inotify and friends keeps this free of platform code
+// and works the same on network and container file systems.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the file is looked at
+const POLL: Duration = Duration::from_millis(50);
+
+/// Quiet time after the last change before the callback runs
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What is compared between polls: None while the file is missing
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+pub struct Watcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+    /// Watch `path`, calling `on_change` once it has changed and been left
+    /// alone for `debounce`
+    pub fn spawn(path: PathBuf, debounce: Duration, on_change: impl Fn() + Send + 'static) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        // Taken before the thread starts, so a change made right after
+        // `spawn` returns is not mistaken for the starting state
+        let mut seen = stamp(&path);
+        let thread = thread::spawn(move || {
+            // When the latest change not yet reported was noticed
+            let mut pending: Option<Instant> = None;
+            while !stopped.load(Ordering::Relaxed) {
+                thread::sleep(POLL);
+                let now = stamp(&path);
+                if now != seen {
+                    seen = now;
+                    pending = Some(Instant::now());
+                } else if pending.is_some_and(|since| since.elapsed() >= debounce) {
+                    pending = None;
+                    on_change();
+                }
+            }
+        });
+        Self { stop, thread: Some(thread) }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn a_burst_of_writes_is_one_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day1.txt");
+        fs::write(&path, "L68\n").unwrap();
+        let (tx, rx) = mpsc::channel();
+        let watcher = Watcher::spawn(path.clone(), Duration::from_millis(200), move || tx.send(()).unwrap());
+
+        for text in ["L68\nR4\n", "L68\nR48\n", "L68\nR48\nL5\n"] {
+            thread::sleep(Duration::from_millis(60));
+            fs::write(&path, text).unwrap();
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(()));
+        assert!(rx.recv_timeout(Duration::from_millis(400)).is_err());
+
+        // Nothing is reported once the watcher is gone
+        drop(watcher);
+        fs::write(&path, "R1\n").unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(500)).is_err());
+    }
+}
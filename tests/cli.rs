@@ -0,0 +1,1915 @@
+// End-to-end tests for the command-line interface
+//
+// These drive the built binary, so they cover flag handling, the stdin
+// fallback and exit codes exactly as a shell script would see them.
+
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(name: &str) -> PathBuf {
+    manifest_dir().join("tests").join("fixtures").join(name)
+}
+
+/// The binary, run from the crate root so day discovery finds src/
+/// A UTF-8 locale keeps the default (Unicode) glyphs whatever the host uses,
+/// and a scratch data directory keeps the run history out of the user's
+fn adventcode() -> Command {
+    let mut cmd = Command::cargo_bin("adventcode").unwrap();
+    cmd.current_dir(manifest_dir())
+        .env("LC_ALL", "C.UTF-8")
+        .env("ADVENTCODE_DATA_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("data"))
+        // Not the user config of whoever runs the tests
+        .env("ADVENTCODE_CONFIG_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("config"));
+    cmd
+}
+
+#[test]
+fn quiet_run_prints_only_the_answer() {
+    adventcode()
+        .args(["-d", "5", "-q", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn quiet_answers_are_bare_for_every_day() {
+    // Each day labels its answer ("Password: 3"); quiet output is the value alone
+    let answers = [
+        (1, "3", "6"),
+        (2, "1227775554", "4174379265"),
+        (3, "357", "3121910778619"),
+        (4, "13", "43"),
+        (5, "3", "14"),
+    ];
+    for (day, part1, part2) in answers {
+        for (part, answer) in [("1", part1), ("2", part2)] {
+            adventcode()
+                .args(["-d", &day.to_string(), "-p", part, "-q", "-f", "-"])
+                .pipe_stdin(fixture(&format!("day{}_example.txt", day)))
+                .unwrap()
+                .assert()
+                .success()
+                .stdout(format!("{}\n", answer));
+        }
+    }
+}
+
+#[test]
+fn time_flag_reports_on_stderr() {
+    adventcode()
+        .args(["-d", "5", "-q", "--time", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr(predicate::str::is_match(r"^Day 5 Part 1 time: [0-9.]+ (µs|ms|s)\n$").unwrap());
+}
+
+#[test]
+fn normal_run_prints_banner_and_result() {
+    adventcode()
+        .args(["-d", "5", "--part", "2", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Day 5: CAFETERIA │ Part 2"))
+        .stdout(predicate::str::ends_with("Result: 14\n"));
+}
+
+#[test]
+fn part_both_solves_both_parts_from_one_read() {
+    // Stdin can only be read once, so both answers prove a single read
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "5", "--part", "both", "-q", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .pipe_stdin(fixture("day5_example.txt"))
+        .unwrap()
+        .assert()
+        .success()
+        .stdout("3\n14\n");
+
+    adventcode()
+        .args(["-d", "5", "-p", "both", "--time", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)Part 1.*Result: 3\n.*Part 2.*Result: 14\n$").unwrap())
+        .stderr(predicate::str::is_match("^Day 5 Part 1 time: .*\nDay 5 Part 2 time: .*\n$").unwrap());
+
+    adventcode()
+        .args(["-d", "5", "-p", "both", "--expected", "3", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("--part both does not go with --expected"));
+}
+
+#[test]
+fn dash_2_is_a_deprecated_part_2() {
+    adventcode()
+        .args(["-d", "5", "-2", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("-2/--part2 is deprecated; use --part 2"))
+        .stdout(predicate::str::ends_with("Result: 14\n"));
+    adventcode().args(["-d", "5", "-2", "--part", "1"]).assert().code(2);
+}
+
+#[test]
+fn subcommands_match_the_flat_flags() {
+    let example = fixture("day5_example.txt");
+    adventcode().args(["run", "-d", "5", "-p", "2", "-q", "-f"]).arg(&example).assert().success().stdout("14\n");
+    let list = adventcode().arg("--list").assert().success().get_output().stdout.clone();
+    adventcode().arg("list").assert().success().stdout(list.clone());
+    // Global flags go on either side of the subcommand
+    adventcode().args(["--ascii", "list"]).assert().success().stdout(list.clone());
+    adventcode().args(["list", "--ascii"]).assert().success().stdout(list);
+
+    adventcode()
+        .args(["bench", "-d", "5", "-p", "2", "-n", "3", "-q", "-f"])
+        .arg(&example)
+        .assert()
+        .success()
+        .stdout("14\n")
+        .stderr(predicate::str::contains("over 3 runs"));
+    adventcode().args(["bench", "-d", "5", "-p", "3"]).assert().code(2);
+    adventcode()
+        .args(["-d", "5", "list"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--day is an option of `adventcode run` and does not go with `list`"));
+}
+
+#[test]
+fn missing_file_falls_back_to_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "1", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .write_stdin("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("missing.txt' not found, reading from stdin"))
+        .stdout(predicate::str::ends_with("Result: Password: 3\n"));
+}
+
+#[test]
+fn stdin_is_used_in_quiet_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "3", "-q", "-f"])
+        .arg(dir.path().join("none.txt"))
+        .pipe_stdin(fixture("day3_example.txt"))
+        .unwrap()
+        .assert()
+        .success()
+        .stdout("357\n")
+        .stderr("");
+}
+
+#[test]
+fn file_dash_reads_the_pipe_over_existing_files() {
+    #[cfg(unix)]
+    use std::process::Stdio;
+
+    // day3.txt is there, and so is a file called "-"; neither is read
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::copy(manifest_dir().join("src/day3.rs"), dir.path().join("src/day3.rs")).unwrap();
+    std::fs::write(dir.path().join("day3.txt"), "11\n").unwrap();
+    std::fs::write(dir.path().join("-"), "22\n").unwrap();
+
+    // `cat day3_example.txt | adventcode -d 3 -f -`
+    #[cfg(unix)]
+    {
+        let mut cat =
+            std::process::Command::new("cat").arg(fixture("day3_example.txt")).stdout(Stdio::piped()).spawn().unwrap();
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_adventcode"))
+            .current_dir(dir.path())
+            .env("ADVENTCODE_DATA_DIR", dir.path().join("data"))
+            .env("ADVENTCODE_CONFIG_DIR", dir.path().join("config"))
+            .args(["-d", "3", "-f", "-"])
+            .stdin(cat.stdout.take().unwrap())
+            .output()
+            .unwrap();
+        cat.wait().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stdout).ends_with("Result: Total output joltage: 357\n"));
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    }
+
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path()).args(["-d", "3", "-q", "-f", "-"]);
+        cmd
+    };
+    run().pipe_stdin(fixture("day3_example.txt")).unwrap().assert().success().stdout("357\n");
+    run()
+        .write_stdin("")
+        .assert()
+        .code(66)
+        .stderr("Error: --file - reads the input from stdin, and nothing was piped there\n");
+    run().arg("--bench").arg("3").assert().code(1).stderr(predicate::str::contains("--bench cannot read stdin"));
+}
+
+#[test]
+fn piped_stdin_beats_a_stale_default_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::copy(manifest_dir().join("src/day1.rs"), dir.path().join("src/day1.rs")).unwrap();
+    std::fs::write(dir.path().join("day1.txt"), "R50\n").unwrap();
+    let example = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+    let run = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path()).args(args);
+        cmd
+    };
+
+    run(&["-d", "1", "-q"])
+        .write_stdin(example)
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr("Reading Day 1's input from stdin, not day1.txt\n");
+    // An empty pipe, an explicit --file or no pipe at all (stdin is
+    // /dev/null): the file is read
+    run(&["-d", "1", "-q"]).write_stdin("").assert().success().stdout("1\n").stderr("");
+    run(&["-d", "1", "-q", "-f", "day1.txt"]).write_stdin(example).assert().success().stdout("1\n");
+    run(&["-d", "1", "-q"]).assert().success().stdout("1\n").stderr("");
+}
+
+#[test]
+fn quiet_without_day_is_an_error() {
+    adventcode()
+        .arg("--quiet")
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("--day is required when using --quiet mode"));
+}
+
+#[test]
+fn unimplemented_day_reports_on_stderr() {
+    adventcode()
+        .args(["-d", "9", "-q"])
+        .write_stdin("")
+        .assert()
+        .code(69)
+        .stdout("")
+        .stderr(predicate::str::contains("Day 9 is not implemented (implemented days: 1, 2, 3, 4, 5)"));
+}
+
+#[test]
+fn unimplemented_day_fails_before_any_output() {
+    adventcode()
+        .args(["-d", "9"])
+        .assert()
+        .code(69)
+        .stdout("")
+        .stderr("Error: Day 9 is not implemented (implemented days: 1, 2, 3, 4, 5); scaffold it with \
+                 `adventcode new --day 9 --title ...`, see \"Adding New Days\" in the README\n");
+}
+
+#[test]
+fn days_outside_1_to_25_are_rejected_by_the_parser() {
+    for day in ["0", "26"] {
+        adventcode()
+            .args(["-d", day])
+            .assert()
+            .code(2)
+            .stdout("")
+            .stderr(predicate::str::contains(format!("invalid value '{}' for '--day <DAY>'", day)))
+            .stderr(predicate::str::contains("1..=25"));
+    }
+}
+
+#[test]
+fn several_days_run_in_order_with_their_headers() {
+    // A crate of its own, so the inputs are the examples
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    for day in [3, 5] {
+        let source = format!("src/day{}.rs", day);
+        std::fs::copy(manifest_dir().join(&source), dir.path().join(source)).unwrap();
+        std::fs::copy(fixture(&format!("day{}_example.txt", day)), dir.path().join(format!("day{}.txt", day))).unwrap();
+    }
+    adventcode()
+        .current_dir(dir.path())
+        .args(["-d", "5,3-3,5", "-q"])
+        .assert()
+        .success()
+        .stdout("357\n3\n");
+
+    let output = adventcode().current_dir(dir.path()).args(["-d", "3,5", "-p", "2"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let day3 = stdout.find("Day 3: ").expect(&stdout);
+    let day5 = stdout.find("Day 5: ").expect(&stdout);
+    assert!(day3 < stdout.find("Result: Total output joltage: 3121910778619").unwrap(), "{}", stdout);
+    assert!(day3 < day5 && day5 < stdout.find("Result: 14").unwrap(), "{}", stdout);
+
+    adventcode()
+        .current_dir(dir.path())
+        .args(["-d", "3,5", "--expected", "3"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("--expected takes a single --day, not 2 days"));
+}
+
+#[test]
+fn bad_day_lists_name_the_available_days() {
+    adventcode()
+        .args(["-d", "1-"])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::contains("'1-' is not a day in 1..=25 or a range like 3-5"));
+    // A range must not take in a day with nothing to run
+    adventcode()
+        .args(["-d", "4-6", "-q"])
+        .assert()
+        .code(69)
+        .stdout("")
+        .stderr(predicate::str::contains("Day 6 is not implemented (implemented days: 1, 2, 3, 4, 5)"));
+}
+
+#[test]
+fn unknown_day_option_is_rejected() {
+    adventcode()
+        .args(["-d", "5", "-q", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .args(["--", "--bogus"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("Day 5 does not understand '--bogus'"));
+}
+
+#[test]
+fn stream_flag_gives_the_same_answer() {
+    adventcode()
+        .args(["-d", "5", "-q", "--stream", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn example_flag_runs_the_header_example() {
+    adventcode()
+        .args(["-d", "5", "-2", "--example"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Result: 14\nExpected: 14\n"));
+}
+
+#[test]
+fn example_flag_prefers_the_example_file() {
+    // A crate whose day 5 has no header comment, so no example of its own
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/day5.rs"), "").unwrap();
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path());
+        cmd
+    };
+    run()
+        .args(["-d", "5", "--example"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("Day 5 has no example: save the puzzle's example as day5_example.txt"));
+
+    std::fs::copy(fixture("day5_example.txt"), dir.path().join("day5_example.txt")).unwrap();
+    run().args(["-d", "5", "--example", "-q"]).assert().success().stdout("3\n");
+    run().args(["--list"]).assert().success().stdout("5\tDay 5\tfalse\ttrue\n");
+    run().args(["-d", "5", "--example", "-f", "day5.txt"]).assert().code(2);
+}
+
+#[test]
+fn input_dir_is_read_before_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(fixture("day5_example.txt"), dir.path().join("day5.txt")).unwrap();
+    adventcode().args(["-d", "5", "-q", "--input-dir"]).arg(dir.path()).assert().success().stdout("3\n");
+
+    // Neither the input directory nor inputs/ has day 4, so stdin is next
+    let missing = dir.path().join("day4.txt");
+    adventcode()
+        .args(["-d", "4", "-q", "--input-dir"])
+        .arg(dir.path())
+        .write_stdin("")
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains(format!("'{}' not found", missing.display())));
+}
+
+#[test]
+fn missing_file_with_empty_stdin_is_a_missing_input_error() {
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "1", "-q", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .write_stdin("")
+        .assert()
+        .code(66)
+        .stdout("")
+        .stderr(predicate::str::contains("missing.txt' not found and nothing was piped on stdin"))
+        .stderr(predicate::str::contains("panicked").not());
+}
+
+#[test]
+fn directory_as_file_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "1", "-q", "-f"])
+        .arg(dir.path())
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains("is a directory, not an input file"));
+}
+
+#[test]
+fn files_over_the_size_limit_are_refused() {
+    adventcode()
+        .args(["-d", "5", "-q", "--max-input-mb", "0", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(66)
+        .stdout("")
+        .stderr(predicate::str::contains("over the 0 MB input limit"));
+
+    adventcode()
+        .args(["-d", "5", "-q", "--max-input-mb", "0", "--allow-large-input", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_file_reports_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("day1.txt");
+    std::fs::write(&path, "L68\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Root (and some CI sandboxes) can read the file anyway; nothing to check then
+    if std::fs::File::open(&path).is_ok() {
+        return;
+    }
+
+    adventcode()
+        .args(["-d", "1", "-q", "-f"])
+        .arg(&path)
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains("exists but could not be read: permission denied"));
+}
+
+#[test]
+fn binary_files_are_not_taken_for_text() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("day1.txt");
+    std::fs::write(&path, b"\x89PNG\r\n\x1a\n\xff").unwrap();
+
+    adventcode()
+        .args(["-d", "1", "-q", "-f"])
+        .arg(&path)
+        .assert()
+        .code(66)
+        .stdout("")
+        .stderr(predicate::str::contains("day1.txt' is not UTF-8 text; is it the puzzle input?"))
+        .stderr(predicate::str::contains("panicked").not());
+    adventcode()
+        .args(["-d", "1", "-q"])
+        .write_stdin(std::fs::read(&path).unwrap())
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains("stdin is not UTF-8 text"));
+}
+
+#[test]
+fn inputs_without_puzzle_text_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let cases: [(&str, &[u8], &str); 3] = [
+        ("empty.txt", b"", "empty.txt' is empty (0 bytes)"),
+        ("spaces.txt", b" \r\n\n\t\n", "spaces.txt' holds only whitespace (6 bytes)"),
+        ("bom.txt", b"\xef\xbb\xbf\n", "bom.txt' holds only whitespace (4 bytes)"),
+    ];
+    for (name, bytes, message) in cases {
+        let path = dir.path().join(name);
+        std::fs::write(&path, bytes).unwrap();
+        for day in ["1", "3", "5"] {
+            adventcode()
+                .args(["-d", day, "-q", "-f"])
+                .arg(&path)
+                .assert()
+                .code(66)
+                .stdout("")
+                .stderr(predicate::str::contains(message))
+                .stderr(predicate::str::contains("pass --allow-empty to solve it anyway"));
+        }
+    }
+    let empty = dir.path().join("empty.txt");
+    adventcode().args(["-d", "3", "-q", "--stream", "-f"]).arg(&empty).assert().code(66);
+    adventcode().args(["-d", "1", "-q", "-f", "-"]).write_stdin("\n\n").assert().code(66).stderr(
+        "Error: stdin holds only whitespace (2 bytes), so there is nothing to solve (pass --allow-empty to solve it anyway)\n",
+    );
+
+    // Asked for, the days solve it as before
+    adventcode().args(["-d", "3", "-q", "--allow-empty", "-f"]).arg(&empty).assert().success().stdout("0\n");
+    adventcode().args(["-d", "1", "-p", "both", "-q", "--allow-empty", "-f"]).arg(&empty).assert().success();
+}
+
+#[test]
+fn output_writes_the_answers_to_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("answers/day3.txt");
+    let example = fixture("day3_example.txt");
+    let read = || std::fs::read_to_string(&path).unwrap();
+
+    // Printed as usual, and written under a directory made for it
+    adventcode()
+        .args(["-d", "3", "--part", "both", "-f"])
+        .arg(&example)
+        .arg("--output")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Result: Total output joltage: 357"));
+    assert_eq!(read(), "Part 1: 357\nPart 2: 3121910778619\n");
+
+    // Replaced by the next run, and with --quiet only written
+    adventcode().args(["-d", "3", "-q", "-f"]).arg(&example).arg("--output").arg(&path).assert().success().stdout("");
+    assert_eq!(read(), "357\n");
+
+    // --append adds to it; a run that fails adds nothing
+    let append = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.args(["-d", "1", "-q", "--append", "--output"]).arg(&path).args(args);
+        cmd
+    };
+    append(&["-f"]).arg(fixture("day1_example.txt")).assert().success();
+    append(&["--strict", "-f", "-"]).write_stdin("X9\n").assert().failure();
+    assert_eq!(read(), "357\n3\n");
+
+    // A path that cannot be written is named in the error
+    let blocked = dir.path().join("answers/day3.txt/day4.txt");
+    adventcode()
+        .args(["-d", "3", "-q", "-f"])
+        .arg(&example)
+        .arg("--output")
+        .arg(&blocked)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(format!("Could not write the answer to {}", blocked.display())));
+    assert_eq!(read(), "357\n3\n");
+}
+
+#[test]
+fn malformed_inputs_are_errors_not_panics() {
+    let run = |day: &str, input: &str, strict: bool| {
+        let mut cmd = adventcode();
+        cmd.args(["-d", day, "-q", "-f", "-"]).write_stdin(input);
+        if strict {
+            cmd.arg("--strict");
+        }
+        cmd.assert().stdout("").stderr(predicate::str::contains("panicked").not())
+    };
+    // A bad line is a warning, and under --strict an error naming the line
+    run("1", "L68\nX12\n", true)
+        .code(1)
+        .stderr("Error: 1 malformed input line(s):\n  line 2: malformed instruction, expected L or R followed by a distance ('X12')\n");
+    run("1", "L68\nLabc\n", true).code(1).stderr(predicate::str::contains("line 2:"));
+    // Day 5 cannot tell its sections apart without the blank line
+    run("5", "3-5\n10-14\n", false)
+        .code(1)
+        .stderr(predicate::str::starts_with("Error: No blank line found in input"));
+    run("5", "3\n\n5\n", false).code(1).stderr(predicate::str::contains("both sections hold bare IDs"));
+}
+
+#[test]
+fn cached_runs_reuse_answers_until_the_input_changes() {
+    let data = tempfile::tempdir().unwrap();
+    let input = data.path().join("day5.txt");
+    std::fs::copy(fixture("day5_example.txt"), &input).unwrap();
+
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path())
+            .args(["-d", "5", "-2", "--cached", "-f"])
+            .arg(&input);
+        cmd
+    };
+
+    run().assert().success().stdout(predicate::str::ends_with("Result: 14\n"));
+    run().assert().success().stdout(predicate::str::ends_with("Result: 14 (cached)\n"));
+
+    // Any edit to the input must miss
+    std::fs::write(&input, "3-5\n\n4\n").unwrap();
+    run().assert().success().stdout(predicate::str::ends_with("Result: 3\n"));
+
+    adventcode()
+        .env("ADVENTCODE_DATA_DIR", data.path())
+        .arg("--clear-cache")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Removed"));
+}
+
+#[test]
+fn list_prints_every_day() {
+    adventcode()
+        .arg("--list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n5\tCAFETERIA\ttrue\t"))
+        .stdout(predicate::str::starts_with("1\t"));
+}
+
+#[test]
+fn list_prints_tab_separated_lines_or_json_for_scripts() {
+    // A crate with day 5's source and example file but no inputs
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::copy(manifest_dir().join("src/day5.rs"), dir.path().join("src/day5.rs")).unwrap();
+    std::fs::copy(fixture("day5_example.txt"), dir.path().join("day5_example.txt")).unwrap();
+    let list = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path()).args(args);
+        cmd
+    };
+    list(&["--list"]).assert().success().stdout("5\tCAFETERIA\tfalse\ttrue\n");
+    list(&["list"]).assert().success().stdout("5\tCAFETERIA\tfalse\ttrue\n");
+    let json = list(&["list", "--json"]).assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json, serde_json::json!([{ "day": 5, "title": "CAFETERIA", "input": false, "example": true }]));
+
+    let json = adventcode().args(["--list", "--json"]).assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    let days: Vec<u64> = json.as_array().unwrap().iter().map(|day| day["day"].as_u64().unwrap()).collect();
+    assert_eq!(days, [1, 2, 3, 4, 5]);
+    assert!(json.as_array().unwrap().iter().all(|day| day["input"] == true));
+
+    // Plain --list is the tab-separated form; there is no --tsv
+    adventcode().args(["--list", "--tsv"]).assert().code(2);
+    adventcode().args(["--json"]).assert().code(2);
+}
+
+#[test]
+fn windows_line_endings_give_the_same_answer() {
+    adventcode()
+        .args(["-d", "4", "-q", "-f"])
+        .arg(fixture("day4_example_crlf.txt"))
+        .assert()
+        .success()
+        .stdout("13\n")
+        .stderr("");
+
+    // Day 5 with an option streams nothing: it reads the rest at once, and
+    // that text loses its BOM and CRLFs too
+    adventcode()
+        .args(["-d", "5", "-q", "--stream", "-f"])
+        .arg(fixture("day5_example_crlf.txt"))
+        .args(["--", "--show-merged"])
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr(predicate::str::contains("malformed").not());
+}
+
+#[test]
+fn ascii_flag_keeps_the_output_ascii() {
+    adventcode()
+        .args(["-d", "5", "-2", "--ascii", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("* Day 5: CAFETERIA | Part 2"))
+        .stdout(predicate::function(|out: &str| out.is_ascii()));
+
+    adventcode()
+        .args(["--list", "--ascii"])
+        .assert()
+        .success()
+        .stdout(predicate::function(|out: &str| out.is_ascii()));
+
+    // A solver's warnings too, on stderr
+    adventcode()
+        .args(["-d", "1", "-q", "--ascii", "-f", "-"])
+        .write_stdin("L68\nX9\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("! line 2: malformed instruction"))
+        .stderr(predicate::function(|err: &str| err.is_ascii()));
+}
+
+#[test]
+fn piped_output_has_no_color() {
+    let plain = |out: &[u8]| !out.contains(&0x1b);
+    // The banner, result line, a FAIL and a warning, each on a pipe
+    for extra in [&[][..], &["--no-color"]] {
+        let output = adventcode()
+            .args(["-d", "5", "-2", "--expected", "13", "-f"])
+            .arg(fixture("day5_example.txt"))
+            .args(extra)
+            .env_remove("NO_COLOR")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Result: 14\nFAIL: expected 13, got 14\n"), "{}", stdout);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("-2/--part2 is deprecated"));
+        assert!(plain(&output.stdout) && plain(&output.stderr));
+    }
+
+    let output =
+        adventcode().args(["-d", "5", "-p", "both", "-q", "--no-color", "-f", "-"]).write_stdin("3-5\n\n4\n").output().unwrap();
+    assert_eq!(output.stdout, b"1\n3\n");
+    assert!(plain(&output.stderr));
+}
+
+#[test]
+fn accessible_flag_spells_out_the_markers() {
+    adventcode()
+        .args(["-d", "5", "-2", "--accessible", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\nDay 5: CAFETERIA | Part 2\n"))
+        .stdout(predicate::function(|out: &str| out.is_ascii()));
+
+    // The list is the same tab-separated lines whatever the markers
+    let list = adventcode().arg("--list").assert().success().get_output().stdout.clone();
+    adventcode().args(["--list", "--accessible"]).assert().success().stdout(list);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compressed_inputs_give_the_same_answers() {
+    for name in ["day5_example.txt.gz", "day5_example.txt.zst"] {
+        for (part, expected) in [(None, "3\n"), (Some("-2"), "14\n")] {
+            adventcode().args(["-d", "5", "-q", "-f"]).arg(fixture(name)).args(part).assert().success().stdout(expected);
+        }
+        adventcode()
+            .args(["-d", "5", "-q", "--stream", "-f"])
+            .arg(fixture(name))
+            .assert()
+            .success()
+            .stdout("3\n");
+    }
+
+    // Compressed stdin is recognised by its magic bytes
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "5", "-q", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .write_stdin(std::fs::read(fixture("day5_example.txt.gz")).unwrap())
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn corrupt_archives_are_reported_by_name() {
+    adventcode()
+        .args(["-d", "5", "-q", "-f"])
+        .arg(fixture("day5_corrupt.txt.gz"))
+        .assert()
+        .code(66)
+        .stdout("")
+        .stderr(predicate::str::contains("day5_corrupt.txt.gz' is not a valid gzip archive"))
+        .stderr(predicate::str::contains("panicked").not());
+}
+
+#[cfg(all(unix, feature = "compression"))]
+#[test]
+fn default_input_may_be_compressed() {
+    // A scratch crate root: day discovery needs src/, the default input sits beside it
+    let dir = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), dir.path().join("src")).unwrap();
+    std::fs::copy(fixture("day5_example.txt.gz"), dir.path().join("day5.txt.gz")).unwrap();
+    adventcode()
+        .current_dir(dir.path())
+        .args(["-d", "5", "-q"])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout("3\n");
+
+    adventcode()
+        .current_dir(dir.path())
+        .arg("--list")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("\n5\tCAFETERIA\ttrue\tfalse\n"));
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn notify_without_a_notification_service_falls_back_to_stderr() {
+    // Point D-Bus at nothing so no desktop ever sees this
+    adventcode()
+        .env("DBUS_SESSION_BUS_ADDRESS", "unix:path=/nonexistent/bus")
+        .args(["-d", "5", "-q", "--notify-no-answer", "--notify-after", "0", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr(predicate::str::contains("Day 5 part 1 finished: Answer: (hidden)"));
+}
+
+#[test]
+fn init_creates_the_project_layout() {
+    let root = tempfile::tempdir().unwrap();
+    let config = root.path().join("config");
+    let init = |stdin: &str, args: &[&str]| {
+        adventcode()
+            .current_dir(root.path())
+            .env("ADVENTCODE_CONFIG_DIR", &config)
+            .arg("init")
+            .args(args)
+            .write_stdin(stdin)
+            .assert()
+            .success()
+    };
+
+    init("", &["--yes"])
+        .stdout(predicate::str::contains("Created adventcode.toml"))
+        .stdout(predicate::str::contains("Skipped"))
+        .stdout(predicate::str::contains("Next steps:"));
+    assert!(root.path().join("adventcode.toml").is_file());
+    assert!(root.path().join("answers.toml").is_file());
+    assert!(root.path().join("inputs").is_dir());
+    assert!(!config.join("session").exists());
+
+    // A second run asks before touching anything, and stores the token privately
+    std::fs::write(root.path().join("answers.toml"), "[day5]\npart1 = \"3\"\n").unwrap();
+    init("\n\nsecret-token\n", &[])
+        .stdout(predicate::str::contains("adventcode.toml already exists"))
+        .stdout(predicate::str::contains("Kept answers.toml"))
+        .stdout(predicate::str::contains("Created"));
+    assert_eq!(std::fs::read_to_string(root.path().join("answers.toml")).unwrap(), "[day5]\npart1 = \"3\"\n");
+    assert_eq!(std::fs::read_to_string(config.join("session")).unwrap(), "secret-token\n");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(config.join("session")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn all_runs_every_day_with_input_and_honors_filters() {
+    // A scratch crate root with example inputs for days 4 and 5 only
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    for day in [4, 5] {
+        std::fs::copy(fixture(&format!("day{}_example.txt", day)), root.path().join(format!("day{}.txt", day))).unwrap();
+    }
+    let all = |args: &[&str]| {
+        let output = adventcode().current_dir(root.path()).args(["--all", "-q"]).args(args).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        // Days 1 to 3 have no input here; one of their rows is enough
+        let wanted = ["Day  1 Part 1", "Day  4", "Day  5"];
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter(|line| wanted.iter().any(|w| line.starts_with(w)))
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        all(&[]),
+        [
+            "Day  1 Part 1: skipped (no input)",
+            "Day  4 Part 1: 13",
+            "Day  4 Part 2: 43",
+            "Day  5 Part 1: 3",
+            "Day  5 Part 2: 14",
+        ]
+    );
+    assert_eq!(
+        all(&["--only-part2", "--skip", "4"]),
+        [
+            "Day  1 Part 1: skipped (by request)",
+            "Day  4 Part 1: skipped (by request)",
+            "Day  4 Part 2: skipped (by request)",
+            "Day  5 Part 1: skipped (by request)",
+            "Day  5 Part 2: 14",
+        ]
+    );
+
+    adventcode()
+        .current_dir(root.path())
+        .args(["--all", "--skip", "1,2,3,4,5"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("leave nothing for --all to run"));
+}
+
+#[cfg(unix)]
+#[test]
+fn all_with_jobs_prints_what_one_at_a_time_does() {
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    for day in 1..=5 {
+        std::fs::copy(fixture(&format!("day{}_example.txt", day)), root.path().join(format!("day{}.txt", day))).unwrap();
+    }
+    let data = root.path().join("data");
+    let all = |args: &[&str]| {
+        let output =
+            adventcode().current_dir(root.path()).env("ADVENTCODE_DATA_DIR", &data).arg("--all").args(args).output().unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // Banners, the report lines of days 1 and 5 (days 2 to 4 fail on
+    // --verbose) and answers come whole and in day order
+    let one = all(&["--", "--verbose"]);
+    assert!(one.contains("Result: Password: 3\n") && one.contains("Day  2 Part 1: failed"), "{}", one);
+    assert_eq!(all(&["--jobs", "4", "--", "--verbose"]), one);
+    assert_eq!(all(&["--jobs", "3", "-q"]), all(&["-q"]));
+
+    // Every part's answer lands in the shared cache
+    all(&["--jobs", "10", "--cached", "-q"]);
+    assert_eq!(all(&["--jobs", "10", "--cached"]).matches("(cached)").count(), 10);
+}
+
+#[test]
+fn rerun_failed_runs_only_the_parts_that_failed_last() {
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    for day in [4, 5] {
+        std::fs::copy(fixture(&format!("day{}_example.txt", day)), root.path().join(format!("day{}.txt", day))).unwrap();
+    }
+    let data = root.path().join("data");
+    std::fs::create_dir(&data).unwrap();
+    let failed = |day, part| {
+        format!("{{\"at\":0,\"day\":{},\"part\":{},\"input\":\"\",\"answer\":\"\",\"millis\":0.0,\"error\":\"boom\"}}\n", day, part)
+    };
+    // Day 5 part 2 failed once but has passed since
+    let passed = "{\"at\":1,\"day\":5,\"part\":2,\"input\":\"x\",\"answer\":\"14\",\"millis\":1.0}\n";
+    let history = [failed(4, 2), failed(5, 1), failed(5, 2), passed.to_string()].concat();
+    std::fs::write(data.join("history.jsonl"), history).unwrap();
+    let rerun = || adventcode().current_dir(root.path()).env("ADVENTCODE_DATA_DIR", &data).args(["--rerun-failed", "-q"]).output().unwrap();
+
+    let output = rerun();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<&str> = stdout.lines().filter(|line| line.starts_with("Day ")).collect();
+    assert_eq!(rows, ["Day  4 Part 2: 43", "Day  5 Part 1: 3"]);
+
+    // Both passed this time, which the history now says
+    let output = rerun();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().starts_with("No failed runs in "));
+
+    // --all records its own failures
+    std::fs::write(root.path().join("day5.txt"), "3-5\nabc\n\n4\n").unwrap();
+    adventcode().current_dir(root.path()).env("ADVENTCODE_DATA_DIR", &data).args(["--all", "-q", "--strict"]).assert().code(1);
+    let output = rerun();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().filter(|line| line.starts_with("Day ")).count(), 2, "{}", stdout);
+    assert!(stdout.starts_with("Day  5 Part 1: "), "{}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn paste_reads_and_saves_the_clipboard() {
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    let paste = |command: String| {
+        let mut cmd = adventcode();
+        cmd.current_dir(root.path()).env("ADVENTCODE_PASTE_COMMAND", command).args(["-d", "5", "-q", "--paste"]);
+        cmd
+    };
+    let copied = format!("cat {}", fixture("day5_example.txt").display());
+
+    paste(copied.clone()).assert().success().stdout("3\n");
+    assert!(!root.path().join("day5.txt").exists());
+    paste(copied.clone()).arg("--save-input").assert().success().stdout("3\n");
+    let saved = std::fs::read_to_string(root.path().join("day5.txt")).unwrap();
+    assert_eq!(saved, std::fs::read_to_string(fixture("day5_example.txt")).unwrap());
+
+    paste("true".into()).assert().code(1).stderr(predicate::str::contains("The clipboard is empty"));
+    paste("printf '\\377'".into()).assert().code(1).stderr(predicate::str::contains("does not hold text"));
+    paste(copied).args(["-f", "day5.txt"]).assert().code(2).stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn expected_reports_pass_or_fail_through_the_exit_code() {
+    adventcode()
+        .args(["-d", "5", "-2", "--expected", " 14\n", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Result: 14\nPASS: 14 (expected 14)\n"));
+
+    adventcode()
+        .args(["-d", "5", "-2", "--expected", "15", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("FAIL: expected 15, got 14"));
+
+    // Quiet keeps stdout to the answer; the verdict goes to stderr
+    adventcode()
+        .args(["-d", "4", "-q", "--expected", "13", "-f"])
+        .arg(fixture("day4_example.txt"))
+        .assert()
+        .success()
+        .stdout("13\n")
+        .stderr("PASS: 13 (expected 13)\n");
+    adventcode()
+        .args(["-d", "4", "-q", "--expected", "Accessible rolls: 13", "-f"])
+        .arg(fixture("day4_example.txt"))
+        .assert()
+        .code(1)
+        .stdout("13\n")
+        .stderr("FAIL: expected Accessible rolls: 13, got 13\n");
+
+    adventcode()
+        .args(["--all", "--expected", "3"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn expect_compares_the_bare_answer_as_text() {
+    let expect = |part: &str, value: &str| {
+        let mut cmd = adventcode();
+        cmd.args(["-d", "5", "-p", part, "-q", "--expect", value, "-f"]).arg(fixture("day5_example.txt"));
+        cmd.assert().stdout(if part == "1" { "3\n" } else { "14\n" })
+    };
+    expect("2", "14").success().stderr("PASS: 14 (expected 14)\n");
+    expect("2", "13").code(1).stderr("FAIL: expected 13, got 14\n");
+    // Not a number, and not parsed as one: compared as text, never an error
+    expect("1", "three").code(1).stderr("FAIL: expected three, got 3\n");
+    expect("1", "3.0").code(1).stderr("FAIL: expected 3.0, got 3\n");
+
+    // A day whose answer is text (an external command's here)
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("adventcode.toml"), "[external]\nday7 = \"tr a-z A-Z\"\n").unwrap();
+    std::fs::write(root.path().join("day7.txt"), "abc,def\n").unwrap();
+    let text = |value: &str| adventcode().current_dir(root.path()).args(["-d", "7", "--expect", value]).assert();
+    text(" ABC,DEF\n").success().stdout(predicate::str::ends_with("Result: ABC,DEF\nPASS: ABC,DEF (expected ABC,DEF)\n"));
+    text("abc,def").code(1).stdout(predicate::str::ends_with("FAIL: expected abc,def, got ABC,DEF\n"));
+}
+
+#[test]
+fn limit_lines_solves_a_marked_prefix_of_the_input() {
+    // Seven lines keep the ranges, the blank line and the IDs 1 and 5
+    adventcode()
+        .args(["-d", "5", "-q", "--limit-lines", "7", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("1\n")
+        .stderr(predicate::str::contains("TRUNCATED INPUT (7 lines of 11)"));
+    adventcode()
+        .args(["-d", "5", "--limit-lines", "7", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Result: 1 (TRUNCATED INPUT)"));
+    // No cut at all when the input is that short
+    adventcode()
+        .args(["-d", "5", "-q", "--limit-lines", "11", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr(predicate::str::contains("TRUNCATED").not());
+
+    adventcode()
+        .args(["-d", "5", "-q", "--limit-lines", "4", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("stop before the blank line between the sections; keep at least 5 lines"));
+    adventcode()
+        .args(["-d", "5", "--limit-lines", "7", "--expected", "3"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn diff_compares_with_the_last_recorded_run() {
+    let data = tempfile::tempdir().unwrap();
+    let history = data.path().join("history.jsonl");
+    let input = std::fs::read_to_string(fixture("day5_example.txt")).unwrap();
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path()).args(["-d", "5", "-2", "--diff", "-f"]).arg(fixture("day5_example.txt"));
+        cmd
+    };
+
+    run()
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Diff: no earlier run on this input, recorded this one\n"));
+
+    // A seeded record with a different answer, newer than the real one
+    let seeded = format!(
+        "{{\"at\":1,\"day\":5,\"part\":2,\"input\":\"{}\",\"answer\":\"13\",\"millis\":60000.0}}\n",
+        adventcode::cache::input_hash(&input)
+    );
+    std::fs::write(&history, std::fs::read_to_string(&history).unwrap() + &seeded).unwrap();
+    run()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("⚠ Diff: answer CHANGED (was 13, now 14); 60.00 s → "))
+        .stdout(predicate::str::contains("(-60.00 s, -100.0%)"));
+
+    // That run was recorded too, so the next one matches it
+    run().assert().success().stdout(predicate::str::contains("Diff: answer identical; "));
+    assert_eq!(std::fs::read_to_string(&history).unwrap().lines().count(), 4);
+}
+
+#[test]
+fn format_templates_replace_the_banner() {
+    let input = fixture("day5_example.txt");
+    adventcode()
+        .args(["-d", "5", "-2", "--format", "{day}.{part} {title} {{{answer}}} <{input}>", "-f"])
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(format!("5.2 CAFETERIA {{14}} <{}>\n", input.display()));
+
+    adventcode()
+        .args(["-d", "5", "--example", "--format", "{answer} {duration_ms}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^3 \d+\.\d{3}\n$").unwrap());
+
+    adventcode()
+        .args(["-d", "5", "--format", "{day} {time}"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown placeholder {time}"));
+}
+
+#[test]
+fn pretty_only_touches_the_result_line() {
+    adventcode()
+        .args(["-d", "3", "-2", "--pretty", "-f"])
+        .arg(fixture("day3_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Result: Total output joltage: 3,121,910,778,619\n"));
+    adventcode()
+        .args(["-d", "3", "-2", "--pretty", "-q", "-f"])
+        .arg(fixture("day3_example.txt"))
+        .assert()
+        .success()
+        .stdout("3121910778619\n");
+}
+
+#[test]
+fn clean_previews_then_deletes_the_chosen_files() {
+    let data = tempfile::tempdir().unwrap();
+    std::fs::write(data.path().join("cache.json"), "{}").unwrap();
+    std::fs::write(data.path().join("history.jsonl"), "{}\n").unwrap();
+    let clean = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path()).arg("clean").args(args);
+        cmd
+    };
+
+    clean(&["--all", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Would remove 2 item(s), 5 bytes in total\n"));
+    assert!(data.path().join("cache.json").exists());
+
+    clean(&["--cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache.json (2 bytes)"))
+        .stdout(predicate::str::contains("history").not());
+    assert!(!data.path().join("cache.json").exists());
+    assert!(data.path().join("history.jsonl").exists());
+
+    // Nothing chosen is a usage error
+    clean(&[]).assert().code(2);
+}
+
+#[test]
+fn thread_count_never_changes_the_output() {
+    for (day, fixture_name) in [("2", "day2_example.txt"), ("3", "day3_example.txt"), ("4", "day4_example.txt")] {
+        let run = |threads: Option<&str>| {
+            let mut cmd = adventcode();
+            cmd.args(["-d", day, "-2", "-f"]).arg(fixture(fixture_name));
+            if let Some(threads) = threads {
+                cmd.args(["--max-threads", threads]);
+            }
+            let output = cmd.output().unwrap();
+            assert!(output.status.success());
+            output.stdout
+        };
+        let default = run(None);
+        assert_eq!(run(Some("1")), default, "day {}", day);
+        assert_eq!(run(Some("2")), default, "day {}", day);
+    }
+
+    adventcode().args(["-d", "3", "--max-threads", "0"]).assert().code(2);
+}
+
+#[test]
+fn bench_baselines_are_saved_then_checked() {
+    let data = tempfile::tempdir().unwrap();
+    let bench = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path()).args(["-d", "5", "--bench", "3", "-f"]).arg(fixture("day5_example.txt"));
+        cmd.args(args);
+        cmd
+    };
+
+    bench(&["--baseline", "check"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("No baseline for Day 5 Part 1"));
+    bench(&["--baseline", "save"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Result: 3\n"))
+        .stdout(predicate::str::contains("over 3 runs"))
+        .stdout(predicate::str::contains("Saved the baseline for Day 5 Part 1"));
+    assert!(data.path().join("bench-baselines.json").exists());
+
+    // Microsecond timings jitter, so only a generous tolerance is reliable here
+    bench(&["--baseline", "check", "--tolerance", "100000%"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Day 5 Part 1: "))
+        .stdout(predicate::str::contains("within 100000%"));
+
+    // A different input still compares, with a warning
+    let other = data.path().join("day5.txt");
+    std::fs::write(&other, "3-5\n\n4\n").unwrap();
+    let mut cmd = adventcode();
+    cmd.env("ADVENTCODE_DATA_DIR", data.path()).args(["-d", "5", "--bench", "3", "--baseline", "check", "--tolerance", "100000%", "-f"]);
+    cmd.arg(&other).assert().success().stderr(predicate::str::contains("recorded with a different input"));
+    adventcode().args(["-d", "5", "--tolerance", "5%"]).assert().code(2);
+}
+
+#[test]
+fn bench_reports_the_range_and_refuses_stdin() {
+    adventcode()
+        .args(["-d", "5", "-q", "--bench", "4", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n")
+        .stderr(predicate::str::is_match(r"^Bench: median .* over 4 runs \(min .*, mean .*, max .*\)\n$").unwrap());
+    // Day 1 labels its answer ("Password: 3"); quiet benches print the value alone
+    adventcode().args(["-d", "1", "-q", "--bench", "2", "-f"]).arg(fixture("day1_example.txt")).assert().success().stdout("3\n");
+
+    let dir = tempfile::tempdir().unwrap();
+    adventcode()
+        .args(["-d", "5", "--bench", "4", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .pipe_stdin(fixture("day5_example.txt"))
+        .unwrap()
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("--bench cannot read stdin, which can only be read once"));
+}
+
+#[test]
+fn output_formats_replace_the_banner_and_summary() {
+    let assert = adventcode().args(["--all", "--skip", "1,2,3,4", "--output-format", "json"]).assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let document: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let results = document["results"].as_array().unwrap();
+    let statuses: Vec<&str> = results.iter().map(|row| row["status"].as_str().unwrap()).collect();
+    assert_eq!(statuses, ["skipped"; 8].iter().copied().chain(["ok", "ok"]).collect::<Vec<_>>());
+    assert_eq!(results[8]["answer"], "862");
+    assert_eq!(results[0]["error"], "by request");
+
+    adventcode()
+        .args(["-d", "5", "--output-format", "csv", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("day,part,title,status,answer,duration_ms,input,error\n5,1,CAFETERIA,ok,3,"));
+    adventcode().args(["-d", "5", "--output-format", "csv", "--format", "{answer}"]).assert().code(2);
+}
+
+#[test]
+fn files_runs_every_match_in_path_order() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(fixture("day5_example.txt"), dir.path().join("day5a.txt")).unwrap();
+    std::fs::write(dir.path().join("day5c.txt"), "3-5\n\n4\n9\n").unwrap();
+    std::fs::write(dir.path().join("day5b.txt"), "3-5\n10-14\n").unwrap();
+    std::fs::write(dir.path().join("day4.txt"), "@@\n").unwrap();
+    let pattern = dir.path().join("day5*.txt").display().to_string();
+
+    let assert = adventcode().args(["-d", "5", "-q", "--files", &pattern]).assert().code(1);
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], ["File", "Answer", "Time"]);
+    let file = |name: &str| dir.path().join(name).display().to_string();
+    assert_eq!(rows[1][..2], [file("day5a.txt").as_str(), "3"]);
+    assert_eq!(rows[2][..3], [file("day5b.txt").as_str(), "error:", "No"]);
+    assert_eq!(rows[3][..2], [file("day5c.txt").as_str(), "1"]);
+
+    let assert = adventcode().args(["-d", "5", "--files", &pattern, "--output-format", "json"]).assert().code(1);
+    let document: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    let results = document["results"].as_array().unwrap();
+    let inputs: Vec<&str> = results.iter().map(|row| row["input"].as_str().unwrap()).collect();
+    assert_eq!(inputs, [file("day5a.txt"), file("day5b.txt"), file("day5c.txt")]);
+    assert_eq!(results[1]["status"], "failed");
+    assert_eq!(results[2]["answer"], "1");
+
+    adventcode().args(["-d", "5", "--files", &pattern, "-f", "day5.txt"]).assert().code(2);
+    adventcode()
+        .args(["-d", "5", "--files", &dir.path().join("day9*").display().to_string()])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no files match"));
+}
+
+#[test]
+fn emit_intermediate_writes_each_days_artifacts() {
+    let root = tempfile::tempdir().unwrap();
+    let dir = root.path().join("debug").join("run");
+    for day in 1..=5 {
+        adventcode()
+            .args(["-d", &day.to_string(), "-q", "--emit-intermediate"])
+            .arg(&dir)
+            .arg("-f")
+            .arg(fixture(&format!("day{}_example.txt", day)))
+            .assert()
+            .success();
+    }
+
+    let mut names: Vec<String> =
+        std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+    let rounds = (1..=9).map(|round| format!("day4_round_{:02}.txt", round));
+    let mut expected: Vec<String> =
+        ["day1_positions.txt", "day2_merged.txt", "day2_ranges.txt", "day3_part1_selections.txt", "day4_grid.txt"]
+            .into_iter()
+            .map(String::from)
+            .chain(rounds)
+            .chain(["day5_merged.txt".to_string(), "day5_ranges.txt".to_string()])
+            .collect();
+    expected.sort();
+    assert_eq!(names, expected);
+
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(read("day5_merged.txt"), "3-5\n10-20\n");
+    // The 13 rolls part 1 counts are the first round's
+    let round = read("day4_round_01.txt");
+    assert_eq!(round.lines().next(), Some("..xx.xx@x."));
+    assert_eq!(round.matches('x').count(), 13);
+    assert_eq!(read("day1_positions.txt").lines().nth(3), Some("line 3: R48 -> 0"));
+}
+
+#[cfg(unix)]
+#[test]
+fn external_days_run_like_native_ones() {
+    // Day 7 lives in a shell script: it counts its input's lines, and fails
+    // loudly on part 2
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    std::fs::write(
+        root.path().join("day7.sh"),
+        "if [ \"$1\" = 2 ]; then echo 'part 2 is not done' >&2; exit 2; fi\nwc -l | tr -d ' '\n",
+    )
+    .unwrap();
+    std::fs::write(root.path().join("adventcode.toml"), "[external]\nday7 = \"sh day7.sh {part}\"\n").unwrap();
+    std::fs::write(root.path().join("day7.txt"), "a\nb\nc\n").unwrap();
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.current_dir(root.path());
+        cmd
+    };
+
+    run().arg("--list").assert().success().stdout(predicate::str::contains("\n7\tDay 7\ttrue\tfalse\n"));
+    run().args(["-d", "7", "-q"]).assert().success().stdout("3\n");
+    run().args(["-d", "7", "--expected", "3"]).assert().success();
+    run()
+        .args(["-d", "7", "-2", "-q"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`sh day7.sh 2` failed (exit status: 2); stderr: part 2 is not done"));
+    run()
+        .args(["--all", "-q", "--skip", "1,2,3,4,5"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Day  7 Part 1: 3"))
+        .stdout(predicate::str::contains("Day  7 Part 2: failed:"));
+}
+
+#[test]
+fn assert_max_time_judges_finished_runs() {
+    adventcode()
+        .args(["-d", "5", "-q", "--assert-max-time", "1m", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n");
+    // Nothing finishes in no time: the answer still prints, then the run fails
+    adventcode()
+        .args(["-d", "5", "-q", "--assert-max-time", "0ms", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(1)
+        .stdout("3\n")
+        .stderr(predicate::str::contains("1 part(s) over the time budget:"))
+        .stderr(predicate::str::contains("Day  5 Part 1: took "))
+        .stderr(predicate::str::contains("over its 0.000 ms budget"));
+    adventcode()
+        .args(["-d", "5", "--assert-max-time", "soon"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("not a duration"));
+}
+
+#[test]
+fn trace_json_writes_nested_complete_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("trace.json");
+    adventcode()
+        .args(["-d", "4", "-2", "-q", "--trace-json"])
+        .arg(&path)
+        .arg("-f")
+        .arg(fixture("day4_example.txt"))
+        .assert()
+        .success()
+        .stdout("43\n");
+
+    let events: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    for event in &events {
+        assert_eq!(event["ph"], "X");
+        assert!(event["name"].is_string() && event["ts"].is_number() && event["dur"].is_number(), "{}", event);
+    }
+    let find = |name: &'static str| events.iter().filter(move |e| e["name"] == name);
+    let within = |inner: &serde_json::Value, outer: &serde_json::Value| {
+        let (ts, dur) = (inner["ts"].as_f64().unwrap(), inner["dur"].as_f64().unwrap());
+        let (start, end) = (outer["ts"].as_f64().unwrap(), outer["ts"].as_f64().unwrap() + outer["dur"].as_f64().unwrap());
+        ts >= start && ts + dur <= end
+    };
+    assert_eq!(find("read input").count(), 1);
+    let solve = find("solve part 2").next().unwrap();
+    assert_eq!(solve["args"]["day"], 4);
+    assert!(within(find("parse").next().unwrap(), solve));
+    // Nine rounds remove rolls and a tenth finds none left
+    let rounds: Vec<&serde_json::Value> = find("round").collect();
+    assert_eq!(rounds.len(), 10);
+    assert_eq!(rounds[9]["args"]["round"], 10);
+    assert!(rounds.iter().all(|round| within(round, solve)));
+}
+
+/// A one-thread HTTP server on a free local port, answering every request
+/// with `respond(path, request head)`; returns its base URL and a count of
+/// the requests it has served
+#[cfg(feature = "fetch")]
+fn serve(
+    respond: impl Fn(&str, &str) -> (u16, Vec<u8>) + Send + 'static,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let served = Arc::new(AtomicUsize::new(0));
+    let count = Arc::clone(&served);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut head = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            while reader.read_line(&mut head).is_ok_and(|n| n > 0) && !head.ends_with("\r\n\r\n") {}
+            let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let (status, body) = respond(&path, &head);
+            count.fetch_add(1, Ordering::SeqCst);
+            let reason = if status == 200 { "OK" } else { "Not Found" };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                reason,
+                body.len()
+            );
+            let _ = stream.write_all(&body);
+        }
+    });
+    (base, served)
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn input_url_downloads_keeps_and_rejects_inputs() {
+    use std::sync::atomic::Ordering;
+
+    let example = std::fs::read(fixture("day5_example.txt")).unwrap();
+    let (base, served) = serve(move |path, _| match path {
+        "/day5.txt" => (200, example.clone()),
+        "/binary" => (200, vec![b'1', 0xff, 0xfe, b'\n']),
+        _ => (404, b"no such input".to_vec()),
+    });
+    let data = tempfile::tempdir().unwrap();
+    let run = || {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path());
+        cmd
+    };
+    let url = format!("{}/day5.txt", base);
+
+    run().args(["-d", "5", "-q", "--input-url", &url]).assert().success().stdout("3\n");
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+    // The second run reads the kept copy; --no-cache downloads again
+    run().args(["-d", "5", "-2", "-q", "--input-url", &url]).assert().success().stdout("14\n");
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+    run().args(["-d", "5", "-2", "-q", "--no-cache", "--input-url", &url]).assert().success().stdout("14\n");
+    assert_eq!(served.load(Ordering::SeqCst), 2);
+    assert_eq!(std::fs::read_dir(data.path().join("inputs")).unwrap().count(), 1);
+
+    run()
+        .args(["-d", "5", "-q", "--input-url", &format!("{}/day6.txt", base)])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("/day6.txt answered HTTP 404 Not Found"));
+    run()
+        .args(["-d", "5", "-q", "--input-url", &format!("{}/binary", base)])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("/binary did not send UTF-8 text (invalid byte at offset 1)"));
+    run()
+        .args(["-d", "5", "--input-url", &url, "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[cfg(all(unix, feature = "fetch"))]
+#[test]
+fn input_url_sends_the_configured_credentials() {
+    let example = std::fs::read(fixture("day5_example.txt")).unwrap();
+    let (base, _) = serve(move |_, head| {
+        let authorized = head.lines().any(|line| line.eq_ignore_ascii_case("authorization: Bearer t0ken"))
+            && head.lines().any(|line| line.eq_ignore_ascii_case("cookie: session=abc"));
+        if authorized { (200, example.clone()) } else { (404, Vec::new()) }
+    });
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("adventcode.toml"), "[input_url]\nbearer = \"t0ken\"\ncookie = \"session=abc\"\n")
+        .unwrap();
+
+    adventcode()
+        .current_dir(root.path())
+        .env("ADVENTCODE_DATA_DIR", root.path().join("data"))
+        .args(["-d", "5", "-q", "--input-url", &format!("{}/private/day5.txt", base)])
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn repl_solves_from_stdin_commands_until_quit() {
+    let script = format!("use day 5\nload {}\ngo\nuse part 2\ngo\nquit\ngo\n", fixture("day5_example.txt").display());
+    adventcode()
+        .arg("repl")
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("aoc> Using Day 5 Part 1\n"))
+        .stdout(predicate::str::contains("Input: ").and(predicate::str::contains("day5_example.txt (11 lines)\n")))
+        .stdout(predicate::str::is_match(r"aoc> Day 5 Part 1: 3 \([^)]*\)\n").unwrap())
+        .stdout(predicate::str::is_match(r"aoc> Day 5 Part 2: 14 \([^)]*\)\naoc> $").unwrap());
+}
+
+#[test]
+fn notes_round_trip_and_stay_out_of_answers() {
+    let data = tempfile::tempdir().unwrap();
+    let notes = || {
+        let mut cmd = adventcode();
+        cmd.env("ADVENTCODE_DATA_DIR", data.path()).args(["notes", "--day", "5"]);
+        cmd
+    };
+    notes().assert().success().stdout("").stderr("Day 5 has no note\n");
+    notes()
+        .args(["--set", "too low twice\n  off-by-one in the merge?\n"])
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Saved the note for Day 5 to "));
+    notes().assert().success().stdout("too low twice\n  off-by-one in the merge?\n");
+    assert!(data.path().join("notes").join("day5.md").exists());
+
+    adventcode()
+        .env("ADVENTCODE_DATA_DIR", data.path())
+        .args(["-d", "5", "-q", "-f"])
+        .arg(fixture("day5_example.txt"))
+        .assert()
+        .success()
+        .stdout("3\n");
+
+    notes().arg("--delete").assert().success().stderr("");
+    notes().assert().success().stdout("");
+    notes().arg("--delete").assert().success().stderr("Day 5 has no note\n");
+}
+
+/// Copy the directory `from` into `to`, recursively
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()));
+        } else {
+            std::fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+#[test]
+fn new_scaffolds_a_day_that_builds_and_runs() {
+    let root = tempfile::tempdir().unwrap();
+    copy_dir(&manifest_dir().join("src"), &root.path().join("src"));
+    for file in ["Cargo.toml", "Cargo.lock", "build.rs"] {
+        std::fs::copy(manifest_dir().join(file), root.path().join(file)).unwrap();
+    }
+    let new = |day: &str| {
+        let mut cmd = adventcode();
+        cmd.current_dir(root.path()).args(["new", "--day", day, "--title", "Warehouse"]);
+        cmd
+    };
+    new("6").assert().success().stdout(predicate::str::starts_with("Wrote src/day6.rs\nWrote src/lib.rs\n"));
+    let lib = std::fs::read_to_string(root.path().join("src/lib.rs")).unwrap();
+    assert!(lib.contains("pub mod day5;\npub mod day6;\n"));
+    assert!(lib.contains("solve: day6::solve, stream: None, source: include_str!(\"day6.rs\") },\n];"));
+    adventcode()
+        .current_dir(root.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n6\tWarehouse\t"));
+    // This binary predates the day, which it says instead of suggesting `new`
+    adventcode()
+        .current_dir(root.path())
+        .args(["-d", "6"])
+        .assert()
+        .code(69)
+        .stderr(predicate::str::contains("src/day6.rs is there, but this binary was built without it"));
+
+    // Neither an existing day nor a second `new` touches anything
+    let day6 = std::fs::read_to_string(root.path().join("src/day6.rs")).unwrap();
+    new("6").assert().code(1).stderr(predicate::str::contains("day6.rs already exists; not overwriting it"));
+    new("5").assert().code(1).stderr(predicate::str::contains("day5.rs already exists"));
+    assert_eq!(std::fs::read_to_string(root.path().join("src/day6.rs")).unwrap(), day6);
+    assert_eq!(std::fs::read_to_string(root.path().join("src/lib.rs")).unwrap(), lib);
+
+    // The scaffolded crate compiles (a target directory of its own keeps
+    // this cargo off the lock the running one holds)
+    let status = std::process::Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .current_dir(root.path())
+        .env("CARGO_TARGET_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("new-day"))
+        .args(["check", "--lib", "--offline", "--no-default-features", "--quiet"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_reruns_on_each_change_until_ctrl_c() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("day5.txt");
+    let example = std::fs::read_to_string(fixture("day5_example.txt")).unwrap();
+    std::fs::write(&input, &example).unwrap();
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_adventcode"))
+        .current_dir(manifest_dir())
+        .env("ADVENTCODE_DATA_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("data"))
+        .args(["-d", "5", "--watch", "-q", "-f"])
+        .arg(&input)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (tx, rx) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    std::thread::spawn(move || stdout.lines().for_each(|line| tx.send(line.unwrap()).unwrap()));
+    let next = || rx.recv_timeout(Duration::from_secs(10)).expect("an answer");
+
+    assert_eq!(next(), "3");
+    std::fs::write(&input, example.replace("17\n", "")).unwrap();
+    assert_eq!(next(), "2");
+    // A deleted input is waited for, not read from stdin
+    std::fs::remove_file(&input).unwrap();
+    std::thread::sleep(Duration::from_millis(600));
+    std::fs::write(&input, &example).unwrap();
+    assert_eq!(next(), "3");
+
+    std::process::Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("day5.txt not found; waiting for it"), "stderr: {}", stderr);
+}
+
+#[test]
+fn timeout_stops_a_runaway_part_and_all_goes_on() {
+    // Day 2's part 2 would go through nine billion 10-digit patterns for
+    // this range, and day 1's part 2 turn the dial 80 billion clicks
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    for day in [1, 2, 5] {
+        let source = format!("src/day{}.rs", day);
+        std::fs::copy(manifest_dir().join(&source), dir.path().join(source)).unwrap();
+    }
+    std::fs::write(dir.path().join("day1.txt"), "R4000000000\n".repeat(20)).unwrap();
+    std::fs::write(dir.path().join("day2.txt"), "1-18446744073709551615\n").unwrap();
+    std::fs::copy(fixture("day5_example.txt"), dir.path().join("day5.txt")).unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path()).args(args).timeout(std::time::Duration::from_secs(30));
+        cmd
+    };
+
+    run(&["-d", "2", "-p", "2", "-q", "--timeout", "0.2"])
+        .assert()
+        .code(124)
+        .stdout("")
+        .stderr("Error: Day 2 Part 2 timed out after 0.2s\n");
+    // --per-range generates part 2's candidates range by range, and looks too
+    run(&["-d", "2", "-p", "2", "-q", "--timeout", "0.2", "--", "--per-range"])
+        .assert()
+        .code(124)
+        .stderr("Error: Day 2 Part 2 timed out after 0.2s\n");
+    run(&["-d", "1", "-p", "2", "-q", "--timeout", "0.2"])
+        .assert()
+        .code(124)
+        .stderr("Error: Day 1 Part 2 timed out after 0.2s\n");
+    run(&["--all", "-q", "--timeout", "0.2"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Day  1 Part 2: failed: Day 1 Part 2 timed out after 0.2s\n"))
+        .stdout(predicate::str::contains("Day  2 Part 2: failed: Day 2 Part 2 timed out after 0.2s\n"))
+        .stdout(predicate::str::contains("Day  5 Part 2: 14\n"));
+    run(&["-d", "5", "-q", "--timeout", "5"]).assert().success().stdout("3\n");
+    run(&["-d", "5", "--timeout", "0"]).assert().code(1);
+}
+
+#[test]
+fn settings_come_from_flags_then_adventcode_toml_then_the_user_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("crate");
+    let user = dir.path().join("config");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir(&user).unwrap();
+    std::fs::copy(manifest_dir().join("src/day5.rs"), root.join("src/day5.rs")).unwrap();
+    // Part 1 answers 0 to 3, one per directory
+    for (inputs, ids) in [(".", "1"), ("flag", "4"), ("local", "4\n5"), ("user", "3\n4\n5")] {
+        std::fs::create_dir_all(root.join(inputs)).unwrap();
+        std::fs::write(root.join(inputs).join("day5.txt"), format!("3-5\n\n{}\n", ids)).unwrap();
+    }
+    let run = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.current_dir(&root).env("ADVENTCODE_CONFIG_DIR", &user).args(args);
+        cmd
+    };
+    let timed = predicate::str::starts_with("Day 5 Part 1 time: ");
+
+    std::fs::write(user.join("config.toml"), "input_dir = \"user\"\nquiet = true\ntime = true\n").unwrap();
+    run(&["-d", "5"]).assert().success().stdout("3\n").stderr(timed.clone());
+    std::fs::write(root.join("adventcode.toml"), "input_dir = \"local\"\ntime = false\n").unwrap();
+    run(&["-d", "5"]).assert().success().stdout("2\n").stderr("");
+    run(&["-d", "5", "--input-dir", "flag", "--time"]).assert().success().stdout("1\n").stderr(timed);
+    run(&["-d", "5", "--no-config"]).assert().success().stdout(predicate::str::contains("Result: 0\n"));
+
+    std::fs::write(user.join("config.toml"), "quiet = \"yes\"\n").unwrap();
+    run(&["-d", "5"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::ends_with("config.toml: quiet must be true or false\n"));
+    run(&["-d", "5", "--no-config"]).assert().success();
+}
+
+#[test]
+fn completion_scripts_ask_the_binary_for_days() {
+    let script = |shell: &str| {
+        let output = adventcode().args(["completions", shell]).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let bash = script("bash");
+    assert!(bash.contains("adventcode list 2>/dev/null"));
+    assert!(bash.contains("complete -F _adventcode_with_days -o nosort"));
+    let zsh = script("zsh");
+    assert!(zsh.starts_with("#compdef adventcode\n"));
+    assert!(zsh.contains("adventcode list 2>/dev/null"));
+    assert!(zsh.contains("'--day=[The day to time]:DAY:_adventcode_days'"));
+    // `new --day` names a day that is not there yet
+    assert!(zsh.contains("'--day=[The day to create]:DAY:'"));
+    let fish = script("fish");
+    assert!(fish.contains("function __fish_adventcode_days\n    adventcode list 2>/dev/null"));
+    assert!(fish.contains("-s d -l day -d 'The day the note is about' -r -f -a \"(__fish_adventcode_days)\""));
+
+    adventcode().args(["completions", "tcsh"]).assert().code(2);
+}
+
+#[cfg(unix)]
+#[test]
+fn bash_completes_days_that_exist() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("adventcode.bash");
+    let output = adventcode().args(["completions", "bash"]).output().unwrap();
+    std::fs::write(&script, output.stdout).unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    for day in [2, 5] {
+        let source = format!("src/day{}.rs", day);
+        std::fs::copy(manifest_dir().join(&source), dir.path().join(source)).unwrap();
+    }
+
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_adventcode")).parent().unwrap();
+    let path = format!("{}:{}", bin_dir.display(), std::env::var("PATH").unwrap_or_default());
+    let complete = |words: &str| {
+        let program = format!(
+            "source {}; COMP_WORDS=({} ''); COMP_CWORD=${{#COMP_WORDS[@]}}-1; _adventcode_with_days; echo \"${{COMPREPLY[*]}}\"",
+            script.display(),
+            words
+        );
+        let output = std::process::Command::new("bash")
+            .args(["-c", &program])
+            .current_dir(dir.path())
+            .env("PATH", &path)
+            .env("ADVENTCODE_CONFIG_DIR", dir.path().join("config"))
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(complete("adventcode -d"), "2 5\n");
+    assert_eq!(complete("adventcode bench --day"), "2 5\n");
+    assert_eq!(complete("adventcode new --day"), "\n");
+}
+
+#[test]
+fn verbose_says_what_was_found_and_read_on_stderr() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::copy(manifest_dir().join("src/day5.rs"), dir.path().join("src/day5.rs")).unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = adventcode();
+        cmd.current_dir(dir.path()).args(args);
+        cmd
+    };
+
+    // Off by default: the bare answer and nothing else
+    run(&["-d", "5", "-q"]).pipe_stdin(fixture("day5_example.txt")).unwrap().assert().success().stdout("3\n").stderr("");
+
+    let output = run(&["-d", "5", "-q", "-v"]).pipe_stdin(fixture("day5_example.txt")).unwrap().output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for line in [
+        "debug: found a day module day=5 path=src/day5.rs\n",
+        "debug: looked for day1.rs to day25.rs dir=src found=1\n",
+        "debug: title from the `DAY N: TITLE` header line day=5 line=2 title=CAFETERIA\n",
+        "debug: reading the input day=5 source=stdin why=\"no input file\"\n",
+        "debug: read the input day=5 bytes=38 lines=11\n",
+        "debug: parsed the sections fresh=4 available=6 swapped=false malformed=0\n",
+    ] {
+        assert!(stderr.contains(line), "{} not in\n{}", line, stderr);
+    }
+    assert!(!stderr.contains("no day module"), "{}", stderr);
+
+    let output = run(&["-vv", "--list"]).output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("trace: no day module day=6 path=src/day6.rs\n"), "{}", stderr);
+    assert!(stderr.contains("trace: input files day=5 input=day5.txt exists=false example=none\n"), "{}", stderr);
+}
@@ -0,0 +1,61 @@
+// Builds with inputs baked in
+//
+// Builds the binary with the `embedded-inputs` feature, embedding day 5's
+// example from a scratch inputs directory, then runs it where day5.txt has
+// been deleted: the embedded copy must take over, and --list must say so.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Build the binary with `inputs` embedded and return its path
+fn build_embedded(inputs: &Path) -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("embedded-inputs");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir())
+        .env("ADVENTCODE_INPUTS_DIR", inputs)
+        .args(["build", "--quiet", "--no-default-features", "--features", "embedded-inputs"])
+        .args(["--bin", "adventcode", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "embedded-inputs build failed");
+    target_dir.join("debug").join("adventcode")
+}
+
+#[test]
+fn embedded_input_stands_in_for_a_deleted_file() {
+    let example = fs::read_to_string(manifest_dir().join("tests/fixtures/day5_example.txt")).unwrap();
+    let inputs = tempfile::tempdir().unwrap();
+    fs::write(inputs.path().join("day5.txt"), &example).unwrap();
+    let bin = build_embedded(inputs.path());
+
+    // A scratch crate root: day discovery needs src/, and day5.txt starts out there
+    let root = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(manifest_dir().join("src"), root.path().join("src")).unwrap();
+    let day5 = root.path().join("day5.txt");
+    fs::write(&day5, "1-1\n\n1\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(&bin).current_dir(root.path()).env("LC_ALL", "C.UTF-8").args(args).output().unwrap();
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // The file on disk wins while it exists
+    assert_eq!(run(&["-d", "5", "-q"]), "1\n");
+    assert!(run(&["--list"]).contains("\n5\tCAFETERIA\ttrue\t"));
+
+    fs::remove_file(&day5).unwrap();
+    assert_eq!(run(&["-d", "5", "-q"]), "3\n");
+    assert_eq!(run(&["-d", "5", "-q", "-2"]), "14\n");
+    // --list counts only files on disk, not the embedded copy
+    assert!(run(&["--list"]).contains("\n5\tCAFETERIA\tfalse\t"));
+    assert!(run(&["--list"]).contains("\n4\tPRINTING DEPARTMENT\tfalse\t"));
+}
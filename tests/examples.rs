@@ -0,0 +1,158 @@
+// Golden tests: every day's solver against its documented example
+//
+// Expected answers live in tests/fixtures/examples.txt, and days whose header
+// comment carries an EXAMPLE section are checked against that too. Every
+// mismatch is collected before failing, so one run shows the full blast
+// radius of a refactor instead of stopping at the first broken day.
+
+use std::fs;
+use std::path::PathBuf;
+
+use adventcode::input;
+use adventcode::meta;
+use adventcode::options::DayOptions;
+use adventcode::output::Output;
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+/// Parse the manifest into (day, [part1, part2]) rows
+fn manifest() -> Vec<(u8, [String; 2])> {
+    let text = fs::read_to_string(fixtures_dir().join("examples.txt")).expect("missing examples.txt");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            assert_eq!(fields.len(), 3, "bad manifest line: {}", line);
+            let day = fields[0].parse().expect("bad day number");
+            (day, [fields[1].to_string(), fields[2].to_string()])
+        })
+        .collect()
+}
+
+#[test]
+fn examples_match_expected_answers() {
+    let rows = manifest();
+    let mut failures = Vec::new();
+
+    for day in adventcode::DAYS {
+        if !rows.iter().any(|(number, _)| *number == day.number) {
+            failures.push(format!("day {}: no entry in examples.txt", day.number));
+        }
+    }
+
+    for (number, expected) in &rows {
+        let Some(day) = adventcode::day(*number) else {
+            failures.push(format!("day {}: listed in examples.txt but not implemented", number));
+            continue;
+        };
+        let path = fixtures_dir().join(format!("day{}_example.txt", number));
+        let Ok(input) = fs::read_to_string(&path) else {
+            failures.push(format!("day {}: missing {}", number, path.display()));
+            continue;
+        };
+
+        for (part, expected) in expected.iter().enumerate() {
+            let mut out = Output::capture();
+            match (day.solve)(&input, part == 1, &DayOptions::default(), &mut out) {
+                Ok(answer) if answer.value.to_string() == *expected => {}
+                Ok(answer) => failures.push(format!(
+                    "day {} part {}: expected {}, got {}",
+                    number,
+                    part + 1,
+                    expected,
+                    answer.value
+                )),
+                Err(e) => failures.push(format!("day {} part {}: error: {}", number, part + 1, e)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{} example(s) failed:\n  {}", failures.len(), failures.join("\n  "));
+}
+
+#[test]
+fn header_examples_match_their_answers() {
+    let mut failures = Vec::new();
+
+    for day in adventcode::DAYS {
+        let source_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("src/day{}.rs", day.number));
+        let source = fs::read_to_string(&source_path).expect("day source file");
+        let Some(example) = meta::parse(day.number, &source).example else {
+            continue;
+        };
+
+        // The header and the fixture should describe the same example
+        let fixture = fixtures_dir().join(format!("day{}_example.txt", day.number));
+        if let Ok(text) = fs::read_to_string(&fixture)
+            && text != example.input
+        {
+            failures.push(format!("day {}: header example differs from {}", day.number, fixture.display()));
+        }
+
+        for (part, expected) in example.answers.iter().enumerate() {
+            let Some(expected) = expected else { continue };
+            let mut out = Output::capture();
+            match (day.solve)(&example.input, part == 1, &DayOptions::default(), &mut out) {
+                Ok(answer) if answer.value.to_string() == *expected => {}
+                Ok(answer) => failures.push(format!(
+                    "day {} part {}: header says {}, got {}",
+                    day.number,
+                    part + 1,
+                    expected,
+                    answer.value
+                )),
+                Err(e) => failures.push(format!("day {} part {}: error: {}", day.number, part + 1, e)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{} header example(s) failed:\n  {}", failures.len(), failures.join("\n  "));
+}
+
+#[test]
+fn mapped_and_read_inputs_behave_identically() {
+    for day in adventcode::DAYS {
+        let path = fixtures_dir().join(format!("day{}_example.txt", day.number));
+        let read = input::read_file(&path).expect("fixture reads");
+        let mapped = input::map_file(&path).expect("fixture maps");
+        assert_eq!(read.as_str().as_bytes(), mapped.as_str().as_bytes(), "day {}", day.number);
+
+        for part2 in [false, true] {
+            let run = |text: &str| {
+                let mut out = Output::capture();
+                let answer = (day.solve)(text, part2, &DayOptions::default(), &mut out).map(|a| a.to_string());
+                (answer.map_err(|e| e.to_string()), out.lines().to_vec(), out.warnings().to_vec())
+            };
+            assert_eq!(run(read.as_str()), run(mapped.as_str()), "day {} part2={}", day.number, part2);
+        }
+    }
+}
+
+#[test]
+fn crlf_fixtures_match_their_lf_versions() {
+    for day in adventcode::DAYS {
+        let read = |name: String| fs::read_to_string(fixtures_dir().join(name)).expect("fixture");
+        let lf = read(format!("day{}_example.txt", day.number));
+        let crlf = read(format!("day{}_example_crlf.txt", day.number));
+        assert!(crlf.starts_with('\u{feff}') && crlf.contains("\r\n"), "day {}: fixture lost its CRLF/BOM", day.number);
+        assert_eq!(input::normalize(&crlf), lf, "day {}", day.number);
+
+        for part2 in [false, true] {
+            let mut out = Output::capture();
+            let expected = (day.solve)(&lf, part2, &DayOptions::default(), &mut out).unwrap().to_string();
+
+            let answer = (day.solve)(&input::normalize(&crlf), part2, &DayOptions::default(), &mut out);
+            assert_eq!(answer.unwrap().to_string(), expected, "day {} part2={}", day.number, part2);
+
+            if let Some(solve_stream) = day.stream {
+                let mut reader = input::InputReader::from_text(&crlf);
+                let answer = solve_stream(&mut reader, part2, &DayOptions::default(), &mut out);
+                assert_eq!(answer.unwrap().to_string(), expected, "day {} part2={} (stream)", day.number, part2);
+            }
+            assert!(out.warnings().is_empty(), "day {}: {:?}", day.number, out.warnings());
+        }
+    }
+}
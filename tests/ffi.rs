@@ -0,0 +1,32 @@
+// The C API, driven from C
+//
+// Runs tests/ffi/run.sh, which builds the `ffi` library into its own target
+// directory and links tests/ffi/harness.c against include/adventcode.h.
+// Skipped when no C compiler is on PATH.
+
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn c_harness_passes() {
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".into());
+    if Command::new(&cc).arg("--version").output().is_err() {
+        eprintln!("no C compiler ({}), skipping", cc);
+        return;
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new("sh")
+        .arg(root.join("tests/ffi/run.sh"))
+        .env("CARGO_TARGET_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi"))
+        .env("CARGO", env!("CARGO"))
+        .output()
+        .expect("sh runs");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "harness failed:\n{}{}", stdout, stderr);
+    assert!(stdout.contains("all checks passed"), "{}", stdout);
+}
@@ -0,0 +1,47 @@
+/* Links against the `ffi` build of the library and checks the C API:
+ * the day 5 example answers, and each error code.
+ * Run through tests/ffi/run.sh. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "adventcode.h"
+
+static int failures = 0;
+
+static void expect(const char *what, int ok) {
+    if (!ok) {
+        fprintf(stderr, "FAIL: %s\n", what);
+        failures++;
+    }
+}
+
+static void expect_answer(uint8_t day, uint8_t part, const char *input, const char *answer) {
+    char *out = NULL;
+    int32_t code = aoc_solve(day, part, input, &out);
+    expect("aoc_solve returns AOC_OK", code == AOC_OK);
+    expect("answer matches", out != NULL && strcmp(out, answer) == 0);
+    aoc_free_string(out);
+}
+
+int main(void) {
+    const char *day5 = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+    expect_answer(5, 1, day5, "3");
+    expect_answer(5, 2, day5, "14");
+
+    char *out = NULL;
+    expect("NULL input", aoc_solve(5, 1, NULL, &out) == AOC_NULL_POINTER && out == NULL);
+    expect("NULL out", aoc_solve(5, 1, day5, NULL) == AOC_NULL_POINTER);
+    expect("invalid UTF-8", aoc_solve(5, 1, "3-5\n\xff\n", &out) == AOC_INVALID_UTF8 && out == NULL);
+    expect("unknown day", aoc_solve(25, 1, day5, &out) == AOC_UNKNOWN_DAY && out == NULL);
+    expect("bad part", aoc_solve(5, 3, day5, &out) == AOC_BAD_PART && out == NULL);
+
+    expect("solver error", aoc_solve(5, 1, "3-5", &out) == AOC_SOLVE_FAILED && out != NULL);
+    aoc_free_string(out);
+    aoc_free_string(NULL);
+
+    if (failures == 0) {
+        printf("ffi harness: all checks passed\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Build the library with the `ffi` feature, compile harness.c against
+# include/adventcode.h and run it.
+#
+#   tests/ffi/run.sh            (uses ./target; set CARGO_TARGET_DIR to change)
+set -eu
+
+root=$(cd "$(dirname "$0")/../.." && pwd)
+target=${CARGO_TARGET_DIR:-$root/target}
+
+"${CARGO:-cargo}" build --quiet --manifest-path "$root/Cargo.toml" --lib --no-default-features --features ffi
+${CC:-cc} -Wall -Wextra -Werror -o "$target/ffi-harness" "$root/tests/ffi/harness.c" \
+    -I "$root/include" -L "$target/debug" -ladventcode
+LD_LIBRARY_PATH="$target/debug" DYLD_LIBRARY_PATH="$target/debug" "$target/ffi-harness"
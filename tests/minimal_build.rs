@@ -0,0 +1,68 @@
+// Builds without the default features
+//
+// Builds the binary without the `tui` feature into its own target directory,
+// as a headless CI box would, then runs it: one day must still solve, and a
+// bare invocation must explain itself instead of trying to open a terminal UI.
+// The WASM library build is compile-checked the same way when the
+// wasm32-unknown-unknown target is installed.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Build the no-default-features binary and return its path
+fn build_minimal() -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("no-tui");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir())
+        .args(["build", "--quiet", "--no-default-features", "--bin", "adventcode", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "no-default-features build failed");
+    target_dir.join("debug").join(format!("adventcode{}", std::env::consts::EXE_SUFFIX))
+}
+
+#[test]
+fn cli_only_build_runs_a_day_and_refuses_the_tui() {
+    let bin = build_minimal();
+    let fixture = manifest_dir().join("tests").join("fixtures").join("day5_example.txt");
+
+    let output = Command::new(&bin)
+        .current_dir(manifest_dir())
+        .args(["-d", "5", "-q", "-f"])
+        .arg(&fixture)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+
+    let output = Command::new(&bin).current_dir(manifest_dir()).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("built without the `tui` feature"), "stderr: {}", stderr);
+    assert!(stderr.contains("--list"), "stderr: {}", stderr);
+}
+
+#[test]
+fn wasm_library_builds() {
+    let sysroot = Command::new("rustc").args(["--print", "sysroot"]).output().expect("rustc runs");
+    let sysroot = PathBuf::from(String::from_utf8_lossy(&sysroot.stdout).trim());
+    if !sysroot.join("lib/rustlib/wasm32-unknown-unknown").exists() {
+        eprintln!("wasm32-unknown-unknown not installed, skipping");
+        return;
+    }
+
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("wasm");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir())
+        .args(["build", "--quiet", "--lib", "--no-default-features", "--features", "wasm"])
+        .args(["--target", "wasm32-unknown-unknown", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "wasm32 build failed");
+}
@@ -0,0 +1,123 @@
+// Streaming vs in-memory input
+//
+// Runs each streaming day on generated medium-sized inputs through both
+// paths and checks the answers match. A counting allocator tracks peak heap
+// use, so the streaming path must also stay far below the input size.
+//
+// This file holds a single test on purpose: the allocator counts every
+// thread, and a second test running alongside would skew the peak.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use adventcode::input::InputReader;
+use adventcode::options::DayOptions;
+use adventcode::output::{Answer, Output};
+use fastrand::Rng;
+
+/// Wraps the system allocator, tracking live bytes and their high-water mark
+struct CountingAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Peak heap growth while `f` runs, in bytes
+fn peak_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = LIVE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK.load(Ordering::Relaxed) - base)
+}
+
+/// Builds a generated input from a seeded RNG
+type Generator = fn(&mut Rng) -> String;
+
+/// Rotations with short distances, so part 2's click simulation stays quick
+fn day1_input(rng: &mut Rng) -> String {
+    (0..200_000)
+        .map(|_| format!("{}{}\n", if rng.bool() { 'L' } else { 'R' }, rng.u32(0..200)))
+        .collect()
+}
+
+/// Banks of 100 batteries
+fn day3_input(rng: &mut Rng) -> String {
+    (0..20_000)
+        .map(|_| {
+            let mut bank: String = (0..100).map(|_| char::from(b'1' + rng.u8(0..9))).collect();
+            bank.push('\n');
+            bank
+        })
+        .collect()
+}
+
+/// A thousand fresh ranges, then a long list of bare IDs
+fn day5_input(rng: &mut Rng) -> String {
+    let mut text = String::new();
+    for _ in 0..1_000 {
+        let start = rng.u64(0..1_000_000_000);
+        text.push_str(&format!("{}-{}\n", start, start + rng.u64(0..1_000_000)));
+    }
+    text.push('\n');
+    for _ in 0..300_000 {
+        text.push_str(&format!("{}\n", rng.u64(0..1_000_000_000)));
+    }
+    text
+}
+
+#[test]
+fn streaming_matches_in_memory_with_flat_memory() {
+    let generators: [(u8, Generator); 3] = [(1, day1_input), (3, day3_input), (5, day5_input)];
+
+    for (number, generate) in generators {
+        let day = adventcode::day(number).unwrap();
+        let stream = day.stream.expect("day should support streaming");
+        let input = generate(&mut Rng::with_seed(u64::from(number)));
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(input.as_bytes()).unwrap();
+
+        for part2 in [false, true] {
+            let opts = DayOptions::default();
+            let expected = (day.solve)(&input, part2, &opts, &mut Output::capture()).unwrap();
+
+            let (answer, peak) = peak_during(|| -> Answer {
+                let mut reader = InputReader::open(file.path()).unwrap();
+                stream(&mut reader, part2, &opts, &mut Output::capture()).unwrap()
+            });
+
+            assert_eq!(answer, expected, "day {} part2={}", number, part2);
+            assert!(
+                peak < 256 * 1024,
+                "day {} part2={} peaked at {} bytes for a {} byte input",
+                number,
+                part2,
+                peak,
+                input.len()
+            );
+        }
+
+        // Sanity check that the counter sees the in-memory path holding the input
+        let (_, peak) = peak_during(|| std::fs::read_to_string(file.path()).unwrap());
+        assert!(peak >= input.len());
+    }
+}
@@ -0,0 +1,23 @@
+// The JavaScript bindings, run inside a wasm runtime
+//
+//   wasm-pack test --node --no-default-features --features wasm
+//
+// Compiled only for wasm32 with the `wasm` feature; native test runs skip it.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const DAY5_EXAMPLE: &str = include_str!("fixtures/day5_example.txt");
+
+#[wasm_bindgen_test]
+fn day5_example_answers() {
+    assert_eq!(adventcode::wasm::solve(5, 1, DAY5_EXAMPLE).unwrap(), "3");
+    assert_eq!(adventcode::wasm::solve(5, 2, DAY5_EXAMPLE).unwrap(), "14");
+}
+
+#[wasm_bindgen_test]
+fn days_lists_day5_and_rejects_unknown_days() {
+    assert!(adventcode::wasm::days().contains(&5));
+    assert!(adventcode::wasm::solve(25, 1, "").is_err());
+}